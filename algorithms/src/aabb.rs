@@ -157,3 +157,38 @@ fn simple_bounding_rect() {
 
     assert_eq!(fast_bounding_rect(path.iter()), rect(-1.0, -4.0, 4.0, 6.0));
 }
+
+#[test]
+fn tight_bounding_rect() {
+    use crate::math::{rect, Size};
+    use crate::path::Path;
+
+    assert_eq!(bounding_rect(Path::new().iter()), Rect::zero());
+    assert_eq!(fast_bounding_rect(Path::new().iter()), Rect::zero());
+
+    // The control points of the cubic are outside of the bounding box of the curve.
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.cubic_bezier_to(point(0.0, 4.0), point(4.0, 4.0), point(4.0, 0.0));
+    builder.line_to(point(4.0, -1.0));
+    builder.close();
+    let path = builder.build();
+
+    let r = bounding_rect(path.iter());
+    assert_eq!(r.origin, point(0.0, -1.0));
+    assert!((r.size.width - 4.0).abs() < 0.0001);
+    // The curve reaches 3/4 of the height of its control points.
+    assert!((r.max_y() - 3.0).abs() < 0.0001, "{:?}", r);
+
+    assert_eq!(fast_bounding_rect(path.iter()), rect(0.0, -1.0, 4.0, 5.0));
+
+    // A single point.
+    let mut builder = Path::builder();
+    builder.move_to(point(1.0, 2.0));
+    builder.close();
+    let path = builder.build();
+    assert_eq!(
+        bounding_rect(path.iter()),
+        Rect::new(point(1.0, 2.0), Size::zero())
+    );
+}
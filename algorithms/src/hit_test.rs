@@ -6,11 +6,20 @@ use crate::path::{FillRule, PathEvent};
 use std::f32;
 
 /// Returns whether the point is inside the path.
+///
+/// All sub-paths are implicitly closed. Points that lie exactly on an edge are considered
+/// inside of the path, regardless of the fill rule. Curves are flattened with the provided
+/// tolerance, only where the point is within their vertical range.
 pub fn hit_test_path<Iter>(point: &Point, path: Iter, fill_rule: FillRule, tolerance: f32) -> bool
 where
     Iter: Iterator<Item = PathEvent>,
 {
-    let winding = path_winding_number_at_position(point, path, tolerance);
+    let mut winding = 0;
+    for evt in path {
+        if test_event(*point, evt, tolerance, &mut winding) {
+            return true;
+        }
+    }
 
     match fill_rule {
         FillRule::EvenOdd => winding % 2 != 0,
@@ -19,81 +28,135 @@ where
 }
 
 /// Compute the winding number of a given position with respect to the path.
+///
+/// This is the sum of the signed crossings of the sub-paths with a horizontal ray
+/// going from the point towards negative x, with edges going towards positive y
+/// counting as +1 and edges going towards negative y counting as -1. All sub-paths
+/// are implicitly closed.
+///
+/// Points that lie exactly on an edge get the winding number of the points just to
+/// their left (towards negative x), or just below them (towards positive y) if the
+/// edge is horizontal.
 pub fn path_winding_number_at_position<Iter>(point: &Point, path: Iter, tolerance: f32) -> i32
 where
     Iter: Iterator<Item = PathEvent>,
 {
-    // Loop over the edges and compute the winding number at that point by accumulating the
-    // winding of all edges intersecting the horizontal line passing through our point which are
-    // left of it.
     let mut winding = 0;
+    for evt in path {
+        test_event(*point, evt, tolerance, &mut winding);
+    }
 
+    winding
+}
+
+/// Compute the contribution of each sub-path to the winding number of a given position.
+///
+/// The result has one element per sub-path, in order, and its sum is the value returned
+/// by `path_winding_number_at_position`. This is useful to find out which sub-paths
+/// cause a region to be filled.
+pub fn subpath_winding_numbers_at_position<Iter>(
+    point: &Point,
+    path: Iter,
+    tolerance: f32,
+) -> Vec<i32>
+where
+    Iter: Iterator<Item = PathEvent>,
+{
+    let mut windings = Vec::new();
     for evt in path {
-        match evt {
-            PathEvent::Begin { .. } => {}
-            PathEvent::Line { from, to } => {
-                test_segment(*point, &LineSegment { from, to }, &mut winding);
-            }
-            PathEvent::End { last, first, .. } => {
-                test_segment(
-                    *point,
-                    &LineSegment {
-                        from: last,
-                        to: first,
-                    },
-                    &mut winding,
-                );
-            }
-            PathEvent::Quadratic { from, ctrl, to } => {
-                let segment = QuadraticBezierSegment { from, ctrl, to };
-                let (min, max) = segment.fast_bounding_range_y();
-                if min > point.y || max < point.y {
-                    continue;
-                }
-                let mut prev = segment.from;
-                segment.for_each_flattened(tolerance, &mut |p| {
-                    test_segment(*point, &LineSegment { from: prev, to: p }, &mut winding);
-                    prev = p;
-                });
+        if let PathEvent::Begin { .. } = evt {
+            windings.push(0);
+        }
+        if let Some(winding) = windings.last_mut() {
+            test_event(*point, evt, tolerance, winding);
+        }
+    }
+
+    windings
+}
+
+// Adds the crossings of an event with the ray going from `point` towards negative x to
+// the winding number. Returns true if `point` is on the event's edge.
+fn test_event(point: Point, evt: PathEvent, tolerance: f32, winding: &mut i32) -> bool {
+    match evt {
+        PathEvent::Begin { .. } => false,
+        PathEvent::Line { from, to } => test_segment(point, &LineSegment { from, to }, winding),
+        PathEvent::End { last, first, .. } => test_segment(
+            point,
+            &LineSegment {
+                from: last,
+                to: first,
+            },
+            winding,
+        ),
+        PathEvent::Quadratic { from, ctrl, to } => {
+            let segment = QuadraticBezierSegment { from, ctrl, to };
+            let (min, max) = segment.fast_bounding_range_y();
+            if min > point.y || max < point.y {
+                return false;
             }
-            PathEvent::Cubic {
+            let mut on_edge = false;
+            let mut prev = segment.from;
+            segment.for_each_flattened(tolerance, &mut |p| {
+                on_edge |= test_segment(point, &LineSegment { from: prev, to: p }, winding);
+                prev = p;
+            });
+            on_edge
+        }
+        PathEvent::Cubic {
+            from,
+            ctrl1,
+            ctrl2,
+            to,
+        } => {
+            let segment = CubicBezierSegment {
                 from,
                 ctrl1,
                 ctrl2,
                 to,
-            } => {
-                let segment = CubicBezierSegment {
-                    from,
-                    ctrl1,
-                    ctrl2,
-                    to,
-                };
-                let (min, max) = segment.fast_bounding_range_y();
-                if min > point.y || max < point.y {
-                    continue;
-                }
-                let mut prev = segment.from;
-                segment.for_each_flattened(tolerance, &mut |p| {
-                    test_segment(*point, &LineSegment { from: prev, to: p }, &mut winding);
-                    prev = p;
-                });
+            };
+            let (min, max) = segment.fast_bounding_range_y();
+            if min > point.y || max < point.y {
+                return false;
             }
+            let mut on_edge = false;
+            let mut prev = segment.from;
+            segment.for_each_flattened(tolerance, &mut |p| {
+                on_edge |= test_segment(point, &LineSegment { from: prev, to: p }, winding);
+                prev = p;
+            });
+            on_edge
         }
     }
-
-    winding
 }
 
-fn test_segment(point: Point, segment: &LineSegment<f32>, winding: &mut i32) {
-    if let Some(pos) = segment.horizontal_line_intersection(point.y) {
-        if pos.x < point.x {
-            if segment.to.y > segment.from.y {
-                *winding += 1;
-            } else if segment.to.y < segment.from.y {
-                *winding -= 1;
-            }
+// Segments are treated as half-open in y so that the ray doesn't count the crossing of
+// two consecutive segments twice when it goes through their shared endpoint. Returns
+// true if `point` is on the segment.
+fn test_segment(point: Point, segment: &LineSegment<f32>, winding: &mut i32) -> bool {
+    let from = segment.from;
+    let to = segment.to;
+    let cross = (to - from).cross(point - from);
+    if cross == 0.0
+        && point.x >= from.x.min(to.x)
+        && point.x <= from.x.max(to.x)
+        && point.y >= from.y.min(to.y)
+        && point.y <= from.y.max(to.y)
+    {
+        return true;
+    }
+
+    // The edge is on the left of the point if the point is on the right side of the
+    // edge's direction when it goes towards positive y.
+    if from.y <= point.y {
+        if to.y > point.y && cross < 0.0 {
+            *winding += 1;
         }
+    } else if to.y <= point.y && cross > 0.0 {
+        *winding -= 1;
     }
+
+    false
 }
 
 #[test]
@@ -170,3 +233,150 @@ fn test_hit_test() {
         0.1
     ));
 }
+
+#[test]
+fn test_hit_test_edges_and_curves() {
+    use crate::math::point;
+    use crate::path::Path;
+
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to(point(10.0, 10.0));
+    builder.line_to(point(0.0, 10.0));
+    builder.close();
+    // A hole with the same orientation.
+    builder.move_to(point(2.0, 2.0));
+    builder.line_to(point(4.0, 2.0));
+    builder.line_to(point(4.0, 4.0));
+    builder.line_to(point(2.0, 4.0));
+    builder.close();
+    // A hole with the opposite orientation.
+    builder.move_to(point(6.0, 6.0));
+    builder.line_to(point(6.0, 8.0));
+    builder.line_to(point(8.0, 8.0));
+    builder.line_to(point(8.0, 6.0));
+    builder.close();
+    // A separate shape, made of a curve and left open.
+    builder.move_to(point(20.0, 0.0));
+    builder.quadratic_bezier_to(point(25.0, 10.0), point(30.0, 0.0));
+    let path = builder.build();
+
+    let hit = |x: f32, y: f32, fill_rule: FillRule| {
+        hit_test_path(&point(x, y), path.iter(), fill_rule, 0.01)
+    };
+
+    for &fill_rule in &[FillRule::EvenOdd, FillRule::NonZero] {
+        assert!(hit(1.0, 1.0, fill_rule));
+        assert!(hit(7.0, 3.0, fill_rule));
+        assert!(!hit(7.0, 7.0, fill_rule));
+        assert!(!hit(-1.0, 5.0, fill_rule));
+        assert!(!hit(15.0, 1.0, fill_rule));
+
+        // The ray goes through vertices.
+        assert!(hit(1.0, 2.0, fill_rule));
+        assert!(!hit(-1.0, 0.0, fill_rule));
+
+        // Under and above the curve.
+        assert!(hit(25.0, 4.0, fill_rule));
+        assert!(!hit(25.0, 6.0, fill_rule));
+        assert!(hit(21.0, 1.6, fill_rule));
+        assert!(!hit(21.0, 2.0, fill_rule));
+
+        // Points on the edges are inside.
+        assert!(hit(0.0, 5.0, fill_rule));
+        assert!(hit(10.0, 10.0, fill_rule));
+        assert!(hit(3.0, 2.0, fill_rule));
+    }
+
+    assert!(!hit(3.0, 3.0, FillRule::EvenOdd));
+    assert!(hit(3.0, 3.0, FillRule::NonZero));
+}
+
+#[test]
+fn test_winding_number() {
+    use crate::math::point;
+    use crate::path::{Builder, Path};
+
+    let square = |builder: &mut Builder, min: f32, max: f32| {
+        builder.move_to(point(min, min));
+        builder.line_to(point(max, min));
+        builder.line_to(point(max, max));
+        builder.line_to(point(min, max));
+        builder.close();
+    };
+
+    // The same square twice.
+    let mut builder = Path::builder();
+    square(&mut builder, 0.0, 10.0);
+    square(&mut builder, 0.0, 10.0);
+    let path = builder.build();
+
+    let winding = |path: &Path, x: f32, y: f32| {
+        path_winding_number_at_position(&point(x, y), path.iter(), 0.1)
+    };
+    let subpath_windings = |path: &Path, x: f32, y: f32| {
+        subpath_winding_numbers_at_position(&point(x, y), path.iter(), 0.1)
+    };
+
+    assert_eq!(winding(&path, 5.0, 5.0), -2);
+    assert_eq!(subpath_windings(&path, 5.0, 5.0), vec![-1, -1]);
+    assert_eq!(winding(&path, 15.0, 5.0), 0);
+    assert_eq!(winding(&path, -5.0, 5.0), 0);
+    assert_eq!(winding(&path, 5.0, -5.0), 0);
+    assert_eq!(subpath_windings(&path, 15.0, 5.0), vec![0, 0]);
+
+    // Points on the edges get the winding number on their left or below them.
+    assert_eq!(winding(&path, 0.0, 5.0), 0);
+    assert_eq!(winding(&path, 10.0, 5.0), -2);
+    assert_eq!(winding(&path, 5.0, 0.0), -2);
+    assert_eq!(winding(&path, 5.0, 10.0), 0);
+
+    // A hole wound in the opposite direction.
+    let mut builder = Path::builder();
+    square(&mut builder, 0.0, 10.0);
+    builder.move_to(point(2.0, 2.0));
+    builder.line_to(point(2.0, 8.0));
+    builder.line_to(point(8.0, 8.0));
+    builder.line_to(point(8.0, 2.0));
+    builder.close();
+    let path = builder.build();
+
+    assert_eq!(subpath_windings(&path, 5.0, 5.0), vec![-1, 1]);
+    assert_eq!(winding(&path, 5.0, 5.0), 0);
+    assert_eq!(winding(&path, 1.0, 5.0), -1);
+    assert!(subpath_windings(&Path::new(), 5.0, 5.0).is_empty());
+}
+
+#[test]
+fn test_winding_number_sign() {
+    use crate::math::point;
+    use crate::path::Path;
+
+    // Clockwise with the y axis pointing down.
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to(point(10.0, 10.0));
+    builder.line_to(point(0.0, 10.0));
+    builder.close();
+    let clockwise = builder.build();
+
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(0.0, 10.0));
+    builder.line_to(point(10.0, 10.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.close();
+    let counter_clockwise = builder.build();
+
+    let p = point(5.0, 5.0);
+    assert_eq!(
+        path_winding_number_at_position(&p, clockwise.iter(), 0.1),
+        -1
+    );
+    assert_eq!(
+        path_winding_number_at_position(&p, counter_clockwise.iter(), 0.1),
+        1
+    );
+}
@@ -14,6 +14,7 @@ pub(crate) mod advanced_path;
 pub mod fit;
 pub mod hatching;
 pub mod hit_test;
+pub mod offset;
 pub mod raycast;
 pub mod splitter;
 pub mod walk;
@@ -0,0 +1,359 @@
+//! Approximate the offset curves of individual bézier segments.
+//!
+//! The offset of a bézier curve (the set of points at a constant distance along its
+//! normal) is not a bézier curve itself. The functions in this module approximate it
+//! with a sequence of cubic bézier segments, subdividing until the approximation is
+//! within a given tolerance of the true offset curve.
+
+use crate::geom::{CubicBezierSegment, QuadraticBezierSegment};
+use crate::math::*;
+use crate::path::Path;
+
+// Maximum number of recursive subdivisions of a single piece of the curve.
+const MAX_DEPTH: u32 = 16;
+
+// The pieces of the curve that meet at a cusp are offset up to this distance (in
+// curve parameter) from the cusp, where their tangent is well defined.
+const CUSP_EPSILON: f32 = 0.001;
+
+/// Approximates the offset of a quadratic bézier segment.
+///
+/// See [`offset_cubic`](fn.offset_cubic.html).
+pub fn offset_quadratic(
+    from: Point,
+    ctrl: Point,
+    to: Point,
+    distance: f32,
+    tolerance: f32,
+) -> Path {
+    let curve = QuadraticBezierSegment { from, ctrl, to }.to_cubic();
+    offset_cubic(
+        curve.from,
+        curve.ctrl1,
+        curve.ctrl2,
+        curve.to,
+        distance,
+        tolerance,
+    )
+}
+
+/// Approximates the offset of a cubic bézier segment.
+///
+/// Positive distances offset the curve along `vector(-tangent.y, tangent.x)`, which is
+/// to the left of the curve in a y-up coordinate system and to the right in a y-down one.
+///
+/// The result is a single open sub-path made of cubic bézier segments. The curve is first
+/// split at its inflection points and cusps, and each piece is then recursively subdivided
+/// until it is within `tolerance` of the true offset curve.
+///
+/// The tangent of the curve flips at a cusp, so the offset curve jumps from one side of the
+/// curve to the other. The two sides are connected with a circular arc around the cusp, as
+/// a round join would, made of quadratic bézier segments.
+pub fn offset_cubic(
+    from: Point,
+    ctrl1: Point,
+    ctrl2: Point,
+    to: Point,
+    distance: f32,
+    tolerance: f32,
+) -> Path {
+    let curve = CubicBezierSegment {
+        from,
+        ctrl1,
+        ctrl2,
+        to,
+    };
+
+    let scale = (ctrl1 - from).length() + (ctrl2 - ctrl1).length() + (to - ctrl2).length();
+    let mut cusps: Vec<f32> = Vec::new();
+    curve.for_each_local_x_extremum_t(&mut |t| {
+        if t > CUSP_EPSILON
+            && t < 1.0 - CUSP_EPSILON
+            && curve.derivative(t).length() <= scale * 0.0001
+        {
+            cusps.push(t);
+        }
+    });
+
+    let mut splits: Vec<f32> = cusps.clone();
+    curve.for_each_inflection_t(&mut |t| splits.push(t));
+    splits.retain(|&t| t > 0.0 && t < 1.0);
+    splits.push(1.0);
+    splits.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    splits.dedup();
+
+    let mut builder = Path::builder();
+    builder.move_to(offset_point(&curve, 0.0, distance));
+    let mut t0 = 0.0;
+    for &t1 in &splits {
+        if t1 - t0 <= 0.00001 {
+            continue;
+        }
+
+        let mut start = t0;
+        if cusps.contains(&t0) {
+            start += CUSP_EPSILON;
+            add_cusp_join(&curve, t0, start, distance, &mut builder);
+        }
+        let end = if cusps.contains(&t1) {
+            t1 - CUSP_EPSILON
+        } else {
+            t1
+        };
+
+        offset_range(&curve, start, end, distance, tolerance, 0, &mut builder);
+        t0 = t1;
+    }
+
+    builder.build()
+}
+
+// Connects the end of the offset before the cusp at `t` to the start of the offset
+// after it with an arc around the cusp.
+fn add_cusp_join(
+    curve: &CubicBezierSegment<f32>,
+    t: f32,
+    next: f32,
+    distance: f32,
+    builder: &mut crate::path::Builder,
+) {
+    let center = curve.sample(t);
+    let from = builder.current_position() - center;
+    let to = offset_point(curve, next, distance) - center;
+    let mut sweep = from.cross(to).atan2(from.dot(to));
+    // The two sides are nearly opposite: go around the tip of the cusp, which is in
+    // the direction of the tangent before it.
+    let incoming = tangent(curve, t - CUSP_EPSILON);
+    let mid = Rotation::new(Angle::radians(sweep * 0.5)).transform_vector(from);
+    if mid.dot(incoming) < 0.0 {
+        sweep -= 2.0 * std::f32::consts::PI * sweep.signum();
+    }
+
+    let radius = from.length();
+    builder.arc(
+        center,
+        vector(radius, radius),
+        Angle::radians(sweep),
+        Angle::zero(),
+    );
+}
+
+fn offset_range(
+    curve: &CubicBezierSegment<f32>,
+    t0: f32,
+    t1: f32,
+    distance: f32,
+    tolerance: f32,
+    depth: u32,
+    builder: &mut crate::path::Builder,
+) {
+    let approx = approximate_range(curve, t0, t1, distance);
+
+    let mut ok = depth >= MAX_DEPTH;
+    if !ok {
+        ok = true;
+        for &s in &[0.25, 0.5, 0.75] {
+            let expected = offset_point(curve, t0 + (t1 - t0) * s, distance);
+            if (approx.sample(s) - expected).length() > tolerance {
+                ok = false;
+                break;
+            }
+        }
+    }
+
+    if ok {
+        builder.cubic_bezier_to(approx.ctrl1, approx.ctrl2, approx.to);
+        return;
+    }
+
+    let mid = (t0 + t1) * 0.5;
+    offset_range(curve, t0, mid, distance, tolerance, depth + 1, builder);
+    offset_range(curve, mid, t1, distance, tolerance, depth + 1, builder);
+}
+
+// Hermite interpolation of the offset curve between t0 and t1.
+fn approximate_range(
+    curve: &CubicBezierSegment<f32>,
+    t0: f32,
+    t1: f32,
+    distance: f32,
+) -> CubicBezierSegment<f32> {
+    let dt = (t1 - t0) / 3.0;
+    let from = offset_point(curve, t0, distance);
+    let to = offset_point(curve, t1, distance);
+    CubicBezierSegment {
+        from,
+        ctrl1: from + offset_derivative(curve, t0, distance) * dt,
+        ctrl2: to - offset_derivative(curve, t1, distance) * dt,
+        to,
+    }
+}
+
+fn tangent(curve: &CubicBezierSegment<f32>, t: f32) -> Vector {
+    let d = curve.derivative(t);
+    if d.square_length() > 0.0 {
+        return d;
+    }
+
+    // At cusps and degenerate endpoints, use the direction of the nearest control points.
+    let candidates = if t < 0.5 {
+        [
+            curve.ctrl1 - curve.from,
+            curve.ctrl2 - curve.from,
+            curve.to - curve.from,
+        ]
+    } else {
+        [
+            curve.to - curve.ctrl2,
+            curve.to - curve.ctrl1,
+            curve.to - curve.from,
+        ]
+    };
+
+    for v in &candidates {
+        if v.square_length() > 0.0 {
+            return *v;
+        }
+    }
+
+    vector(1.0, 0.0)
+}
+
+fn offset_point(curve: &CubicBezierSegment<f32>, t: f32, distance: f32) -> Point {
+    let d = tangent(curve, t).normalize();
+    curve.sample(t) + vector(-d.y, d.x) * distance
+}
+
+// The derivative of the offset curve is the derivative of the curve scaled by
+// `1 - distance * curvature`.
+fn offset_derivative(curve: &CubicBezierSegment<f32>, t: f32, distance: f32) -> Vector {
+    let d1 = curve.derivative(t);
+    let length = d1.length();
+    if length == 0.0 {
+        return d1;
+    }
+
+    let one_t = 1.0 - t;
+    let d2 = ((curve.from.to_vector() - curve.ctrl1.to_vector() * 2.0 + curve.ctrl2.to_vector())
+        * one_t
+        + (curve.ctrl1.to_vector() - curve.ctrl2.to_vector() * 2.0 + curve.to.to_vector()) * t)
+        * 6.0;
+    let curvature = d1.cross(d2) / (length * length * length);
+
+    d1 * (1.0 - distance * curvature)
+}
+
+#[test]
+fn offset_within_tolerance() {
+    use crate::path::PathEvent;
+
+    fn check(path: &Path, curve: &CubicBezierSegment<f32>, distance: f32, tolerance: f32) {
+        // Densely sample the true offset curve.
+        let num_samples = 5000;
+        let expected: Vec<Point> = (0..=num_samples)
+            .map(|i| offset_point(curve, i as f32 / num_samples as f32, distance))
+            .collect();
+
+        let mut num_segments = 0;
+        for evt in path.iter() {
+            if let PathEvent::Cubic {
+                from,
+                ctrl1,
+                ctrl2,
+                to,
+            } = evt
+            {
+                num_segments += 1;
+                let segment = CubicBezierSegment {
+                    from,
+                    ctrl1,
+                    ctrl2,
+                    to,
+                };
+                for i in 0..=20 {
+                    let p = segment.sample(i as f32 / 20.0);
+                    let d = expected
+                        .iter()
+                        .map(|e| (*e - p).length())
+                        .fold(std::f32::MAX, f32::min);
+                    assert!(d < tolerance * 1.1, "{:?} is {} away from the offset", p, d);
+                }
+            }
+        }
+
+        assert!(num_segments > 0);
+    }
+
+    let tolerance = 0.05;
+    let curves = [
+        // Simple arch.
+        CubicBezierSegment {
+            from: point(0.0, 0.0),
+            ctrl1: point(10.0, 30.0),
+            ctrl2: point(40.0, 30.0),
+            to: point(50.0, 0.0),
+        },
+        // With an inflection point.
+        CubicBezierSegment {
+            from: point(0.0, 0.0),
+            ctrl1: point(20.0, 30.0),
+            ctrl2: point(30.0, -30.0),
+            to: point(50.0, 0.0),
+        },
+    ];
+
+    for curve in &curves {
+        for &distance in &[2.0, -3.0] {
+            let path = offset_cubic(
+                curve.from,
+                curve.ctrl1,
+                curve.ctrl2,
+                curve.to,
+                distance,
+                tolerance,
+            );
+            check(&path, curve, distance, tolerance);
+        }
+    }
+
+    let quadratic = QuadraticBezierSegment {
+        from: point(0.0, 0.0),
+        ctrl: point(20.0, 40.0),
+        to: point(40.0, 0.0),
+    };
+    let path = offset_quadratic(quadratic.from, quadratic.ctrl, quadratic.to, 4.0, tolerance);
+    check(&path, &quadratic.to_cubic(), 4.0, tolerance);
+
+    // With a cusp at t = 0.5, where the offset jumps to the other side of the curve.
+    let cusp = CubicBezierSegment {
+        from: point(0.0, 0.0),
+        ctrl1: point(50.0, 50.0),
+        ctrl2: point(0.0, 50.0),
+        to: point(50.0, 0.0),
+    };
+    let tip = cusp.sample(0.5);
+    for &distance in &[2.0, -3.0] {
+        let path = offset_cubic(
+            cusp.from, cusp.ctrl1, cusp.ctrl2, cusp.to, distance, tolerance,
+        );
+        check(&path, &cusp, distance, tolerance);
+
+        // The two sides are joined with an arc that goes around the tip.
+        let mut max_y = std::f32::MIN;
+        let mut num_arc_segments = 0;
+        for evt in path.iter() {
+            if let PathEvent::Quadratic { from, ctrl, to } = evt {
+                num_arc_segments += 1;
+                let arc = QuadraticBezierSegment { from, ctrl, to };
+                for i in 0..=10 {
+                    let p = arc.sample(i as f32 / 10.0);
+                    let d = ((p - tip).length() - distance.abs()).abs();
+                    assert!(d < tolerance, "{:?} is {} away from the join", p, d);
+                    max_y = max_y.max(p.y);
+                }
+            }
+        }
+        assert!(num_arc_segments > 0);
+        assert!((max_y - (tip.y + distance.abs())).abs() < tolerance);
+    }
+}
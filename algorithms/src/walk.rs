@@ -42,6 +42,7 @@
 use crate::geom::{Arc, CubicBezierSegment, QuadraticBezierSegment};
 use crate::math::*;
 use crate::path::builder::*;
+use crate::path::iterator::PathIterator;
 use crate::path::PathEvent;
 
 use std::f32;
@@ -60,6 +61,72 @@ where
     }
 }
 
+/// Computes `count` points spaced by equal arc length along a path, along with the
+/// normalized tangent of the path at each point.
+///
+/// The curves are flattened with the provided tolerance and the sub-paths are walked one
+/// after the other as if they were a single curve. If all sub-paths are closed, the points
+/// wrap around: the first point is at the start of the path and the last one is one step
+/// before the end. Otherwise both ends of the path are included.
+pub fn sample_uniform<Iter>(path: Iter, count: usize, tolerance: f32) -> Vec<(Point, Vector)>
+where
+    Iter: Iterator<Item = PathEvent> + Clone,
+{
+    let mut length = 0.0;
+    let mut all_closed = true;
+    let mut end = None;
+    for evt in path.clone().flattened(tolerance) {
+        let (from, to) = match evt {
+            PathEvent::Line { from, to } => (from, to),
+            PathEvent::End {
+                last,
+                first,
+                close: true,
+            } => (last, first),
+            PathEvent::End { close: false, .. } => {
+                all_closed = false;
+                continue;
+            }
+            _ => continue,
+        };
+        let d = (to - from).length();
+        if d > 0.0 {
+            length += d;
+            end = Some((to, (to - from) / d));
+        }
+    }
+
+    let mut result = Vec::with_capacity(count);
+    let end = match end {
+        Some(end) if count > 0 => end,
+        _ => return result,
+    };
+
+    let num_steps = if all_closed || count == 1 {
+        count
+    } else {
+        count - 1
+    };
+    {
+        let mut pattern = RegularPattern {
+            callback: |position, tangent, _distance| {
+                result.push((position, tangent));
+                result.len() < count
+            },
+            interval: length / num_steps as f32,
+        };
+        walk_along_path(path.flattened(tolerance), 0.0, &mut pattern);
+    }
+
+    // Because of floating point precision, the walk can stop right before the end of
+    // the path.
+    if result.len() < count {
+        result.push(end);
+    }
+
+    result
+}
+
 /// Types implementing the `Pattern` can be used to walk along a path
 /// at constant speed.
 ///
@@ -160,6 +227,7 @@ impl<'l> FlatPathBuilder for PathWalker<'l> {
                 self.next_distance = distance;
             } else {
                 self.done = true;
+                return;
             }
         }
 
@@ -285,6 +353,61 @@ where
     }
 }
 
+#[test]
+fn test_sample_uniform() {
+    use crate::path::Path;
+
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(1.0, 0.0));
+    let path = builder.build();
+
+    let samples = sample_uniform(path.iter(), 5, 0.01);
+    assert_eq!(samples.len(), 5);
+    for (i, &(position, tangent)) in samples.iter().enumerate() {
+        assert!((position - point(i as f32 * 0.25, 0.0)).length() < 0.0001);
+        assert_eq!(tangent, vector(1.0, 0.0));
+    }
+
+    // Closed sub-paths wrap around.
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(1.0, 0.0));
+    builder.line_to(point(1.0, 1.0));
+    builder.line_to(point(0.0, 1.0));
+    builder.close();
+    let path = builder.build();
+
+    let samples = sample_uniform(path.iter(), 8, 0.01);
+    let expected = [
+        (point(0.0, 0.0), vector(1.0, 0.0)),
+        (point(0.5, 0.0), vector(1.0, 0.0)),
+        (point(1.0, 0.0), vector(1.0, 0.0)),
+        (point(1.0, 0.5), vector(0.0, 1.0)),
+        (point(1.0, 1.0), vector(0.0, 1.0)),
+        (point(0.5, 1.0), vector(-1.0, 0.0)),
+        (point(0.0, 1.0), vector(-1.0, 0.0)),
+        (point(0.0, 0.5), vector(0.0, -1.0)),
+    ];
+    assert_eq!(samples.len(), expected.len());
+    for (sample, expected) in samples.iter().zip(expected.iter()) {
+        assert!((sample.0 - expected.0).length() < 0.0001);
+        assert!((sample.1 - expected.1).length() < 0.0001);
+    }
+
+    // The end of an open path is included even if the walk falls short of it.
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.quadratic_bezier_to(point(1.0, 1.0), point(2.0, 0.0));
+    let path = builder.build();
+    let samples = sample_uniform(path.iter(), 7, 0.01);
+    assert_eq!(samples.len(), 7);
+    assert!((samples[6].0 - point(2.0, 0.0)).length() < 0.0001);
+
+    assert!(sample_uniform(path.iter(), 0, 0.01).is_empty());
+    assert!(sample_uniform(Path::new().iter(), 3, 0.01).is_empty());
+}
+
 #[test]
 fn walk_square() {
     let expected = [
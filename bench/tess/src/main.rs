@@ -5,12 +5,12 @@ extern crate bencher;
 extern crate tess2_sys as tess2;
 
 use lyon::extra::rust_logo::build_logo_path;
-use lyon::math::Point;
+use lyon::math::{point, Point};
 use lyon::path::builder::*;
 use lyon::path::iterator::PathIterator;
 use lyon::path::Path;
 use lyon::tessellation::geometry_builder::{simple_builder, VertexBuffers};
-use lyon::tessellation::{EventQueue, FillTessellator};
+use lyon::tessellation::{EventQueue, FillScratch, FillTessellator};
 use lyon::tessellation::{FillOptions, LineJoin};
 use lyon::tessellation::{StrokeOptions, StrokeTessellator};
 
@@ -105,6 +105,64 @@ fn fill_tess_06_logo_with_ids(bench: &mut Bencher) {
     })
 }
 
+fn small_paths() -> Vec<Path> {
+    let mut paths = Vec::new();
+    for i in 0..100 {
+        let offset = i as f32;
+        let mut builder = Path::builder();
+        builder.move_to(point(offset, 0.0));
+        builder.line_to(point(offset + 10.0, 1.0));
+        builder.quadratic_bezier_to(point(offset + 12.0, 6.0), point(offset + 8.0, 10.0));
+        builder.line_to(point(offset + 1.0, 8.0));
+        builder.close();
+        paths.push(builder.build());
+    }
+
+    paths
+}
+
+fn fill_tess_07_small_paths_fresh(bench: &mut Bencher) {
+    let paths = small_paths();
+    let options = FillOptions::default();
+    let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::with_capacity(512, 1450);
+
+    bench.iter(|| {
+        for _ in 0..N {
+            for path in &paths {
+                buffers.vertices.clear();
+                buffers.indices.clear();
+                let mut tess = FillTessellator::new();
+                tess.tessellate_path(path, &options, &mut simple_builder(&mut buffers))
+                    .unwrap();
+            }
+        }
+    })
+}
+
+fn fill_tess_08_small_paths_scratch(bench: &mut Bencher) {
+    let paths = small_paths();
+    let options = FillOptions::default();
+    let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::with_capacity(512, 1450);
+    let mut scratch = FillScratch::new();
+
+    bench.iter(|| {
+        for _ in 0..N {
+            for path in &paths {
+                buffers.vertices.clear();
+                buffers.indices.clear();
+                let mut tess = FillTessellator::new();
+                tess.tessellate_path_with_scratch(
+                    path,
+                    &options,
+                    &mut scratch,
+                    &mut simple_builder(&mut buffers),
+                )
+                .unwrap();
+            }
+        }
+    })
+}
+
 fn fill_tess_03_logo_no_intersections(bench: &mut Bencher) {
     let mut path = Path::builder().with_svg();
     build_logo_path(&mut path);
@@ -336,7 +394,9 @@ benchmark_group!(
     fill_tess_01_logo,
     fill_tess_06_logo_with_ids,
     fill_tess_03_logo_no_intersections,
-    fill_tess_05_logo_no_curve
+    fill_tess_05_logo_no_curve,
+    fill_tess_07_small_paths_fresh,
+    fill_tess_08_small_paths_scratch
 );
 
 #[cfg(feature = "libtess2")]
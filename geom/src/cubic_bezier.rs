@@ -1,7 +1,9 @@
 use crate::cubic_bezier_intersections::cubic_bezier_intersections_t;
 use crate::cubic_to_quadratic::*;
 pub use crate::flatten_cubic::Flattened;
-use crate::flatten_cubic::{find_cubic_bezier_inflection_points, flatten_cubic_bezier_with_t};
+use crate::flatten_cubic::{
+    find_cubic_bezier_inflection_points, flatten_cubic_bezier_with_t, num_flattening_segments,
+};
 use crate::generic_math::{rect, Point, Rect, Vector};
 use crate::monotonic::Monotonic;
 use crate::scalar::Scalar;
@@ -424,6 +426,21 @@ impl<S: Scalar> CubicBezierSegment<S> {
         flatten_cubic_bezier_with_t(self, tolerance, callback);
     }
 
+    /// Returns the number of line segments that approximate the curve when flattening it
+    /// with the provided tolerance, without computing them.
+    pub fn num_flattening_segments(&self, tolerance: S) -> u32 {
+        num_flattening_segments(self, tolerance, u32::MAX)
+    }
+
+    /// Same as `num_flattening_segments` but stops counting as soon as there are more than
+    /// `max` segments, in which case a value greater than `max` is returned.
+    ///
+    /// The cost of counting is proportional to the number of segments, so this is useful
+    /// to bound the work done for curves with huge coordinates.
+    pub fn num_flattening_segments_up_to(&self, tolerance: S, max: u32) -> u32 {
+        num_flattening_segments(self, tolerance, max)
+    }
+
     /// Compute the length of the segment using a flattened approximation.
     pub fn approximate_length(&self, tolerance: S) -> S {
         let mut from = self.from;
@@ -1326,3 +1343,24 @@ fn test_cubic_intersection_deduping() {
     assert!(f64::abs(intersections[0].x) < epsilon);
     assert!(f64::abs(intersections[0].y) < epsilon);
 }
+
+#[test]
+fn num_flattening_segments_up_to() {
+    let curve = CubicBezierSegment {
+        from: Point::new(0.0f32, 0.0),
+        ctrl1: Point::new(1.0e7, -1.0e7),
+        ctrl2: Point::new(-1.0e7, 1.0e7),
+        to: Point::new(0.0, 10.0),
+    };
+    assert!(curve.num_flattening_segments_up_to(0.01, 64) > 64);
+
+    let curve = CubicBezierSegment {
+        from: Point::new(0.0f32, 0.0),
+        ctrl1: Point::new(10.0, 0.0),
+        ctrl2: Point::new(10.0, 10.0),
+        to: Point::new(0.0, 10.0),
+    };
+    let n = curve.num_flattening_segments(0.1);
+    assert_eq!(curve.num_flattening_segments_up_to(0.1, n), n);
+    assert!(curve.num_flattening_segments_up_to(0.1, n - 1) > n - 1);
+}
@@ -6,6 +6,7 @@ use crate::scalar::Scalar;
 use crate::CubicBezierSegment;
 use crate::cubic_to_quadratic::single_curve_approximation;
 use crate::quadratic_bezier::FlattenedT as FlattenedQuadraticSegment;
+use arrayvec::ArrayVec;
 
 // Computes the number of quadratic bézier segments to approximate a cubic one.
 // Derived by Raph Levien from section 10.6 of Sedeberg's CAGD notes
@@ -22,11 +23,86 @@ fn num_quadratics<S: Scalar>(curve: &CubicBezierSegment<S>, tolerance: S) -> S {
     (err / (S::value(432.0) * tolerance * tolerance)).powf(S::ONE / S::SIX).ceil()
 }
 
+// If the control points of the curve are all on a line (within the tolerance), returns
+// the parameters at which the curve reverses its direction along the line, followed by 1.0.
+//
+// Such curves can go back and forth along the line, which the regular flattening
+// algorithm doesn't handle well since there is no curvature to measure: the polyline
+// going through the reversal points is the exact flattening.
+fn collinear_flattening_t<S: Scalar>(curve: &CubicBezierSegment<S>, tolerance: S) -> Option<ArrayVec<[S; 3]>> {
+    let v1 = curve.ctrl1 - curve.from;
+    let v2 = curve.ctrl2 - curve.from;
+    let v3 = curve.to - curve.from;
+
+    let mut result = ArrayVec::new();
+
+    // Pick the longest vector to get the most precise direction.
+    let mut dir = v3;
+    for v in &[v1, v2] {
+        if v.square_length() > dir.square_length() {
+            dir = *v;
+        }
+    }
+    let length = dir.length();
+    if length <= S::EPSILON {
+        // All points are at the same position.
+        result.push(S::ONE);
+        return Some(result);
+    }
+    let dir = dir / length;
+
+    let max_distance = tolerance * S::value(0.25);
+    if S::abs(dir.cross(v1)) > max_distance
+        || S::abs(dir.cross(v2)) > max_distance
+        || S::abs(dir.cross(v3)) > max_distance
+    {
+        return None;
+    }
+
+    // The position along the line is a cubic polynomial, find the roots of its derivative
+    // a * t² + b * t + c.
+    let (p1, p2, p3) = (dir.dot(v1), dir.dot(v2), dir.dot(v3));
+    let a = S::THREE * (p3 + S::THREE * (p1 - p2));
+    let b = S::SIX * (p2 - S::TWO * p1);
+    let c = S::THREE * p1;
+
+    let mut push = |t: S| {
+        if t > S::ZERO && t < S::ONE {
+            result.push(t);
+        }
+    };
+    if S::abs(a) <= S::EPSILON {
+        if S::abs(b) > S::EPSILON {
+            push(-c / b);
+        }
+    } else {
+        let discriminant = b * b - S::FOUR * a * c;
+        if discriminant > S::ZERO {
+            let sqrt = S::sqrt(discriminant);
+            let (t1, t2) = ((-b - sqrt) / (S::TWO * a), (-b + sqrt) / (S::TWO * a));
+            push(S::min(t1, t2));
+            push(S::max(t1, t2));
+        }
+    }
+    result.push(S::ONE);
+
+    Some(result)
+}
+
 pub fn flatten_cubic_bezier_with_t<S: Scalar, F>(curve: &CubicBezierSegment<S>, tolerance: S, callback: &mut F)
 where
     F: FnMut(Point<S>, S),
 {
     debug_assert!(tolerance >= S::EPSILON);
+
+    if let Some(collinear_t) = collinear_flattening_t(curve, tolerance) {
+        for &t in &collinear_t {
+            let point = if t == S::ONE { curve.to } else { curve.sample(t) };
+            callback(point, t);
+        }
+        return;
+    }
+
     let quadratics_tolerance = tolerance * S::value(0.2);
     let flattening_tolerance = tolerance * S::value(0.8);
 
@@ -47,6 +123,40 @@ where
     }
 }
 
+// Counts the segments of the flattened curve, stopping as soon as there are more than `max`.
+pub fn num_flattening_segments<S: Scalar>(curve: &CubicBezierSegment<S>, tolerance: S, max: u32) -> u32 {
+    debug_assert!(tolerance >= S::EPSILON);
+
+    if let Some(collinear_t) = collinear_flattening_t(curve, tolerance) {
+        return collinear_t.len() as u32;
+    }
+
+    let quadratics_tolerance = tolerance * S::value(0.2);
+    let flattening_tolerance = tolerance * S::value(0.8);
+
+    let num_quadratics = num_quadratics(&curve, quadratics_tolerance);
+    // Each quadratic produces at least one segment. This also avoids iterating over a
+    // huge number of quadratics with huge or infinite coordinates.
+    if !(num_quadratics <= S::value(max as f32)) {
+        return max.saturating_add(1);
+    }
+    let step = S::ONE / num_quadratics;
+
+    let mut count: u32 = 0;
+    let mut t0 = S::ZERO;
+    for _ in 0..num_quadratics.to_u32().unwrap_or(u32::MAX) {
+        let t1 = t0 + step;
+        let quadratic = single_curve_approximation(&curve.split_range(t0..t1));
+        count = count.saturating_add(quadratic.num_flattening_segments(flattening_tolerance));
+        if count > max {
+            break;
+        }
+        t0 = t1;
+    }
+
+    count
+}
+
 pub struct Flattened<S: Scalar> {
     curve: CubicBezierSegment<S>,
     current_curve: FlattenedQuadraticSegment<S>,
@@ -54,6 +164,8 @@ pub struct Flattened<S: Scalar> {
     tolerance: S,
     range_step: S,
     range_start: S,
+    // The remaining parameters, in reverse order, if the curve is collinear.
+    collinear_t: Option<ArrayVec<[S; 3]>>,
 }
 
 impl<S: Scalar> Flattened<S> {
@@ -64,6 +176,11 @@ impl<S: Scalar> Flattened<S> {
         let quadratics_tolerance = tolerance * S::value(0.2);
         let flattening_tolerance = tolerance * S::value(0.8);
 
+        let collinear_t = collinear_flattening_t(&curve, tolerance).map(|mut t| {
+            t.reverse();
+            t
+        });
+
         let num_quadratics = num_quadratics(&curve, quadratics_tolerance);
 
         let range_step = S::ONE / num_quadratics;
@@ -83,6 +200,7 @@ impl<S: Scalar> Flattened<S> {
             tolerance: flattening_tolerance,
             range_start: S::ZERO,
             range_step,
+            collinear_t,
         }
     }
 }
@@ -91,6 +209,12 @@ impl<S: Scalar> Iterator for Flattened<S> {
     type Item = Point<S>;
 
     fn next(&mut self) -> Option<Point<S>> {
+        if let Some(ref mut collinear_t) = self.collinear_t {
+            return collinear_t.pop().map(|t| {
+                if t == S::ONE { self.curve.to } else { self.curve.sample(t) }
+            });
+        }
+
         if let Some(t_inner) = self.current_curve.next() {
             let t = self.range_start + t_inner * self.range_step;
             return Some(self.curve.sample(t));
@@ -120,6 +244,10 @@ impl<S: Scalar> Iterator for Flattened<S> {
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
+        if let Some(ref collinear_t) = self.collinear_t {
+            return (collinear_t.len(), Some(collinear_t.len()));
+        }
+
         (self.remaining_sub_curves as usize * self.current_curve.size_hint().0, None)
     }
 }
@@ -360,3 +488,40 @@ fn flatten_with_t() {
         }
     }
 }
+
+#[test]
+fn flatten_collinear_back_and_forth() {
+    // A curve along the x axis that goes past its end point, then back past its start
+    // point before reaching its end point.
+    let curve = CubicBezierSegment {
+        from: Point::new(2.0f32, 1.0),
+        ctrl1: Point::new(32.0, 1.0),
+        ctrl2: Point::new(-28.0, 1.0),
+        to: Point::new(6.0, 1.0),
+    };
+
+    // Extent of the curve, by dense sampling.
+    let mut min_x = std::f32::MAX;
+    let mut max_x = std::f32::MIN;
+    for i in 0..=1000 {
+        let x = curve.sample(i as f32 / 1000.0).x;
+        min_x = min_x.min(x);
+        max_x = max_x.max(x);
+    }
+    assert!(max_x > 6.0 && min_x < 2.0);
+
+    let mut points = Vec::new();
+    curve.for_each_flattened(0.1, &mut |p| points.push(p));
+    let iter_points: Vec<Point<f32>> = curve.flattened(0.1).collect();
+    assert_eq!(points, iter_points);
+
+    assert_eq!(points.len(), 3);
+    assert_eq!(*points.last().unwrap(), curve.to);
+    let flattened_max = points.iter().fold(std::f32::MIN, |m, p| m.max(p.x));
+    let flattened_min = points.iter().fold(std::f32::MAX, |m, p| m.min(p.x));
+    assert!((flattened_max - max_x).abs() < 0.01);
+    assert!((flattened_min - min_x).abs() < 0.01);
+    for p in &points {
+        assert_eq!(p.y, 1.0);
+    }
+}
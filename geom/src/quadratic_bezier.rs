@@ -369,6 +369,17 @@ impl<S: Scalar> QuadraticBezierSegment<S> {
         });
     }
 
+    /// Returns the number of line segments that approximate the curve when flattening it
+    /// with the provided tolerance, without computing them.
+    pub fn num_flattening_segments(&self, tolerance: S) -> u32 {
+        let params = FlatteningParameters::from_curve(self, tolerance);
+        if params.is_point {
+            return 0;
+        }
+
+        params.count.to_u32().unwrap_or(u32::MAX).max(1)
+    }
+
     /// Returns the flattened representation of the curve as an iterator, starting *after* the
     /// current point.
     pub fn flattened(&self, tolerance: S) -> Flattened<S> {
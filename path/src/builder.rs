@@ -78,6 +78,7 @@ use crate::events::PathEvent;
 use crate::geom::{Arc, ArcFlags, CubicBezierSegment, QuadraticBezierSegment, SvgArc};
 use crate::math::*;
 use crate::path_state::PathState;
+use crate::Winding;
 use std::marker::Sized;
 
 pub trait Build {
@@ -154,6 +155,17 @@ pub trait PathBuilder: FlatPathBuilder {
     {
         SvgPathBuilder::new(self)
     }
+
+    /// Returns a builder that replaces curves with implausibly far control points with
+    /// line segments.
+    ///
+    /// See [`ControlPointGuard`](struct.ControlPointGuard.html).
+    fn with_control_point_guard(self, max_ratio: f32) -> ControlPointGuard<Self>
+    where
+        Self: Sized,
+    {
+        ControlPointGuard::new(self, max_ratio)
+    }
 }
 
 /// A path building interface that tries to stay close to SVG's path specification.
@@ -194,6 +206,78 @@ pub fn build_polygon<Builder: FlatPathBuilder>(builder: &mut Builder, points: &[
     builder.close();
 }
 
+/// Adds a closed circle sub-path.
+///
+/// The circle starts and ends at its rightmost point.
+pub fn build_circle<Builder: PathBuilder>(
+    builder: &mut Builder,
+    center: Point,
+    radius: f32,
+    winding: Winding,
+) {
+    build_ellipse(
+        builder,
+        center,
+        vector(radius, radius),
+        Angle::zero(),
+        winding,
+    );
+}
+
+/// Adds a closed ellipse sub-path.
+///
+/// The ellipse starts and ends at the end of its (rotated) x radius.
+pub fn build_ellipse<Builder: PathBuilder>(
+    builder: &mut Builder,
+    center: Point,
+    radii: Vector,
+    x_rotation: Angle,
+    winding: Winding,
+) {
+    build_arc(
+        builder,
+        center,
+        radii,
+        Angle::zero(),
+        Angle::two_pi(),
+        x_rotation,
+        winding,
+    );
+    builder.close();
+}
+
+/// Adds an elliptical arc as a new sub-path, which is left open.
+///
+/// The direction of the arc is determined by `winding` and only the magnitude of
+/// `sweep_angle` is taken into account. The arc is approximated with quadratic
+/// bézier segments.
+pub fn build_arc<Builder: PathBuilder>(
+    builder: &mut Builder,
+    center: Point,
+    radii: Vector,
+    start_angle: Angle,
+    sweep_angle: Angle,
+    x_rotation: Angle,
+    winding: Winding,
+) {
+    let sweep_angle = match winding {
+        Winding::CounterClockwise => Angle::radians(sweep_angle.radians.abs()),
+        Winding::Clockwise => Angle::radians(-sweep_angle.radians.abs()),
+    };
+    let arc = Arc {
+        center,
+        radii,
+        start_angle,
+        sweep_angle,
+        x_rotation,
+    };
+
+    builder.move_to(arc.from());
+    arc.for_each_quadratic_bezier(&mut |curve| {
+        builder.quadratic_bezier_to(curve.ctrl, curve.to);
+    });
+}
+
 /// Implements the Svg building interface on top of a PathBuilder.
 pub struct SvgPathBuilder<Builder: PathBuilder> {
     builder: Builder,
@@ -454,3 +538,90 @@ impl<Builder: FlatPathBuilder> FlatteningBuilder<Builder> {
         self.tolerance = tolerance
     }
 }
+
+/// A builder that guards against curves with control points that are implausibly far
+/// from their endpoints.
+///
+/// Such curves typically come from broken or malicious input data. They can cause
+/// numerical overflows and make flattening produce a huge amount of geometry. A curve is
+/// considered degenerate if one of its control points is further from the closest
+/// endpoint than `max_ratio` times the distance between the endpoints. Degenerate
+/// curves are replaced with a line segment between their endpoints and other commands
+/// are forwarded as is.
+///
+/// Note that curves starting and ending at the same position are always considered
+/// degenerate.
+pub struct ControlPointGuard<Builder> {
+    builder: Builder,
+    max_ratio: f32,
+}
+
+impl<Builder: PathBuilder> ControlPointGuard<Builder> {
+    /// Default ratio between the distance to the control points and the distance
+    /// between the endpoints.
+    pub const DEFAULT_MAX_RATIO: f32 = 1000.0;
+
+    pub fn new(builder: Builder, max_ratio: f32) -> Self {
+        ControlPointGuard { builder, max_ratio }
+    }
+
+    fn is_degenerate(&self, from: Point, ctrl: &[Point], to: Point) -> bool {
+        let max_distance = (to - from).length() * self.max_ratio;
+        ctrl.iter().any(|p| {
+            let d = (*p - from).length().min((*p - to).length());
+            d > max_distance || d.is_nan()
+        })
+    }
+}
+
+impl<Builder: PathBuilder + Build> Build for ControlPointGuard<Builder> {
+    type PathType = Builder::PathType;
+
+    fn build(self) -> Builder::PathType {
+        self.builder.build()
+    }
+
+    fn build_and_reset(&mut self) -> Builder::PathType {
+        self.builder.build_and_reset()
+    }
+}
+
+impl<Builder: PathBuilder> FlatPathBuilder for ControlPointGuard<Builder> {
+    fn move_to(&mut self, to: Point) {
+        self.builder.move_to(to);
+    }
+
+    fn line_to(&mut self, to: Point) {
+        self.builder.line_to(to);
+    }
+
+    fn close(&mut self) {
+        self.builder.close();
+    }
+
+    fn current_position(&self) -> Point {
+        self.builder.current_position()
+    }
+}
+
+impl<Builder: PathBuilder> PathBuilder for ControlPointGuard<Builder> {
+    fn quadratic_bezier_to(&mut self, ctrl: Point, to: Point) {
+        if self.is_degenerate(self.current_position(), &[ctrl], to) {
+            self.builder.line_to(to);
+        } else {
+            self.builder.quadratic_bezier_to(ctrl, to);
+        }
+    }
+
+    fn cubic_bezier_to(&mut self, ctrl1: Point, ctrl2: Point, to: Point) {
+        if self.is_degenerate(self.current_position(), &[ctrl1, ctrl2], to) {
+            self.builder.line_to(to);
+        } else {
+            self.builder.cubic_bezier_to(ctrl1, ctrl2, to);
+        }
+    }
+
+    fn arc(&mut self, center: Point, radii: Vector, sweep_angle: Angle, x_rotation: Angle) {
+        self.builder.arc(center, radii, sweep_angle, x_rotation);
+    }
+}
@@ -40,6 +40,140 @@ mod tests {
         assert!(max_deviation <= tolerance, "Deviation from orignal curve is larger than the allowed tolerance. {} > {}", max_deviation, tolerance);
     }
 
+    #[test]
+    fn test_control_point_guard() {
+        use crate::builder::FlatPathBuilder;
+
+        let mut builder = Path::builder().with_control_point_guard(1000.0);
+        builder.move_to(point(0.0, 0.0));
+        builder.cubic_bezier_to(point(1.0, 1.0), point(2.0, 1.0), point(3.0, 0.0));
+        builder.cubic_bezier_to(point(1.0e6, 1.0e6), point(4.0, 1.0), point(5.0, 0.0));
+        builder.quadratic_bezier_to(point(5.5, -1.0e7), point(6.0, 0.0));
+        builder.close();
+        let path = builder.build();
+
+        assert_eq!(
+            path.iter().collect::<Vec<_>>(),
+            vec![
+                PathEvent::Begin {
+                    at: point(0.0, 0.0)
+                },
+                PathEvent::Cubic {
+                    from: point(0.0, 0.0),
+                    ctrl1: point(1.0, 1.0),
+                    ctrl2: point(2.0, 1.0),
+                    to: point(3.0, 0.0)
+                },
+                PathEvent::Line {
+                    from: point(3.0, 0.0),
+                    to: point(5.0, 0.0)
+                },
+                PathEvent::Line {
+                    from: point(5.0, 0.0),
+                    to: point(6.0, 0.0)
+                },
+                PathEvent::End {
+                    last: point(6.0, 0.0),
+                    first: point(0.0, 0.0),
+                    close: true
+                },
+            ]
+        );
+
+        // Flattening the guarded curve produces a small number of segments.
+        let mut builder = Path::builder()
+            .flattened(0.01)
+            .with_control_point_guard(1000.0);
+        builder.move_to(point(0.0, 0.0));
+        builder.cubic_bezier_to(point(1.0e6, 1.0e6), point(4.0, 1.0), point(5.0, 0.0));
+        let path = builder.build();
+        assert!(path.iter().count() < 10);
+    }
+
+    #[test]
+    fn test_build_circle_winding() {
+        use crate::builder::{build_arc, build_circle, build_ellipse};
+        use crate::iterator::PathIterator;
+        use crate::Winding;
+
+        fn signed_area(path: &Path) -> f32 {
+            let mut area = 0.0;
+            for evt in path.iter().flattened(0.01) {
+                match evt {
+                    PathEvent::Line { from, to }
+                    | PathEvent::End {
+                        last: from,
+                        first: to,
+                        ..
+                    } => {
+                        area += from.to_vector().cross(to.to_vector()) * 0.5;
+                    }
+                    _ => {}
+                }
+            }
+            area
+        }
+
+        let pi = std::f32::consts::PI;
+        for &winding in &[Winding::CounterClockwise, Winding::Clockwise] {
+            let sign = if winding == Winding::Clockwise {
+                -1.0
+            } else {
+                1.0
+            };
+
+            let mut builder = Path::builder();
+            build_circle(&mut builder, point(5.0, 5.0), 2.0, winding);
+            let area = signed_area(&builder.build());
+            assert!(
+                (area - sign * pi * 4.0).abs() < 0.1,
+                "{:?} {}",
+                winding,
+                area
+            );
+
+            let mut builder = Path::builder();
+            build_ellipse(
+                &mut builder,
+                point(1.0, -1.0),
+                vector(3.0, 1.0),
+                Angle::degrees(30.0),
+                winding,
+            );
+            let area = signed_area(&builder.build());
+            assert!(
+                (area - sign * pi * 3.0).abs() < 0.1,
+                "{:?} {}",
+                winding,
+                area
+            );
+
+            // A half disc, closed by the segment between the ends of the arc. The sign
+            // of the sweep angle is ignored.
+            for &sweep in &[pi, -pi] {
+                let mut builder = Path::builder();
+                build_arc(
+                    &mut builder,
+                    point(0.0, 0.0),
+                    vector(1.0, 1.0),
+                    Angle::zero(),
+                    Angle::radians(sweep),
+                    Angle::zero(),
+                    winding,
+                );
+                let area = signed_area(&builder.build());
+                assert!(
+                    (area - sign * pi * 0.5).abs() < 0.05,
+                    "{:?} {}",
+                    winding,
+                    area
+                );
+            }
+        }
+
+        assert_eq!(Winding::default(), Winding::CounterClockwise);
+    }
+
     // Approximate distance from a path to a point
     fn distance_path_point(path: &Path, point: Point) -> f32 {
         let mut smallest_dist = std::f32::INFINITY;
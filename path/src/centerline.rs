@@ -0,0 +1,324 @@
+//! Approximation of the medial axis of filled shapes.
+
+use crate::geom::LineSegment;
+use crate::iterator::PathIterator;
+use crate::math::{point, Point};
+use crate::{Path, PathEvent, PathSlice};
+
+// Upper bound on the number of samples, to keep the cost of small tolerances on large
+// shapes under control.
+const MAX_SAMPLES: f32 = 65536.0;
+
+/// Approximates the centerline of the fill of a path.
+///
+/// The shape is filled with the even-odd fill rule and sampled on a grid whose spacing
+/// is the tolerance, or larger if the shape would need too many samples. The medial axis
+/// is found between neighboring samples whose nearest points on the boundary are on
+/// opposite sides of the shape. The samples of the medial axis are connected into a tree
+/// and its longest branch is returned as an open polyline, simplified within the
+/// tolerance.
+///
+/// Branches of the medial axis going into corners are ignored, so the centerline of a
+/// long rectangle runs through the middle of the rectangle and stops at half of its width
+/// from the ends. If the fill has several disjoint parts, only the longest centerline is
+/// returned. The result is empty if no centerline was found, for example if the shape is
+/// thinner than the sampling grid.
+pub fn centerline(path: &PathSlice, tolerance: f32) -> Path {
+    let mut edges = Vec::new();
+    for evt in path.iter().flattened(tolerance) {
+        match evt {
+            PathEvent::Line { from, to }
+            | PathEvent::End {
+                last: from,
+                first: to,
+                ..
+            } => {
+                if from != to {
+                    edges.push(LineSegment { from, to });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let samples = medial_samples(&edges, tolerance);
+    let spine = longest_branch(&samples.points, samples.step * 2.0);
+
+    let mut builder = Path::builder();
+    if spine.len() > 1 {
+        let mut simplified = Vec::new();
+        simplify(&spine, tolerance, &mut simplified);
+        builder.move_to(simplified[0]);
+        for p in &simplified[1..] {
+            builder.line_to(*p);
+        }
+    }
+
+    builder.build()
+}
+
+struct MedialSamples {
+    points: Vec<Point>,
+    step: f32,
+}
+
+fn medial_samples(edges: &[LineSegment<f32>], tolerance: f32) -> MedialSamples {
+    let mut min = point(std::f32::MAX, std::f32::MAX);
+    let mut max = point(std::f32::MIN, std::f32::MIN);
+    for edge in edges {
+        min = min.min(edge.from).min(edge.to);
+        max = max.max(edge.from).max(edge.to);
+    }
+
+    let size = max - min;
+    let step = tolerance.max((size.x * size.y / MAX_SAMPLES).sqrt());
+    if edges.is_empty() || !(step > 0.0) {
+        return MedialSamples {
+            points: Vec::new(),
+            step: 0.0,
+        };
+    }
+
+    // The nearest point on the boundary of each sample that is inside the shape.
+    let nx = (size.x / step) as usize + 1;
+    let ny = (size.y / step) as usize + 1;
+    let mut nearest: Vec<Option<Point>> = vec![None; nx * ny];
+    let mut crossings = Vec::new();
+    for j in 0..ny {
+        let y = min.y + (j as f32 + 0.5) * step;
+        crossings.clear();
+        for edge in edges {
+            if (edge.from.y <= y) != (edge.to.y <= y) {
+                let t = (y - edge.from.y) / (edge.to.y - edge.from.y);
+                crossings.push(edge.from.x + (edge.to.x - edge.from.x) * t);
+            }
+        }
+
+        for i in 0..nx {
+            let p = point(min.x + (i as f32 + 0.5) * step, y);
+            let inside = crossings.iter().filter(|&&x| x < p.x).count() % 2 == 1;
+            if inside {
+                nearest[j * nx + i] = Some(nearest_point(edges, p));
+            }
+        }
+    }
+
+    // Neighboring samples that are pushed away from the boundary in opposite directions
+    // are on either side of the medial axis.
+    let mut points = Vec::new();
+    for j in 0..ny {
+        for i in 0..nx {
+            let p = point(
+                min.x + (i as f32 + 0.5) * step,
+                min.y + (j as f32 + 0.5) * step,
+            );
+            let np = match nearest[j * nx + i] {
+                Some(np) => np,
+                None => continue,
+            };
+
+            let neighbors = [
+                (i + 1 < nx, j * nx + i + 1, point(p.x + step, p.y)),
+                (j + 1 < ny, (j + 1) * nx + i, point(p.x, p.y + step)),
+            ];
+            for &(valid, idx, q) in &neighbors {
+                let nq = match nearest.get(idx) {
+                    Some(&Some(nq)) if valid => nq,
+                    _ => continue,
+                };
+
+                let a = np - p;
+                let b = nq - q;
+                let opposite = a.dot(b) < -0.5 * a.length() * b.length();
+                if opposite && (np - nq).length() > step {
+                    points.push(p.lerp(q, 0.5));
+                }
+            }
+        }
+    }
+
+    MedialSamples { points, step }
+}
+
+fn nearest_point(edges: &[LineSegment<f32>], p: Point) -> Point {
+    let mut nearest = edges[0].from;
+    let mut min_distance = std::f32::MAX;
+    for edge in edges {
+        let v = edge.to - edge.from;
+        let t = ((p - edge.from).dot(v) / v.square_length())
+            .max(0.0)
+            .min(1.0);
+        let candidate = edge.from + v * t;
+        let distance = (candidate - p).square_length();
+        if distance < min_distance {
+            min_distance = distance;
+            nearest = candidate;
+        }
+    }
+
+    nearest
+}
+
+// Connects the points into a minimum spanning forest without edges longer than
+// `max_link` and returns the longest path in it.
+fn longest_branch(points: &[Point], max_link: f32) -> Vec<Point> {
+    let n = points.len();
+    let mut adjacency: Vec<Vec<usize>> = vec![Vec::new(); n];
+    let mut in_tree = vec![false; n];
+    let mut closest = vec![(std::f32::MAX, 0); n];
+    for _ in 0..n {
+        let mut idx = n;
+        for i in 0..n {
+            if !in_tree[i] && (idx == n || closest[i].0 < closest[idx].0) {
+                idx = i;
+            }
+        }
+
+        in_tree[idx] = true;
+        let (distance, parent) = closest[idx];
+        if distance <= max_link {
+            adjacency[idx].push(parent);
+            adjacency[parent].push(idx);
+        }
+
+        for i in 0..n {
+            let distance = (points[i] - points[idx]).length();
+            if !in_tree[i] && distance < closest[i].0 {
+                closest[i] = (distance, idx);
+            }
+        }
+    }
+
+    // The longest path of a tree goes between the point that is the farthest from any
+    // point and the point that is the farthest from it.
+    let mut visited = vec![false; n];
+    let mut longest = (0.0, Vec::new());
+    for root in 0..n {
+        if visited[root] {
+            continue;
+        }
+
+        let (from, _, _) = farthest(points, &adjacency, root);
+        let (to, length, parents) = farthest(points, &adjacency, from);
+        let mut branch = vec![points[to]];
+        let mut idx = to;
+        while idx != from {
+            idx = parents[idx];
+            branch.push(points[idx]);
+        }
+        for (i, &parent) in parents.iter().enumerate() {
+            if parent != n {
+                visited[i] = true;
+            }
+        }
+
+        if length > longest.0 {
+            longest = (length, branch);
+        }
+    }
+
+    longest.1
+}
+
+// Returns the farthest point from the start in its tree, its distance and the parent
+// of each point of the tree in the walk from the start (`points.len()` for points that
+// are not in the tree).
+fn farthest(points: &[Point], adjacency: &[Vec<usize>], start: usize) -> (usize, f32, Vec<usize>) {
+    let n = points.len();
+    let mut parents = vec![n; n];
+    let mut distances = vec![0.0; n];
+    let mut farthest = start;
+    let mut stack = vec![start];
+    parents[start] = start;
+    while let Some(idx) = stack.pop() {
+        if distances[idx] > distances[farthest] {
+            farthest = idx;
+        }
+        for &next in &adjacency[idx] {
+            if parents[next] == n {
+                parents[next] = idx;
+                distances[next] = distances[idx] + (points[next] - points[idx]).length();
+                stack.push(next);
+            }
+        }
+    }
+
+    (farthest, distances[farthest], parents)
+}
+
+// Ramer-Douglas-Peucker simplification of a polyline.
+fn simplify(points: &[Point], tolerance: f32, output: &mut Vec<Point>) {
+    let segment = LineSegment {
+        from: points[0],
+        to: points[points.len() - 1],
+    };
+    let mut max = 0.0;
+    let mut split = 0;
+    for (i, p) in points.iter().enumerate().skip(1).take(points.len() - 2) {
+        let d = if segment.from == segment.to {
+            (*p - segment.from).length()
+        } else {
+            segment.to_line().distance_to_point(p)
+        };
+        if d > max {
+            max = d;
+            split = i;
+        }
+    }
+
+    if max > tolerance {
+        simplify(&points[..=split], tolerance, output);
+        output.pop();
+        simplify(&points[split..], tolerance, output);
+    } else {
+        output.push(segment.from);
+        output.push(segment.to);
+    }
+}
+
+#[test]
+fn centerline_of_a_long_rectangle() {
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(100.0, 0.0));
+    builder.line_to(point(100.0, 10.0));
+    builder.line_to(point(0.0, 10.0));
+    builder.close();
+    let rectangle = builder.build();
+
+    let tolerance = 0.5;
+    let path = centerline(&rectangle.as_slice(), tolerance);
+
+    let events: Vec<PathEvent> = path.iter().collect();
+    let (start, end) = match (events.first(), events.last()) {
+        (
+            Some(&PathEvent::Begin { at }),
+            Some(&PathEvent::End {
+                last, close: false, ..
+            }),
+        ) => (at.min(last), at.max(last)),
+        _ => panic!("{:?}", events),
+    };
+
+    // A single open sub-path.
+    assert_eq!(
+        events
+            .iter()
+            .filter(|evt| match evt {
+                PathEvent::Begin { .. } => true,
+                _ => false,
+            })
+            .count(),
+        1
+    );
+
+    // Running lengthwise through the middle.
+    assert!(start.x < 10.0, "{:?}", start);
+    assert!(end.x > 90.0, "{:?}", end);
+    for evt in &events {
+        if let PathEvent::Line { to, .. } = evt {
+            assert!((to.y - 5.0).abs() <= tolerance, "{:?}", to);
+        }
+    }
+    assert!((start.y - 5.0).abs() <= tolerance, "{:?}", start);
+}
@@ -57,6 +57,7 @@
 //! ```
 
 use crate::events::{Event, IdEvent, PathEvent};
+use crate::iterator::Reversed;
 use crate::math::Point;
 use crate::{ControlPointId, EndpointId, EventId, Position, PositionStore};
 
@@ -352,6 +353,13 @@ impl<'l, Endpoint, ControlPoint> CommandsPathSlice<'l, Endpoint, ControlPoint> {
             control_points: &self.control_points[..],
         }
     }
+
+    /// Returns an iterator over the events of the path in reverse order.
+    ///
+    /// See [`Reversed`](../iterator/struct.Reversed.html).
+    pub fn reversed_events(&self) -> Reversed<&Endpoint, &ControlPoint> {
+        Reversed::new(self.events())
+    }
 }
 
 impl<'l, Endpoint, ControlPoint> std::ops::Index<EndpointId>
@@ -1092,3 +1100,37 @@ fn next_event() {
     assert_eq!(path.next_event_id_in_path(id), None);
     assert_eq!(path.next_event_id_in_sub_path(id), first);
 }
+
+#[test]
+fn reversed_events() {
+    let endpoints = [
+        Point::new(0.0, 0.0),
+        Point::new(1.0, 0.0),
+        Point::new(1.0, 1.0),
+        Point::new(5.0, 0.0),
+    ];
+    let control_points = [Point::new(2.0, 0.0)];
+
+    let mut builder = PathCommands::builder();
+    builder.move_to(EndpointId(0));
+    builder.line_to(EndpointId(1));
+    builder.quadratic_bezier_to(ControlPointId(0), EndpointId(2));
+    builder.close();
+    builder.move_to(EndpointId(3));
+    builder.line_to(EndpointId(0));
+    let cmds = builder.build();
+
+    let path = cmds.path_slice(&endpoints, &control_points);
+    let reversed: Vec<_> = path.reversed_events().collect();
+    assert_eq!(reversed.len(), 7);
+    assert_eq!(
+        reversed[4],
+        Event::Quadratic {
+            from: &endpoints[2],
+            ctrl: &control_points[0],
+            to: &endpoints[1],
+        }
+    );
+
+    assert!(Reversed::new(reversed.into_iter()).eq(path.events()));
+}
@@ -0,0 +1,97 @@
+//! Subdivide paths so that none of their segments exceed a maximum length.
+
+use crate::iterator::PathIterator;
+use crate::math::Point;
+use crate::{Path, PathEvent, PathSlice};
+
+/// Flattens a path and splits its line segments so that none of them is longer than
+/// `max_len`.
+///
+/// Curves are first approximated with line segments using the provided tolerance.
+/// Long segments are then split into the smallest number of equal parts that are
+/// at most `max_len` long. This is useful before applying non-linear deformations or
+/// projections to the path.
+pub fn densify(path: &PathSlice, max_len: f32, tolerance: f32) -> Path {
+    let mut builder = Path::builder();
+    for evt in path.iter().flattened(tolerance) {
+        match evt {
+            PathEvent::Begin { at } => {
+                builder.move_to(at);
+            }
+            PathEvent::Line { from, to } => {
+                add_intermediate_points(from, to, max_len, &mut builder);
+                builder.line_to(to);
+            }
+            PathEvent::End { last, first, close } => {
+                if close {
+                    add_intermediate_points(last, first, max_len, &mut builder);
+                    builder.close();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    builder.build()
+}
+
+fn add_intermediate_points(from: Point, to: Point, max_len: f32, builder: &mut crate::Builder) {
+    let length = (to - from).length();
+    if !(max_len > 0.0) || length <= max_len {
+        return;
+    }
+
+    let num_segments = (length / max_len).ceil() as u32;
+    for i in 1..num_segments {
+        builder.line_to(from.lerp(to, i as f32 / num_segments as f32));
+    }
+}
+
+#[test]
+fn densify_long_line() {
+    use crate::math::point;
+
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    let path = builder.build();
+
+    let dense = densify(&path.as_slice(), 3.0, 0.1);
+
+    let mut num_lines = 0;
+    for evt in dense.iter() {
+        if let PathEvent::Line { from, to } = evt {
+            num_lines += 1;
+            assert!((to - from).length() <= 3.0 + 0.0001);
+        }
+    }
+
+    // 10 / 3 rounded up.
+    assert_eq!(num_lines, 4);
+
+    // The closing segment of a closed path is densified as well.
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(4.0, 0.0));
+    builder.line_to(point(4.0, 4.0));
+    builder.close();
+    let path = builder.build();
+
+    let dense = densify(&path.as_slice(), 1.0, 0.1);
+    let mut num_segments = 0;
+    for evt in dense.iter() {
+        match evt {
+            PathEvent::Line { from, to } => {
+                num_segments += 1;
+                assert!((to - from).length() <= 1.0 + 0.0001);
+            }
+            PathEvent::End { last, first, .. } => {
+                num_segments += 1;
+                assert!((first - last).length() <= 1.0 + 0.0001);
+            }
+            _ => {}
+        }
+    }
+    // 4 + 4 + ceil(4 * sqrt(2)).
+    assert_eq!(num_segments, 14);
+}
@@ -37,6 +37,74 @@ pub type PathEvent = Event<Point, Point>;
 /// A path event representing endpoints and control points as IDs.
 pub type IdEvent = Event<EndpointId, ControlPointId>;
 
+/// The kind of a path segment.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub enum SegmentKind {
+    Line,
+    Quadratic,
+    Cubic,
+}
+
+/// The endpoint and control point IDs of a path segment.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct IdSegment {
+    pub kind: SegmentKind,
+    pub from: EndpointId,
+    pub to: EndpointId,
+    /// The control points of the segment.
+    ///
+    /// Quadratic bézier curves only use the first one and line segments
+    /// don't use any. Unused control points are set to `ControlPointId::INVALID`.
+    pub ctrl: [ControlPointId; 2],
+}
+
+impl IdSegment {
+    /// Returns the segment corresponding to an edge event, if any.
+    ///
+    /// The `End` event of a closed sub-path produces the closing line segment.
+    pub fn from_event(event: IdEvent) -> Option<Self> {
+        let none = ControlPointId::INVALID;
+        match event {
+            Event::Line { from, to } => Some(IdSegment {
+                kind: SegmentKind::Line,
+                from,
+                to,
+                ctrl: [none, none],
+            }),
+            Event::Quadratic { from, ctrl, to } => Some(IdSegment {
+                kind: SegmentKind::Quadratic,
+                from,
+                to,
+                ctrl: [ctrl, none],
+            }),
+            Event::Cubic {
+                from,
+                ctrl1,
+                ctrl2,
+                to,
+            } => Some(IdSegment {
+                kind: SegmentKind::Cubic,
+                from,
+                to,
+                ctrl: [ctrl1, ctrl2],
+            }),
+            Event::End {
+                last,
+                first,
+                close: true,
+            } => Some(IdSegment {
+                kind: SegmentKind::Line,
+                from: last,
+                to: first,
+                ctrl: [none, none],
+            }),
+            Event::Begin { .. } | Event::End { .. } => None,
+        }
+    }
+}
+
 impl<Ep, Cp> Event<Ep, Cp> {
     pub fn is_edge(&self) -> bool {
         match self {
@@ -0,0 +1,520 @@
+//! Fit cubic bézier curves to sequences of points.
+//!
+//! This implements the algorithm described by Philip J. Schneider in "An Algorithm for
+//! Automatically Fitting Digitized Curves" (Graphics Gems, 1990): a cubic bézier curve
+//! is fitted with least squares using a chord-length parameterization, which is refined
+//! with Newton-Raphson iterations. If the fit is not good enough, the points are split
+//! at the point of maximum error and each half is fitted recursively.
+
+use crate::geom::CubicBezierSegment;
+use crate::math::{Point, Vector};
+use crate::Path;
+
+// Number of Newton-Raphson reparameterization passes before splitting.
+const MAX_ITERATIONS: u32 = 4;
+
+// Cosine of the angle between consecutive segments above which `fit_curve` splits.
+const CORNER_COS_ANGLE: f32 = 0.5;
+
+// Number of samples after which the streaming builder starts fitting curves.
+const MAX_SAMPLES: usize = 32;
+
+/// A path builder that turns a stream of input samples, for example from a pen or a mouse,
+/// into a compact path made of smooth cubic bézier curves.
+///
+/// Curves are fitted to the samples as they come: once enough samples are accumulated,
+/// the curves that are fitted to the first samples are appended to the path and only the
+/// samples of the last curve are kept to continue the fit. The curves are fitted so that
+/// no sample is further than the tolerance from the path and consecutive curves have
+/// continuous tangents.
+///
+/// # Example
+///
+/// ```
+/// # use lyon_path::StreamingFitBuilder;
+/// # use lyon_path::math::point;
+/// let mut builder = StreamingFitBuilder::new(0.5);
+/// for i in 0..100 {
+///     let x = i as f32;
+///     builder.push(point(x, (x * 0.1).sin() * 10.0));
+/// }
+/// let path = builder.finish();
+/// ```
+pub struct StreamingFitBuilder {
+    builder: crate::Builder,
+    samples: Vec<Point>,
+    // The tangent at the end of the last curve appended to the path.
+    start_tangent: Option<Vector>,
+    tolerance: f32,
+    curves: Vec<(CubicBezierSegment<f32>, usize)>,
+}
+
+impl StreamingFitBuilder {
+    /// Constructor.
+    pub fn new(tolerance: f32) -> Self {
+        StreamingFitBuilder {
+            builder: Path::builder(),
+            samples: Vec::new(),
+            start_tangent: None,
+            tolerance,
+            curves: Vec::new(),
+        }
+    }
+
+    /// Adds a sample.
+    pub fn push(&mut self, p: Point) {
+        match self.samples.last() {
+            None => {
+                self.builder.move_to(p);
+            }
+            Some(&last) if last == p => {
+                return;
+            }
+            _ => {}
+        }
+
+        self.samples.push(p);
+
+        if self.samples.len() >= MAX_SAMPLES {
+            self.fit_samples(false);
+        }
+    }
+
+    /// Fits the remaining samples and builds the path.
+    pub fn finish(mut self) -> Path {
+        self.fit_samples(true);
+
+        self.builder.build()
+    }
+
+    fn fit_samples(&mut self, flush: bool) {
+        if self.samples.len() < 2 {
+            return;
+        }
+
+        let start_tangent = self
+            .start_tangent
+            .unwrap_or_else(|| start_tangent(&self.samples));
+        let end_tangent = end_tangent(&self.samples);
+        self.curves.clear();
+        fit_cubics(
+            &self.samples,
+            0,
+            start_tangent,
+            end_tangent,
+            self.tolerance,
+            &mut self.curves,
+        );
+
+        // Keep the samples of the last curve so that it can be refined with the next
+        // samples, unless we are done. A single curve is kept until it accumulates too
+        // many samples.
+        let num_curves = if flush {
+            self.curves.len()
+        } else if self.curves.len() > 1 {
+            self.curves.len() - 1
+        } else if self.samples.len() >= MAX_SAMPLES * 4 {
+            1
+        } else {
+            return;
+        };
+
+        for &(curve, _) in &self.curves[..num_curves] {
+            self.builder
+                .cubic_bezier_to(curve.ctrl1, curve.ctrl2, curve.to);
+        }
+
+        let (last_curve, last_idx) = self.curves[num_curves - 1];
+        self.samples.drain(..last_idx);
+        if last_curve.to != last_curve.ctrl2 {
+            self.start_tangent = Some((last_curve.to - last_curve.ctrl2).normalize());
+        }
+    }
+}
+
+/// Approximates a sequence of points with a path made of cubic bézier curves.
+///
+/// The resulting path goes through the first and last points and no point is further
+/// than `tolerance` from it. The points are split into several curves where the error is
+/// too large and at sharp corners, where the direction between consecutive points changes
+/// by more than 60 degrees. Corners are preserved in the output.
+pub fn fit_curve(points: &[Point], tolerance: f32) -> Path {
+    let mut points = points.to_vec();
+    points.dedup();
+
+    let mut builder = Path::builder();
+    if points.is_empty() {
+        return builder.build();
+    }
+
+    builder.move_to(points[0]);
+
+    let mut curves = Vec::new();
+    let mut start = 0;
+    for i in 1..points.len() {
+        let is_corner = i + 1 < points.len() && {
+            let v0 = (points[i] - points[i - 1]).normalize();
+            let v1 = (points[i + 1] - points[i]).normalize();
+            v0.dot(v1) < CORNER_COS_ANGLE
+        };
+
+        if is_corner || i + 1 == points.len() {
+            let run = &points[start..=i];
+            fit_cubics(
+                run,
+                start,
+                start_tangent(run),
+                end_tangent(run),
+                tolerance,
+                &mut curves,
+            );
+            start = i;
+        }
+    }
+
+    for (curve, _) in curves {
+        builder.cubic_bezier_to(curve.ctrl1, curve.ctrl2, curve.to);
+    }
+
+    builder.build()
+}
+
+/// Fits cubic bézier curves to a sequence of points.
+///
+/// The tangents are unit vectors pointing from the first point towards the next ones and
+/// from the last point towards the previous ones. Each fitted curve is pushed along with
+/// the index of its last point, offset by `first_idx`.
+pub(crate) fn fit_cubics(
+    points: &[Point],
+    first_idx: usize,
+    start_tangent: Vector,
+    end_tangent: Vector,
+    tolerance: f32,
+    output: &mut Vec<(CubicBezierSegment<f32>, usize)>,
+) {
+    let last = points.len() - 1;
+    let (first_point, last_point) = (points[0], points[last]);
+
+    if points.len() == 2 {
+        let d = (last_point - first_point).length() / 3.0;
+        output.push((
+            CubicBezierSegment {
+                from: first_point,
+                ctrl1: first_point + start_tangent * d,
+                ctrl2: last_point + end_tangent * d,
+                to: last_point,
+            },
+            first_idx + last,
+        ));
+        return;
+    }
+
+    let tolerance_sq = tolerance * tolerance;
+    let mut u = chord_length_parameterize(points);
+    let mut curve = generate_bezier(points, &u, start_tangent, end_tangent);
+    let (max_error, mut split) = compute_max_error(points, &curve, &u);
+    if max_error < tolerance_sq {
+        output.push((curve, first_idx + last));
+        return;
+    }
+
+    // If the error is not too large, try to improve the parameterization.
+    if max_error < tolerance_sq * 4.0 {
+        for _ in 0..MAX_ITERATIONS {
+            for (p, t) in points.iter().zip(u.iter_mut()) {
+                *t = newton_raphson_root_find(&curve, *p, *t);
+            }
+            curve = generate_bezier(points, &u, start_tangent, end_tangent);
+            let (error, idx) = compute_max_error(points, &curve, &u);
+            if error < tolerance_sq {
+                output.push((curve, first_idx + last));
+                return;
+            }
+            split = idx;
+        }
+    }
+
+    let mut center_tangent = points[split - 1] - points[split + 1];
+    if center_tangent.square_length() == 0.0 {
+        center_tangent = points[split - 1] - points[split];
+    }
+    let center_tangent = center_tangent.normalize();
+
+    fit_cubics(
+        &points[..=split],
+        first_idx,
+        start_tangent,
+        center_tangent,
+        tolerance,
+        output,
+    );
+    fit_cubics(
+        &points[split..],
+        first_idx + split,
+        -center_tangent,
+        end_tangent,
+        tolerance,
+        output,
+    );
+}
+
+pub(crate) fn start_tangent(points: &[Point]) -> Vector {
+    (points[1] - points[0]).normalize()
+}
+
+pub(crate) fn end_tangent(points: &[Point]) -> Vector {
+    let n = points.len();
+    (points[n - 2] - points[n - 1]).normalize()
+}
+
+fn chord_length_parameterize(points: &[Point]) -> Vec<f32> {
+    let mut u = Vec::with_capacity(points.len());
+    let mut length = 0.0;
+    u.push(0.0);
+    for segment in points.windows(2) {
+        length += (segment[1] - segment[0]).length();
+        u.push(length);
+    }
+
+    for t in &mut u {
+        *t /= length;
+    }
+
+    u
+}
+
+// Least-squares fit of the control points of a curve with fixed endpoints and tangent
+// directions.
+fn generate_bezier(
+    points: &[Point],
+    u: &[f32],
+    start_tangent: Vector,
+    end_tangent: Vector,
+) -> CubicBezierSegment<f32> {
+    let first = points[0];
+    let last = points[points.len() - 1];
+
+    let mut c = [[0.0f32; 2]; 2];
+    let mut x = [0.0f32; 2];
+    for (p, &t) in points.iter().zip(u.iter()) {
+        let mt = 1.0 - t;
+        let b0 = mt * mt * mt;
+        let b1 = 3.0 * t * mt * mt;
+        let b2 = 3.0 * t * t * mt;
+        let b3 = t * t * t;
+        let a1 = start_tangent * b1;
+        let a2 = end_tangent * b2;
+
+        c[0][0] += a1.dot(a1);
+        c[0][1] += a1.dot(a2);
+        c[1][1] += a2.dot(a2);
+
+        let tmp = p.to_vector() - (first.to_vector() * (b0 + b1) + last.to_vector() * (b2 + b3));
+        x[0] += a1.dot(tmp);
+        x[1] += a2.dot(tmp);
+    }
+    c[1][0] = c[0][1];
+
+    let det_c0_c1 = c[0][0] * c[1][1] - c[1][0] * c[0][1];
+    let det_c0_x = c[0][0] * x[1] - c[1][0] * x[0];
+    let det_x_c1 = x[0] * c[1][1] - x[1] * c[0][1];
+
+    let (mut alpha_l, mut alpha_r) = if det_c0_c1 == 0.0 {
+        (0.0, 0.0)
+    } else {
+        (det_x_c1 / det_c0_c1, det_c0_x / det_c0_c1)
+    };
+
+    // Fall back to a heuristic if the least squares solution is degenerate.
+    let segment_length = (last - first).length();
+    let epsilon = 1.0e-6 * segment_length;
+    if alpha_l < epsilon || alpha_r < epsilon {
+        alpha_l = segment_length / 3.0;
+        alpha_r = alpha_l;
+    }
+
+    CubicBezierSegment {
+        from: first,
+        ctrl1: first + start_tangent * alpha_l,
+        ctrl2: last + end_tangent * alpha_r,
+        to: last,
+    }
+}
+
+// Returns the maximum squared distance between the points and the curve, and the
+// index of the corresponding point.
+fn compute_max_error(points: &[Point], curve: &CubicBezierSegment<f32>, u: &[f32]) -> (f32, usize) {
+    let mut max = 0.0;
+    let mut split = points.len() / 2;
+    for i in 1..(points.len() - 1) {
+        let d = (curve.sample(u[i]) - points[i]).square_length();
+        if d >= max {
+            max = d;
+            split = i;
+        }
+    }
+
+    (max, split)
+}
+
+// Improves the parameter of the point on the curve that is the closest to `p`.
+fn newton_raphson_root_find(curve: &CubicBezierSegment<f32>, p: Point, t: f32) -> f32 {
+    let d = curve.sample(t) - p;
+    let d1 = curve.derivative(t);
+    // Second derivative.
+    let mt = 1.0 - t;
+    let d2 = ((curve.ctrl2 - curve.ctrl1 * 2.0 + curve.from.to_vector()) * mt
+        + (curve.to - curve.ctrl2 * 2.0 + curve.ctrl1.to_vector()) * t)
+        * 6.0;
+
+    let numerator = d.dot(d1);
+    let denominator = d1.square_length() + d.dot(d2);
+    if denominator == 0.0 {
+        return t;
+    }
+
+    t - numerator / denominator
+}
+
+#[test]
+fn streaming_fit_arc() {
+    use crate::iterator::PathIterator;
+    use crate::math::point;
+    use crate::PathEvent;
+
+    let radius = 100.0;
+    let tolerance = 0.5;
+    let samples: Vec<Point> = (0..=300)
+        .map(|i| {
+            let angle = std::f32::consts::PI * i as f32 / 300.0;
+            point(angle.cos() * radius, angle.sin() * radius)
+        })
+        .collect();
+
+    let mut builder = StreamingFitBuilder::new(tolerance);
+    for &p in &samples {
+        builder.push(p);
+    }
+    let path = builder.finish();
+
+    let mut num_curves = 0;
+    let mut polyline = Vec::new();
+    for evt in path.iter() {
+        if let PathEvent::Cubic { .. } = evt {
+            num_curves += 1;
+        }
+    }
+    for evt in path.iter().flattened(0.01) {
+        match evt {
+            PathEvent::Begin { at } => polyline.push(at),
+            PathEvent::Line { to, .. } => polyline.push(to),
+            _ => {}
+        }
+    }
+
+    assert_eq!(polyline[0], samples[0]);
+    assert_eq!(*polyline.last().unwrap(), *samples.last().unwrap());
+    // A half circle doesn't need many curves.
+    assert!(num_curves <= 4, "{}", num_curves);
+
+    // The fitted curve stays close to the arc.
+    for p in &polyline {
+        let error = ((*p - point(0.0, 0.0)).length() - radius).abs();
+        assert!(error < tolerance * 1.5, "{:?} {}", p, error);
+    }
+
+    // And all of the samples are close to the fitted curve.
+    let distance_to_segment = |p: Point, a: Point, b: Point| {
+        let ab = b - a;
+        let t = ((p - a).dot(ab) / ab.square_length()).max(0.0).min(1.0);
+        (a + ab * t - p).length()
+    };
+    for p in &samples {
+        let d = polyline
+            .windows(2)
+            .map(|segment| distance_to_segment(*p, segment[0], segment[1]))
+            .fold(std::f32::MAX, f32::min);
+        assert!(d < tolerance * 1.5, "{:?} {}", p, d);
+    }
+}
+
+#[test]
+fn fit_curve_cubic() {
+    use crate::math::point;
+    use crate::PathEvent;
+
+    let cubic = CubicBezierSegment {
+        from: point(0.0, 0.0),
+        ctrl1: point(30.0, 60.0),
+        ctrl2: point(70.0, -20.0),
+        to: point(100.0, 40.0),
+    };
+    let points: Vec<Point> = (0..=100).map(|i| cubic.sample(i as f32 / 100.0)).collect();
+
+    let path = fit_curve(&points, 0.2);
+
+    let mut curves = Vec::new();
+    for evt in path.iter() {
+        if let PathEvent::Cubic {
+            from,
+            ctrl1,
+            ctrl2,
+            to,
+        } = evt
+        {
+            curves.push(CubicBezierSegment {
+                from,
+                ctrl1,
+                ctrl2,
+                to,
+            });
+        }
+    }
+
+    // The points come from a single cubic curve and are not evenly spaced, the fit
+    // shouldn't need many more curves.
+    assert!(!curves.is_empty() && curves.len() <= 5, "{}", curves.len());
+    assert_eq!(curves[0].from, cubic.from);
+    assert_eq!(curves.last().unwrap().to, cubic.to);
+
+    // Each point of the fitted curves is close to the original curve.
+    let reference: Vec<Point> = (0..=2000)
+        .map(|i| cubic.sample(i as f32 / 2000.0))
+        .collect();
+    for curve in &curves {
+        for i in 0..=50 {
+            let p = curve.sample(i as f32 / 50.0);
+            let d = reference
+                .iter()
+                .map(|q| (*q - p).length())
+                .fold(std::f32::MAX, f32::min);
+            assert!(d < 0.25, "{:?} {}", p, d);
+        }
+    }
+}
+
+#[test]
+fn fit_curve_corner() {
+    use crate::math::point;
+    use crate::PathEvent;
+
+    // Two straight legs joined by a right angle.
+    let mut points = Vec::new();
+    for i in 0..=10 {
+        points.push(point(i as f32, 0.0));
+    }
+    for i in 1..=10 {
+        points.push(point(10.0, i as f32));
+    }
+
+    let path = fit_curve(&points, 0.01);
+    let endpoints: Vec<Point> = path
+        .iter()
+        .filter_map(|evt| match evt {
+            PathEvent::Cubic { to, .. } => Some(to),
+            _ => None,
+        })
+        .collect();
+
+    assert_eq!(endpoints, vec![point(10.0, 0.0), point(10.0, 10.0)]);
+}
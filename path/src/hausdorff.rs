@@ -0,0 +1,167 @@
+//! Hausdorff distance between two paths.
+
+use crate::geom::LineSegment;
+use crate::iterator::PathIterator;
+use crate::math::Point;
+use crate::{PathEvent, PathSlice};
+
+/// Computes the Hausdorff distance between two paths.
+///
+/// This is the largest distance from a point of one path to the closest point of the
+/// other path, in both directions. It is useful to check that an approximation of a
+/// path, for example a simplified version of it, stays within a given distance of the
+/// original.
+///
+/// Curves are flattened with the provided tolerance, and the edges of each path are
+/// sampled at intervals of at most `tolerance`, so the result is approximate within
+/// the tolerance. Only the outlines are considered, the filled areas are not.
+pub fn hausdorff_distance(a: &PathSlice, b: &PathSlice, tolerance: f32) -> f32 {
+    let a = segments(a, tolerance);
+    let b = segments(b, tolerance);
+
+    directed_distance(&a, &b, tolerance).max(directed_distance(&b, &a, tolerance))
+}
+
+// The largest distance from a point of `a` to `b`.
+fn directed_distance(a: &[LineSegment<f32>], b: &[LineSegment<f32>], tolerance: f32) -> f32 {
+    if a.is_empty() || b.is_empty() {
+        return if a.is_empty() && b.is_empty() {
+            0.0
+        } else {
+            std::f32::INFINITY
+        };
+    }
+
+    let distance_to_b = |p: Point| {
+        b.iter()
+            .map(|segment| square_distance_to_segment(p, segment))
+            .fold(std::f32::INFINITY, f32::min)
+    };
+
+    let mut max = 0.0f32;
+    for segment in a {
+        let num_samples = (segment.length() / tolerance).ceil().max(1.0) as u32;
+        for i in 0..=num_samples {
+            let p = segment.sample(i as f32 / num_samples as f32);
+            max = max.max(distance_to_b(p));
+        }
+    }
+
+    max.sqrt()
+}
+
+fn square_distance_to_segment(p: Point, segment: &LineSegment<f32>) -> f32 {
+    let v = segment.to - segment.from;
+    let square_length = v.square_length();
+    let t = if square_length > 0.0 {
+        ((p - segment.from).dot(v) / square_length)
+            .max(0.0)
+            .min(1.0)
+    } else {
+        0.0
+    };
+
+    (segment.sample(t) - p).square_length()
+}
+
+fn segments(path: &PathSlice, tolerance: f32) -> Vec<LineSegment<f32>> {
+    let mut segments = Vec::new();
+    for evt in path.iter().flattened(tolerance) {
+        match evt {
+            PathEvent::Begin { at } => {
+                // Make sure isolated points are taken into account.
+                segments.push(LineSegment { from: at, to: at });
+            }
+            PathEvent::Line { from, to } => {
+                segments.push(LineSegment { from, to });
+            }
+            PathEvent::End {
+                last,
+                first,
+                close: true,
+            } => {
+                segments.push(LineSegment {
+                    from: last,
+                    to: first,
+                });
+            }
+            _ => {}
+        }
+    }
+
+    segments
+}
+
+#[test]
+fn hausdorff_distance_simplified() {
+    use crate::math::point;
+    use crate::Path;
+
+    // Ramer-Douglas-Peucker simplification of a polyline.
+    fn simplify(points: &[Point], epsilon: f32, output: &mut Vec<Point>) {
+        let segment = LineSegment {
+            from: points[0],
+            to: points[points.len() - 1],
+        };
+        let mut max = 0.0;
+        let mut split = 0;
+        for (i, p) in points.iter().enumerate().skip(1).take(points.len() - 2) {
+            let d = segment.to_line().distance_to_point(p);
+            if d > max {
+                max = d;
+                split = i;
+            }
+        }
+
+        if max > epsilon {
+            simplify(&points[..=split], epsilon, output);
+            output.pop();
+            simplify(&points[split..], epsilon, output);
+        } else {
+            output.push(segment.from);
+            output.push(segment.to);
+        }
+    }
+
+    let points: Vec<Point> = (0..=200)
+        .map(|i| {
+            let x = i as f32 * 0.1;
+            point(x, x.sin() * 3.0 + (x * 3.0).cos() * 0.2)
+        })
+        .collect();
+
+    let epsilon = 0.1;
+    let mut simplified = Vec::new();
+    simplify(&points, epsilon, &mut simplified);
+    assert!(simplified.len() < points.len() / 2);
+
+    let to_path = |points: &[Point]| {
+        let mut builder = Path::builder();
+        builder.move_to(points[0]);
+        for p in &points[1..] {
+            builder.line_to(*p);
+        }
+        builder.build()
+    };
+    let original = to_path(&points);
+    let simplified = to_path(&simplified);
+
+    let d = hausdorff_distance(&original.as_slice(), &simplified.as_slice(), 0.01);
+    assert!(d > 0.0);
+    assert!(d <= epsilon + 0.01, "{}", d);
+
+    let d = hausdorff_distance(&original.as_slice(), &original.as_slice(), 0.01);
+    assert!(d < 0.0001, "{}", d);
+
+    // A translated copy is exactly as far as the translation.
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    let a = builder.build();
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 2.0));
+    builder.line_to(point(10.0, 2.0));
+    let b = builder.build();
+    let d = hausdorff_distance(&a.as_slice(), &b.as_slice(), 0.01);
+    assert!((d - 2.0).abs() < 0.0001, "{}", d);
+}
@@ -0,0 +1,256 @@
+//! Path construction from an implicit function.
+
+use crate::math::{point, Point, Rect};
+use crate::Path;
+
+/// Builds the iso-contour of a scalar field using the marching squares algorithm.
+///
+/// The field is sampled on a regular grid of `resolution.0` by `resolution.1` cells
+/// covering `bounds`. The resulting path follows the locations where the linearly
+/// interpolated samples are equal to `iso`.
+///
+/// Contours that are entirely contained in the bounds produce closed sub-paths, while
+/// contours that cross the edge of the bounds produce open sub-paths. All contours are
+/// oriented consistently, with the area where the field is greater than `iso` on the
+/// same side.
+///
+/// When the four corners of a cell alternate between values above and below `iso`
+/// (saddle point), the ambiguity is resolved by sampling the average of the four corners:
+/// if it is above `iso`, the corners above are connected to each other, otherwise the
+/// corners below are.
+pub fn iso_contour(
+    sampler: impl Fn(Point) -> f32,
+    bounds: &Rect,
+    resolution: (u32, u32),
+    iso: f32,
+) -> Path {
+    let mut builder = Path::builder();
+
+    let nx = resolution.0 as usize;
+    let ny = resolution.1 as usize;
+    if nx == 0 || ny == 0 {
+        return builder.build();
+    }
+
+    let step_x = bounds.size.width / nx as f32;
+    let step_y = bounds.size.height / ny as f32;
+    let grid_point = |i: usize, j: usize| {
+        point(
+            bounds.origin.x + i as f32 * step_x,
+            bounds.origin.y + j as f32 * step_y,
+        )
+    };
+
+    let mut samples = Vec::with_capacity((nx + 1) * (ny + 1));
+    for j in 0..(ny + 1) {
+        for i in 0..(nx + 1) {
+            samples.push(sampler(grid_point(i, j)));
+        }
+    }
+    let sample = |i: usize, j: usize| samples[j * (nx + 1) + i];
+
+    // Edges of the grid are identified by their index: horizontal edges first,
+    // then vertical edges.
+    let num_horizontal_edges = nx * (ny + 1);
+    let num_edges = num_horizontal_edges + (nx + 1) * ny;
+    let horizontal_edge = |i: usize, j: usize| j * nx + i;
+    let vertical_edge = |i: usize, j: usize| num_horizontal_edges + j * (nx + 1) + i;
+
+    let edge_position = |edge: usize| {
+        let (i0, j0, i1, j1) = if edge < num_horizontal_edges {
+            let (i, j) = (edge % nx, edge / nx);
+            (i, j, i + 1, j)
+        } else {
+            let edge = edge - num_horizontal_edges;
+            let (i, j) = (edge % (nx + 1), edge / (nx + 1));
+            (i, j, i, j + 1)
+        };
+        let a = sample(i0, j0);
+        let b = sample(i1, j1);
+        let t = if a == b { 0.5 } else { (iso - a) / (b - a) };
+        grid_point(i0, j0).lerp(grid_point(i1, j1), t.max(0.0).min(1.0))
+    };
+
+    // Each segment goes from an entry edge to an exit edge, in the order the
+    // boundary of its cell is traversed. This way segments of adjacent cells
+    // are chained head to tail.
+    let mut segments: Vec<(usize, usize)> = Vec::new();
+    for j in 0..ny {
+        for i in 0..nx {
+            let corners = [(i, j), (i + 1, j), (i + 1, j + 1), (i, j + 1)];
+            let edges = [
+                horizontal_edge(i, j),
+                vertical_edge(i + 1, j),
+                horizontal_edge(i, j + 1),
+                vertical_edge(i, j),
+            ];
+
+            // (edge, is_entry) for each crossing along the boundary of the cell.
+            let mut crossings = [(0, false); 4];
+            let mut num_crossings = 0;
+            for k in 0..4 {
+                let (ia, ja) = corners[k];
+                let (ib, jb) = corners[(k + 1) % 4];
+                let above_a = sample(ia, ja) > iso;
+                let above_b = sample(ib, jb) > iso;
+                if above_a != above_b {
+                    crossings[num_crossings] = (edges[k], above_b);
+                    num_crossings += 1;
+                }
+            }
+
+            match num_crossings {
+                2 => {
+                    if crossings[0].1 {
+                        segments.push((crossings[0].0, crossings[1].0));
+                    } else {
+                        segments.push((crossings[1].0, crossings[0].0));
+                    }
+                }
+                4 => {
+                    // Saddle point.
+                    let center =
+                        (sample(i, j) + sample(i + 1, j) + sample(i + 1, j + 1) + sample(i, j + 1))
+                            * 0.25;
+                    // Rotate the crossings so that the first one is an entry.
+                    if !crossings[0].1 {
+                        crossings.rotate_left(1);
+                    }
+                    if center > iso {
+                        // Cut off the areas below.
+                        segments.push((crossings[2].0, crossings[1].0));
+                        segments.push((crossings[0].0, crossings[3].0));
+                    } else {
+                        // Cut off the areas above.
+                        segments.push((crossings[0].0, crossings[1].0));
+                        segments.push((crossings[2].0, crossings[3].0));
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let none = std::usize::MAX;
+    let mut segment_from_edge = vec![none; num_edges];
+    let mut has_incoming = vec![false; num_edges];
+    for (idx, &(from, to)) in segments.iter().enumerate() {
+        segment_from_edge[from] = idx;
+        has_incoming[to] = true;
+    }
+
+    let mut visited = vec![false; segments.len()];
+
+    // Open contours start at the boundary of the grid, trace them first.
+    for start in 0..segments.len() {
+        if has_incoming[segments[start].0] {
+            continue;
+        }
+
+        builder.move_to(edge_position(segments[start].0));
+        let mut idx = start;
+        while idx != none && !visited[idx] {
+            visited[idx] = true;
+            let to = segments[idx].1;
+            builder.line_to(edge_position(to));
+            idx = segment_from_edge[to];
+        }
+    }
+
+    // The remaining segments form closed contours.
+    for start in 0..segments.len() {
+        if visited[start] {
+            continue;
+        }
+
+        builder.move_to(edge_position(segments[start].0));
+        let mut idx = start;
+        loop {
+            visited[idx] = true;
+            let to = segments[idx].1;
+            idx = segment_from_edge[to];
+            if idx == none || visited[idx] {
+                break;
+            }
+            builder.line_to(edge_position(to));
+        }
+        builder.close();
+    }
+
+    builder.build()
+}
+
+#[test]
+fn iso_contour_circle() {
+    use crate::math::{rect, Vector};
+    use crate::PathEvent;
+
+    let center = point(1.0, -2.0);
+    let path = iso_contour(
+        |p| (p - center).length(),
+        &rect(-9.0, -12.0, 20.0, 20.0),
+        (40, 40),
+        5.0,
+    );
+
+    let mut num_subpaths = 0;
+    let mut num_points = 0;
+    let mut closed = false;
+    let mut area = 0.0;
+    for evt in &path {
+        match evt {
+            PathEvent::Begin { at } => {
+                num_subpaths += 1;
+                num_points += 1;
+                assert!(((at - center).length() - 5.0).abs() < 0.05);
+            }
+            PathEvent::Line { from, to } => {
+                num_points += 1;
+                assert!(((to - center).length() - 5.0).abs() < 0.05);
+                area += (from - center).cross(to - center) * 0.5;
+            }
+            PathEvent::End { last, first, close } => {
+                closed = close;
+                let (a, b): (Vector, Vector) = (last - center, first - center);
+                area += a.cross(b) * 0.5;
+            }
+            _ => panic!("unexpected event"),
+        }
+    }
+
+    assert_eq!(num_subpaths, 1);
+    assert!(closed);
+    assert!(num_points > 20);
+    let expected = std::f32::consts::PI * 25.0;
+    assert!((area.abs() - expected).abs() < expected * 0.02);
+}
+
+#[test]
+fn iso_contour_open() {
+    // A half-plane crossing the bounds produces a single open contour.
+    let path = iso_contour(
+        |p| p.x,
+        &crate::math::rect(0.0, 0.0, 10.0, 10.0),
+        (5, 5),
+        3.0,
+    );
+
+    let mut num_subpaths = 0;
+    for evt in &path {
+        match evt {
+            crate::PathEvent::Begin { at } => {
+                num_subpaths += 1;
+                assert!((at.x - 3.0).abs() < 0.0001);
+            }
+            crate::PathEvent::Line { to, .. } => {
+                assert!((to.x - 3.0).abs() < 0.0001);
+            }
+            crate::PathEvent::End { close, .. } => {
+                assert!(!close);
+            }
+            _ => panic!("unexpected event"),
+        }
+    }
+
+    assert_eq!(num_subpaths, 1);
+}
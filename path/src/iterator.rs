@@ -97,7 +97,7 @@ use crate::geom::{
     QuadraticBezierSegment,
 };
 use crate::math::*;
-use crate::PathEvent;
+use crate::{Event, PathEvent};
 
 /// An extension trait for `PathEvent` iterators.
 pub trait PathIterator: Iterator<Item = PathEvent> + Sized {
@@ -115,6 +115,13 @@ pub trait PathIterator: Iterator<Item = PathEvent> + Sized {
     fn bezier_segments(self) -> BezierSegments<Self> {
         BezierSegments { iter: self }
     }
+
+    /// Returns an iterator over the events of the path in reverse order.
+    ///
+    /// See [`Reversed`](struct.Reversed.html).
+    fn reversed(self) -> Reversed<Point, Point> {
+        Reversed::new(self)
+    }
 }
 
 impl<Iter> PathIterator for Iter where Iter: Iterator<Item = PathEvent> {}
@@ -366,6 +373,79 @@ where
     }
 }
 
+/// An iterator over the events of a path in reverse order.
+///
+/// Sub-paths are produced in the reverse order and each of them is walked backwards:
+/// the `Begin` event is at the last endpoint of the original sub-path, the endpoints
+/// and the control points of each edge are swapped and the `End` event keeps the
+/// `close` flag of the original sub-path. Reversing twice produces the original events.
+///
+/// The events are buffered when the iterator is created.
+pub struct Reversed<Endpoint, ControlPoint> {
+    events: std::vec::IntoIter<Event<Endpoint, ControlPoint>>,
+}
+
+impl<Endpoint: Clone, ControlPoint> Reversed<Endpoint, ControlPoint> {
+    /// Create the iterator.
+    pub fn new(events: impl IntoIterator<Item = Event<Endpoint, ControlPoint>>) -> Self {
+        let events: Vec<_> = events.into_iter().collect();
+        let mut reversed = Vec::with_capacity(events.len());
+        // The first endpoint and close flag of the reversed sub-path.
+        let mut sub_path_start = None;
+        for evt in events.into_iter().rev() {
+            reversed.push(match evt {
+                Event::End { last, close, .. } => {
+                    sub_path_start = Some((last.clone(), close));
+                    Event::Begin { at: last }
+                }
+                Event::Line { from, to } => Event::Line { from: to, to: from },
+                Event::Quadratic { from, ctrl, to } => Event::Quadratic {
+                    from: to,
+                    ctrl,
+                    to: from,
+                },
+                Event::Cubic {
+                    from,
+                    ctrl1,
+                    ctrl2,
+                    to,
+                } => Event::Cubic {
+                    from: to,
+                    ctrl1: ctrl2,
+                    ctrl2: ctrl1,
+                    to: from,
+                },
+                Event::Begin { at } => {
+                    let (first, close) = match sub_path_start.take() {
+                        Some(start) => start,
+                        None => (at.clone(), false),
+                    };
+                    Event::End {
+                        last: at,
+                        first,
+                        close,
+                    }
+                }
+            });
+        }
+
+        Reversed {
+            events: reversed.into_iter(),
+        }
+    }
+}
+
+impl<Endpoint, ControlPoint> Iterator for Reversed<Endpoint, ControlPoint> {
+    type Item = Event<Endpoint, ControlPoint>;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.events.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.events.size_hint()
+    }
+}
+
 #[test]
 fn test_from_polyline_open() {
     let points = &[
@@ -463,3 +543,66 @@ fn test_from_polyline_closed() {
     );
     assert_eq!(evts.next(), None);
 }
+
+#[test]
+fn test_reversed() {
+    use crate::Path;
+
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(1.0, 0.0));
+    builder.quadratic_bezier_to(point(2.0, 0.0), point(2.0, 1.0));
+    builder.cubic_bezier_to(point(2.0, 2.0), point(1.0, 2.0), point(0.0, 2.0));
+    builder.close();
+    builder.move_to(point(10.0, 0.0));
+    builder.line_to(point(11.0, 0.0));
+    let path = builder.build();
+
+    let reversed: Vec<PathEvent> = path.iter().reversed().collect();
+    assert_eq!(
+        &reversed[..],
+        &[
+            PathEvent::Begin {
+                at: point(11.0, 0.0)
+            },
+            PathEvent::Line {
+                from: point(11.0, 0.0),
+                to: point(10.0, 0.0)
+            },
+            PathEvent::End {
+                last: point(10.0, 0.0),
+                first: point(11.0, 0.0),
+                close: false
+            },
+            PathEvent::Begin {
+                at: point(0.0, 2.0)
+            },
+            PathEvent::Cubic {
+                from: point(0.0, 2.0),
+                ctrl1: point(1.0, 2.0),
+                ctrl2: point(2.0, 2.0),
+                to: point(2.0, 1.0)
+            },
+            PathEvent::Quadratic {
+                from: point(2.0, 1.0),
+                ctrl: point(2.0, 0.0),
+                to: point(1.0, 0.0)
+            },
+            PathEvent::Line {
+                from: point(1.0, 0.0),
+                to: point(0.0, 0.0)
+            },
+            PathEvent::End {
+                last: point(0.0, 0.0),
+                first: point(0.0, 2.0),
+                close: true
+            },
+        ][..]
+    );
+
+    // Same as reversing the path itself.
+    assert!(path.reversed().iter().eq(path.iter().reversed()));
+
+    // Round-trip.
+    assert!(path.iter().reversed().reversed().eq(path.iter()));
+}
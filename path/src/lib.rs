@@ -45,19 +45,36 @@ pub use lyon_geom as geom;
 pub extern crate serde;
 
 pub mod builder;
+mod centerline;
 pub mod commands;
+mod densify;
 mod events;
+mod fit;
+mod hausdorff;
+mod iso_contour;
 pub mod iterator;
+mod overlap;
 mod path;
 mod path_state;
 mod builder_tests;
 pub mod polygon;
+mod rectangles;
+mod simplify;
+mod svg_points;
 
+pub use crate::centerline::centerline;
+pub use crate::densify::densify;
 pub use crate::events::*;
+pub use crate::iso_contour::iso_contour;
+pub use crate::fit::{fit_curve, StreamingFitBuilder};
 pub use crate::geom::math;
 pub use crate::geom::ArcFlags;
+pub use crate::hausdorff::hausdorff_distance;
+pub use crate::overlap::overlap_area;
 pub use crate::path::*;
 pub use crate::path_state::*;
+pub use crate::rectangles::rectangle_decomposition;
+pub use crate::simplify::flatten_optimized;
 
 use math::Point;
 use std::fmt;
@@ -88,6 +105,24 @@ impl FillRule {
     }
 }
 
+/// The direction in which a closed shape is traversed.
+///
+/// The directions are defined in a coordinate system where the y axis points up, so
+/// counter-clockwise shapes have a positive signed area. They appear mirrored in
+/// coordinate systems where the y axis points down.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub enum Winding {
+    CounterClockwise,
+    Clockwise,
+}
+
+impl Default for Winding {
+    fn default() -> Self {
+        Winding::CounterClockwise
+    }
+}
+
 /// ID of a control point in a path.
 #[derive(Copy, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
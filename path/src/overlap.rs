@@ -0,0 +1,157 @@
+//! Area of the intersection of two paths.
+
+use crate::geom::LineSegment;
+use crate::iterator::PathIterator;
+use crate::math::Point;
+use crate::{PathEvent, PathSlice};
+
+struct Edge {
+    segment: LineSegment<f32>,
+    // Whether the edge belongs to the first path.
+    is_a: bool,
+}
+
+/// Computes the area of the intersection of the fills of two paths.
+///
+/// The paths are filled with the even-odd fill rule, and curves are flattened with the
+/// provided tolerance. Other fill rules are not supported. Edges with NaN coordinates
+/// are ignored.
+///
+/// The intersection path is not built: the plane is cut into horizontal slabs at each
+/// vertex and edge intersection, so that the area of each slab that is inside of both
+/// shapes is a sum of trapezoids. Every pair of edges is tested for intersections and
+/// every edge is visited for each slab, so the cost is quadratic in the number of edges
+/// of the flattened paths. This is meant for simple shapes rather than complex paths.
+pub fn overlap_area(a: &PathSlice, b: &PathSlice, tolerance: f32) -> f32 {
+    let mut edges = Vec::new();
+    add_edges(a, true, tolerance, &mut edges);
+    add_edges(b, false, tolerance, &mut edges);
+
+    let mut ys = Vec::with_capacity(edges.len() * 2);
+    for edge in &edges {
+        ys.push(edge.segment.from.y);
+        ys.push(edge.segment.to.y);
+    }
+    for i in 0..edges.len() {
+        for j in (i + 1)..edges.len() {
+            if let Some(p) = edges[i].segment.intersection(&edges[j].segment) {
+                ys.push(p.y);
+            }
+        }
+    }
+    ys.retain(|y| !y.is_nan());
+    ys.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    ys.dedup();
+
+    let mut area = 0.0;
+    let mut crossings: Vec<(f32, bool)> = Vec::new();
+    for slab in ys.windows(2) {
+        let (y0, y1) = (slab[0], slab[1]);
+        let height = y1 - y0;
+        if height <= 0.0 {
+            continue;
+        }
+
+        // The width of the slab inside both shapes varies linearly with y, so its
+        // area is the width at the middle times the height.
+        let y = (y0 + y1) * 0.5;
+        crossings.clear();
+        for edge in &edges {
+            let s = &edge.segment;
+            let (min_y, max_y) = if s.from.y < s.to.y {
+                (s.from.y, s.to.y)
+            } else {
+                (s.to.y, s.from.y)
+            };
+            if y < min_y || y > max_y {
+                continue;
+            }
+            let t = (y - s.from.y) / (s.to.y - s.from.y);
+            let x = s.from.x + (s.to.x - s.from.x) * t;
+            if !x.is_nan() {
+                crossings.push((x, edge.is_a));
+            }
+        }
+        crossings.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+
+        let mut in_a = false;
+        let mut in_b = false;
+        for i in 0..crossings.len() {
+            if crossings[i].1 {
+                in_a = !in_a;
+            } else {
+                in_b = !in_b;
+            }
+            if in_a && in_b && i + 1 < crossings.len() {
+                area += (crossings[i + 1].0 - crossings[i].0) * height;
+            }
+        }
+    }
+
+    area
+}
+
+fn add_edges(path: &PathSlice, is_a: bool, tolerance: f32, edges: &mut Vec<Edge>) {
+    let is_nan = |p: Point| p.x.is_nan() || p.y.is_nan();
+    let mut add = |from: Point, to: Point| {
+        if from.y != to.y && !is_nan(from) && !is_nan(to) {
+            edges.push(Edge {
+                segment: LineSegment { from, to },
+                is_a,
+            });
+        }
+    };
+
+    for evt in path.iter().flattened(tolerance) {
+        match evt {
+            PathEvent::Line { from, to } => add(from, to),
+            PathEvent::End { last, first, .. } => add(last, first),
+            _ => {}
+        }
+    }
+}
+
+#[test]
+fn overlap_area_squares() {
+    use crate::math::{point, Transform};
+    use crate::Path;
+
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(1.0, 0.0));
+    builder.line_to(point(1.0, 1.0));
+    builder.line_to(point(0.0, 1.0));
+    builder.close();
+    let square = builder.build();
+
+    let translated = |v| square.transformed(&Transform::create_translation(v, v));
+
+    let offset = translated(0.5);
+    let area = overlap_area(&square.as_slice(), &offset.as_slice(), 0.01);
+    assert!((area - 0.25).abs() < 0.0001, "{}", area);
+
+    let area = overlap_area(&square.as_slice(), &square.as_slice(), 0.01);
+    assert!((area - 1.0).abs() < 0.0001, "{}", area);
+
+    let disjoint = translated(2.0);
+    assert_eq!(
+        overlap_area(&square.as_slice(), &disjoint.as_slice(), 0.01),
+        0.0
+    );
+
+    // A diamond inside of the square.
+    let mut builder = Path::builder();
+    builder.move_to(point(0.5, 0.0));
+    builder.line_to(point(1.0, 0.5));
+    builder.line_to(point(0.5, 1.0));
+    builder.line_to(point(0.0, 0.5));
+    builder.close();
+    let diamond = builder.build();
+    let area = overlap_area(&square.as_slice(), &diamond.as_slice(), 0.01);
+    assert!((area - 0.5).abs() < 0.0001, "{}", area);
+
+    // NaN coordinates must not cause a panic.
+    let nan = square.transformed(&Transform::create_scale(std::f32::NAN, 1.0));
+    overlap_area(&square.as_slice(), &nan.as_slice(), 0.01);
+    overlap_area(&nan.as_slice(), &nan.as_slice(), 0.01);
+}
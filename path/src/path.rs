@@ -3,11 +3,15 @@
 
 use crate::builder::*;
 use crate::geom::traits::Transformation;
-use crate::geom::Arc;
+use crate::geom::{Arc, ArcFlags, CubicBezierSegment, QuadraticBezierSegment, SvgArc};
+use crate::iterator::{Flattened, PathIterator};
 use crate::math::*;
-use crate::{AttributeStore, ControlPointId, EndpointId, Event, IdEvent, PathEvent, PositionStore};
+use crate::{
+    AttributeStore, ControlPointId, EndpointId, Event, IdEvent, IdSegment, PathEvent, PositionStore,
+};
 
 use std::iter::IntoIterator;
+use std::ops::Range;
 use std::u32;
 
 /// Enumeration corresponding to the [Event](https://docs.rs/lyon_core/*/lyon_core/events/enum.Event.html) enum
@@ -67,6 +71,8 @@ pub struct Path {
     points: Box<[Point]>,
     verbs: Box<[Verb]>,
     num_attributes: usize,
+    // Empty unless the path was built with named attributes.
+    attribute_names: Box<[String]>,
 }
 
 /// A view on a `Path`.
@@ -75,6 +81,7 @@ pub struct PathSlice<'l> {
     points: &'l [Point],
     verbs: &'l [Verb],
     num_attributes: usize,
+    attribute_names: &'l [String],
 }
 
 impl Path {
@@ -89,6 +96,19 @@ impl Path {
         BuilderWithAttributes::new(num_attributes)
     }
 
+    /// Creates a [BuilderWithAttributes](struct.BuilderWithAttributes.html) to build a path
+    /// with custom attributes that can be looked up by name.
+    ///
+    /// The attributes are passed to the builder in the same order as the names.
+    ///
+    /// See [`Path::attribute`](struct.Path.html#method.attribute).
+    pub fn builder_with_named_attributes(names: &[&str]) -> BuilderWithAttributes {
+        let mut builder = BuilderWithAttributes::new(names.len());
+        builder.attribute_names = names.iter().map(|name| name.to_string()).collect();
+
+        builder
+    }
+
     /// Creates an Empty `Path`.
     #[inline]
     pub fn new() -> Path {
@@ -96,7 +116,63 @@ impl Path {
             points: Box::new([]),
             verbs: Box::new([]),
             num_attributes: 0,
+            attribute_names: Box::new([]),
+        }
+    }
+
+    /// Builds a path from text containing a pair of coordinates per line.
+    ///
+    /// Each non-empty line must contain the `x` and `y` coordinates of a point, separated
+    /// by whitespace and/or a comma. The points are connected into polylines, and blank
+    /// lines (or lines containing only whitespace) separate sub-paths. The sub-paths are
+    /// not closed.
+    ///
+    /// Malformed lines (with a number of values other than two, or values that can't be
+    /// parsed as numbers) produce a `ParseError::InvalidLine` error carrying the line number,
+    /// starting at 1.
+    pub fn from_points_csv(text: &str) -> Result<Path, ParseError> {
+        let mut builder = Path::builder();
+        let mut new_sub_path = true;
+        for (idx, line) in text.lines().enumerate() {
+            let invalid = ParseError::InvalidLine { line: idx + 1 };
+            if line.trim().is_empty() {
+                new_sub_path = true;
+                continue;
+            }
+
+            let mut values = line
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .filter(|s| !s.is_empty())
+                .map(|s| s.parse::<f32>());
+            let p = match (values.next(), values.next(), values.next()) {
+                (Some(Ok(x)), Some(Ok(y)), None) => point(x, y),
+                _ => {
+                    return Err(invalid);
+                }
+            };
+
+            if new_sub_path {
+                builder.move_to(p);
+                new_sub_path = false;
+            } else {
+                builder.line_to(p);
+            }
         }
+
+        Ok(builder.build())
+    }
+
+    /// Builds a path from the `points` attribute of SVG `<polyline>` and `<polygon>`
+    /// elements.
+    ///
+    /// The coordinates are separated by commas and/or whitespace, and consecutive pairs of
+    /// coordinates are connected into a single sub-path, which is closed if `closed` is
+    /// true, as for `<polygon>` elements.
+    ///
+    /// Malformed input, including an odd number of coordinates, produces a
+    /// `ParseError::InvalidToken` error carrying the byte offset of the invalid token.
+    pub fn from_svg_points(points: &str, closed: bool) -> Result<Path, ParseError> {
+        crate::svg_points::parse_svg_points(points, closed)
     }
 
     /// Returns a view on this `Path`.
@@ -106,6 +182,7 @@ impl Path {
             points: &self.points[..],
             verbs: &self.verbs[..],
             num_attributes: self.num_attributes,
+            attribute_names: &self.attribute_names,
         }
     }
 
@@ -115,22 +192,64 @@ impl Path {
         interpolated_attributes(self.num_attributes, &self.points, endpoint)
     }
 
+    /// Returns the names of the custom attributes if the path was built with named
+    /// attributes, or an empty slice otherwise.
+    pub fn attribute_names(&self) -> &[String] {
+        &self.attribute_names
+    }
+
+    /// Returns the index of a named custom attribute.
+    pub fn attribute_index(&self, name: &str) -> Option<usize> {
+        self.attribute_names.iter().position(|n| n == name)
+    }
+
+    /// Returns the value of a named custom attribute at an endpoint, or `None` if there
+    /// is no attribute with this name.
+    pub fn attribute(&self, endpoint: EndpointId, name: &str) -> Option<f32> {
+        self.attribute_index(name)
+            .map(|idx| self.attributes(endpoint)[idx])
+    }
+
     /// Iterates over the entire `Path`.
     pub fn iter(&self) -> Iter {
         Iter::new(self.num_attributes, &self.points[..], &self.verbs[..])
     }
 
+    /// Iterates over the `Path` with curves approximated by line segments.
+    ///
+    /// See [`PathSlice::flattened`](struct.PathSlice.html#method.flattened).
+    pub fn flattened(&self, tolerance: f32) -> Flattened<Iter> {
+        self.iter().flattened(tolerance)
+    }
+
+    /// Invokes a callback for each event of the `Path` with curves approximated by line
+    /// segments.
+    ///
+    /// See [`PathSlice::for_each_flattened`](struct.PathSlice.html#method.for_each_flattened).
+    pub fn for_each_flattened(&self, tolerance: f32, callback: &mut dyn FnMut(&PathEvent)) {
+        self.as_slice().for_each_flattened(tolerance, callback)
+    }
+
     /// Iterates over the endpoint and control point ids of the `Path`.
     pub fn id_iter(&self) -> IdIter {
         IdIter::new(self.num_attributes, &self.verbs[..])
     }
 
+    /// Iterates over the endpoint and control point ids of the segments of the `Path`.
+    pub fn id_segments(&self) -> IdSegmentIter {
+        IdSegmentIter {
+            iter: self.id_iter(),
+        }
+    }
+
     pub fn iter_with_attributes(&self) -> IterWithAttributes {
         IterWithAttributes::new(self.num_attributes(), &self.points[..], &self.verbs[..])
     }
 
     /// Applies a transform to all endpoints and control points of this path and
     /// Returns the result.
+    ///
+    /// Custom endpoint attributes are left unchanged.
     pub fn transformed<T: Transformation<f32>>(&self, transform: &T) -> Self {
         let mut result = self.clone();
         result.apply_transform(transform);
@@ -138,13 +257,70 @@ impl Path {
         result
     }
 
+    /// Approximates the total length of the path.
+    ///
+    /// See [`PathSlice::approximate_length`](struct.PathSlice.html#method.approximate_length).
+    pub fn approximate_length(&self, tolerance: f32) -> f32 {
+        self.as_slice().approximate_length(tolerance)
+    }
+
+    /// Approximates the length of each sub-path.
+    ///
+    /// See [`PathSlice::approximate_subpath_lengths`](struct.PathSlice.html#method.approximate_subpath_lengths).
+    pub fn approximate_subpath_lengths(&self, tolerance: f32) -> Vec<f32> {
+        self.as_slice().approximate_subpath_lengths(tolerance)
+    }
+
+    /// Returns information about each sub-path.
+    ///
+    /// See [`PathSlice::subpath_info`](struct.PathSlice.html#method.subpath_info).
+    pub fn subpath_info(&self) -> Vec<SubpathInfo> {
+        self.as_slice().subpath_info()
+    }
+
+    /// Computes the total number of turns of the tangent along the sub-paths.
+    ///
+    /// See [`PathSlice::turning_number`](struct.PathSlice.html#method.turning_number).
+    pub fn turning_number(&self) -> i32 {
+        self.as_slice().turning_number()
+    }
+
     /// Reversed version of this path with edge loops are specified in the opposite
     /// order.
     pub fn reversed(&self) -> Self {
         reverse_path(self.as_slice())
     }
 
-    fn apply_transform<T: Transformation<f32>>(&mut self, transform: &T) {
+    /// Reverses the direction of a single sub-path in place.
+    ///
+    /// Sub-paths are indexed in the order in which they appear in the path.
+    /// The other sub-paths are left untouched.
+    ///
+    /// # Panics
+    ///
+    /// If the path has fewer than `subpath_index + 1` sub-paths.
+    pub fn reverse_subpath(&mut self, subpath_index: usize) {
+        let (verbs, points) = self
+            .as_slice()
+            .subpath_ranges()
+            .nth(subpath_index)
+            .expect("Sub-path index out of bounds");
+
+        let reversed = reverse_path(PathSlice {
+            points: &self.points[points.clone()],
+            verbs: &self.verbs[verbs.clone()],
+            num_attributes: self.num_attributes,
+            attribute_names: &self.attribute_names,
+        });
+
+        self.points[points].copy_from_slice(&reversed.points);
+        self.verbs[verbs].copy_from_slice(&reversed.verbs);
+    }
+
+    /// Applies a transform to all endpoints and control points of this path in place.
+    ///
+    /// Custom endpoint attributes are left unchanged.
+    pub fn apply_transform<T: Transformation<f32>>(&mut self, transform: &T) {
         let iter = IdIter::new(self.num_attributes, &self.verbs[..]);
 
         for evt in iter {
@@ -180,9 +356,10 @@ impl Path {
 
     /// Concatenate two paths.
     ///
-    /// They must have the same number of custom attributes.
+    /// They must have the same number of custom attributes, with the same names.
     pub fn merge(&self, other: &Self) -> Self {
         assert_eq!(self.num_attributes, other.num_attributes);
+        assert_eq!(self.attribute_names, other.attribute_names);
 
         let mut verbs = Vec::with_capacity(self.verbs.len() + other.verbs.len());
         let mut points = Vec::with_capacity(self.points.len() + other.points.len());
@@ -195,6 +372,81 @@ impl Path {
             verbs: verbs.into_boxed_slice(),
             points: points.into_boxed_slice(),
             num_attributes: self.num_attributes,
+            attribute_names: self.attribute_names.clone(),
+        }
+    }
+
+    /// Returns a copy of this path without its degenerate sub-paths.
+    ///
+    /// A sub-path is degenerate if it has edges but all of its endpoints and control
+    /// points are at the same position (for example `move_to(p)`, `line_to(p)`,
+    /// `close()`), which produces NaN normals when stroking and empty regions when
+    /// filling. Two points are considered to be at the same position if the sum of the
+    /// absolute differences of their coordinates is smaller than `epsilon`, which should
+    /// be chosen according to the scale of the path. Sub-paths made of a single `move_to`
+    /// are kept since they can be rendered with stroke caps.
+    ///
+    /// The endpoint and control point ids of this path can't be used with the result:
+    /// the ids of the sub-paths that follow a removed one are shifted.
+    pub fn without_degenerate_sub_paths(&self, epsilon: f32) -> Self {
+        let slice = self.as_slice();
+        let mut verbs = Vec::with_capacity(self.verbs.len());
+        let mut points = Vec::with_capacity(self.points.len());
+        for (verb_range, point_range) in slice.subpath_ranges() {
+            let sub_path = PathSlice {
+                points: &self.points[point_range.clone()],
+                verbs: &self.verbs[verb_range.clone()],
+                num_attributes: self.num_attributes,
+                attribute_names: &self.attribute_names,
+            };
+
+            let mut first = None;
+            let mut has_edges = false;
+            let mut is_degenerate = true;
+            let mut check = |p: Point| {
+                let first = *first.get_or_insert(p);
+                let d = (p - first).abs();
+                if d.x + d.y >= epsilon {
+                    is_degenerate = false;
+                }
+            };
+            for evt in sub_path.iter() {
+                match evt {
+                    PathEvent::Begin { at } => check(at),
+                    PathEvent::Line { to, .. } => {
+                        has_edges = true;
+                        check(to);
+                    }
+                    PathEvent::Quadratic { ctrl, to, .. } => {
+                        has_edges = true;
+                        check(ctrl);
+                        check(to);
+                    }
+                    PathEvent::Cubic {
+                        ctrl1, ctrl2, to, ..
+                    } => {
+                        has_edges = true;
+                        check(ctrl1);
+                        check(ctrl2);
+                        check(to);
+                    }
+                    PathEvent::End { .. } => {}
+                }
+            }
+
+            if has_edges && is_degenerate {
+                continue;
+            }
+
+            verbs.extend_from_slice(&self.verbs[verb_range]);
+            points.extend_from_slice(&self.points[point_range]);
+        }
+
+        Path {
+            verbs: verbs.into_boxed_slice(),
+            points: points.into_boxed_slice(),
+            num_attributes: self.num_attributes,
+            attribute_names: self.attribute_names.clone(),
         }
     }
 }
@@ -255,11 +507,71 @@ impl<'l> PathSlice<'l> {
         Iter::new(self.num_attributes, self.points, self.verbs)
     }
 
+    /// Iterates over the path, with the custom attributes of each endpoint.
+    pub fn iter_with_attributes(&self) -> IterWithAttributes<'l> {
+        IterWithAttributes::new(self.num_attributes, self.points, self.verbs)
+    }
+
+    /// Iterates over the path with curves approximated by line segments.
+    ///
+    /// `Quadratic` and `Cubic` events are replaced with sequences of `Line` events that
+    /// stay within `tolerance` of the curves, while the `Begin` and `End` events are
+    /// left unchanged. The curves are flattened lazily, as the iterator advances.
+    pub fn flattened(&self, tolerance: f32) -> Flattened<Iter<'l>> {
+        self.iter().flattened(tolerance)
+    }
+
+    /// Invokes a callback for each event of the path with curves approximated by line
+    /// segments.
+    ///
+    /// This produces the same events as `flattened` without the state of an iterator,
+    /// which is convenient to consume the segments directly, for example to rasterize
+    /// them. Each sub-path starts with a `Begin` event and ends with an `End` event, and
+    /// the closing edge of closed sub-paths goes from `last` to `first` in the `End`
+    /// event, as with the other iterators. The segments of each curve end exactly at the
+    /// endpoint of the curve. Nothing is allocated.
+    pub fn for_each_flattened(&self, tolerance: f32, callback: &mut dyn FnMut(&PathEvent)) {
+        for evt in self.iter() {
+            match evt {
+                PathEvent::Quadratic { from, ctrl, to } => {
+                    let curve = QuadraticBezierSegment { from, ctrl, to };
+                    for_each_flattened_curve(from, to, callback, &mut |cb| {
+                        curve.for_each_flattened(tolerance, &mut |p| cb(p))
+                    });
+                }
+                PathEvent::Cubic {
+                    from,
+                    ctrl1,
+                    ctrl2,
+                    to,
+                } => {
+                    let curve = CubicBezierSegment {
+                        from,
+                        ctrl1,
+                        ctrl2,
+                        to,
+                    };
+                    for_each_flattened_curve(from, to, callback, &mut |cb| {
+                        curve.for_each_flattened(tolerance, &mut |p| cb(p))
+                    });
+                }
+                _ => callback(&evt),
+            }
+        }
+    }
+
     /// Iterates over the endpoint and control point ids of the `Path`.
     pub fn id_iter(&self) -> IdIter {
         IdIter::new(self.num_attributes, self.verbs)
     }
 
+    /// Iterates over the endpoint and control point ids of the segments of the `Path`.
+    pub fn id_segments(&self) -> IdSegmentIter {
+        IdSegmentIter {
+            iter: self.id_iter(),
+        }
+    }
+
     pub fn is_empty(&self) -> bool {
         self.verbs.is_empty()
     }
@@ -269,287 +581,703 @@ impl<'l> PathSlice<'l> {
     pub fn attributes(&self, endpoint: EndpointId) -> &[f32] {
         interpolated_attributes(self.num_attributes, &self.points, endpoint)
     }
-}
-
-impl<'l> IntoIterator for PathSlice<'l> {
-    type Item = PathEvent;
-    type IntoIter = Iter<'l>;
 
-    fn into_iter(self) -> Iter<'l> {
-        self.iter()
+    /// Returns the names of the custom attributes if the path was built with named
+    /// attributes, or an empty slice otherwise.
+    pub fn attribute_names(&self) -> &'l [String] {
+        self.attribute_names
     }
-}
 
-impl<'l, 'a> IntoIterator for &'a PathSlice<'l> {
-    type Item = PathEvent;
-    type IntoIter = Iter<'l>;
+    /// Computes the smallest circle enclosing the flattened path.
+    ///
+    /// Returns the center and radius of the circle. The curves are approximated
+    /// with the provided tolerance before running Welzl's algorithm on the
+    /// resulting vertices. An empty path produces a circle of radius zero
+    /// at the origin.
+    ///
+    /// The vertices are shuffled with a fixed seed beforehand, which makes the
+    /// expected cost linear in the number of vertices regardless of their order
+    /// while keeping the result deterministic.
+    pub fn bounding_circle(&self, tolerance: f32) -> (Point, f32) {
+        let mut points = Vec::new();
+        for evt in self.iter().flattened(tolerance) {
+            match evt {
+                PathEvent::Begin { at } => points.push(at),
+                PathEvent::Line { to, .. } => points.push(to),
+                _ => {}
+            }
+        }
 
-    fn into_iter(self) -> Iter<'l> {
-        self.iter()
+        smallest_enclosing_circle(&mut points)
     }
-}
 
-impl<'l> PositionStore for PathSlice<'l> {
-    fn get_endpoint(&self, id: EndpointId) -> Point {
-        self.points[id.to_usize()]
+    /// Approximates the total length of the path, including the closing segment of
+    /// closed sub-paths.
+    ///
+    /// Line segments are measured exactly. Curves are flattened with the provided
+    /// tolerance and the lengths of the resulting line segments are summed. Since the
+    /// flattened curve is a polyline inscribed in the curve, the result slightly
+    /// underestimates the real length and gets closer to it as the tolerance decreases.
+    pub fn approximate_length(&self, tolerance: f32) -> f32 {
+        self.approximate_subpath_lengths(tolerance).iter().sum()
     }
 
-    fn get_control_point(&self, id: ControlPointId) -> Point {
-        self.points[id.to_usize()]
-    }
-}
+    /// Approximates the length of each sub-path, in order.
+    ///
+    /// See [`approximate_length`](#method.approximate_length).
+    pub fn approximate_subpath_lengths(&self, tolerance: f32) -> Vec<f32> {
+        let mut lengths = Vec::new();
+        let mut length = 0.0;
+        for evt in self.iter() {
+            match evt {
+                PathEvent::Begin { .. } => {
+                    length = 0.0;
+                }
+                PathEvent::Line { from, to } => {
+                    length += (to - from).length();
+                }
+                PathEvent::Quadratic { from, ctrl, to } => {
+                    length +=
+                        QuadraticBezierSegment { from, ctrl, to }.approximate_length(tolerance);
+                }
+                PathEvent::Cubic {
+                    from,
+                    ctrl1,
+                    ctrl2,
+                    to,
+                } => {
+                    length += CubicBezierSegment {
+                        from,
+                        ctrl1,
+                        ctrl2,
+                        to,
+                    }
+                    .approximate_length(tolerance);
+                }
+                PathEvent::End { last, first, close } => {
+                    if close {
+                        length += (first - last).length();
+                    }
+                    lengths.push(length);
+                }
+            }
+        }
 
-impl<'l> AttributeStore for PathSlice<'l> {
-    fn get(&self, id: EndpointId) -> &[f32] {
-        interpolated_attributes(self.num_attributes, self.points, id)
+        lengths
     }
 
-    fn num_attributes(&self) -> usize {
-        self.num_attributes
+    /// Returns information about each sub-path, in order, without iterating over the
+    /// events.
+    ///
+    /// This makes it possible to know whether a sub-path is closed before processing its
+    /// segments. The event indices refer to the sequence of events produced by `iter`.
+    pub fn subpath_info(&self) -> Vec<SubpathInfo> {
+        self.subpath_ranges()
+            .map(|(range, _)| {
+                let verbs = &self.verbs[range.clone()];
+                SubpathInfo {
+                    start_event: range.start,
+                    end_event: range.end - 1,
+                    closed: verbs.last() == Some(&Verb::Close),
+                    num_segments: verbs
+                        .iter()
+                        .filter(|verb| match verb {
+                            Verb::LineTo | Verb::QuadraticTo | Verb::CubicTo => true,
+                            _ => false,
+                        })
+                        .count(),
+                }
+            })
+            .collect()
     }
-}
 
-/// Builds path objects.
-pub struct Builder {
-    points: Vec<Point>,
-    verbs: Vec<Verb>,
-    current_position: Point,
-    first_position: Point,
-    first_vertex: EndpointId,
-    first_verb: u32,
-    need_moveto: bool,
-    last_cmd: Verb,
-}
+    /// Splits the path into groups of sub-paths that form connected regions.
+    ///
+    /// Each sub-path that is not contained in another one starts a component, and
+    /// the sub-paths directly inside of it (its holes) are added to that component.
+    /// Sub-paths nested inside of holes start new components, and so on.
+    ///
+    /// Containment is determined with point-in-polygon tests against the sub-paths
+    /// flattened with the provided tolerance, testing the first endpoint of each
+    /// sub-path. Sub-paths that intersect or merely touch each other are not
+    /// grouped together.
+    pub fn connected_components(&self, tolerance: f32) -> Vec<Path> {
+        let ranges: Vec<_> = self.subpath_ranges().collect();
+
+        let mut polygons: Vec<Vec<Point>> = Vec::with_capacity(ranges.len());
+        for evt in self.iter().flattened(tolerance) {
+            match evt {
+                PathEvent::Begin { at } => polygons.push(vec![at]),
+                PathEvent::Line { to, .. } => polygons.last_mut().unwrap().push(to),
+                _ => {}
+            }
+        }
+        debug_assert_eq!(polygons.len(), ranges.len());
+
+        // For each sub-path, the list of sub-paths that contain it.
+        let containers: Vec<Vec<usize>> = (0..polygons.len())
+            .map(|i| {
+                (0..polygons.len())
+                    .filter(|&j| j != i && polygon_contains_point(&polygons[j], polygons[i][0]))
+                    .collect()
+            })
+            .collect();
+
+        let mut components: Vec<Vec<usize>> = Vec::new();
+        let mut component_of_root = vec![None; polygons.len()];
+        for i in 0..polygons.len() {
+            let depth = containers[i].len();
+            if depth % 2 == 0 {
+                component_of_root[i] = Some(components.len());
+                components.push(vec![i]);
+            }
+        }
+        for i in 0..polygons.len() {
+            let depth = containers[i].len();
+            if depth % 2 == 1 {
+                // The direct container is the one that is the most deeply nested.
+                let parent = *containers[i]
+                    .iter()
+                    .max_by_key(|&&j| containers[j].len())
+                    .unwrap();
+                if let Some(component) = component_of_root[parent] {
+                    components[component].push(i);
+                }
+            }
+        }
 
-impl Builder {
-    pub fn new() -> Self {
-        Builder::with_capacity(0, 0)
-    }
+        components
+            .iter()
+            .map(|subpaths| {
+                let mut verbs = Vec::new();
+                let mut points = Vec::new();
+                for &i in subpaths {
+                    let (verb_range, point_range) = ranges[i].clone();
+                    verbs.extend_from_slice(&self.verbs[verb_range]);
+                    points.extend_from_slice(&self.points[point_range]);
+                }
 
-    pub fn with_capacity(points: usize, edges: usize) -> Self {
-        Builder {
-            points: Vec::with_capacity(points),
-            verbs: Vec::with_capacity(edges),
-            current_position: Point::new(0.0, 0.0),
-            first_position: Point::new(0.0, 0.0),
-            first_vertex: EndpointId(0),
-            first_verb: 0,
-            need_moveto: true,
-            last_cmd: Verb::End,
-        }
+                Path {
+                    verbs: verbs.into_boxed_slice(),
+                    points: points.into_boxed_slice(),
+                    num_attributes: self.num_attributes,
+                    attribute_names: self.attribute_names.to_vec().into_boxed_slice(),
+                }
+            })
+            .collect()
     }
 
-    pub fn with_svg(self) -> SvgPathBuilder<Self> {
-        SvgPathBuilder::new(self)
-    }
+    /// Computes the normals of the edges on each side of the vertices of the flattened path.
+    ///
+    /// This is useful to extrude the shape with hard bevels: vertices at corners have
+    /// different incoming and outgoing normals and should be split, while vertices on smooth
+    /// parts of the path (including the inside of flattened curves) have identical normals.
+    ///
+    /// The normals point to the right of the direction of the edges, which is outwards for
+    /// sub-paths that go clockwise in a y-down coordinate system. The ends of open sub-paths
+    /// use the normal of their only edge on both sides.
+    pub fn boundary_normals(&self, tolerance: f32) -> Vec<BoundaryVertex> {
+        struct Vertex {
+            position: Point,
+            tangent_in: Option<Vector>,
+            tangent_out: Option<Vector>,
+        }
 
-    pub fn flattened(self, tolerance: f32) -> FlatteningBuilder<Self> {
-        FlatteningBuilder::new(self, tolerance)
-    }
+        fn flush(sub_path: &mut Vec<Vertex>, closed: bool, output: &mut Vec<BoundaryVertex>) {
+            let len = sub_path.len();
+            if len < 2 {
+                sub_path.clear();
+                return;
+            }
 
-    pub fn move_to(&mut self, to: Point) -> EndpointId {
-        nan_check(to);
-        self.end_if_needed();
-        self.need_moveto = false;
-        self.first_position = to;
-        let id = EndpointId(self.points.len() as u32);
-        self.first_vertex = id;
-        self.first_verb = self.verbs.len() as u32;
-        self.current_position = to;
-        self.points.push(to);
-        self.verbs.push(Verb::Begin);
-        self.last_cmd = Verb::Begin;
+            if closed {
+                let last = sub_path.last().unwrap().position;
+                let first = sub_path[0].position;
+                if last == first {
+                    sub_path[0].tangent_in = sub_path[len - 1].tangent_in;
+                    sub_path.pop();
+                } else {
+                    sub_path[len - 1].tangent_out = Some(first - last);
+                    sub_path[0].tangent_in = Some(first - last);
+                }
+            } else {
+                sub_path[0].tangent_in = sub_path[0].tangent_out;
+                sub_path[len - 1].tangent_out = sub_path[len - 1].tangent_in;
+            }
 
-        id
-    }
+            let normal = |v: Vector| vector(v.y, -v.x).normalize();
+            let len = sub_path.len();
+            for i in 0..len {
+                let v = &sub_path[i];
+                let (incoming, outgoing) = match (v.tangent_in, v.tangent_out) {
+                    (Some(t_in), Some(t_out)) => (normal(t_in), normal(t_out)),
+                    _ => {
+                        // Inside of a flattened curve.
+                        let prev = sub_path[(i + len - 1) % len].position;
+                        let next = sub_path[(i + 1) % len].position;
+                        let n = (normal(v.position - prev) + normal(next - v.position)).normalize();
+                        (n, n)
+                    }
+                };
+
+                output.push(BoundaryVertex {
+                    position: v.position,
+                    incoming,
+                    outgoing,
+                });
+            }
 
-    pub fn line_to(&mut self, to: Point) -> EndpointId {
-        nan_check(to);
-        self.move_to_if_needed();
-        let id = EndpointId(self.points.len() as u32);
-        self.points.push(to);
-        self.verbs.push(Verb::LineTo);
-        self.current_position = to;
-        self.last_cmd = Verb::LineTo;
+            sub_path.clear();
+        }
 
-        id
-    }
+        fn add_curve(sub_path: &mut Vec<Vertex>, start: Vector, end: Vector, points: &[Point]) {
+            sub_path.last_mut().unwrap().tangent_out = Some(start);
+            let last = points.len() - 1;
+            for (i, &position) in points.iter().enumerate() {
+                let tangent_in = if i == last { Some(end) } else { None };
+                sub_path.push(Vertex {
+                    position,
+                    tangent_in,
+                    tangent_out: None,
+                });
+            }
+        }
 
-    pub fn close(&mut self) {
-        // Relative path ops tend to accumulate small floating point imprecisions
-        // which results in the last segment ending almost but not quite at the
-        // start of the sub-path, causing a new edge to be inserted which often
-        // intersects with the first or last edge. This can affect algorithms that
-        // Don't handle self-intersecting paths.
-        // Deal with this by snapping the last point if it is very close to the
-        // start of the sub path.
-        if let Some(p) = self.points.last_mut() {
-            let d = (*p - self.first_position).abs();
-            if d.x + d.y < 0.0001 {
-                *p = self.first_position;
+        let mut output = Vec::new();
+        let mut sub_path = Vec::new();
+        let mut points = Vec::new();
+        for evt in self.iter() {
+            match evt {
+                PathEvent::Begin { at } => {
+                    sub_path.push(Vertex {
+                        position: at,
+                        tangent_in: None,
+                        tangent_out: None,
+                    });
+                }
+                PathEvent::Line { from, to } => {
+                    if from != to {
+                        add_curve(&mut sub_path, to - from, to - from, &[to]);
+                    }
+                }
+                PathEvent::Quadratic { from, ctrl, to } => {
+                    if from != to || ctrl != from {
+                        points.clear();
+                        QuadraticBezierSegment { from, ctrl, to }
+                            .for_each_flattened(tolerance, &mut |p| points.push(p));
+                        add_curve(
+                            &mut sub_path,
+                            non_zero_or(ctrl - from, to - from),
+                            non_zero_or(to - ctrl, to - from),
+                            &points,
+                        );
+                    }
+                }
+                PathEvent::Cubic {
+                    from,
+                    ctrl1,
+                    ctrl2,
+                    to,
+                } => {
+                    if from != to || ctrl1 != from || ctrl2 != from {
+                        points.clear();
+                        CubicBezierSegment {
+                            from,
+                            ctrl1,
+                            ctrl2,
+                            to,
+                        }
+                        .for_each_flattened(tolerance, &mut |p| points.push(p));
+                        add_curve(
+                            &mut sub_path,
+                            non_zero_or(ctrl1 - from, non_zero_or(ctrl2 - from, to - from)),
+                            non_zero_or(to - ctrl2, non_zero_or(to - ctrl1, to - from)),
+                            &points,
+                        );
+                    }
+                }
+                PathEvent::End { close, .. } => {
+                    flush(&mut sub_path, close, &mut output);
+                }
             }
         }
 
-        self.verbs.push(Verb::Close);
-        self.current_position = self.first_position;
-        self.need_moveto = true;
-        self.last_cmd = Verb::Close;
+        output
     }
 
-    pub fn quadratic_bezier_to(&mut self, ctrl: Point, to: Point) -> EndpointId {
-        nan_check(ctrl);
-        nan_check(to);
-        self.move_to_if_needed();
-        self.points.push(ctrl);
-        let id = EndpointId(self.points.len() as u32);
-        self.points.push(to);
-        self.verbs.push(Verb::QuadraticTo);
-        self.current_position = to;
-        self.last_cmd = Verb::QuadraticTo;
+    /// Computes the turning angle at each vertex of the path.
+    ///
+    /// The angle, in radians between 0 and PI, is measured between the
+    /// tangent at the end of the incoming edge and the tangent at the start
+    /// of the outgoing edge: a value close to zero means a smooth join while
+    /// a large value means a corner.
+    ///
+    /// Each vertex is identified by the incoming edge, using the index of the
+    /// event in the sequence produced by `iter()`. The first and last
+    /// vertices of open sub-paths are not reported.
+    pub fn corner_angles(&self) -> Vec<(usize, f32)> {
+        fn turning_angle(a: Vector, b: Vector) -> f32 {
+            a.cross(b).atan2(a.dot(b)).abs()
+        }
 
-        id
-    }
+        let mut angles = Vec::new();
+        // Tangent at the start of the first edge of the current sub-path.
+        let mut first_tangent = None;
+        // Incoming edge and its tangent at the current position.
+        let mut previous: Option<(usize, Vector)> = None;
+        for (id, evt) in self.iter().enumerate() {
+            let (start, end) = match evt {
+                PathEvent::Begin { .. } => {
+                    first_tangent = None;
+                    previous = None;
+                    continue;
+                }
+                PathEvent::Line { from, to } => (to - from, to - from),
+                PathEvent::Quadratic { from, ctrl, to } => (
+                    non_zero_or(ctrl - from, to - from),
+                    non_zero_or(to - ctrl, to - from),
+                ),
+                PathEvent::Cubic {
+                    from,
+                    ctrl1,
+                    ctrl2,
+                    to,
+                } => (
+                    non_zero_or(ctrl1 - from, non_zero_or(ctrl2 - from, to - from)),
+                    non_zero_or(to - ctrl2, non_zero_or(to - ctrl1, to - from)),
+                ),
+                PathEvent::End {
+                    last,
+                    first,
+                    close: true,
+                } => {
+                    if let Some((prev_id, prev_tangent)) = previous {
+                        let closing = first - last;
+                        let mut last_id = prev_id;
+                        let mut last_tangent = prev_tangent;
+                        if closing != vector(0.0, 0.0) {
+                            angles.push((prev_id, turning_angle(prev_tangent, closing)));
+                            last_id = id;
+                            last_tangent = closing;
+                        }
+                        if let Some(first_tangent) = first_tangent {
+                            angles.push((last_id, turning_angle(last_tangent, first_tangent)));
+                        }
+                    }
+                    continue;
+                }
+                PathEvent::End { close: false, .. } => {
+                    continue;
+                }
+            };
 
-    pub fn cubic_bezier_to(&mut self, ctrl1: Point, ctrl2: Point, to: Point) -> EndpointId {
-        nan_check(ctrl1);
-        nan_check(ctrl2);
-        nan_check(to);
-        self.move_to_if_needed();
-        self.points.push(ctrl1);
-        self.points.push(ctrl2);
-        let id = EndpointId(self.points.len() as u32);
-        self.points.push(to);
-        self.verbs.push(Verb::CubicTo);
-        self.current_position = to;
-        self.last_cmd = Verb::CubicTo;
+            if start == vector(0.0, 0.0) {
+                continue;
+            }
 
-        id
+            if let Some((prev_id, prev_tangent)) = previous {
+                angles.push((prev_id, turning_angle(prev_tangent, start)));
+            } else {
+                first_tangent = Some(start);
+            }
+
+            previous = Some((id, end));
+        }
+
+        angles
     }
 
-    pub fn arc(&mut self, center: Point, radii: Vector, sweep_angle: Angle, x_rotation: Angle) {
-        nan_check(center);
-        nan_check(radii.to_point());
-        debug_assert!(!sweep_angle.get().is_nan());
-        debug_assert!(!x_rotation.get().is_nan());
+    /// Computes the total number of turns of the tangent along the sub-paths.
+    ///
+    /// Each sub-path is considered closed, as when it is filled. A simple closed curve has
+    /// a turning number of 1 if it goes counter-clockwise and -1 if it goes clockwise (see
+    /// [`Winding`](enum.Winding.html)), and curves that loop around themselves have a
+    /// larger magnitude. A figure-eight has a turning number of 0. The turning numbers of
+    /// the sub-paths are summed.
+    ///
+    /// Curves are flattened with a tolerance relative to the size of the path. The result
+    /// is ambiguous for sub-paths that make a U-turn, for example at a cusp.
+    pub fn turning_number(&self) -> i32 {
+        let mut min = point(std::f32::MAX, std::f32::MAX);
+        let mut max = point(std::f32::MIN, std::f32::MIN);
+        for evt in self.iter() {
+            let (a, b, c) = match evt {
+                PathEvent::Begin { at } => (at, at, at),
+                PathEvent::Line { to, .. } => (to, to, to),
+                PathEvent::Quadratic { ctrl, to, .. } => (ctrl, ctrl, to),
+                PathEvent::Cubic {
+                    ctrl1, ctrl2, to, ..
+                } => (ctrl1, ctrl2, to),
+                PathEvent::End { .. } => continue,
+            };
+            min = min.min(a).min(b).min(c);
+            max = max.max(a).max(b).max(c);
+        }
+        let extent = max - min;
+        let tolerance = extent.x.max(extent.y) * 0.001;
+        if !(tolerance > 0.0) {
+            return 0;
+        }
 
-        let start_angle = (self.current_position - center).angle_from_x_axis() - x_rotation;
-        let arc = Arc {
-            start_angle,
-            center,
-            radii,
-            sweep_angle,
-            x_rotation,
+        let mut total = 0.0;
+        let mut first_edge = None;
+        let mut previous_edge: Option<Vector> = None;
+        let mut turn = |edge: Vector, previous_edge: &mut Option<Vector>| {
+            if edge == vector(0.0, 0.0) {
+                return;
+            }
+            if let Some(previous) = *previous_edge {
+                total += previous.cross(edge).atan2(previous.dot(edge));
+            }
+            *previous_edge = Some(edge);
         };
 
-        // If the current position is not on the arc, move or line to the beginning of the
-        // arc.
-        let arc_start = arc.from();
-        if (arc_start - self.current_position).square_length() < 0.01 {
-            if self.need_moveto {
-                self.move_to(arc_start);
-            } else {
-                self.line_to(arc_start);
+        for evt in self.flattened(tolerance) {
+            match evt {
+                PathEvent::Begin { .. } => {
+                    first_edge = None;
+                    previous_edge = None;
+                }
+                PathEvent::Line { from, to } => {
+                    turn(to - from, &mut previous_edge);
+                    if first_edge.is_none() {
+                        first_edge = previous_edge;
+                    }
+                }
+                PathEvent::End { last, first, .. } => {
+                    turn(first - last, &mut previous_edge);
+                    if let Some(first_edge) = first_edge {
+                        turn(first_edge, &mut previous_edge);
+                    }
+                }
+                _ => {}
             }
         }
 
-        arc.for_each_quadratic_bezier(&mut |curve| {
-            self.quadratic_bezier_to(curve.ctrl, curve.to);
-        });
+        (total / (2.0 * std::f32::consts::PI)).round() as i32
     }
 
-    /// Add a closed polygon.
-    pub fn polygon(&mut self, points: &[Point]) {
-        self.points.reserve(points.len());
-        self.verbs.reserve(points.len() + 1);
-        build_polygon(self, points);
-    }
+    /// Returns the affine transform that maps this path onto `other` if they have the
+    /// same shape up to an affine transform.
+    ///
+    /// The paths must have the same structure: the same sequence of sub-paths and
+    /// segment types, with their points in the same order. The transform is the least
+    /// squares fit between the points of both paths, and is returned only if it maps
+    /// every point of this path within `tolerance` of the corresponding point of `other`.
+    ///
+    /// This is useful to deduplicate shapes such as glyphs or icons, storing a single
+    /// path and a transform per instance.
+    ///
+    /// If all of the points of this path are collinear the transform is ambiguous and
+    /// only translations are considered.
+    pub fn shape_equal_ignoring_transform(
+        &self,
+        other: &PathSlice,
+        tolerance: f32,
+    ) -> Option<Transform> {
+        if self.verbs != other.verbs {
+            return None;
+        }
 
-    fn move_to_if_needed(&mut self) {
-        if self.need_moveto {
-            let first = self.first_position;
-            self.move_to(first);
+        fn positions(path: &PathSlice) -> Vec<Point> {
+            let mut positions = Vec::new();
+            for evt in path.iter() {
+                match evt {
+                    PathEvent::Begin { at } => {
+                        positions.push(at);
+                    }
+                    PathEvent::Line { to, .. } => {
+                        positions.push(to);
+                    }
+                    PathEvent::Quadratic { ctrl, to, .. } => {
+                        positions.push(ctrl);
+                        positions.push(to);
+                    }
+                    PathEvent::Cubic {
+                        ctrl1, ctrl2, to, ..
+                    } => {
+                        positions.push(ctrl1);
+                        positions.push(ctrl2);
+                        positions.push(to);
+                    }
+                    PathEvent::End { .. } => {}
+                }
+            }
+
+            positions
         }
-    }
 
-    fn end_if_needed(&mut self) {
-        if (self.last_cmd as u8) <= (Verb::Begin as u8) {
-            self.verbs.push(Verb::End);
+        let src = positions(self);
+        let dst = positions(other);
+        if src.is_empty() {
+            return Some(Transform::identity());
         }
-    }
 
-    pub fn current_position(&self) -> Point {
-        self.current_position
-    }
+        let centroid = |points: &[Point]| {
+            let mut sum = (0.0f64, 0.0f64);
+            for p in points {
+                sum.0 += p.x as f64;
+                sum.1 += p.y as f64;
+            }
+            let n = points.len() as f64;
+            (sum.0 / n, sum.1 / n)
+        };
+        let (src_cx, src_cy) = centroid(&src);
+        let (dst_cx, dst_cy) = centroid(&dst);
+
+        // Covariance of the source points and cross-covariance with the destination.
+        let (mut sxx, mut sxy, mut syy) = (0.0f64, 0.0f64, 0.0f64);
+        let (mut qx_px, mut qx_py, mut qy_px, mut qy_py) = (0.0f64, 0.0f64, 0.0f64, 0.0f64);
+        for (p, q) in src.iter().zip(dst.iter()) {
+            let (px, py) = (p.x as f64 - src_cx, p.y as f64 - src_cy);
+            let (qx, qy) = (q.x as f64 - dst_cx, q.y as f64 - dst_cy);
+            sxx += px * px;
+            sxy += px * py;
+            syy += py * py;
+            qx_px += qx * px;
+            qx_py += qx * py;
+            qy_px += qy * px;
+            qy_py += qy * py;
+        }
 
-    pub fn build(mut self) -> Path {
-        self.end_if_needed();
-        Path {
-            points: self.points.into_boxed_slice(),
-            verbs: self.verbs.into_boxed_slice(),
-            num_attributes: 0,
+        let det = sxx * syy - sxy * sxy;
+        let (a, b, c, d) = if det.abs() > 1e-9 * (sxx + syy) * (sxx + syy) {
+            // Linear part of the transform: the cross-covariance multiplied by the inverse
+            // of the covariance.
+            let inv = 1.0 / det;
+            (
+                (qx_px * syy - qx_py * sxy) * inv,
+                (qx_py * sxx - qx_px * sxy) * inv,
+                (qy_px * syy - qy_py * sxy) * inv,
+                (qy_py * sxx - qy_px * sxy) * inv,
+            )
+        } else {
+            (1.0, 0.0, 0.0, 1.0)
+        };
+
+        let tx = dst_cx - (a * src_cx + b * src_cy);
+        let ty = dst_cy - (c * src_cx + d * src_cy);
+        let transform =
+            Transform::row_major(a as f32, c as f32, b as f32, d as f32, tx as f32, ty as f32);
+
+        for (p, q) in src.iter().zip(dst.iter()) {
+            if (transform.transform_point(*p) - *q).length() > tolerance {
+                return None;
+            }
         }
+
+        Some(transform)
     }
 }
 
-impl Build for Builder {
-    type PathType = Path;
+impl<'l> PathSlice<'l> {
+    // Iterates over the ranges of verbs and points of each sub-path.
+    fn subpath_ranges(&self) -> impl Iterator<Item = (Range<usize>, Range<usize>)> + 'l {
+        let attrib_stride = (self.num_attributes + 1) / 2;
+        let verbs = self.verbs;
+        let mut v = 0;
+        let mut p = 0;
+        std::iter::from_fn(move || {
+            if v >= verbs.len() {
+                return None;
+            }
 
-    fn build(self) -> Path {
-        self.build()
+            let verb_start = v;
+            let point_start = p;
+            while v < verbs.len() {
+                let verb = verbs[v];
+                if v > verb_start && verb == Verb::Begin {
+                    break;
+                }
+                p += n_stored_points(verb, attrib_stride);
+                v += 1;
+                if verb == Verb::Close || verb == Verb::End {
+                    break;
+                }
+            }
+
+            Some((verb_start..v, point_start..p))
+        })
     }
+}
 
-    fn build_and_reset(&mut self) -> Path {
-        self.current_position = Point::new(0.0, 0.0);
-        self.first_position = Point::new(0.0, 0.0);
+impl<'l> IntoIterator for PathSlice<'l> {
+    type Item = PathEvent;
+    type IntoIter = Iter<'l>;
 
-        Path {
-            points: std::mem::replace(&mut self.points, Vec::new()).into_boxed_slice(),
-            verbs: std::mem::replace(&mut self.verbs, Vec::new()).into_boxed_slice(),
-            num_attributes: 0,
-        }
+    fn into_iter(self) -> Iter<'l> {
+        self.iter()
     }
 }
 
-impl FlatPathBuilder for Builder {
-    fn move_to(&mut self, to: Point) {
-        self.move_to(to);
-    }
+impl<'l, 'a> IntoIterator for &'a PathSlice<'l> {
+    type Item = PathEvent;
+    type IntoIter = Iter<'l>;
 
-    fn line_to(&mut self, to: Point) {
-        self.line_to(to);
+    fn into_iter(self) -> Iter<'l> {
+        self.iter()
     }
+}
 
-    fn close(&mut self) {
-        self.close();
+impl<'l> PositionStore for PathSlice<'l> {
+    fn get_endpoint(&self, id: EndpointId) -> Point {
+        self.points[id.to_usize()]
     }
 
-    fn current_position(&self) -> Point {
-        self.current_position
+    fn get_control_point(&self, id: ControlPointId) -> Point {
+        self.points[id.to_usize()]
     }
 }
 
-impl PolygonBuilder for Builder {
-    fn polygon(&mut self, points: &[Point]) {
-        self.polygon(points);
+impl<'l> AttributeStore for PathSlice<'l> {
+    fn get(&self, id: EndpointId) -> &[f32] {
+        interpolated_attributes(self.num_attributes, self.points, id)
     }
-}
 
-impl PathBuilder for Builder {
-    fn quadratic_bezier_to(&mut self, ctrl: Point, to: Point) {
-        self.quadratic_bezier_to(ctrl, to);
+    fn num_attributes(&self) -> usize {
+        self.num_attributes
     }
+}
 
-    fn cubic_bezier_to(&mut self, ctrl1: Point, ctrl2: Point, to: Point) {
-        self.cubic_bezier_to(ctrl1, ctrl2, to);
-    }
+/// An error produced when parsing path data.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ParseError {
+    /// The line with the provided number (starting at 1) could not be parsed.
+    InvalidLine { line: usize },
+    /// The token starting at the provided byte offset is invalid or, if the offset is
+    /// the length of the input, the input ended unexpectedly.
+    InvalidToken { offset: usize },
+}
 
-    fn arc(&mut self, center: Point, radii: Vector, sweep_angle: Angle, x_rotation: Angle) {
-        self.arc(center, radii, sweep_angle, x_rotation);
-    }
+/// A vertex of the boundary of a path with the normals of its adjacent edges.
+///
+/// See `PathSlice::boundary_normals`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct BoundaryVertex {
+    pub position: Point,
+    /// Normal of the edge ending at this vertex.
+    pub incoming: Vector,
+    /// Normal of the edge starting at this vertex.
+    pub outgoing: Vector,
 }
 
-/// Builds path objects with custom attributes.
-pub struct BuilderWithAttributes {
+/// Information about a sub-path.
+///
+/// See `PathSlice::subpath_info`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct SubpathInfo {
+    /// Index of the sub-path's `Begin` event in the path's event sequence.
+    pub start_event: usize,
+    /// Index of the sub-path's `End` event in the path's event sequence.
+    pub end_event: usize,
+    /// Whether the sub-path is closed.
+    pub closed: bool,
+    /// Number of line and curve segments, not counting the closing edge.
+    pub num_segments: usize,
+}
+
+/// Builds path objects.
+pub struct Builder {
     points: Vec<Point>,
     verbs: Vec<Verb>,
     current_position: Point,
@@ -558,16 +1286,15 @@ pub struct BuilderWithAttributes {
     first_verb: u32,
     need_moveto: bool,
     last_cmd: Verb,
-    num_attributes: usize,
 }
 
-impl BuilderWithAttributes {
-    pub fn new(num_attributes: usize) -> Self {
-        BuilderWithAttributes::with_capacity(num_attributes, 0, 0)
+impl Builder {
+    pub fn new() -> Self {
+        Builder::with_capacity(0, 0)
     }
 
-    pub fn with_capacity(num_attributes: usize, points: usize, edges: usize) -> Self {
-        BuilderWithAttributes {
+    pub fn with_capacity(points: usize, edges: usize) -> Self {
+        Builder {
             points: Vec::with_capacity(points),
             verbs: Vec::with_capacity(edges),
             current_position: Point::new(0.0, 0.0),
@@ -576,53 +1303,125 @@ impl BuilderWithAttributes {
             first_verb: 0,
             need_moveto: true,
             last_cmd: Verb::End,
-            num_attributes,
         }
     }
 
-    fn push_attributes(&mut self, attributes: &[f32]) {
-        assert_eq!(attributes.len(), self.num_attributes);
-        for i in 0..(self.num_attributes / 2) {
-            let x = attributes[i * 2];
-            let y = attributes[i * 2 + 1];
-            self.points.push(point(x, y));
-        }
-        if self.num_attributes % 2 == 1 {
-            let x = attributes[self.num_attributes - 1];
-            self.points.push(point(x, 0.0));
-        }
+    pub fn with_svg(self) -> SvgPathBuilder<Self> {
+        SvgPathBuilder::new(self)
     }
 
-    pub fn move_to(&mut self, to: Point, attributes: &[f32]) -> EndpointId {
+    pub fn flattened(self, tolerance: f32) -> FlatteningBuilder<Self> {
+        FlatteningBuilder::new(self, tolerance)
+    }
+
+    pub fn move_to(&mut self, to: Point) -> EndpointId {
         nan_check(to);
         self.end_if_needed();
-        let id = EndpointId(self.points.len() as u32);
         self.need_moveto = false;
         self.first_position = to;
+        let id = EndpointId(self.points.len() as u32);
         self.first_vertex = id;
         self.first_verb = self.verbs.len() as u32;
         self.current_position = to;
         self.points.push(to);
-        self.push_attributes(attributes);
         self.verbs.push(Verb::Begin);
         self.last_cmd = Verb::Begin;
 
         id
     }
 
-    pub fn line_to(&mut self, to: Point, attributes: &[f32]) -> EndpointId {
+    pub fn line_to(&mut self, to: Point) -> EndpointId {
         nan_check(to);
         self.move_to_if_needed();
         let id = EndpointId(self.points.len() as u32);
         self.points.push(to);
         self.verbs.push(Verb::LineTo);
-        self.push_attributes(attributes);
         self.current_position = to;
         self.last_cmd = Verb::LineTo;
 
         id
     }
 
+    /// Adds a line segment to `to`, rounding the corner at the current position.
+    ///
+    /// Instead of a sharp corner, a circular arc of the given radius, tangent to the
+    /// incoming and outgoing segments, is inserted. The radius is clamped so that the
+    /// arc fits within the lengths of the two segments.
+    ///
+    /// The corner is only rounded if the previous segment is a line. Otherwise this
+    /// is equivalent to `line_to`.
+    ///
+    /// Rounding the corner edits the end of the incoming segment: the endpoint id
+    /// returned by the previous `line_to` then refers to the start of the arc. If the
+    /// arc spans the whole incoming segment, that endpoint is removed and its id is
+    /// invalidated.
+    pub fn line_to_rounded(&mut self, to: Point, radius: f32) -> EndpointId {
+        nan_check(to);
+        if self.need_moveto || self.last_cmd != Verb::LineTo || radius <= 0.0 {
+            return self.line_to(to);
+        }
+
+        let corner = self.current_position;
+        let prev = self.points[self.points.len() - 2];
+        let incoming = prev - corner;
+        let outgoing = to - corner;
+        let in_length = incoming.length();
+        let out_length = outgoing.length();
+        if in_length == 0.0 || out_length == 0.0 {
+            return self.line_to(to);
+        }
+
+        let d_in = incoming / in_length;
+        let d_out = outgoing / out_length;
+        let angle = d_in.dot(d_out).max(-1.0).min(1.0).acos();
+        let half_angle_tan = (angle * 0.5).tan();
+        if angle < 1e-4 || std::f32::consts::PI - angle < 1e-4 {
+            // U-turn or straight line.
+            return self.line_to(to);
+        }
+
+        // Distance between the corner and the tangent points.
+        let tangent_distance = (radius / half_angle_tan).min(in_length).min(out_length);
+        let radius = tangent_distance * half_angle_tan;
+
+        let arc_start = corner + d_in * tangent_distance;
+        let arc_end = corner + d_out * tangent_distance;
+        let bisector = (d_in + d_out).normalize();
+        let center = corner + bisector * (radius / (angle * 0.5).sin());
+
+        if tangent_distance < in_length {
+            // Move the end of the incoming segment to the start of the arc.
+            *self.points.last_mut().unwrap() = arc_start;
+        } else {
+            // The arc starts at the beginning of the incoming segment.
+            self.points.pop();
+            self.verbs.pop();
+        }
+        self.current_position = arc_start;
+
+        let start = arc_start - center;
+        let end = arc_end - center;
+        let arc = Arc {
+            center,
+            radii: vector(radius, radius),
+            start_angle: start.angle_from_x_axis(),
+            sweep_angle: Angle::radians(start.cross(end).atan2(start.dot(end))),
+            x_rotation: Angle::zero(),
+        };
+        arc.for_each_quadratic_bezier(&mut |curve| {
+            self.quadratic_bezier_to(curve.ctrl, curve.to);
+        });
+
+        if tangent_distance < out_length {
+            self.line_to(to)
+        } else {
+            // The arc ends at the target point.
+            *self.points.last_mut().unwrap() = to;
+            self.current_position = to;
+            EndpointId(self.points.len() as u32 - 1)
+        }
+    }
+
     pub fn close(&mut self) {
         // Relative path ops tend to accumulate small floating point imprecisions
         // which results in the last segment ending almost but not quite at the
@@ -644,19 +1443,13 @@ impl BuilderWithAttributes {
         self.last_cmd = Verb::Close;
     }
 
-    pub fn quadratic_bezier_to(
-        &mut self,
-        ctrl: Point,
-        to: Point,
-        attributes: &[f32],
-    ) -> EndpointId {
+    pub fn quadratic_bezier_to(&mut self, ctrl: Point, to: Point) -> EndpointId {
         nan_check(ctrl);
         nan_check(to);
         self.move_to_if_needed();
         self.points.push(ctrl);
         let id = EndpointId(self.points.len() as u32);
         self.points.push(to);
-        self.push_attributes(attributes);
         self.verbs.push(Verb::QuadraticTo);
         self.current_position = to;
         self.last_cmd = Verb::QuadraticTo;
@@ -664,13 +1457,7 @@ impl BuilderWithAttributes {
         id
     }
 
-    pub fn cubic_bezier_to(
-        &mut self,
-        ctrl1: Point,
-        ctrl2: Point,
-        to: Point,
-        attributes: &[f32],
-    ) -> EndpointId {
+    pub fn cubic_bezier_to(&mut self, ctrl1: Point, ctrl2: Point, to: Point) -> EndpointId {
         nan_check(ctrl1);
         nan_check(ctrl2);
         nan_check(to);
@@ -679,7 +1466,6 @@ impl BuilderWithAttributes {
         self.points.push(ctrl2);
         let id = EndpointId(self.points.len() as u32);
         self.points.push(to);
-        self.push_attributes(attributes);
         self.verbs.push(Verb::CubicTo);
         self.current_position = to;
         self.last_cmd = Verb::CubicTo;
@@ -687,24 +1473,174 @@ impl BuilderWithAttributes {
         id
     }
 
-    fn move_to_if_needed(&mut self) {
-        if !self.need_moveto {
+    /// Starts a new sub-path at an offset from the current position.
+    ///
+    /// Before the first command, the current position is the origin.
+    pub fn move_by(&mut self, to: Vector) -> EndpointId {
+        let to = self.current_position + to;
+        self.move_to(to)
+    }
+
+    /// Adds a line segment to an offset from the current position.
+    pub fn line_by(&mut self, to: Vector) -> EndpointId {
+        let to = self.current_position + to;
+        self.line_to(to)
+    }
+
+    /// Adds a quadratic bézier segment with the control point and the endpoint
+    /// expressed relatively to the current position.
+    pub fn quadratic_bezier_by(&mut self, ctrl: Vector, to: Vector) -> EndpointId {
+        let from = self.current_position;
+        self.quadratic_bezier_to(from + ctrl, from + to)
+    }
+
+    /// Adds a cubic bézier segment with the control points and the endpoint
+    /// expressed relatively to the current position.
+    pub fn cubic_bezier_by(&mut self, ctrl1: Vector, ctrl2: Vector, to: Vector) -> EndpointId {
+        let from = self.current_position;
+        self.cubic_bezier_to(from + ctrl1, from + ctrl2, from + to)
+    }
+
+    /// Adds a cubic bézier segment whose first control point is the reflection of the
+    /// second control point of the previous segment about the current position, like the
+    /// SVG `S` command.
+    ///
+    /// If the previous segment is not a cubic bézier curve, the first control point is
+    /// the current position.
+    pub fn smooth_cubic_bezier_to(&mut self, ctrl2: Point, to: Point) -> EndpointId {
+        let ctrl1 = self.reflected_ctrl(Verb::CubicTo);
+        self.cubic_bezier_to(ctrl1, ctrl2, to)
+    }
+
+    /// Adds a quadratic bézier segment whose control point is the reflection of the
+    /// control point of the previous segment about the current position, like the SVG
+    /// `T` command.
+    ///
+    /// If the previous segment is not a quadratic bézier curve, the control point is the
+    /// current position.
+    pub fn smooth_quadratic_bezier_to(&mut self, to: Point) -> EndpointId {
+        let ctrl = self.reflected_ctrl(Verb::QuadraticTo);
+        self.quadratic_bezier_to(ctrl, to)
+    }
+
+    fn reflected_ctrl(&self, verb: Verb) -> Point {
+        if self.need_moveto || self.last_cmd != verb {
+            return self.current_position;
+        }
+
+        // The last control point is stored right before the current position.
+        let ctrl = self.points[self.points.len() - 2];
+        self.current_position + (self.current_position - ctrl)
+    }
+
+    pub fn arc(&mut self, center: Point, radii: Vector, sweep_angle: Angle, x_rotation: Angle) {
+        nan_check(center);
+        nan_check(radii.to_point());
+        debug_assert!(!sweep_angle.get().is_nan());
+        debug_assert!(!x_rotation.get().is_nan());
+
+        let start_angle = (self.current_position - center).angle_from_x_axis() - x_rotation;
+        let arc = Arc {
+            start_angle,
+            center,
+            radii,
+            sweep_angle,
+            x_rotation,
+        };
+
+        // If the current position is not on the arc, move or line to the beginning of the
+        // arc.
+        let arc_start = arc.from();
+        if (arc_start - self.current_position).square_length() < 0.01 {
+            if self.need_moveto {
+                self.move_to(arc_start);
+            } else {
+                self.line_to(arc_start);
+            }
+        }
+
+        arc.for_each_quadratic_bezier(&mut |curve| {
+            self.quadratic_bezier_to(curve.ctrl, curve.to);
+        });
+    }
+
+    /// Adds an elliptical arc from the current position to `to`, with the same
+    /// parameters as the SVG `A` command.
+    ///
+    /// The arc is approximated with quadratic bézier segments. Negative radii are
+    /// replaced with their absolute value and if either radius is zero, a line segment
+    /// is added instead. If `to` is the current position, nothing is added.
+    pub fn arc_to(&mut self, radii: Vector, x_rotation: Angle, flags: ArcFlags, to: Point) {
+        nan_check(radii.to_point());
+        nan_check(to);
+        debug_assert!(!x_rotation.get().is_nan());
+
+        let from = self.current_position;
+        if from == to {
             return;
         }
 
-        assert!(!self.points.is_empty());
+        let arc = SvgArc {
+            from,
+            to,
+            radii: radii.abs(),
+            x_rotation,
+            flags,
+        };
 
-        let first = self.first_position;
-        self.need_moveto = false;
-        self.current_position = first;
-        self.points.push(first);
-        let first_idx = self.first_vertex.to_usize();
-        for i in 0..(self.num_attributes + 1) / 2 {
-            let val = self.points[first_idx + i];
-            self.points.push(val);
+        if arc.is_straight_line() {
+            self.line_to(to);
+            return;
+        }
+
+        self.move_to_if_needed();
+        arc.to_arc().for_each_quadratic_bezier(&mut |curve| {
+            self.quadratic_bezier_to(curve.ctrl, curve.to);
+        });
+
+        // Make sure the arc ends exactly at the requested position.
+        *self.points.last_mut().unwrap() = to;
+        self.current_position = to;
+    }
+
+    /// Appends the sub-paths of another path.
+    ///
+    /// The current sub-path, if any, is ended before the first sub-path of `other` is
+    /// added, so the appended sub-paths keep their own structure. Custom attributes of
+    /// `other` are ignored.
+    pub fn extend_from_path(&mut self, other: &Path) {
+        self.points.reserve(other.points.len());
+        self.verbs.reserve(other.verbs.len());
+        for evt in other.iter() {
+            self.path_event(evt);
+        }
+    }
+
+    /// Appends the sub-paths of a path slice with a transform applied to all of their
+    /// endpoints and control points.
+    ///
+    /// This is useful to assemble a single path out of many instances of a few shapes.
+    /// As with `extend_from_path`, the current sub-path is ended first and custom
+    /// attributes are ignored. See `BuilderWithAttributes::append_transformed` to keep
+    /// them.
+    pub fn append_transformed<T: Transformation<f32>>(&mut self, slice: &PathSlice, transform: &T) {
+        for evt in slice.iter() {
+            self.path_event(evt.transformed(transform));
+        }
+    }
+
+    /// Add a closed polygon.
+    pub fn polygon(&mut self, points: &[Point]) {
+        self.points.reserve(points.len());
+        self.verbs.reserve(points.len() + 1);
+        build_polygon(self, points);
+    }
+
+    fn move_to_if_needed(&mut self) {
+        if self.need_moveto {
+            let first = self.first_position;
+            self.move_to(first);
         }
-        self.verbs.push(Verb::Begin);
-        self.last_cmd = Verb::Begin;
     }
 
     fn end_if_needed(&mut self) {
@@ -717,144 +1653,533 @@ impl BuilderWithAttributes {
         self.current_position
     }
 
+    /// Builds the path.
+    ///
+    /// The storage of the path is shrunk to fit its content (it is stored in boxed
+    /// slices), so reserving generously with `with_capacity` doesn't waste memory
+    /// in long-lived paths.
     pub fn build(mut self) -> Path {
         self.end_if_needed();
         Path {
             points: self.points.into_boxed_slice(),
             verbs: self.verbs.into_boxed_slice(),
-            num_attributes: self.num_attributes,
+            num_attributes: 0,
+            attribute_names: Box::new([]),
         }
     }
 }
 
-#[inline]
-fn nan_check(p: Point) {
-    debug_assert!(p.x.is_finite());
-    debug_assert!(p.y.is_finite());
-}
+impl Build for Builder {
+    type PathType = Path;
 
-/// An iterator for `Path` and `PathSlice`.
-#[derive(Clone)]
-pub struct Iter<'l> {
-    points: PointIter<'l>,
-    verbs: ::std::slice::Iter<'l, Verb>,
-    current: Point,
-    first: Point,
-    num_attributes: usize,
-    // Number of slots in the points array occupied by the custom attributes.
-    attrib_stride: usize,
-}
+    fn build(self) -> Path {
+        self.build()
+    }
 
-impl<'l> Iter<'l> {
-    fn new(num_attributes: usize, points: &'l [Point], verbs: &'l [Verb]) -> Self {
-        Iter {
-            points: PointIter::new(points),
-            verbs: verbs.iter(),
-            current: point(0.0, 0.0),
-            first: point(0.0, 0.0),
-            num_attributes,
-            attrib_stride: (num_attributes + 1) / 2,
+    fn build_and_reset(&mut self) -> Path {
+        self.current_position = Point::new(0.0, 0.0);
+        self.first_position = Point::new(0.0, 0.0);
+
+        Path {
+            points: std::mem::replace(&mut self.points, Vec::new()).into_boxed_slice(),
+            verbs: std::mem::replace(&mut self.verbs, Vec::new()).into_boxed_slice(),
+            num_attributes: 0,
+            attribute_names: Box::new([]),
         }
     }
+}
 
-    #[inline]
-    fn skip_attributes(&mut self) {
-        self.points.advance_n(self.attrib_stride);
+impl FlatPathBuilder for Builder {
+    fn move_to(&mut self, to: Point) {
+        self.move_to(to);
     }
-}
 
-impl<'l> Iterator for Iter<'l> {
-    type Item = PathEvent;
-    #[inline]
-    fn next(&mut self) -> Option<PathEvent> {
-        match self.verbs.next() {
-            Some(&Verb::Begin) => {
-                self.current = self.points.next();
-                self.skip_attributes();
-                self.first = self.current;
-                Some(PathEvent::Begin { at: self.current })
-            }
-            Some(&Verb::LineTo) => {
-                let from = self.current;
-                self.current = self.points.next();
-                self.skip_attributes();
-                Some(PathEvent::Line {
-                    from,
-                    to: self.current,
-                })
-            }
-            Some(&Verb::QuadraticTo) => {
-                let from = self.current;
-                let ctrl = self.points.next();
-                self.current = self.points.next();
-                self.skip_attributes();
-                Some(PathEvent::Quadratic {
-                    from,
-                    ctrl,
-                    to: self.current,
-                })
-            }
-            Some(&Verb::CubicTo) => {
-                let from = self.current;
-                let ctrl1 = self.points.next();
-                let ctrl2 = self.points.next();
-                self.current = self.points.next();
-                self.skip_attributes();
-                Some(PathEvent::Cubic {
-                    from,
-                    ctrl1,
-                    ctrl2,
-                    to: self.current,
-                })
-            }
-            Some(&Verb::Close) => {
-                let last = self.current;
-                self.current = self.first;
-                Some(PathEvent::End {
-                    last,
-                    first: self.first,
-                    close: true,
-                })
-            }
-            Some(&Verb::End) => {
-                let last = self.current;
-                self.current = self.first;
-                Some(PathEvent::End {
-                    last,
-                    first: self.first,
-                    close: false,
-                })
-            }
-            None => None,
-        }
+    fn line_to(&mut self, to: Point) {
+        self.line_to(to);
     }
-}
 
-/// Manually implemented to avoid iterator overhead when skipping over
-/// several points where the custom attributes are stored.
-///
-/// It makes an unfortunately large difference (the simple iterator
-/// benchmarks are 2 to 3 times faster).
-#[derive(Copy, Clone)]
-struct PointIter<'l> {
-    ptr: *const Point,
-    end: *const Point,
-    _marker: std::marker::PhantomData<&'l Point>,
+    fn close(&mut self) {
+        self.close();
+    }
+
+    fn current_position(&self) -> Point {
+        self.current_position
+    }
 }
 
-impl<'l> PointIter<'l> {
-    fn new(slice: &[Point]) -> Self {
-        let ptr = slice.as_ptr();
-        let end = unsafe { ptr.offset(slice.len() as isize) };
-        PointIter {
-            ptr,
-            end,
-            _marker: std::marker::PhantomData,
-        }
+impl PolygonBuilder for Builder {
+    fn polygon(&mut self, points: &[Point]) {
+        self.polygon(points);
     }
+}
 
-    #[inline]
-    fn next(&mut self) -> Point {
+impl PathBuilder for Builder {
+    fn quadratic_bezier_to(&mut self, ctrl: Point, to: Point) {
+        self.quadratic_bezier_to(ctrl, to);
+    }
+
+    fn cubic_bezier_to(&mut self, ctrl1: Point, ctrl2: Point, to: Point) {
+        self.cubic_bezier_to(ctrl1, ctrl2, to);
+    }
+
+    fn arc(&mut self, center: Point, radii: Vector, sweep_angle: Angle, x_rotation: Angle) {
+        self.arc(center, radii, sweep_angle, x_rotation);
+    }
+}
+
+/// Builds path objects with custom attributes.
+pub struct BuilderWithAttributes {
+    points: Vec<Point>,
+    verbs: Vec<Verb>,
+    current_position: Point,
+    first_position: Point,
+    first_vertex: EndpointId,
+    first_verb: u32,
+    need_moveto: bool,
+    last_cmd: Verb,
+    num_attributes: usize,
+    attribute_names: Vec<String>,
+}
+
+impl BuilderWithAttributes {
+    pub fn new(num_attributes: usize) -> Self {
+        BuilderWithAttributes::with_capacity(num_attributes, 0, 0)
+    }
+
+    pub fn with_capacity(num_attributes: usize, points: usize, edges: usize) -> Self {
+        BuilderWithAttributes {
+            points: Vec::with_capacity(points),
+            verbs: Vec::with_capacity(edges),
+            current_position: Point::new(0.0, 0.0),
+            first_position: Point::new(0.0, 0.0),
+            first_vertex: EndpointId(0),
+            first_verb: 0,
+            need_moveto: true,
+            last_cmd: Verb::End,
+            num_attributes,
+            attribute_names: Vec::new(),
+        }
+    }
+
+    fn push_attributes(&mut self, attributes: &[f32]) {
+        assert_eq!(attributes.len(), self.num_attributes);
+        for i in 0..(self.num_attributes / 2) {
+            let x = attributes[i * 2];
+            let y = attributes[i * 2 + 1];
+            self.points.push(point(x, y));
+        }
+        if self.num_attributes % 2 == 1 {
+            let x = attributes[self.num_attributes - 1];
+            self.points.push(point(x, 0.0));
+        }
+    }
+
+    pub fn move_to(&mut self, to: Point, attributes: &[f32]) -> EndpointId {
+        nan_check(to);
+        self.end_if_needed();
+        let id = EndpointId(self.points.len() as u32);
+        self.need_moveto = false;
+        self.first_position = to;
+        self.first_vertex = id;
+        self.first_verb = self.verbs.len() as u32;
+        self.current_position = to;
+        self.points.push(to);
+        self.push_attributes(attributes);
+        self.verbs.push(Verb::Begin);
+        self.last_cmd = Verb::Begin;
+
+        id
+    }
+
+    pub fn line_to(&mut self, to: Point, attributes: &[f32]) -> EndpointId {
+        nan_check(to);
+        self.move_to_if_needed();
+        let id = EndpointId(self.points.len() as u32);
+        self.points.push(to);
+        self.verbs.push(Verb::LineTo);
+        self.push_attributes(attributes);
+        self.current_position = to;
+        self.last_cmd = Verb::LineTo;
+
+        id
+    }
+
+    pub fn close(&mut self) {
+        // Relative path ops tend to accumulate small floating point imprecisions
+        // which results in the last segment ending almost but not quite at the
+        // start of the sub-path, causing a new edge to be inserted which often
+        // intersects with the first or last edge. This can affect algorithms that
+        // Don't handle self-intersecting paths.
+        // Deal with this by snapping the last point if it is very close to the
+        // start of the sub path.
+        if let Some(p) = self.points.last_mut() {
+            let d = (*p - self.first_position).abs();
+            if d.x + d.y < 0.0001 {
+                *p = self.first_position;
+            }
+        }
+
+        self.verbs.push(Verb::Close);
+        self.current_position = self.first_position;
+        self.need_moveto = true;
+        self.last_cmd = Verb::Close;
+    }
+
+    pub fn quadratic_bezier_to(
+        &mut self,
+        ctrl: Point,
+        to: Point,
+        attributes: &[f32],
+    ) -> EndpointId {
+        nan_check(ctrl);
+        nan_check(to);
+        self.move_to_if_needed();
+        self.points.push(ctrl);
+        let id = EndpointId(self.points.len() as u32);
+        self.points.push(to);
+        self.push_attributes(attributes);
+        self.verbs.push(Verb::QuadraticTo);
+        self.current_position = to;
+        self.last_cmd = Verb::QuadraticTo;
+
+        id
+    }
+
+    pub fn cubic_bezier_to(
+        &mut self,
+        ctrl1: Point,
+        ctrl2: Point,
+        to: Point,
+        attributes: &[f32],
+    ) -> EndpointId {
+        nan_check(ctrl1);
+        nan_check(ctrl2);
+        nan_check(to);
+        self.move_to_if_needed();
+        self.points.push(ctrl1);
+        self.points.push(ctrl2);
+        let id = EndpointId(self.points.len() as u32);
+        self.points.push(to);
+        self.push_attributes(attributes);
+        self.verbs.push(Verb::CubicTo);
+        self.current_position = to;
+        self.last_cmd = Verb::CubicTo;
+
+        id
+    }
+
+    /// Appends the sub-paths of a path slice with a transform applied to all of their
+    /// endpoints and control points.
+    ///
+    /// The custom attributes of the endpoints are copied unchanged. The current sub-path
+    /// is ended first.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the slice doesn't have the same number of custom attributes as the
+    /// builder.
+    pub fn append_transformed<T: Transformation<f32>>(&mut self, slice: &PathSlice, transform: &T) {
+        assert_eq!(slice.num_attributes, self.num_attributes);
+        for evt in slice.iter_with_attributes() {
+            match evt {
+                Event::Begin {
+                    at: (to, attributes),
+                } => {
+                    self.move_to(transform.transform_point(to), attributes);
+                }
+                Event::Line {
+                    to: (to, attributes),
+                    ..
+                } => {
+                    self.line_to(transform.transform_point(to), attributes);
+                }
+                Event::Quadratic {
+                    ctrl,
+                    to: (to, attributes),
+                    ..
+                } => {
+                    self.quadratic_bezier_to(
+                        transform.transform_point(ctrl),
+                        transform.transform_point(to),
+                        attributes,
+                    );
+                }
+                Event::Cubic {
+                    ctrl1,
+                    ctrl2,
+                    to: (to, attributes),
+                    ..
+                } => {
+                    self.cubic_bezier_to(
+                        transform.transform_point(ctrl1),
+                        transform.transform_point(ctrl2),
+                        transform.transform_point(to),
+                        attributes,
+                    );
+                }
+                Event::End { close: true, .. } => {
+                    self.close();
+                }
+                Event::End { close: false, .. } => {}
+            }
+        }
+    }
+
+    fn move_to_if_needed(&mut self) {
+        if !self.need_moveto {
+            return;
+        }
+
+        assert!(!self.points.is_empty());
+
+        let first = self.first_position;
+        self.need_moveto = false;
+        self.current_position = first;
+        self.points.push(first);
+        let first_idx = self.first_vertex.to_usize();
+        for i in 0..(self.num_attributes + 1) / 2 {
+            let val = self.points[first_idx + i];
+            self.points.push(val);
+        }
+        self.verbs.push(Verb::Begin);
+        self.last_cmd = Verb::Begin;
+    }
+
+    fn end_if_needed(&mut self) {
+        if (self.last_cmd as u8) <= (Verb::Begin as u8) {
+            self.verbs.push(Verb::End);
+        }
+    }
+
+    pub fn current_position(&self) -> Point {
+        self.current_position
+    }
+
+    /// Builds the path.
+    ///
+    /// The storage of the path is shrunk to fit its content (it is stored in boxed
+    /// slices), so reserving generously with `with_capacity` doesn't waste memory
+    /// in long-lived paths.
+    pub fn build(mut self) -> Path {
+        self.end_if_needed();
+        Path {
+            points: self.points.into_boxed_slice(),
+            verbs: self.verbs.into_boxed_slice(),
+            num_attributes: self.num_attributes,
+            attribute_names: self.attribute_names.into_boxed_slice(),
+        }
+    }
+}
+
+#[inline]
+fn nan_check(p: Point) {
+    debug_assert!(p.x.is_finite());
+    debug_assert!(p.y.is_finite());
+}
+
+/// An iterator for `Path` and `PathSlice`.
+///
+/// The iterator is double-ended: iterating from the back yields exactly the same
+/// events as iterating from the front, in the opposite order. In particular each
+/// sub-path produces its `End` event first and its `Begin` event last, and the
+/// events themselves are not reversed (their `from` and `to` are unchanged). Use
+/// `PathIterator::reversed` to walk the path in the opposite direction instead.
+///
+/// The `from` endpoint of a segment and the `first` endpoint of an `End` event are
+/// read directly from the path's storage, so iterating from the back doesn't
+/// allocate. Both ends can be consumed alternately, until they meet.
+#[derive(Clone)]
+pub struct Iter<'l> {
+    points: PointIter<'l>,
+    verbs: ::std::slice::Iter<'l, Verb>,
+    current: Point,
+    first: Point,
+    num_attributes: usize,
+    // Number of slots in the points array occupied by the custom attributes.
+    attrib_stride: usize,
+}
+
+impl<'l> Iter<'l> {
+    fn new(num_attributes: usize, points: &'l [Point], verbs: &'l [Verb]) -> Self {
+        Iter {
+            points: PointIter::new(points),
+            verbs: verbs.iter(),
+            current: point(0.0, 0.0),
+            first: point(0.0, 0.0),
+            num_attributes,
+            attrib_stride: (num_attributes + 1) / 2,
+        }
+    }
+
+    #[inline]
+    fn skip_attributes(&mut self) {
+        self.points.advance_n(self.attrib_stride);
+    }
+    // Finds the first endpoint of the sub-path that ends at the back of the iterator
+    // by walking back to its Begin verb.
+    fn first_from_back(&self) -> Point {
+        let mut offset = 0;
+        for &verb in self.verbs.as_slice().iter().rev() {
+            match verb {
+                Verb::Close | Verb::End => break,
+                _ => {}
+            }
+            offset += n_stored_points(verb, self.attrib_stride);
+            if verb == Verb::Begin {
+                return self.points.back(-(offset as isize));
+            }
+        }
+
+        // The Begin verb was already consumed from the front.
+        self.first
+    }
+}
+
+impl<'l> Iterator for Iter<'l> {
+    type Item = PathEvent;
+    #[inline]
+    fn next(&mut self) -> Option<PathEvent> {
+        match self.verbs.next() {
+            Some(&Verb::Begin) => {
+                self.current = self.points.next();
+                self.skip_attributes();
+                self.first = self.current;
+                Some(PathEvent::Begin { at: self.current })
+            }
+            Some(&Verb::LineTo) => {
+                let from = self.current;
+                self.current = self.points.next();
+                self.skip_attributes();
+                Some(PathEvent::Line {
+                    from,
+                    to: self.current,
+                })
+            }
+            Some(&Verb::QuadraticTo) => {
+                let from = self.current;
+                let ctrl = self.points.next();
+                self.current = self.points.next();
+                self.skip_attributes();
+                Some(PathEvent::Quadratic {
+                    from,
+                    ctrl,
+                    to: self.current,
+                })
+            }
+            Some(&Verb::CubicTo) => {
+                let from = self.current;
+                let ctrl1 = self.points.next();
+                let ctrl2 = self.points.next();
+                self.current = self.points.next();
+                self.skip_attributes();
+                Some(PathEvent::Cubic {
+                    from,
+                    ctrl1,
+                    ctrl2,
+                    to: self.current,
+                })
+            }
+            Some(&Verb::Close) => {
+                let last = self.current;
+                self.current = self.first;
+                Some(PathEvent::End {
+                    last,
+                    first: self.first,
+                    close: true,
+                })
+            }
+            Some(&Verb::End) => {
+                let last = self.current;
+                self.current = self.first;
+                Some(PathEvent::End {
+                    last,
+                    first: self.first,
+                    close: false,
+                })
+            }
+            None => None,
+        }
+    }
+}
+
+impl<'l> DoubleEndedIterator for Iter<'l> {
+    #[inline]
+    fn next_back(&mut self) -> Option<PathEvent> {
+        // Offset from the end of the remaining points to the previous endpoint.
+        let previous = -(1 + self.attrib_stride as isize);
+        match self.verbs.next_back() {
+            Some(&Verb::Begin) => {
+                self.points.retreat_n(1 + self.attrib_stride);
+                Some(PathEvent::Begin {
+                    at: self.points.back(0),
+                })
+            }
+            Some(&Verb::LineTo) => {
+                self.points.retreat_n(1 + self.attrib_stride);
+                Some(PathEvent::Line {
+                    from: self.points.back(previous),
+                    to: self.points.back(0),
+                })
+            }
+            Some(&Verb::QuadraticTo) => {
+                self.points.retreat_n(2 + self.attrib_stride);
+                Some(PathEvent::Quadratic {
+                    from: self.points.back(previous),
+                    ctrl: self.points.back(0),
+                    to: self.points.back(1),
+                })
+            }
+            Some(&Verb::CubicTo) => {
+                self.points.retreat_n(3 + self.attrib_stride);
+                Some(PathEvent::Cubic {
+                    from: self.points.back(previous),
+                    ctrl1: self.points.back(0),
+                    ctrl2: self.points.back(1),
+                    to: self.points.back(2),
+                })
+            }
+            Some(&Verb::Close) => Some(PathEvent::End {
+                last: self.points.back(previous),
+                first: self.first_from_back(),
+                close: true,
+            }),
+            Some(&Verb::End) => Some(PathEvent::End {
+                last: self.points.back(previous),
+                first: self.first_from_back(),
+                close: false,
+            }),
+            None => None,
+        }
+    }
+}
+
+/// Manually implemented to avoid iterator overhead when skipping over
+/// several points where the custom attributes are stored.
+///
+/// It makes an unfortunately large difference (the simple iterator
+/// benchmarks are 2 to 3 times faster).
+#[derive(Copy, Clone)]
+struct PointIter<'l> {
+    start: *const Point,
+    ptr: *const Point,
+    end: *const Point,
+    _marker: std::marker::PhantomData<&'l Point>,
+}
+
+impl<'l> PointIter<'l> {
+    fn new(slice: &[Point]) -> Self {
+        let ptr = slice.as_ptr();
+        let end = unsafe { ptr.offset(slice.len() as isize) };
+        PointIter {
+            start: ptr,
+            ptr,
+            end,
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    #[inline]
+    fn next(&mut self) -> Point {
         // Don't bother panicking here. calls to next
         // are always followed by advance_n which will
         // catch the issue and panic.
@@ -877,6 +2202,26 @@ impl<'l> PointIter<'l> {
             assert!(self.ptr <= self.end)
         }
     }
+
+    #[inline]
+    fn retreat_n(&mut self, n: usize) {
+        assert!(n <= (self.end as usize - self.ptr as usize) / std::mem::size_of::<Point>());
+        unsafe {
+            self.end = self.end.offset(-(n as isize));
+        }
+    }
+
+    // Reads the point at a given offset from the end of the remaining points.
+    // Negative offsets may point to slots that were already consumed from the
+    // front, positive ones to slots that were just consumed from the back.
+    #[inline]
+    fn back(&self, offset: isize) -> Point {
+        unsafe {
+            let ptr = self.end.offset(offset);
+            assert!(ptr >= self.start);
+            *ptr
+        }
+    }
 }
 
 /// An iterator for `Path` and `PathSlice`.
@@ -1080,6 +2425,28 @@ impl<'l> Iterator for IdIter<'l> {
     }
 }
 
+/// An iterator over the endpoint and control point ids of the segments of a `Path`.
+///
+/// Produced by `Path::id_segments` and `PathSlice::id_segments`.
+#[derive(Clone, Debug)]
+pub struct IdSegmentIter<'l> {
+    iter: IdIter<'l>,
+}
+
+impl<'l> Iterator for IdSegmentIter<'l> {
+    type Item = IdSegment;
+    #[inline]
+    fn next(&mut self) -> Option<IdSegment> {
+        while let Some(evt) = self.iter.next() {
+            if let Some(segment) = IdSegment::from_event(evt) {
+                return Some(segment);
+            }
+        }
+
+        None
+    }
+}
+
 #[inline]
 fn interpolated_attributes(
     num_attributes: usize,
@@ -1101,6 +2468,7 @@ fn interpolated_attributes(
 
 fn reverse_path(path: PathSlice) -> Path {
     let mut builder = Path::builder_with_attributes(path.num_attributes());
+    builder.attribute_names = path.attribute_names.to_vec();
 
     let attrib_stride = (path.num_attributes() + 1) / 2;
     let points = &path.points[..];
@@ -1151,21 +2519,153 @@ fn reverse_path(path: PathSlice) -> Path {
                 );
             }
         }
-        p -= n_stored_points(v, attrib_stride);
+        p -= n_stored_points(v, attrib_stride);
+    }
+
+    builder.build()
+}
+
+fn n_stored_points(verb: Verb, attrib_stride: usize) -> usize {
+    match verb {
+        Verb::Begin => attrib_stride + 1,
+        Verb::LineTo => attrib_stride + 1,
+        Verb::QuadraticTo => attrib_stride + 2,
+        Verb::CubicTo => attrib_stride + 3,
+        Verb::Close => 0,
+        Verb::End => 0,
+    }
+}
+
+// Even-odd point in polygon test.
+fn polygon_contains_point(polygon: &[Point], p: Point) -> bool {
+    let mut inside = false;
+    let mut prev = match polygon.last() {
+        Some(last) => *last,
+        None => return false,
+    };
+    for &current in polygon {
+        if (current.y > p.y) != (prev.y > p.y) {
+            let x = prev.x + (p.y - prev.y) / (current.y - prev.y) * (current.x - prev.x);
+            if p.x < x {
+                inside = !inside;
+            }
+        }
+        prev = current;
+    }
+
+    inside
+}
+
+// Emits the line segments of a flattened curve. The last point produced by the flattening
+// can be slightly off because of floating point precision, so it is replaced with the
+// endpoint of the curve to keep the segments connected to the next events.
+fn for_each_flattened_curve(
+    from: Point,
+    to: Point,
+    callback: &mut dyn FnMut(&PathEvent),
+    flatten: &mut dyn FnMut(&mut dyn FnMut(Point)),
+) {
+    let mut prev = from;
+    let mut pending = None;
+    flatten(&mut |p| {
+        if let Some(next) = pending {
+            callback(&PathEvent::Line {
+                from: prev,
+                to: next,
+            });
+            prev = next;
+        }
+        pending = Some(p);
+    });
+
+    if pending.is_some() {
+        callback(&PathEvent::Line { from: prev, to });
+    }
+}
+
+fn non_zero_or(v: Vector, fallback: Vector) -> Vector {
+    if v == vector(0.0, 0.0) {
+        fallback
+    } else {
+        v
+    }
+}
+
+// Iterative form of Welzl's algorithm.
+//
+// The expected linear running time relies on the points being visited in random
+// order: ordered inputs such as the vertices of a flattened circle otherwise hit
+// the cubic worst case. A fixed-seed shuffle keeps the output reproducible.
+fn smallest_enclosing_circle(points: &mut [Point]) -> (Point, f32) {
+    if points.is_empty() {
+        return (point(0.0, 0.0), 0.0);
+    }
+
+    // Fisher-Yates shuffle driven by a xorshift generator.
+    let mut seed: u32 = 0x9E37_79B9;
+    for i in (1..points.len()).rev() {
+        seed ^= seed << 13;
+        seed ^= seed >> 17;
+        seed ^= seed << 5;
+        points.swap(i, seed as usize % (i + 1));
+    }
+
+    let mut circle = (points[0], 0.0);
+    for i in 1..points.len() {
+        if circle_contains(circle, points[i]) {
+            continue;
+        }
+        circle = (points[i], 0.0);
+        for j in 0..i {
+            if circle_contains(circle, points[j]) {
+                continue;
+            }
+            circle = circle_from_2_points(points[i], points[j]);
+            for k in 0..j {
+                if !circle_contains(circle, points[k]) {
+                    circle = circle_from_3_points(points[i], points[j], points[k]);
+                }
+            }
+        }
     }
 
-    builder.build()
+    circle
 }
 
-fn n_stored_points(verb: Verb, attrib_stride: usize) -> usize {
-    match verb {
-        Verb::Begin => attrib_stride + 1,
-        Verb::LineTo => attrib_stride + 1,
-        Verb::QuadraticTo => attrib_stride + 2,
-        Verb::CubicTo => attrib_stride + 3,
-        Verb::Close => 0,
-        Verb::End => 0,
+fn circle_contains(circle: (Point, f32), p: Point) -> bool {
+    (p - circle.0).length() <= circle.1 * (1.0 + 1e-5) + 1e-5
+}
+
+fn circle_from_2_points(a: Point, b: Point) -> (Point, f32) {
+    let center = a.lerp(b, 0.5);
+    (center, (a - center).length())
+}
+
+fn circle_from_3_points(a: Point, b: Point, c: Point) -> (Point, f32) {
+    let ab = b - a;
+    let ac = c - a;
+    let d = 2.0 * ab.cross(ac);
+    if d.abs() < 1e-10 {
+        // The points are aligned, the circle is defined by the two farthest ones.
+        let candidates = [
+            circle_from_2_points(a, b),
+            circle_from_2_points(a, c),
+            circle_from_2_points(b, c),
+        ];
+        let mut circle = candidates[0];
+        for candidate in &candidates[1..] {
+            if candidate.1 > circle.1 {
+                circle = *candidate;
+            }
+        }
+        return circle;
     }
+
+    let ab2 = ab.square_length();
+    let ac2 = ac.square_length();
+    let offset = vector(ac.y * ab2 - ab.y * ac2, ab.x * ac2 - ac.x * ab2) / d;
+
+    (a + offset, offset.length())
 }
 
 #[test]
@@ -1491,288 +2991,1603 @@ fn test_path_builder_1() {
     );
     assert_eq!(
         it.next(),
-        Some(Event::Quadratic {
-            from: (point(13.0, 0.0), &[9.0][..]),
-            ctrl: point(14.0, 0.0),
-            to: (point(14.0, 1.0), &[10.0][..]),
+        Some(Event::Quadratic {
+            from: (point(13.0, 0.0), &[9.0][..]),
+            ctrl: point(14.0, 0.0),
+            to: (point(14.0, 1.0), &[10.0][..]),
+        })
+    );
+    assert_eq!(
+        it.next(),
+        Some(Event::Cubic {
+            from: (point(14.0, 1.0), &[10.0][..]),
+            ctrl1: point(15.0, 0.0),
+            ctrl2: point(15.0, 1.0),
+            to: (point(15.0, 2.0), &[11.0][..]),
+        })
+    );
+    assert_eq!(
+        it.next(),
+        Some(Event::End {
+            last: (point(15.0, 2.0), &[11.0][..]),
+            first: (point(10.0, 0.0), &[6.0][..]),
+            close: true
+        })
+    );
+
+    // Not clear that this is the most useful behavior.
+    // Closing when there is no path should probably be dropped.
+    assert_eq!(
+        it.next(),
+        Some(Event::End {
+            last: (point(10.0, 0.0), &[6.0][..]),
+            first: (point(10.0, 0.0), &[6.0][..]),
+            close: true,
+        })
+    );
+
+    assert_eq!(
+        it.next(),
+        Some(Event::Begin {
+            at: (point(1.0, 1.0), &[12.0][..])
+        })
+    );
+    assert_eq!(
+        it.next(),
+        Some(Event::End {
+            last: (point(1.0, 1.0), &[12.0][..]),
+            first: (point(1.0, 1.0), &[12.0][..]),
+            close: false
+        })
+    );
+    assert_eq!(
+        it.next(),
+        Some(Event::Begin {
+            at: (point(2.0, 2.0), &[13.0][..])
+        })
+    );
+    assert_eq!(
+        it.next(),
+        Some(Event::End {
+            last: (point(2.0, 2.0), &[13.0][..]),
+            first: (point(2.0, 2.0), &[13.0][..]),
+            close: false
+        })
+    );
+    assert_eq!(
+        it.next(),
+        Some(Event::Begin {
+            at: (point(3.0, 3.0), &[14.0][..])
+        })
+    );
+    assert_eq!(
+        it.next(),
+        Some(Event::Line {
+            from: (point(3.0, 3.0), &[14.0][..]),
+            to: (point(4.0, 4.0), &[15.0][..])
+        })
+    );
+    assert_eq!(
+        it.next(),
+        Some(Event::End {
+            last: (point(4.0, 4.0), &[15.0][..]),
+            first: (point(3.0, 3.0), &[14.0][..]),
+            close: false
+        })
+    );
+    assert_eq!(it.next(), None);
+    assert_eq!(it.next(), None);
+    assert_eq!(it.next(), None);
+}
+
+#[test]
+fn test_path_builder_empty() {
+    let path = Path::builder_with_attributes(5).build();
+    let mut it = path.iter();
+    assert_eq!(it.next(), None);
+    assert_eq!(it.next(), None);
+}
+
+#[test]
+fn test_path_builder_empty_move_to() {
+    let mut p = Path::builder_with_attributes(1);
+    p.move_to(point(1.0, 2.0), &[0.0]);
+    p.move_to(point(3.0, 4.0), &[1.0]);
+    p.move_to(point(5.0, 6.0), &[2.0]);
+
+    let path = p.build();
+    let mut it = path.iter();
+    assert_eq!(
+        it.next(),
+        Some(PathEvent::Begin {
+            at: point(1.0, 2.0)
+        })
+    );
+    assert_eq!(
+        it.next(),
+        Some(PathEvent::End {
+            last: point(1.0, 2.0),
+            first: point(1.0, 2.0),
+            close: false,
+        })
+    );
+    assert_eq!(
+        it.next(),
+        Some(PathEvent::Begin {
+            at: point(3.0, 4.0)
+        })
+    );
+    assert_eq!(
+        it.next(),
+        Some(PathEvent::End {
+            last: point(3.0, 4.0),
+            first: point(3.0, 4.0),
+            close: false,
+        })
+    );
+    assert_eq!(
+        it.next(),
+        Some(PathEvent::Begin {
+            at: point(5.0, 6.0)
+        })
+    );
+    assert_eq!(
+        it.next(),
+        Some(PathEvent::End {
+            last: point(5.0, 6.0),
+            first: point(5.0, 6.0),
+            close: false,
+        })
+    );
+    assert_eq!(it.next(), None);
+    assert_eq!(it.next(), None);
+}
+
+#[test]
+fn test_path_builder_line_to_after_close() {
+    let mut p = Path::builder();
+    p.line_to(point(1.0, 0.0));
+    p.close();
+    p.line_to(point(2.0, 0.0));
+
+    let path = p.build();
+    let mut it = path.iter();
+    assert_eq!(
+        it.next(),
+        Some(PathEvent::Begin {
+            at: point(0.0, 0.0)
+        })
+    );
+    assert_eq!(
+        it.next(),
+        Some(PathEvent::Line {
+            from: point(0.0, 0.0),
+            to: point(1.0, 0.0)
+        })
+    );
+    assert_eq!(
+        it.next(),
+        Some(PathEvent::End {
+            last: point(1.0, 0.0),
+            first: point(0.0, 0.0),
+            close: true
+        })
+    );
+    assert_eq!(
+        it.next(),
+        Some(PathEvent::Begin {
+            at: point(0.0, 0.0)
+        })
+    );
+    assert_eq!(
+        it.next(),
+        Some(PathEvent::Line {
+            from: point(0.0, 0.0),
+            to: point(2.0, 0.0)
+        })
+    );
+    assert_eq!(
+        it.next(),
+        Some(PathEvent::End {
+            last: point(2.0, 0.0),
+            first: point(0.0, 0.0),
+            close: false
+        })
+    );
+    assert_eq!(it.next(), None);
+}
+
+#[test]
+fn test_merge_paths() {
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(5.0, 0.0));
+    builder.line_to(point(5.0, 5.0));
+    builder.close();
+
+    let path1 = builder.build();
+
+    let mut builder = Path::builder();
+    builder.move_to(point(1.0, 1.0));
+    builder.line_to(point(4.0, 0.0));
+    builder.line_to(point(4.0, 4.0));
+    builder.close();
+
+    let path2 = builder.build();
+
+    let path = path1.merge(&path2);
+
+    let mut it = path.iter();
+    assert_eq!(
+        it.next(),
+        Some(PathEvent::Begin {
+            at: point(0.0, 0.0)
+        })
+    );
+    assert_eq!(
+        it.next(),
+        Some(PathEvent::Line {
+            from: point(0.0, 0.0),
+            to: point(5.0, 0.0)
         })
     );
     assert_eq!(
         it.next(),
-        Some(Event::Cubic {
-            from: (point(14.0, 1.0), &[10.0][..]),
-            ctrl1: point(15.0, 0.0),
-            ctrl2: point(15.0, 1.0),
-            to: (point(15.0, 2.0), &[11.0][..]),
+        Some(PathEvent::Line {
+            from: point(5.0, 0.0),
+            to: point(5.0, 5.0)
         })
     );
     assert_eq!(
         it.next(),
-        Some(Event::End {
-            last: (point(15.0, 2.0), &[11.0][..]),
-            first: (point(10.0, 0.0), &[6.0][..]),
+        Some(PathEvent::End {
+            last: point(5.0, 5.0),
+            first: point(0.0, 0.0),
             close: true
         })
     );
-
-    // Not clear that this is the most useful behavior.
-    // Closing when there is no path should probably be dropped.
     assert_eq!(
         it.next(),
-        Some(Event::End {
-            last: (point(10.0, 0.0), &[6.0][..]),
-            first: (point(10.0, 0.0), &[6.0][..]),
-            close: true,
+        Some(PathEvent::Begin {
+            at: point(1.0, 1.0)
         })
     );
-
     assert_eq!(
         it.next(),
-        Some(Event::Begin {
-            at: (point(1.0, 1.0), &[12.0][..])
+        Some(PathEvent::Line {
+            from: point(1.0, 1.0),
+            to: point(4.0, 0.0)
         })
     );
     assert_eq!(
         it.next(),
-        Some(Event::End {
-            last: (point(1.0, 1.0), &[12.0][..]),
-            first: (point(1.0, 1.0), &[12.0][..]),
-            close: false
+        Some(PathEvent::Line {
+            from: point(4.0, 0.0),
+            to: point(4.0, 4.0)
         })
     );
     assert_eq!(
         it.next(),
-        Some(Event::Begin {
-            at: (point(2.0, 2.0), &[13.0][..])
+        Some(PathEvent::End {
+            last: point(4.0, 4.0),
+            first: point(1.0, 1.0),
+            close: true
         })
     );
+    assert_eq!(it.next(), None);
+}
+
+#[test]
+fn test_bounding_circle() {
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(1.0, 0.0));
+    builder.line_to(point(1.0, 1.0));
+    builder.line_to(point(0.0, 1.0));
+    builder.close();
+    let path = builder.build();
+
+    let (center, radius) = path.as_slice().bounding_circle(0.01);
+    assert!((center - point(0.5, 0.5)).length() < 0.0001);
+    assert!((radius - 2.0f32.sqrt() / 2.0).abs() < 0.0001);
+
+    let (center, radius) = Path::new().as_slice().bounding_circle(0.01);
+    assert_eq!(center, point(0.0, 0.0));
+    assert_eq!(radius, 0.0);
+}
+
+#[test]
+fn test_corner_angles() {
+    use std::f32::consts::PI;
+
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(1.0, 0.0));
+    builder.line_to(point(1.0, 1.0));
+    builder.line_to(point(0.0, 1.0));
+    builder.close();
+    let path = builder.build();
+
+    let angles = path.as_slice().corner_angles();
+    assert_eq!(angles.len(), 4);
+    for &(_, angle) in &angles {
+        assert!((angle - PI * 0.5).abs() < 0.0001);
+    }
+    assert_eq!(angles[0].0, 1);
+    assert_eq!(angles[3].0, 4);
+
+    let mut builder = Path::builder();
+    builder.move_to(point(1.0, 0.0));
+    builder.arc(
+        point(0.0, 0.0),
+        vector(1.0, 1.0),
+        Angle::degrees(270.0),
+        Angle::zero(),
+    );
+    let path = builder.build();
+
+    let angles = path.as_slice().corner_angles();
+    assert!(!angles.is_empty());
+    for &(_, angle) in &angles {
+        assert!(angle < 0.01);
+    }
+}
+
+#[test]
+fn test_reverse_subpath() {
+    fn signed_areas(path: &Path) -> Vec<f32> {
+        let mut areas = Vec::new();
+        for evt in path.iter() {
+            match evt {
+                PathEvent::Begin { .. } => areas.push(0.0),
+                PathEvent::Line { from, to } => {
+                    *areas.last_mut().unwrap() += from.to_vector().cross(to.to_vector());
+                }
+                PathEvent::End { last, first, .. } => {
+                    *areas.last_mut().unwrap() += last.to_vector().cross(first.to_vector());
+                }
+                _ => panic!(),
+            }
+        }
+
+        areas
+    }
+
+    let mut builder = Path::builder_with_attributes(1);
+    builder.move_to(point(0.0, 0.0), &[0.0]);
+    builder.line_to(point(10.0, 0.0), &[1.0]);
+    builder.line_to(point(10.0, 10.0), &[2.0]);
+    builder.line_to(point(0.0, 10.0), &[3.0]);
+    builder.close();
+    builder.move_to(point(2.0, 2.0), &[4.0]);
+    builder.line_to(point(8.0, 2.0), &[5.0]);
+    builder.line_to(point(8.0, 8.0), &[6.0]);
+    builder.line_to(point(2.0, 8.0), &[7.0]);
+    builder.close();
+    let mut path = builder.build();
+
+    let before = signed_areas(&path);
+    path.reverse_subpath(1);
+    let after = signed_areas(&path);
+
+    assert_eq!(after[0], before[0]);
+    assert_eq!(after[1], -before[1]);
+
+    let mut it = path.id_iter().skip(5);
+    if let Some(IdEvent::Begin { at }) = it.next() {
+        assert_eq!(path[at], point(2.0, 8.0));
+        assert_eq!(path.attributes(at), &[7.0]);
+    } else {
+        panic!();
+    }
+    if let Some(IdEvent::Line { to, .. }) = it.next() {
+        assert_eq!(path[to], point(8.0, 8.0));
+        assert_eq!(path.attributes(to), &[6.0]);
+    } else {
+        panic!();
+    }
+}
+
+#[test]
+fn test_connected_components() {
+    fn square(builder: &mut Builder, x: f32, y: f32, size: f32) {
+        builder.move_to(point(x, y));
+        builder.line_to(point(x + size, y));
+        builder.line_to(point(x + size, y + size));
+        builder.line_to(point(x, y + size));
+        builder.close();
+    }
+
+    let mut builder = Path::builder();
+    square(&mut builder, 0.0, 0.0, 10.0);
+    square(&mut builder, 20.0, 0.0, 10.0);
+    square(&mut builder, 2.0, 2.0, 6.0);
+    square(&mut builder, 22.0, 2.0, 6.0);
+    let path = builder.build();
+
+    let components = path.as_slice().connected_components(0.1);
+    assert_eq!(components.len(), 2);
+
+    for (component, x) in components.iter().zip(&[0.0, 20.0]) {
+        let starts: Vec<Point> = component
+            .iter()
+            .filter_map(|evt| match evt {
+                PathEvent::Begin { at } => Some(at),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(starts, vec![point(*x, 0.0), point(x + 2.0, 2.0)]);
+    }
+}
+
+#[test]
+fn test_line_to_rounded() {
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to_rounded(point(10.0, 10.0), 2.0);
+    let path = builder.build();
+
+    let center = point(8.0, 2.0);
+    let mut num_curves = 0;
+    for evt in path.iter() {
+        match evt {
+            PathEvent::Line { from, to } => {
+                assert!(from != point(10.0, 0.0));
+                assert!(to != point(10.0, 0.0));
+            }
+            PathEvent::Quadratic { .. } => {
+                num_curves += 1;
+            }
+            _ => {}
+        }
+    }
+    assert!(num_curves > 0);
+
+    // All points around the corner are on the fillet arc.
+    let mut corner_points = 0;
+    for evt in path.iter().flattened(0.01) {
+        if let PathEvent::Line { to, .. } = evt {
+            if to.x > 8.0 && to.y < 2.0 {
+                corner_points += 1;
+                assert!(((to - center).length() - 2.0).abs() < 0.01);
+            }
+        }
+    }
+    assert!(corner_points > 2);
+
+    // The radius is clamped to the length of the segments.
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(1.0, 0.0));
+    builder.line_to_rounded(point(1.0, 5.0), 100.0);
+    let path = builder.build();
+
+    match path.iter().nth(1) {
+        Some(PathEvent::Quadratic { from, .. }) => {
+            assert_eq!(from, point(0.0, 0.0));
+        }
+        evt => panic!("unexpected event {:?}", evt),
+    }
+    let last = path.iter().last().unwrap();
     assert_eq!(
-        it.next(),
-        Some(Event::End {
-            last: (point(2.0, 2.0), &[13.0][..]),
-            first: (point(2.0, 2.0), &[13.0][..]),
+        last,
+        PathEvent::End {
+            last: point(1.0, 5.0),
+            first: point(0.0, 0.0),
             close: false
-        })
+        }
     );
+}
+
+#[test]
+fn test_id_segments() {
+    use crate::SegmentKind;
+
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(1.0, 0.0));
+    builder.quadratic_bezier_to(point(2.0, 0.0), point(2.0, 1.0));
+    builder.cubic_bezier_to(point(2.0, 2.0), point(1.0, 2.0), point(0.0, 2.0));
+    builder.close();
+    builder.move_to(point(10.0, 0.0));
+    builder.line_to(point(11.0, 0.0));
+    builder.line_to(point(11.0, 1.0));
+    let path = builder.build();
+
+    let segments: Vec<IdSegment> = path.as_slice().id_segments().collect();
+    let kinds: Vec<SegmentKind> = segments.iter().map(|s| s.kind).collect();
+    assert_eq!(
+        kinds,
+        vec![
+            SegmentKind::Line,
+            SegmentKind::Quadratic,
+            SegmentKind::Cubic,
+            SegmentKind::Line,
+            SegmentKind::Line,
+            SegmentKind::Line,
+        ]
+    );
+
+    // Segments are connected within each sub-path.
+    for pair in segments[0..4].windows(2) {
+        assert_eq!(pair[0].to, pair[1].from);
+    }
+    assert_eq!(segments[3].to, segments[0].from);
+    assert_eq!(segments[4].to, segments[5].from);
+
+    assert_eq!(segments[0].ctrl, [ControlPointId::INVALID; 2]);
+    assert_eq!(path[segments[1].ctrl[0]], point(2.0, 0.0));
+    assert_eq!(segments[1].ctrl[1], ControlPointId::INVALID);
+    assert_eq!(path[segments[2].ctrl[0]], point(2.0, 2.0));
+    assert_eq!(path[segments[2].ctrl[1]], point(1.0, 2.0));
+    assert_eq!(path[segments[2].to], point(0.0, 2.0));
+}
+
+#[test]
+fn test_without_degenerate_sub_paths() {
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(1.0, 0.0));
+    builder.line_to(point(1.0, 1.0));
+    builder.close();
+    // A closed sub-path that is a single point.
+    builder.move_to(point(5.0, 5.0));
+    builder.line_to(point(5.0, 5.0));
+    builder.close();
+    // An open one.
+    builder.move_to(point(6.0, 6.0));
+    builder.line_to(point(6.00001, 6.0));
+    builder.move_to(point(2.0, 0.0));
+    builder.line_to(point(3.0, 0.0));
+    builder.line_to(point(3.0, 1.0));
+    // A lone move_to is kept.
+    builder.move_to(point(7.0, 7.0));
+    // Trailing degenerate sub-path.
+    builder.move_to(point(8.0, 8.0));
+    builder.quadratic_bezier_to(point(8.0, 8.0), point(8.0, 8.0));
+    let path = builder.build();
+
+    // The builder keeps degenerate sub-paths.
+    assert_eq!(path.subpath_info().len(), 6);
+
+    let path = path.without_degenerate_sub_paths(0.0001);
+
+    let events: Vec<PathEvent> = path.iter().collect();
+    assert_eq!(
+        events,
+        vec![
+            PathEvent::Begin {
+                at: point(0.0, 0.0)
+            },
+            PathEvent::Line {
+                from: point(0.0, 0.0),
+                to: point(1.0, 0.0)
+            },
+            PathEvent::Line {
+                from: point(1.0, 0.0),
+                to: point(1.0, 1.0)
+            },
+            PathEvent::End {
+                last: point(1.0, 1.0),
+                first: point(0.0, 0.0),
+                close: true
+            },
+            PathEvent::Begin {
+                at: point(2.0, 0.0)
+            },
+            PathEvent::Line {
+                from: point(2.0, 0.0),
+                to: point(3.0, 0.0)
+            },
+            PathEvent::Line {
+                from: point(3.0, 0.0),
+                to: point(3.0, 1.0)
+            },
+            PathEvent::End {
+                last: point(3.0, 1.0),
+                first: point(2.0, 0.0),
+                close: false
+            },
+            PathEvent::Begin {
+                at: point(7.0, 7.0)
+            },
+            PathEvent::End {
+                last: point(7.0, 7.0),
+                first: point(7.0, 7.0),
+                close: false
+            },
+        ]
+    );
+
+    // Custom attributes are carried over.
+    let mut builder = Path::builder_with_attributes(1);
+    builder.move_to(point(0.0, 0.0), &[1.0]);
+    builder.line_to(point(0.0, 0.0), &[2.0]);
+    builder.close();
+    builder.move_to(point(1.0, 0.0), &[3.0]);
+    builder.line_to(point(2.0, 0.0), &[4.0]);
+    let path = builder.build().without_degenerate_sub_paths(0.0001);
+
+    let mut it = path.iter_with_attributes();
     assert_eq!(
         it.next(),
         Some(Event::Begin {
-            at: (point(3.0, 3.0), &[14.0][..])
+            at: (point(1.0, 0.0), &[3.0][..])
         })
     );
     assert_eq!(
         it.next(),
         Some(Event::Line {
-            from: (point(3.0, 3.0), &[14.0][..]),
-            to: (point(4.0, 4.0), &[15.0][..])
+            from: (point(1.0, 0.0), &[3.0][..]),
+            to: (point(2.0, 0.0), &[4.0][..]),
         })
     );
+
+    // The epsilon depends on the scale of the path.
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(0.00001, 0.0));
+    builder.line_to(point(0.00001, 0.00001));
+    builder.close();
+    let path = builder.build();
+    assert_eq!(path.without_degenerate_sub_paths(0.0001).iter().count(), 0);
     assert_eq!(
-        it.next(),
-        Some(Event::End {
-            last: (point(4.0, 4.0), &[15.0][..]),
-            first: (point(3.0, 3.0), &[14.0][..]),
-            close: false
-        })
+        path.without_degenerate_sub_paths(0.000001).iter().count(),
+        4
     );
-    assert_eq!(it.next(), None);
-    assert_eq!(it.next(), None);
-    assert_eq!(it.next(), None);
 }
 
 #[test]
-fn test_path_builder_empty() {
-    let path = Path::builder_with_attributes(5).build();
-    let mut it = path.iter();
-    assert_eq!(it.next(), None);
-    assert_eq!(it.next(), None);
+fn test_boundary_normals() {
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to(point(10.0, 10.0));
+    builder.line_to(point(0.0, 10.0));
+    builder.close();
+    let path = builder.build();
+
+    let vertices = path.as_slice().boundary_normals(0.1);
+    assert_eq!(vertices.len(), 4);
+
+    let corners = [
+        point(0.0, 0.0),
+        point(10.0, 0.0),
+        point(10.0, 10.0),
+        point(0.0, 10.0),
+    ];
+    for (i, v) in vertices.iter().enumerate() {
+        let prev = corners[(i + 3) % 4];
+        let next = corners[(i + 1) % 4];
+        assert_eq!(v.position, corners[i]);
+        assert!(v.incoming != v.outgoing);
+        assert!(v.incoming.dot(v.position - prev).abs() < 1e-5);
+        assert!(v.outgoing.dot(next - v.position).abs() < 1e-5);
+        assert!((v.incoming.length() - 1.0).abs() < 1e-5);
+        assert!((v.outgoing.length() - 1.0).abs() < 1e-5);
+        // Pointing outwards.
+        let center = point(5.0, 5.0);
+        assert!(v.incoming.dot(v.position - center) > 0.0);
+        assert!(v.outgoing.dot(v.position - center) > 0.0);
+    }
+
+    // Smooth curves don't have split normals.
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.quadratic_bezier_to(point(10.0, 0.0), point(10.0, 10.0));
+    builder.quadratic_bezier_to(point(10.0, 20.0), point(0.0, 20.0));
+    let path = builder.build();
+
+    let vertices = path.as_slice().boundary_normals(0.1);
+    assert!(vertices.len() > 4);
+    for v in &vertices {
+        assert!((v.incoming - v.outgoing).length() < 1e-5);
+    }
+}
+
+#[test]
+fn test_shape_equal_ignoring_transform() {
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(1.0, 0.0));
+    builder.line_to(point(1.0, 1.0));
+    builder.line_to(point(0.0, 1.0));
+    builder.close();
+    let square = builder.build();
+
+    let expected = Transform::create_scale(3.0, 2.0).post_translate(vector(10.0, -5.0));
+    let transformed = square.transformed(&expected);
+
+    let transform = square
+        .as_slice()
+        .shape_equal_ignoring_transform(&transformed.as_slice(), 0.001)
+        .unwrap();
+    for p in &[point(0.0, 0.0), point(1.0, 2.0), point(-3.0, 5.0)] {
+        let a = transform.transform_point(*p);
+        let b = expected.transform_point(*p);
+        assert!((a - b).length() < 0.001);
+    }
+
+    // A different shape with the same structure.
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(1.0, 0.0));
+    builder.line_to(point(2.0, 1.0));
+    builder.line_to(point(0.0, 1.0));
+    builder.close();
+    let other = builder.build();
+    assert!(square
+        .as_slice()
+        .shape_equal_ignoring_transform(&other.as_slice(), 0.001)
+        .is_none());
+
+    // A different structure.
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(1.0, 0.0));
+    builder.line_to(point(1.0, 1.0));
+    builder.close();
+    let triangle = builder.build();
+    assert!(square
+        .as_slice()
+        .shape_equal_ignoring_transform(&triangle.as_slice(), 0.001)
+        .is_none());
+}
+
+#[test]
+fn test_from_points_csv() {
+    let text = "0 0\n1, 0\n 1,1 \n\n5.5\t-2\n6 -3\n";
+    let path = Path::from_points_csv(text).unwrap();
+    let events: Vec<PathEvent> = path.iter().collect();
+    assert_eq!(
+        events,
+        vec![
+            PathEvent::Begin {
+                at: point(0.0, 0.0)
+            },
+            PathEvent::Line {
+                from: point(0.0, 0.0),
+                to: point(1.0, 0.0)
+            },
+            PathEvent::Line {
+                from: point(1.0, 0.0),
+                to: point(1.0, 1.0)
+            },
+            PathEvent::End {
+                last: point(1.0, 1.0),
+                first: point(0.0, 0.0),
+                close: false
+            },
+            PathEvent::Begin {
+                at: point(5.5, -2.0)
+            },
+            PathEvent::Line {
+                from: point(5.5, -2.0),
+                to: point(6.0, -3.0)
+            },
+            PathEvent::End {
+                last: point(6.0, -3.0),
+                first: point(5.5, -2.0),
+                close: false
+            },
+        ]
+    );
+
+    assert_eq!(
+        Path::from_points_csv("0 0\n1 2\n1 x\n").unwrap_err(),
+        ParseError::InvalidLine { line: 3 }
+    );
+    assert_eq!(
+        Path::from_points_csv("0 0 0\n").unwrap_err(),
+        ParseError::InvalidLine { line: 1 }
+    );
+}
+
+#[test]
+fn test_builder_arc_to() {
+    let center = point(1.0, 0.0);
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.arc_to(
+        vector(1.0, 1.0),
+        Angle::zero(),
+        ArcFlags {
+            large_arc: false,
+            sweep: true,
+        },
+        point(2.0, 0.0),
+    );
+    assert_eq!(builder.current_position(), point(2.0, 0.0));
+    let path = builder.build();
+
+    let mut num_curves = 0;
+    let mut last = point(0.0, 0.0);
+    for evt in path.iter() {
+        if let PathEvent::Quadratic { from, to, .. } = evt {
+            num_curves += 1;
+            assert!(((from - center).length() - 1.0).abs() < 0.0001);
+            assert!(((to - center).length() - 1.0).abs() < 0.0001);
+            last = to;
+        }
+    }
+    assert!(num_curves > 1);
+    assert_eq!(last, point(2.0, 0.0));
+
+    // Zero radii are treated as a line and coincident endpoints add nothing.
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.arc_to(
+        vector(0.0, 1.0),
+        Angle::zero(),
+        ArcFlags::default(),
+        point(1.0, 0.0),
+    );
+    builder.arc_to(
+        vector(1.0, 1.0),
+        Angle::zero(),
+        ArcFlags::default(),
+        point(1.0, 0.0),
+    );
+    let path = builder.build();
+
+    assert_eq!(
+        path.iter().collect::<Vec<_>>(),
+        vec![
+            PathEvent::Begin {
+                at: point(0.0, 0.0)
+            },
+            PathEvent::Line {
+                from: point(0.0, 0.0),
+                to: point(1.0, 0.0)
+            },
+            PathEvent::End {
+                last: point(1.0, 0.0),
+                first: point(0.0, 0.0),
+                close: false
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_builder_relative_commands() {
+    let mut builder = Path::builder();
+    // Relative to the origin before the first command.
+    builder.line_by(vector(1.0, 1.0));
+    builder.move_to(point(5.0, 5.0));
+    builder.line_by(vector(1.0, 0.0));
+    builder.quadratic_bezier_by(vector(1.0, 1.0), vector(2.0, 0.0));
+    builder.line_to(point(10.0, 10.0));
+    builder.cubic_bezier_by(vector(0.0, 1.0), vector(1.0, 1.0), vector(1.0, 0.0));
+    builder.close();
+    builder.move_by(vector(-1.0, 0.0));
+    builder.line_by(vector(0.0, -2.0));
+    let path = builder.build();
+
+    assert_eq!(
+        path.iter().collect::<Vec<_>>(),
+        vec![
+            PathEvent::Begin {
+                at: point(0.0, 0.0)
+            },
+            PathEvent::Line {
+                from: point(0.0, 0.0),
+                to: point(1.0, 1.0)
+            },
+            PathEvent::End {
+                last: point(1.0, 1.0),
+                first: point(0.0, 0.0),
+                close: false
+            },
+            PathEvent::Begin {
+                at: point(5.0, 5.0)
+            },
+            PathEvent::Line {
+                from: point(5.0, 5.0),
+                to: point(6.0, 5.0)
+            },
+            PathEvent::Quadratic {
+                from: point(6.0, 5.0),
+                ctrl: point(7.0, 6.0),
+                to: point(8.0, 5.0)
+            },
+            PathEvent::Line {
+                from: point(8.0, 5.0),
+                to: point(10.0, 10.0)
+            },
+            PathEvent::Cubic {
+                from: point(10.0, 10.0),
+                ctrl1: point(10.0, 11.0),
+                ctrl2: point(11.0, 11.0),
+                to: point(11.0, 10.0)
+            },
+            PathEvent::End {
+                last: point(11.0, 10.0),
+                first: point(5.0, 5.0),
+                close: true
+            },
+            PathEvent::Begin {
+                at: point(4.0, 5.0)
+            },
+            PathEvent::Line {
+                from: point(4.0, 5.0),
+                to: point(4.0, 3.0)
+            },
+            PathEvent::End {
+                last: point(4.0, 3.0),
+                first: point(4.0, 5.0),
+                close: false
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_approximate_length() {
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(3.0, 0.0));
+    builder.line_to(point(3.0, 4.0));
+    builder.close();
+    builder.move_to(point(10.0, 0.0));
+    builder.quadratic_bezier_to(point(11.0, 0.0), point(12.0, 0.0));
+    builder.cubic_bezier_to(point(12.0, 1.0), point(12.0, 2.0), point(12.0, 3.0));
+    let path = builder.build();
+
+    let lengths = path.approximate_subpath_lengths(0.01);
+    assert_eq!(lengths.len(), 2);
+    assert!((lengths[0] - 12.0).abs() < 0.0001);
+    assert!((lengths[1] - 5.0).abs() < 0.0001);
+    assert!((path.approximate_length(0.01) - 17.0).abs() < 0.0001);
+
+    // A circle.
+    let mut builder = Path::builder();
+    builder.move_to(point(1.0, 0.0));
+    builder.arc(
+        point(0.0, 0.0),
+        vector(1.0, 1.0),
+        Angle::radians(2.0 * std::f32::consts::PI),
+        Angle::zero(),
+    );
+    builder.close();
+    let path = builder.build();
+
+    // The arc itself is approximated with quadratic bézier curves.
+    let length = path.approximate_length(0.0001);
+    assert!(
+        (length - 2.0 * std::f32::consts::PI).abs() < 0.02,
+        "{}",
+        length
+    );
+    assert!(path.approximate_length(0.1) <= length);
+}
+
+#[test]
+fn test_transformed() {
+    let mut builder = Path::builder_with_attributes(1);
+    builder.move_to(point(0.0, 0.0), &[1.0]);
+    builder.line_to(point(1.0, 0.0), &[2.0]);
+    builder.quadratic_bezier_to(point(2.0, 1.0), point(1.0, 2.0), &[3.0]);
+    builder.cubic_bezier_to(point(0.0, 3.0), point(-1.0, 2.0), point(-1.0, 1.0), &[4.0]);
+    builder.close();
+    let path = builder.build();
+
+    let transform = Transform::create_rotation(Angle::radians(0.5))
+        .post_scale(2.0, 3.0)
+        .post_translate(vector(10.0, -5.0));
+    let transformed = path.transformed(&transform);
+
+    let expected: Vec<PathEvent> = path.iter().map(|evt| evt.transformed(&transform)).collect();
+    assert_eq!(transformed.iter().collect::<Vec<_>>(), expected);
+
+    let mut in_place = path.clone();
+    in_place.apply_transform(&transform);
+    assert_eq!(in_place.iter().collect::<Vec<_>>(), expected);
+
+    let attributes: Vec<f32> = transformed
+        .id_iter()
+        .filter_map(|evt| match evt {
+            IdEvent::Begin { at } => Some(transformed.attributes(at)[0]),
+            IdEvent::Line { to, .. }
+            | IdEvent::Quadratic { to, .. }
+            | IdEvent::Cubic { to, .. } => Some(transformed.attributes(to)[0]),
+            IdEvent::End { .. } => None,
+        })
+        .collect();
+    assert_eq!(attributes, vec![1.0, 2.0, 3.0, 4.0]);
+}
+
+#[test]
+#[should_panic]
+fn merge_different_attribute_names() {
+    let mut builder = Path::builder_with_named_attributes(&["r"]);
+    builder.move_to(point(0.0, 0.0), &[1.0]);
+    builder.line_to(point(1.0, 0.0), &[0.0]);
+    let red = builder.build();
+
+    let mut builder = Path::builder_with_named_attributes(&["g"]);
+    builder.move_to(point(0.0, 0.0), &[1.0]);
+    builder.line_to(point(1.0, 0.0), &[0.0]);
+    let green = builder.build();
+
+    red.merge(&green);
 }
 
 #[test]
-fn test_path_builder_empty_move_to() {
-    let mut p = Path::builder_with_attributes(1);
-    p.move_to(point(1.0, 2.0), &[0.0]);
-    p.move_to(point(3.0, 4.0), &[1.0]);
-    p.move_to(point(5.0, 6.0), &[2.0]);
+fn test_named_attributes() {
+    let mut builder = Path::builder_with_named_attributes(&["r", "g", "b"]);
+    let a = builder.move_to(point(0.0, 0.0), &[1.0, 0.5, 0.0]);
+    let b = builder.line_to(point(1.0, 0.0), &[0.0, 1.0, 0.25]);
+    builder.close();
+    let path = builder.build();
+
+    assert_eq!(path.attribute_names(), &["r", "g", "b"]);
+    assert_eq!(path.attribute_index("b"), Some(2));
+    assert_eq!(path.attribute(a, "r"), Some(1.0));
+    assert_eq!(path.attribute(a, "g"), Some(0.5));
+    assert_eq!(path.attribute(b, "g"), Some(1.0));
+    assert_eq!(path.attribute(b, "b"), Some(0.25));
+    assert_eq!(path.attribute(b, "a"), None);
+
+    // The names are kept when transforming the path.
+    let translated = path.transformed(&Transform::create_translation(1.0, 1.0));
+    assert_eq!(translated.attribute(b, "b"), Some(0.25));
+
+    // And by the other operations that produce paths.
+    assert_eq!(path.as_slice().attribute_names(), &["r", "g", "b"]);
+    assert_eq!(path.reversed().attribute_names(), &["r", "g", "b"]);
+    assert_eq!(path.merge(&path).attribute_names(), &["r", "g", "b"]);
+    let components = path.as_slice().connected_components(0.1);
+    assert_eq!(components.len(), 1);
+    assert_eq!(components[0].attribute_names(), &["r", "g", "b"]);
+
+    // Positional attributes have no name.
+    let mut builder = Path::builder_with_attributes(1);
+    let a = builder.move_to(point(0.0, 0.0), &[1.0]);
+    builder.line_to(point(1.0, 0.0), &[2.0]);
+    let path = builder.build();
+    assert!(path.attribute_names().is_empty());
+    assert_eq!(path.attribute(a, "r"), None);
+}
 
-    let path = p.build();
-    let mut it = path.iter();
-    assert_eq!(
-        it.next(),
-        Some(PathEvent::Begin {
-            at: point(1.0, 2.0)
-        })
-    );
-    assert_eq!(
-        it.next(),
-        Some(PathEvent::End {
-            last: point(1.0, 2.0),
-            first: point(1.0, 2.0),
-            close: false,
-        })
-    );
-    assert_eq!(
-        it.next(),
-        Some(PathEvent::Begin {
-            at: point(3.0, 4.0)
-        })
-    );
-    assert_eq!(
-        it.next(),
-        Some(PathEvent::End {
-            last: point(3.0, 4.0),
-            first: point(3.0, 4.0),
-            close: false,
+#[test]
+fn test_extend_from_path() {
+    let triangle = |offset: f32| {
+        let mut builder = Path::builder();
+        builder.move_to(point(offset, 0.0));
+        builder.line_to(point(offset + 1.0, 0.0));
+        builder.line_to(point(offset, 1.0));
+        builder.close();
+        builder.build()
+    };
+    let a = triangle(0.0);
+    let b = triangle(10.0);
+
+    // Start with an open sub-path which must not be joined with the appended ones.
+    let mut builder = Path::builder();
+    builder.move_to(point(-5.0, 0.0));
+    builder.line_to(point(-5.0, 5.0));
+    builder.extend_from_path(&a);
+    builder.extend_from_path(&b);
+    let path = builder.build();
+
+    let ends: Vec<PathEvent> = path
+        .iter()
+        .filter(|evt| match evt {
+            PathEvent::Begin { .. } | PathEvent::End { .. } => true,
+            _ => false,
         })
-    );
+        .collect();
     assert_eq!(
-        it.next(),
-        Some(PathEvent::Begin {
-            at: point(5.0, 6.0)
-        })
+        ends,
+        vec![
+            PathEvent::Begin {
+                at: point(-5.0, 0.0)
+            },
+            PathEvent::End {
+                last: point(-5.0, 5.0),
+                first: point(-5.0, 0.0),
+                close: false
+            },
+            PathEvent::Begin {
+                at: point(0.0, 0.0)
+            },
+            PathEvent::End {
+                last: point(0.0, 1.0),
+                first: point(0.0, 0.0),
+                close: true
+            },
+            PathEvent::Begin {
+                at: point(10.0, 0.0)
+            },
+            PathEvent::End {
+                last: point(10.0, 1.0),
+                first: point(10.0, 0.0),
+                close: true
+            },
+        ]
     );
+
+    let mut builder = Path::builder();
+    builder.extend_from_path(&a);
+    builder.extend_from_path(&b);
+    let path = builder.build();
     assert_eq!(
-        it.next(),
-        Some(PathEvent::End {
-            last: point(5.0, 6.0),
-            first: point(5.0, 6.0),
-            close: false,
-        })
+        path.iter().collect::<Vec<_>>(),
+        a.merge(&b).iter().collect::<Vec<_>>()
     );
-    assert_eq!(it.next(), None);
-    assert_eq!(it.next(), None);
 }
 
 #[test]
-fn test_path_builder_line_to_after_close() {
-    let mut p = Path::builder();
-    p.line_to(point(1.0, 0.0));
-    p.close();
-    p.line_to(point(2.0, 0.0));
-
-    let path = p.build();
-    let mut it = path.iter();
-    assert_eq!(
-        it.next(),
-        Some(PathEvent::Begin {
-            at: point(0.0, 0.0)
-        })
-    );
-    assert_eq!(
-        it.next(),
-        Some(PathEvent::Line {
-            from: point(0.0, 0.0),
-            to: point(1.0, 0.0)
-        })
+fn test_reverse_iteration() {
+    let mut builder = Path::builder_with_attributes(3);
+    builder.move_to(point(0.0, 0.0), &[1.0, 2.0, 3.0]);
+    builder.line_to(point(1.0, 0.0), &[4.0, 5.0, 6.0]);
+    builder.quadratic_bezier_to(point(2.0, 0.0), point(2.0, 1.0), &[7.0, 8.0, 9.0]);
+    builder.cubic_bezier_to(
+        point(2.0, 2.0),
+        point(1.0, 2.0),
+        point(0.0, 2.0),
+        &[10.0, 11.0, 12.0],
     );
+    builder.close();
+    builder.move_to(point(5.0, 5.0), &[0.0, 0.0, 0.0]);
+    builder.move_to(point(10.0, 0.0), &[0.0, 0.0, 0.0]);
+    builder.line_to(point(11.0, 0.0), &[0.0, 0.0, 0.0]);
+    builder.line_to(point(11.0, 1.0), &[0.0, 0.0, 0.0]);
+    let path = builder.build();
+
+    let forward: Vec<PathEvent> = path.iter().collect();
+    let mut backward: Vec<PathEvent> = path.iter().rev().collect();
+    backward.reverse();
+    assert_eq!(forward, backward);
+
+    // The endpoints of a sub-path are visited in opposite order.
+    let endpoints = |events: &mut dyn Iterator<Item = PathEvent>| -> Vec<Point> {
+        events
+            .filter_map(|evt| match evt {
+                PathEvent::Begin { at } => Some(at),
+                PathEvent::Line { to, .. }
+                | PathEvent::Quadratic { to, .. }
+                | PathEvent::Cubic { to, .. } => Some(to),
+                PathEvent::End { .. } => None,
+            })
+            .collect()
+    };
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(1.0, 0.0));
+    builder.quadratic_bezier_to(point(2.0, 0.0), point(2.0, 1.0));
+    builder.cubic_bezier_to(point(2.0, 2.0), point(1.0, 2.0), point(0.0, 2.0));
+    builder.close();
+    let subpath = builder.build();
+    let mut forward = endpoints(&mut subpath.iter());
+    let backward = endpoints(&mut subpath.iter().rev());
     assert_eq!(
-        it.next(),
-        Some(PathEvent::End {
-            last: point(1.0, 0.0),
-            first: point(0.0, 0.0),
-            close: true
-        })
+        forward,
+        vec![
+            point(0.0, 0.0),
+            point(1.0, 0.0),
+            point(2.0, 1.0),
+            point(0.0, 2.0)
+        ]
     );
+    forward.reverse();
+    assert_eq!(forward, backward);
+
+    // Consuming both ends alternately.
+    let mut iter = path.iter();
+    let mut front = Vec::new();
+    let mut back = Vec::new();
+    loop {
+        match iter.next() {
+            Some(evt) => front.push(evt),
+            None => break,
+        }
+        match iter.next_back() {
+            Some(evt) => back.push(evt),
+            None => break,
+        }
+    }
+    back.reverse();
+    front.extend(back);
+    assert_eq!(front, path.iter().collect::<Vec<_>>());
+}
+
+#[test]
+fn test_builder_smooth_curves() {
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    // Not preceded by a curve.
+    builder.smooth_quadratic_bezier_to(point(1.0, 0.0));
+    builder.quadratic_bezier_to(point(2.0, 1.0), point(3.0, 0.0));
+    builder.smooth_quadratic_bezier_to(point(6.0, 0.0));
+    builder.smooth_quadratic_bezier_to(point(9.0, 0.0));
+    builder.cubic_bezier_to(point(10.0, 1.0), point(11.0, 2.0), point(12.0, 0.0));
+    builder.smooth_cubic_bezier_to(point(14.0, 1.0), point(15.0, 0.0));
+    // The previous segment is a cubic curve, not a quadratic one.
+    builder.smooth_quadratic_bezier_to(point(16.0, 0.0));
+    builder.line_to(point(17.0, 0.0));
+    builder.smooth_cubic_bezier_to(point(18.0, 1.0), point(19.0, 0.0));
+    let path = builder.build();
+
+    let events: Vec<PathEvent> = path.iter().collect();
     assert_eq!(
-        it.next(),
-        Some(PathEvent::Begin {
-            at: point(0.0, 0.0)
-        })
+        &events[1..9],
+        &[
+            PathEvent::Quadratic {
+                from: point(0.0, 0.0),
+                ctrl: point(0.0, 0.0),
+                to: point(1.0, 0.0)
+            },
+            PathEvent::Quadratic {
+                from: point(1.0, 0.0),
+                ctrl: point(2.0, 1.0),
+                to: point(3.0, 0.0)
+            },
+            PathEvent::Quadratic {
+                from: point(3.0, 0.0),
+                ctrl: point(4.0, -1.0),
+                to: point(6.0, 0.0)
+            },
+            PathEvent::Quadratic {
+                from: point(6.0, 0.0),
+                ctrl: point(8.0, 1.0),
+                to: point(9.0, 0.0)
+            },
+            PathEvent::Cubic {
+                from: point(9.0, 0.0),
+                ctrl1: point(10.0, 1.0),
+                ctrl2: point(11.0, 2.0),
+                to: point(12.0, 0.0)
+            },
+            PathEvent::Cubic {
+                from: point(12.0, 0.0),
+                ctrl1: point(13.0, -2.0),
+                ctrl2: point(14.0, 1.0),
+                to: point(15.0, 0.0)
+            },
+            PathEvent::Quadratic {
+                from: point(15.0, 0.0),
+                ctrl: point(15.0, 0.0),
+                to: point(16.0, 0.0)
+            },
+            PathEvent::Line {
+                from: point(16.0, 0.0),
+                to: point(17.0, 0.0)
+            },
+        ][..]
     );
     assert_eq!(
-        it.next(),
-        Some(PathEvent::Line {
-            from: point(0.0, 0.0),
-            to: point(2.0, 0.0)
-        })
+        events[9],
+        PathEvent::Cubic {
+            from: point(17.0, 0.0),
+            ctrl1: point(17.0, 0.0),
+            ctrl2: point(18.0, 1.0),
+            to: point(19.0, 0.0)
+        }
     );
+}
+
+#[test]
+fn test_flattened() {
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.quadratic_bezier_to(point(20.0, 0.0), point(20.0, 10.0));
+    builder.cubic_bezier_to(point(20.0, 20.0), point(0.0, 20.0), point(0.0, 10.0));
+    builder.close();
+    builder.move_to(point(30.0, 0.0));
+    builder.line_to(point(40.0, 0.0));
+    let path = builder.build();
+
+    let tolerance = 0.01;
+    let events: Vec<PathEvent> = path.flattened(tolerance).collect();
+    assert!(events.len() > 10);
+
+    let mut endpoints = Vec::new();
+    let mut previous = None;
+    for evt in &events {
+        match *evt {
+            PathEvent::Quadratic { .. } | PathEvent::Cubic { .. } => {
+                panic!("Unexpected curve {:?}", evt);
+            }
+            PathEvent::Line { from, to } => {
+                // The line segments are contiguous.
+                assert_eq!(Some(from), previous);
+                previous = Some(to);
+            }
+            PathEvent::Begin { at } => {
+                previous = Some(at);
+                endpoints.push(*evt);
+            }
+            PathEvent::End { .. } => {
+                endpoints.push(*evt);
+            }
+        }
+    }
+
     assert_eq!(
-        it.next(),
-        Some(PathEvent::End {
-            last: point(2.0, 0.0),
-            first: point(0.0, 0.0),
-            close: false
-        })
+        endpoints,
+        vec![
+            PathEvent::Begin {
+                at: point(0.0, 0.0)
+            },
+            PathEvent::End {
+                last: point(0.0, 10.0),
+                first: point(0.0, 0.0),
+                close: true
+            },
+            PathEvent::Begin {
+                at: point(30.0, 0.0)
+            },
+            PathEvent::End {
+                last: point(40.0, 0.0),
+                first: point(30.0, 0.0),
+                close: false
+            },
+        ]
     );
-    assert_eq!(it.next(), None);
+
+    // A larger tolerance produces fewer segments.
+    assert!(path.flattened(1.0).count() < events.len());
 }
 
 #[test]
-fn test_merge_paths() {
+fn test_subpath_info() {
     let mut builder = Path::builder();
     builder.move_to(point(0.0, 0.0));
-    builder.line_to(point(5.0, 0.0));
-    builder.line_to(point(5.0, 5.0));
+    builder.line_to(point(1.0, 0.0));
+    builder.quadratic_bezier_to(point(2.0, 0.0), point(2.0, 1.0));
+    builder.move_to(point(10.0, 0.0));
+    builder.line_to(point(11.0, 0.0));
+    builder.cubic_bezier_to(point(12.0, 0.0), point(12.0, 1.0), point(11.0, 1.0));
+    builder.line_to(point(10.0, 1.0));
     builder.close();
+    builder.move_to(point(20.0, 0.0));
+    let path = builder.build();
 
-    let path1 = builder.build();
+    let info = path.subpath_info();
+    assert_eq!(
+        info,
+        vec![
+            SubpathInfo {
+                start_event: 0,
+                end_event: 3,
+                closed: false,
+                num_segments: 2,
+            },
+            SubpathInfo {
+                start_event: 4,
+                end_event: 8,
+                closed: true,
+                num_segments: 3,
+            },
+            SubpathInfo {
+                start_event: 9,
+                end_event: 10,
+                closed: false,
+                num_segments: 0,
+            },
+        ]
+    );
+
+    // The indices match the event sequence.
+    let events: Vec<PathEvent> = path.iter().collect();
+    for sub_path in &info {
+        match events[sub_path.start_event] {
+            PathEvent::Begin { .. } => {}
+            _ => panic!(),
+        }
+        match events[sub_path.end_event] {
+            PathEvent::End { close, .. } => assert_eq!(close, sub_path.closed),
+            _ => panic!(),
+        }
+    }
+
+    assert!(Path::new().subpath_info().is_empty());
+}
 
+#[test]
+fn test_turning_number() {
+    use std::f32::consts::PI;
+
+    let polygon = |points: &[Point]| {
+        let mut builder = Path::builder();
+        builder.polygon(points);
+        builder.build()
+    };
+
+    let triangle = polygon(&[point(0.0, 0.0), point(1.0, 0.0), point(0.0, 1.0)]);
+    assert_eq!(triangle.turning_number(), 1);
+    assert_eq!(triangle.reversed().turning_number(), -1);
+
+    let figure_eight = polygon(&[
+        point(0.0, 0.0),
+        point(1.0, 1.0),
+        point(1.0, 0.0),
+        point(0.0, 1.0),
+    ]);
+    assert_eq!(figure_eight.turning_number(), 0);
+
+    // A circle traversed twice.
     let mut builder = Path::builder();
-    builder.move_to(point(1.0, 1.0));
-    builder.line_to(point(4.0, 0.0));
-    builder.line_to(point(4.0, 4.0));
+    builder.move_to(point(1.0, 0.0));
+    for i in 1..40 {
+        let angle = i as f32 * 4.0 * PI / 40.0;
+        builder.quadratic_bezier_to(
+            point(angle.cos() * 1.1, angle.sin() * 1.1),
+            point(angle.cos(), angle.sin()),
+        );
+    }
     builder.close();
+    assert_eq!(builder.build().turning_number(), 2);
 
-    let path2 = builder.build();
+    // Open sub-paths are implicitly closed.
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(0.0, 1.0));
+    builder.line_to(point(1.0, 0.0));
+    assert_eq!(builder.build().turning_number(), -1);
+}
 
-    let path = path1.merge(&path2);
+#[test]
+fn test_append_transformed() {
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(1.0, 0.0));
+    builder.line_to(point(1.0, 1.0));
+    builder.line_to(point(0.0, 1.0));
+    builder.close();
+    let square = builder.build();
 
-    let mut it = path.iter();
-    assert_eq!(
-        it.next(),
-        Some(PathEvent::Begin {
-            at: point(0.0, 0.0)
-        })
-    );
-    assert_eq!(
-        it.next(),
-        Some(PathEvent::Line {
+    let translation = Transform::create_translation(10.0, 20.0);
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(5.0, 5.0));
+    builder.append_transformed(&square.as_slice(), &translation);
+    let path = builder.build();
+
+    let events: Vec<PathEvent> = path.iter().collect();
+    let mut expected = vec![
+        PathEvent::Begin {
+            at: point(0.0, 0.0),
+        },
+        PathEvent::Line {
             from: point(0.0, 0.0),
-            to: point(5.0, 0.0)
-        })
-    );
-    assert_eq!(
-        it.next(),
-        Some(PathEvent::Line {
-            from: point(5.0, 0.0),
-            to: point(5.0, 5.0)
-        })
-    );
-    assert_eq!(
-        it.next(),
-        Some(PathEvent::End {
+            to: point(5.0, 5.0),
+        },
+        PathEvent::End {
             last: point(5.0, 5.0),
             first: point(0.0, 0.0),
-            close: true
-        })
-    );
-    assert_eq!(
-        it.next(),
-        Some(PathEvent::Begin {
-            at: point(1.0, 1.0)
-        })
-    );
-    assert_eq!(
-        it.next(),
-        Some(PathEvent::Line {
-            from: point(1.0, 1.0),
-            to: point(4.0, 0.0)
-        })
-    );
-    assert_eq!(
-        it.next(),
-        Some(PathEvent::Line {
-            from: point(4.0, 0.0),
-            to: point(4.0, 4.0)
-        })
+            close: false,
+        },
+    ];
+    expected.extend(square.iter().map(|evt| evt.transformed(&translation)));
+    assert_eq!(events, expected);
+
+    // With custom attributes.
+    let mut builder = Path::builder_with_attributes(1);
+    builder.move_to(point(0.0, 0.0), &[1.0]);
+    builder.line_to(point(1.0, 0.0), &[2.0]);
+    builder.quadratic_bezier_to(point(1.0, 1.0), point(0.0, 1.0), &[3.0]);
+    builder.close();
+    let square = builder.build();
+
+    let mut builder = Path::builder_with_attributes(1);
+    builder.append_transformed(&square.as_slice(), &translation);
+    builder.append_transformed(
+        &square.as_slice(),
+        &translation.post_translate(vector(5.0, 0.0)),
     );
+    let path = builder.build();
+
+    let mut endpoints = Vec::new();
+    for evt in path.iter_with_attributes() {
+        match evt {
+            Event::Begin { at } => endpoints.push((at.0, at.1[0])),
+            Event::Quadratic { ctrl, to, .. } => {
+                endpoints.push((ctrl, -1.0));
+                endpoints.push((to.0, to.1[0]));
+            }
+            Event::Line { to, .. } => endpoints.push((to.0, to.1[0])),
+            Event::Cubic { .. } => panic!(),
+            Event::End { close, .. } => assert!(close),
+        }
+    }
     assert_eq!(
-        it.next(),
-        Some(PathEvent::End {
-            last: point(4.0, 4.0),
-            first: point(1.0, 1.0),
-            close: true
-        })
+        endpoints,
+        vec![
+            (point(10.0, 20.0), 1.0),
+            (point(11.0, 20.0), 2.0),
+            (point(11.0, 21.0), -1.0),
+            (point(10.0, 21.0), 3.0),
+            (point(15.0, 20.0), 1.0),
+            (point(16.0, 20.0), 2.0),
+            (point(16.0, 21.0), -1.0),
+            (point(15.0, 21.0), 3.0),
+        ]
     );
-    assert_eq!(it.next(), None);
+}
+
+#[test]
+fn test_for_each_flattened() {
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.quadratic_bezier_to(point(20.0, 0.0), point(20.0, 10.0));
+    builder.cubic_bezier_to(point(20.0, 20.0), point(0.0, 20.0), point(0.0, 10.0));
+    builder.close();
+    builder.move_to(point(50.0, 0.0));
+    builder.quadratic_bezier_to(point(60.0, 0.0), point(60.0, 10.0));
+    let path = builder.build();
+
+    let mut events = Vec::new();
+    path.for_each_flattened(0.01, &mut |evt| events.push(*evt));
+
+    let expected: Vec<PathEvent> = path.flattened(0.01).collect();
+    assert_eq!(events.len(), expected.len());
+    for (evt, expected) in events.iter().zip(expected.iter()) {
+        assert_eq!(
+            std::mem::discriminant(evt),
+            std::mem::discriminant(expected)
+        );
+    }
+
+    // Properly paired begin and end events with continuous edges in between.
+    let mut current = None;
+    let mut num_closed = 0;
+    for evt in &events {
+        match *evt {
+            PathEvent::Begin { at } => {
+                assert!(current.is_none());
+                current = Some((at, at));
+            }
+            PathEvent::Line { from, to } => {
+                let (first, last) = current.unwrap();
+                assert_eq!(from, last);
+                current = Some((first, to));
+            }
+            PathEvent::End { last, first, close } => {
+                assert_eq!(current, Some((first, last)));
+                if close {
+                    // The closing edge.
+                    assert_eq!(last, point(0.0, 10.0));
+                    assert_eq!(first, point(0.0, 0.0));
+                    num_closed += 1;
+                }
+                current = None;
+            }
+            _ => panic!("{:?}", evt),
+        }
+    }
+    assert!(current.is_none());
+    assert_eq!(num_closed, 1);
+}
+
+#[test]
+fn test_built_path_storage_is_shrunk() {
+    let mut builder = Builder::with_capacity(1000, 1000);
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(1.0, 0.0));
+    builder.line_to(point(1.0, 1.0));
+    builder.close();
+    let path = builder.build();
+
+    let points = path.points.into_vec();
+    let verbs = path.verbs.into_vec();
+    assert_eq!(points.capacity(), points.len());
+    assert_eq!(verbs.capacity(), verbs.len());
+
+    let mut builder = BuilderWithAttributes::with_capacity(1, 1000, 1000);
+    builder.move_to(point(0.0, 0.0), &[0.0]);
+    builder.line_to(point(1.0, 0.0), &[1.0]);
+    builder.close();
+    let path = builder.build();
+
+    let points = path.points.into_vec();
+    let verbs = path.verbs.into_vec();
+    assert_eq!(points.capacity(), points.len());
+    assert_eq!(verbs.capacity(), verbs.len());
 }
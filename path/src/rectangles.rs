@@ -0,0 +1,135 @@
+//! Decomposition of rectilinear shapes into rectangles.
+
+use crate::geom::LineSegment;
+use crate::iterator::PathIterator;
+use crate::math::{rect, Point, Rect};
+use crate::{PathEvent, PathSlice};
+
+// Rectilinear paths don't have curves, this only matters for paths that do.
+const TOLERANCE: f32 = 0.1;
+
+/// Decomposes the fill of a rectilinear path into non-overlapping rectangles.
+///
+/// The path is filled with the even-odd fill rule and is expected to only have
+/// axis-aligned edges. The shape is cut into horizontal slabs at each vertex and the
+/// rectangles of consecutive slabs that have the same horizontal extent are merged.
+///
+/// Paths that are not rectilinear are flattened, and each trapezoid is replaced with
+/// a rectangle of the same area and height, so the result only approximates the shape.
+pub fn rectangle_decomposition(path: &PathSlice) -> Vec<Rect> {
+    let mut edges = Vec::new();
+    let mut add = |from: Point, to: Point| {
+        if from.y != to.y {
+            edges.push(LineSegment { from, to });
+        }
+    };
+    for evt in path.iter().flattened(TOLERANCE) {
+        match evt {
+            PathEvent::Line { from, to } => add(from, to),
+            PathEvent::End { last, first, .. } => add(last, first),
+            _ => {}
+        }
+    }
+
+    let mut ys = Vec::with_capacity(edges.len() * 2);
+    for edge in &edges {
+        ys.push(edge.from.y);
+        ys.push(edge.to.y);
+    }
+    ys.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    ys.dedup();
+
+    let mut result: Vec<Rect> = Vec::new();
+    // Indices in `result` of the rectangles that touch the bottom of the previous slab.
+    let mut open: Vec<usize> = Vec::new();
+    let mut next_open = Vec::new();
+    let mut crossings = Vec::new();
+    for slab in ys.windows(2) {
+        let (y0, y1) = (slab[0], slab[1]);
+        let y = (y0 + y1) * 0.5;
+
+        crossings.clear();
+        for edge in &edges {
+            let (min_y, max_y) = if edge.from.y < edge.to.y {
+                (edge.from.y, edge.to.y)
+            } else {
+                (edge.to.y, edge.from.y)
+            };
+            if y < min_y || y > max_y {
+                continue;
+            }
+            let t = (y - edge.from.y) / (edge.to.y - edge.from.y);
+            crossings.push(edge.from.x + (edge.to.x - edge.from.x) * t);
+        }
+        crossings.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+        next_open.clear();
+        for span in crossings.chunks(2) {
+            if span.len() < 2 || span[0] == span[1] {
+                continue;
+            }
+            let (x0, x1) = (span[0], span[1]);
+
+            let extended = open.iter().cloned().find(|&idx| {
+                let r = &result[idx];
+                r.min_x() == x0 && r.max_x() == x1 && r.max_y() == y0
+            });
+
+            match extended {
+                Some(idx) => {
+                    result[idx].size.height = y1 - result[idx].origin.y;
+                    next_open.push(idx);
+                }
+                None => {
+                    next_open.push(result.len());
+                    result.push(rect(x0, y0, x1 - x0, y1 - y0));
+                }
+            }
+        }
+
+        std::mem::swap(&mut open, &mut next_open);
+    }
+
+    result
+}
+
+#[test]
+fn rectangle_decomposition_l_shape() {
+    use crate::math::point;
+    use crate::Path;
+
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(2.0, 0.0));
+    builder.line_to(point(2.0, 1.0));
+    builder.line_to(point(1.0, 1.0));
+    builder.line_to(point(1.0, 3.0));
+    builder.line_to(point(0.0, 3.0));
+    builder.close();
+    let path = builder.build();
+
+    let rects = rectangle_decomposition(&path.as_slice());
+    assert_eq!(
+        rects,
+        vec![rect(0.0, 0.0, 2.0, 1.0), rect(0.0, 1.0, 1.0, 2.0)]
+    );
+
+    // A square with a square hole.
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(3.0, 0.0));
+    builder.line_to(point(3.0, 3.0));
+    builder.line_to(point(0.0, 3.0));
+    builder.close();
+    builder.move_to(point(1.0, 1.0));
+    builder.line_to(point(2.0, 1.0));
+    builder.line_to(point(2.0, 2.0));
+    builder.line_to(point(1.0, 2.0));
+    builder.close();
+    let path = builder.build();
+
+    let rects = rectangle_decomposition(&path.as_slice());
+    let area: f32 = rects.iter().map(|r| r.size.width * r.size.height).sum();
+    assert_eq!(rects.len(), 4);
+    assert_eq!(area, 8.0);
+}
@@ -0,0 +1,137 @@
+//! Simplification of flattened paths.
+
+use crate::iterator::PathIterator;
+use crate::math::Point;
+use crate::{Path, PathEvent, PathSlice};
+
+// Share of the tolerance used to flatten the curves before simplifying them.
+const FLATTENING_SHARE: f32 = 0.05;
+
+/// Flattens a path into as few line segments as possible within the tolerance.
+///
+/// Adaptive flattening chooses the number of segments of each curve from its overall
+/// shape, which produces more points than needed on curves with varying curvature. This
+/// function instead flattens the curves with a small fraction of the tolerance and then
+/// removes the points that aren't needed, using the rest of the tolerance: each segment
+/// is extended greedily over the flattened points for as long as the points it skips stay
+/// within the tolerance. Whole sub-paths are simplified at once, so points are removed
+/// across the boundaries between segments as well.
+///
+/// The result stays within `tolerance` of the original path (as measured by the Hausdorff
+/// distance). The first point of each sub-path is kept. This is more expensive than
+/// adaptive flattening and meant for exporting compact polylines rather than rendering.
+pub fn flatten_optimized(path: &PathSlice, tolerance: f32) -> Path {
+    let mut builder = Path::builder();
+    let mut points = Vec::new();
+    let mut simplified = Vec::new();
+    for evt in path.iter().flattened(tolerance * FLATTENING_SHARE) {
+        match evt {
+            PathEvent::Begin { at } => {
+                points.clear();
+                points.push(at);
+            }
+            PathEvent::Line { to, .. } => {
+                points.push(to);
+            }
+            PathEvent::End { first, close, .. } => {
+                if close {
+                    points.push(first);
+                }
+
+                simplified.clear();
+                simplify(
+                    &points,
+                    tolerance * (1.0 - FLATTENING_SHARE),
+                    &mut simplified,
+                );
+
+                if close && simplified.len() > 1 {
+                    simplified.pop();
+                }
+
+                builder.move_to(simplified[0]);
+                for p in &simplified[1..] {
+                    builder.line_to(*p);
+                }
+                if close {
+                    builder.close();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    builder.build()
+}
+
+// Keeps the points that allow each segment to go as far as possible while the skipped
+// points stay within the tolerance.
+fn simplify(points: &[Point], tolerance: f32, output: &mut Vec<Point>) {
+    let mut start = 0;
+    output.push(points[0]);
+    while start + 1 < points.len() {
+        let mut end = start + 1;
+        while end + 1 < points.len()
+            && points[start + 1..=end]
+                .iter()
+                .all(|p| distance_to_segment(*p, points[start], points[end + 1]) <= tolerance)
+        {
+            end += 1;
+        }
+        output.push(points[end]);
+        start = end;
+    }
+}
+
+fn distance_to_segment(p: Point, from: Point, to: Point) -> f32 {
+    let v = to - from;
+    let square_length = v.square_length();
+    if square_length == 0.0 {
+        return (p - from).length();
+    }
+
+    let t = ((p - from).dot(v) / square_length).max(0.0).min(1.0);
+    (from + v * t - p).length()
+}
+
+#[test]
+fn flatten_optimized_cubic() {
+    use crate::math::point;
+
+    // A curve with a sharp bend and long flat parts.
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.cubic_bezier_to(point(100.0, 0.0), point(100.0, 0.0), point(100.0, 100.0));
+    builder.line_to(point(50.0, 100.0));
+    builder.line_to(point(0.0, 100.0));
+    builder.close();
+    let path = builder.build();
+
+    let is_point = |evt: &PathEvent| match evt {
+        PathEvent::Begin { .. } | PathEvent::Line { .. } => true,
+        _ => false,
+    };
+
+    for &tolerance in &[0.05, 0.25, 1.0] {
+        let num_adaptive_points = path.iter().flattened(tolerance).filter(is_point).count();
+        let optimized = flatten_optimized(&path.as_slice(), tolerance);
+
+        let num_optimized_points = optimized.iter().filter(is_point).count();
+        assert!(
+            num_optimized_points < num_adaptive_points,
+            "{} {}",
+            num_optimized_points,
+            num_adaptive_points,
+        );
+
+        let distance =
+            crate::hausdorff_distance(&path.as_slice(), &optimized.as_slice(), tolerance * 0.1);
+        assert!(distance <= tolerance * 1.1, "{} {}", distance, tolerance);
+
+        // The redundant point in the middle of the straight line is removed.
+        assert!(optimized.iter().all(|evt| match evt {
+            PathEvent::Line { to, .. } => to != point(50.0, 100.0),
+            _ => true,
+        }));
+    }
+}
@@ -0,0 +1,176 @@
+//! Parsing of the `points` attribute of SVG `<polyline>` and `<polygon>` elements.
+
+use crate::math::{vector, Vector};
+use crate::{ParseError, Path};
+
+// Parses the `points` attribute of SVG `<polyline>` and `<polygon>` elements.
+pub(crate) fn parse_svg_points(data: &str, closed: bool) -> Result<Path, ParseError> {
+    let mut parser = Parser {
+        data: data.as_bytes(),
+        pos: 0,
+    };
+    let mut builder = Path::builder();
+    let mut is_empty = true;
+
+    loop {
+        parser.skip_separators();
+        if parser.pos == parser.data.len() {
+            break;
+        }
+
+        let p = parser.vector()?.to_point();
+        if is_empty {
+            builder.move_to(p);
+            is_empty = false;
+        } else {
+            builder.line_to(p);
+        }
+    }
+
+    if closed && !is_empty {
+        builder.close();
+    }
+
+    Ok(builder.build())
+}
+
+struct Parser<'l> {
+    data: &'l [u8],
+    pos: usize,
+}
+
+impl<'l> Parser<'l> {
+    fn skip_separators(&mut self) {
+        while self.pos < self.data.len() {
+            match self.data[self.pos] {
+                b' ' | b'\t' | b'\n' | b'\r' | b'\x0C' | b',' => self.pos += 1,
+                _ => return,
+            }
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.data.get(self.pos).cloned()
+    }
+
+    fn skip_digits(&mut self) -> usize {
+        let start = self.pos;
+        while self.peek().map_or(false, |c| c.is_ascii_digit()) {
+            self.pos += 1;
+        }
+
+        self.pos - start
+    }
+
+    fn number(&mut self) -> Result<f32, ParseError> {
+        self.skip_separators();
+        let start = self.pos;
+        let invalid = ParseError::InvalidToken { offset: start };
+
+        if let Some(b'+') | Some(b'-') = self.peek() {
+            self.pos += 1;
+        }
+        let mut num_digits = self.skip_digits();
+        if self.peek() == Some(b'.') {
+            self.pos += 1;
+            num_digits += self.skip_digits();
+        }
+        if num_digits == 0 {
+            self.pos = start;
+            return Err(invalid);
+        }
+
+        // The exponent is only part of the number if it has digits, so that "2e" is
+        // not mistaken for a malformed number.
+        if let Some(b'e') | Some(b'E') = self.peek() {
+            let mantissa_end = self.pos;
+            self.pos += 1;
+            if let Some(b'+') | Some(b'-') = self.peek() {
+                self.pos += 1;
+            }
+            if self.skip_digits() == 0 {
+                self.pos = mantissa_end;
+            }
+        }
+
+        // The slice only contains ASCII characters.
+        let text = std::str::from_utf8(&self.data[start..self.pos]).unwrap();
+        text.parse::<f32>().map_err(|_| invalid)
+    }
+
+    fn vector(&mut self) -> Result<Vector, ParseError> {
+        let x = self.number()?;
+        let y = self.number()?;
+
+        Ok(vector(x, y))
+    }
+}
+
+#[cfg(test)]
+fn endpoints(path: &Path) -> Vec<crate::math::Point> {
+    use crate::PathEvent;
+
+    path.iter()
+        .filter_map(|evt| match evt {
+            PathEvent::Begin { at } => Some(at),
+            PathEvent::Line { to, .. }
+            | PathEvent::Quadratic { to, .. }
+            | PathEvent::Cubic { to, .. } => Some(to),
+            PathEvent::End { .. } => None,
+        })
+        .collect()
+}
+
+#[test]
+fn parse_svg_points_attribute() {
+    use crate::math::point;
+    use crate::PathEvent;
+
+    let triangle = Path::from_svg_points("0,0 1,0 1,1", true).unwrap();
+    assert_eq!(
+        triangle.iter().collect::<Vec<_>>(),
+        vec![
+            PathEvent::Begin {
+                at: point(0.0, 0.0)
+            },
+            PathEvent::Line {
+                from: point(0.0, 0.0),
+                to: point(1.0, 0.0)
+            },
+            PathEvent::Line {
+                from: point(1.0, 0.0),
+                to: point(1.0, 1.0)
+            },
+            PathEvent::End {
+                last: point(1.0, 1.0),
+                first: point(0.0, 0.0),
+                close: true
+            },
+        ]
+    );
+
+    // Any mix of commas and whitespace separates the coordinates.
+    let polyline = Path::from_svg_points(" 0 0,1 ,0\n1\t1 ", false).unwrap();
+    assert_eq!(
+        endpoints(&polyline),
+        vec![point(0.0, 0.0), point(1.0, 0.0), point(1.0, 1.0)]
+    );
+    assert_eq!(
+        polyline.iter().last(),
+        Some(PathEvent::End {
+            last: point(1.0, 1.0),
+            first: point(0.0, 0.0),
+            close: false
+        })
+    );
+
+    let error = |offset| Some(ParseError::InvalidToken { offset });
+    assert_eq!(Path::from_svg_points("0,0 1,x", true).err(), error(6));
+    // Odd number of coordinates.
+    assert_eq!(Path::from_svg_points("0,0 1", true).err(), error(5));
+    assert!(Path::from_svg_points("", true)
+        .unwrap()
+        .iter()
+        .next()
+        .is_none());
+}
@@ -191,11 +191,41 @@ where
 /// A `PathBuilder` that builds a `String` representation of the path
 /// using the SVG syntax.
 ///
-/// No effort is put into making the serializer fast or make the
-/// output compact. Intended primarily for debugging purposes.
+/// Consecutive absolute line segments share the same `L` command. By default coordinates
+/// are written with as many decimals as needed to represent them exactly, see
+/// `with_precision`. Custom attributes are not serialized.
+///
+/// # Examples
+///
+/// ```
+/// # extern crate lyon_svg as svg;
+/// # extern crate lyon_path;
+/// # use lyon_path::Path;
+/// # use lyon_path::builder::*;
+/// # use lyon_path::math::point;
+/// # use svg::path_utils::PathSerializer;
+/// # fn main() {
+/// let mut builder = Path::builder();
+/// builder.move_to(point(0.0, 0.0));
+/// builder.line_to(point(1.0, 0.0));
+/// builder.line_to(point(1.0, 1.0));
+/// builder.close();
+/// let path = builder.build();
+///
+/// // Serialize an existing path by replaying its events.
+/// let mut serializer = PathSerializer::new();
+/// for evt in path.iter() {
+///     serializer.path_event(evt);
+/// }
+/// assert_eq!(serializer.build(), "M 0 0 L 1 0 1 1 Z");
+/// # }
+/// ```
 pub struct PathSerializer {
     path: String,
     current: Point,
+    first: Point,
+    previous_command: char,
+    decimals: Option<usize>,
 }
 
 impl PathSerializer {
@@ -203,7 +233,63 @@ impl PathSerializer {
         PathSerializer {
             path: String::new(),
             current: point(0.0, 0.0),
+            first: point(0.0, 0.0),
+            previous_command: ' ',
+            decimals: None,
+        }
+    }
+
+    /// Constructor that rounds the values to the provided number of decimals.
+    ///
+    /// Trailing zeros are omitted.
+    pub fn with_precision(decimals: usize) -> Self {
+        PathSerializer {
+            decimals: Some(decimals),
+            ..PathSerializer::new()
+        }
+    }
+
+    fn push_command(&mut self, command: char, values: &[f32]) {
+        // Consecutive line segments share the same command.
+        if command != 'L' || self.previous_command != 'L' {
+            if !self.path.is_empty() {
+                self.path.push(' ');
+            }
+            self.path.push(command);
+        }
+
+        for val in values {
+            self.path.push(' ');
+            self.push_number(*val);
+        }
+
+        self.previous_command = command;
+    }
+
+    fn push_number(&mut self, val: f32) {
+        let mut text = match self.decimals {
+            Some(decimals) => format!("{:.*}", decimals, val),
+            None => format!("{}", val),
+        };
+
+        if text.contains('.') {
+            let len = text.trim_end_matches('0').trim_end_matches('.').len();
+            text.truncate(len);
+        }
+
+        if text == "-0" {
+            text = "0".to_string();
         }
+
+        self.path.push_str(&text);
+    }
+}
+
+fn flag(value: bool) -> f32 {
+    if value {
+        1.0
+    } else {
+        0.0
     }
 }
 
@@ -216,23 +302,27 @@ impl Build for PathSerializer {
 
     fn build_and_reset(&mut self) -> String {
         self.current = point(0.0, 0.0);
+        self.first = point(0.0, 0.0);
+        self.previous_command = ' ';
         mem::replace(&mut self.path, String::new())
     }
 }
 
 impl FlatPathBuilder for PathSerializer {
     fn move_to(&mut self, to: Point) {
-        self.path += &format!("M {} {} ", to.x, to.y);
+        self.push_command('M', &[to.x, to.y]);
         self.current = to;
+        self.first = to;
     }
 
     fn line_to(&mut self, to: Point) {
-        self.path += &format!("L {} {} ", to.x, to.y);
+        self.push_command('L', &[to.x, to.y]);
         self.current = to;
     }
 
     fn close(&mut self) {
-        self.path.push_str("Z");
+        self.push_command('Z', &[]);
+        self.current = self.first;
     }
 
     fn current_position(&self) -> Point {
@@ -242,14 +332,13 @@ impl FlatPathBuilder for PathSerializer {
 
 impl PathBuilder for PathSerializer {
     fn quadratic_bezier_to(&mut self, ctrl: Point, to: Point) {
-        self.path += &format!("Q {} {} {} {}", ctrl.x, ctrl.y, to.x, to.y);
+        self.push_command('Q', &[ctrl.x, ctrl.y, to.x, to.y]);
+        self.current = to;
     }
 
     fn cubic_bezier_to(&mut self, ctrl1: Point, ctrl2: Point, to: Point) {
-        self.path += &format!(
-            "C {} {} {} {} {} {}",
-            ctrl1.x, ctrl1.y, ctrl2.x, ctrl2.y, to.x, to.y
-        );
+        self.push_command('C', &[ctrl1.x, ctrl1.y, ctrl2.x, ctrl2.y, to.x, to.y]);
+        self.current = to;
     }
 
     fn arc(&mut self, center: Point, radii: Vector, sweep_angle: Angle, x_rotation: Angle) {
@@ -262,95 +351,102 @@ impl PathBuilder for PathSerializer {
             x_rotation,
         }
         .to_svg_arc();
-        self.path += &format!(
-            "A {} {} {} {} {} {} {}",
-            radii.x,
-            radii.y,
-            svg.x_rotation.get(),
-            svg.flags.large_arc,
-            svg.flags.sweep,
-            svg.to.x,
-            svg.to.y
-        );
+        self.arc_to(svg.radii, svg.x_rotation, svg.flags, svg.to);
     }
 }
 
 impl SvgBuilder for PathSerializer {
     fn relative_move_to(&mut self, to: Vector) {
-        self.path += &format!("m {} {} ", to.x, to.y);
+        self.push_command('m', &[to.x, to.y]);
+        self.current += to;
+        self.first = self.current;
     }
 
     fn relative_line_to(&mut self, to: Vector) {
-        self.path += &format!("l {} {} ", to.x, to.y);
+        self.push_command('l', &[to.x, to.y]);
+        self.current += to;
     }
 
     fn relative_quadratic_bezier_to(&mut self, ctrl: Vector, to: Vector) {
-        self.path += &format!("q {} {} {} {}", ctrl.x, ctrl.y, to.x, to.y);
+        self.push_command('q', &[ctrl.x, ctrl.y, to.x, to.y]);
+        self.current += to;
     }
 
     fn relative_cubic_bezier_to(&mut self, ctrl1: Vector, ctrl2: Vector, to: Vector) {
-        self.path += &format!(
-            "c {} {} {} {} {} {}",
-            ctrl1.x, ctrl1.y, ctrl2.x, ctrl2.y, to.x, to.y
-        );
+        self.push_command('c', &[ctrl1.x, ctrl1.y, ctrl2.x, ctrl2.y, to.x, to.y]);
+        self.current += to;
     }
 
     fn smooth_cubic_bezier_to(&mut self, ctrl2: Point, to: Point) {
-        self.path += &format!("S {} {} {} {}", ctrl2.x, ctrl2.y, to.x, to.y);
+        self.push_command('S', &[ctrl2.x, ctrl2.y, to.x, to.y]);
+        self.current = to;
     }
 
     fn smooth_relative_cubic_bezier_to(&mut self, ctrl2: Vector, to: Vector) {
-        self.path += &format!("s {} {} {} {}", ctrl2.x, ctrl2.y, to.x, to.y);
+        self.push_command('s', &[ctrl2.x, ctrl2.y, to.x, to.y]);
+        self.current += to;
     }
 
     fn smooth_quadratic_bezier_to(&mut self, to: Point) {
-        self.path += &format!("T {} {} ", to.x, to.y);
+        self.push_command('T', &[to.x, to.y]);
+        self.current = to;
     }
 
     fn smooth_relative_quadratic_bezier_to(&mut self, to: Vector) {
-        self.path += &format!("t {} {} ", to.x, to.y);
+        self.push_command('t', &[to.x, to.y]);
+        self.current += to;
     }
 
     fn horizontal_line_to(&mut self, x: f32) {
-        self.path += &format!("H {} ", x);
+        self.push_command('H', &[x]);
+        self.current.x = x;
     }
 
     fn relative_horizontal_line_to(&mut self, dx: f32) {
-        self.path += &format!("h {} ", dx);
+        self.push_command('h', &[dx]);
+        self.current.x += dx;
     }
 
     fn vertical_line_to(&mut self, y: f32) {
-        self.path += &format!("V {} ", y);
+        self.push_command('V', &[y]);
+        self.current.y = y;
     }
 
     fn relative_vertical_line_to(&mut self, dy: f32) {
-        self.path += &format!("v {} ", dy);
+        self.push_command('v', &[dy]);
+        self.current.y += dy;
     }
 
     fn arc_to(&mut self, radii: Vector, x_rotation: Angle, flags: ArcFlags, to: Point) {
-        self.path += &format!(
-            "A {} {} {} {} {} {} {} ",
-            radii.x,
-            radii.y,
-            x_rotation.get() * 180.0 / PI,
-            if flags.large_arc { 1u32 } else { 0 },
-            if flags.sweep { 1u32 } else { 0 },
-            to.x,
-            to.y
+        self.push_command(
+            'A',
+            &[
+                radii.x,
+                radii.y,
+                x_rotation.get() * 180.0 / PI,
+                flag(flags.large_arc),
+                flag(flags.sweep),
+                to.x,
+                to.y,
+            ],
         );
+        self.current = to;
     }
 
     fn relative_arc_to(&mut self, radii: Vector, x_rotation: Angle, flags: ArcFlags, to: Vector) {
-        self.path += &format!(
-            "a {} {} {} {} {} {} {} ",
-            radii.x,
-            radii.y,
-            x_rotation.get() * 180.0 / PI,
-            if flags.large_arc { 1u32 } else { 0 },
-            if flags.sweep { 1u32 } else { 0 },
-            to.x,
-            to.y
+        self.push_command(
+            'a',
+            &[
+                radii.x,
+                radii.y,
+                x_rotation.get() * 180.0 / PI,
+                flag(flags.large_arc),
+                flag(flags.sweep),
+                to.x,
+                to.y,
+            ],
         );
+        self.current += to;
     }
 }
 
@@ -359,3 +455,55 @@ impl PolygonBuilder for PathSerializer {
         build_polygon(self, points);
     }
 }
+
+#[test]
+fn path_serializer() {
+    use crate::path::Path;
+
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to(point(10.0, 10.5));
+    builder.quadratic_bezier_to(point(5.0, 15.0), point(0.0, 10.0));
+    builder.line_to(point(-0.0, 5.0));
+    builder.close();
+    builder.move_to(point(20.0, 0.0));
+    builder.cubic_bezier_to(point(21.0, 1.0), point(22.0, -1.0), point(23.0, 0.0));
+    builder.line_to(point(1.0 / 3.0, 2.0 / 3.0));
+    let path = builder.build();
+
+    let serialize = |mut serializer: PathSerializer| {
+        for evt in path.iter() {
+            serializer.path_event(evt);
+        }
+        serializer.build()
+    };
+
+    assert_eq!(
+        serialize(PathSerializer::with_precision(2)),
+        "M 0 0 L 10 0 10 10.5 Q 5 15 0 10 L 0 5 Z M 20 0 C 21 1 22 -1 23 0 L 0.33 0.67"
+    );
+
+    // Without precision, the coordinates are written as exactly as possible.
+    assert!(serialize(PathSerializer::new()).starts_with(
+        "M 0 0 L 10 0 10 10.5 Q 5 15 0 10 L 0 5 Z M 20 0 C 21 1 22 -1 23 0 L 0.33333334 "
+    ));
+
+    assert_eq!(PathSerializer::new().build(), "");
+
+    let mut serializer = PathSerializer::new();
+    serializer.move_to(point(1.0, 1.0));
+    serializer.relative_line_to(vector(1.0, 0.0));
+    serializer.arc_to(
+        vector(2.0, 2.0),
+        Angle::degrees(90.0),
+        ArcFlags {
+            large_arc: true,
+            sweep: false,
+        },
+        point(5.0, 5.0),
+    );
+    serializer.horizontal_line_to(0.0);
+    assert_eq!(serializer.current_position(), point(0.0, 5.0));
+    assert_eq!(serializer.build(), "M 1 1 l 1 0 A 2 2 90 1 0 5 5 H 0");
+}
@@ -23,16 +23,17 @@
 //! More explanation about flattening and tolerance in the
 //! [lyon_geom crate](https://docs.rs/lyon_geom/#flattening).
 
+use crate::algorithms::hatching::{HatchSegment, Hatcher, HatchingOptions, RegularHatchingPattern};
 use crate::geom::math::*;
 use crate::geom::Arc;
 use crate::geometry_builder::*;
 use crate::path::builder::FlatPathBuilder;
 use crate::path::iterator::FromPolyline;
-use crate::path::EndpointId;
+use crate::path::{EndpointId, PathEvent, PathSlice};
 use crate::stroke::{StrokeBuilder, StrokeTessellator};
 use crate::{
-    FillOptions, FillTessellator, Side, StrokeAttributes, StrokeAttributesData, StrokeOptions,
-    TessellationResult, VertexId, VertexSource,
+    FillOptions, FillTessellator, LineJoin, Side, StrokeAttributes, StrokeAttributesData,
+    StrokeOptions, TessellationResult, VertexId, VertexSource,
 };
 
 use std::f32::consts::PI;
@@ -193,7 +194,7 @@ fn stroke_thin_rectangle(
 }
 
 /// The radius of each corner of a rounded rectangle.
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub struct BorderRadii {
     pub top_left: f32,
     pub top_right: f32,
@@ -223,6 +224,9 @@ impl BorderRadii {
 }
 
 /// Tessellate an axis-aligned rounded rectangle.
+///
+/// The corners are approximated with the tolerance of the options. Radii that don't fit
+/// in the rectangle are reduced so that the corners don't overlap.
 pub fn fill_rounded_rectangle(
     rect: &Rect,
     radii: &BorderRadii,
@@ -322,7 +326,8 @@ pub fn fill_rounded_rectangle(
             let step = circle_flattening_step(radius, options.tolerance);
             let num_segments = (arc_len / step).ceil();
 
-            let num_recursions = num_segments.log2() as u32;
+            // Each recursion doubles the number of segments.
+            let num_recursions = num_segments.log2().ceil() as u32;
 
             fill_border_radius(
                 centers[i],
@@ -383,6 +388,8 @@ fn fill_border_radius(
 }
 
 /// Tessellate the stroke for an axis-aligned rounded rectangle.
+///
+/// The radii are clamped in the same way as in `fill_rounded_rectangle`.
 pub fn stroke_rounded_rectangle(
     rect: &Rect,
     radii: &BorderRadii,
@@ -528,7 +535,8 @@ pub fn fill_circle(
     let arc_len = 0.5 * PI * radius;
     let step = circle_flattening_step(radius, options.tolerance);
     let num_segments = (arc_len / step).ceil();
-    let num_recursions = num_segments.log2() as u32;
+    // Each recursion doubles the number of segments.
+    let num_recursions = num_segments.log2().ceil() as u32;
 
     for i in 0..4 {
         fill_border_radius(
@@ -546,6 +554,9 @@ pub fn fill_circle(
 }
 
 /// Tessellate the stroke for a circle.
+///
+/// The stroke is a closed ring between the circles of radius `radius - line_width / 2`
+/// and `radius + line_width / 2`, or a disk if the line is wider than the circle.
 pub fn stroke_circle(
     center: Point,
     radius: f32,
@@ -559,13 +570,19 @@ pub fn stroke_circle(
         return Ok(output.end_geometry());
     }
 
+    // The outer side of the stroke needs more segments than the circle itself.
+    let outer_radius = radius + options.line_width * 0.5;
+    let arc_len = 2.0 * PI * outer_radius;
+    let step = circle_flattening_step(outer_radius, options.tolerance);
+    let num_points = (arc_len / step).ceil() as u32 - 1;
+
+    if options.line_width * 0.5 >= radius {
+        return stroke_thick_circle(center, radius, num_points + 1, options, output);
+    }
+
     let angle = (0.0, 2.0 * PI);
     let starting_point = center + vector(1.0, 0.0) * radius;
 
-    let arc_len = 2.0 * PI * radius;
-    let step = circle_flattening_step(radius, options.tolerance);
-    let num_points = (arc_len / step).ceil() as u32 - 1;
-
     {
         // output borrow scope start
         let mut builder = StrokeBuilder::new(options, &(), &mut [], output);
@@ -577,6 +594,64 @@ pub fn stroke_circle(
     Ok(output.end_geometry())
 }
 
+// A fall-back for circles that are smaller than the line width, for which the inner side
+// of the stroke would go past the center. The stroke covers a disk in this case.
+#[inline(never)]
+fn stroke_thick_circle(
+    center: Point,
+    radius: f32,
+    num_segments: u32,
+    options: &StrokeOptions,
+    output: &mut dyn StrokeGeometryBuilder,
+) -> TessellationResult {
+    let outer_radius = if options.apply_line_width {
+        radius + options.line_width * 0.5
+    } else {
+        radius
+    };
+
+    let mut attributes = StrokeAttributesData {
+        normal: vector(0.0, 0.0),
+        advancement: 0.0,
+        side: Side::Left,
+        src: VertexSource::Endpoint {
+            id: EndpointId::INVALID,
+        },
+        store: &(),
+        buffer: &mut [],
+        buffer_is_valid: true,
+    };
+
+    let center_id = output.add_stroke_vertex(center, StrokeAttributes(&mut attributes))?;
+
+    attributes.side = Side::Right;
+    let mut first = None;
+    let mut prev = None;
+    for i in 0..num_segments {
+        let angle = i as f32 * 2.0 * PI / num_segments as f32;
+        let normal = vector(angle.cos(), angle.sin());
+        attributes.normal = normal;
+        attributes.advancement = angle * radius;
+        let id = output.add_stroke_vertex(
+            center + normal * outer_radius,
+            StrokeAttributes(&mut attributes),
+        )?;
+
+        if let Some(prev) = prev {
+            output.add_triangle(center_id, prev, id);
+        } else {
+            first = Some(id);
+        }
+        prev = Some(id);
+    }
+
+    if let (Some(first), Some(last)) = (first, prev) {
+        output.add_triangle(center_id, last, first);
+    }
+
+    Ok(output.end_geometry())
+}
+
 // tessellate the stroke for rounded corners using the inner points.
 // assumming the builder started with move_to().
 fn stroke_border_radius(
@@ -597,59 +672,40 @@ fn stroke_border_radius(
     }
 }
 
-/* TODO
 /// Tessellate an ellipse.
+///
+/// The ellipse is approximated with the tolerance of the options and rotated by
+/// `x_rotation` around its center.
 pub fn fill_ellipse(
     center: Point,
     radii: Vector,
     x_rotation: Angle,
     options: &FillOptions,
-    output: &mut dyn GeometryBuilder<FillVertex>,
+    output: &mut dyn FillGeometryBuilder,
 ) -> TessellationResult {
-    if radii.x == radii.y {
-        return Ok(fill_circle(center, radii.x, options, output)?);
-    }
-
-    // TODO: This is far from optimal compared to the circle tessellation, but it
-    // correctly takes the tolerance threshold into account which is harder to do
-    // than with circles.
-
     let arc = Arc {
         center,
         radii,
         x_rotation,
         start_angle: Angle::radians(0.0),
-        sweep_angle: Angle::radians(2.0 * PI-0.01),
+        sweep_angle: Angle::radians(2.0 * PI),
     };
 
-    use crate::path::builder::{Build, PathBuilder, FlatteningBuilder};
-    use crate::path_fill::EventsBuilder;
-
-    let mut path = FlatteningBuilder::new(
-        EventsBuilder::new(),
-        options.tolerance
-    ).with_svg();
-
-    // TODO don't need to go through quadratic bézier approximation here.
-    path.move_to(arc.sample(0.0));
-    arc.for_each_quadratic_bezier(&mut|curve| {
-        path.quadratic_bezier_to(curve.ctrl, curve.to);
-    });
-    path.close();
+    // The ellipse is the image of a circle by a transformation that doesn't scale
+    // distances by more than the largest radius, so the flattening step of a circle with
+    // that radius keeps the ellipse within the tolerance.
+    let radius = radii.x.abs().max(radii.y.abs());
+    if radius == 0.0 {
+        output.begin_geometry();
+        return Ok(output.end_geometry());
+    }
 
-    let events = path.build();
+    let step = circle_flattening_step(radius, options.tolerance);
+    let num_points = ((2.0 * PI * radius / step).ceil() as u32).max(3);
+    let points = (0..num_points).map(|i| arc.sample(i as f32 / num_points as f32));
 
-    // TODO: We could avoid checking for intersections, however the way we
-    // generate the path is a little silly and because of finite float precision,
-    // it will sometimes produce an intersection where the end of ellipse meets
-    // the beginning, which confuses the fill tessellator.
-    FillTessellator::new().tessellate_events(
-        &events,
-        &options,
-        output,
-    )
+    fill_polyline(points, &mut FillTessellator::new(), options, output)
 }
-*/
 
 /// Tessellate the stroke for an ellipse.
 pub fn stroke_ellipse(
@@ -729,6 +785,177 @@ where
     )
 }
 
+/// Tessellate a thick open polyline as a ribbon of triangles.
+///
+/// This is a faster alternative to `stroke_polyline` for the common case of a thick
+/// line without caps. The line width, join, miter limit and tolerance are read from the
+/// stroke options, the other options are ignored.
+///
+/// Each point of the polyline produces a pair of vertices on each side of the line and
+/// the triangles are emitted in triangle strip order, so that a `StripBuffersBuilder`
+/// can write straight and mitered sections as a single strip. Joins that don't fit in
+/// the strip add a fan of triangles that starts a new one.
+///
+/// Miter joins that exceed the miter limit and `MiterClip` joins are replaced with bevel
+/// joins. Consecutive points that are too close to each other are skipped.
+pub fn fill_polyline_as_ribbon<Iter>(
+    points: Iter,
+    options: &StrokeOptions,
+    output: &mut dyn BasicGeometryBuilder,
+) -> TessellationResult
+where
+    Iter: IntoIterator<Item = Point>,
+{
+    output.begin_geometry();
+
+    let mut polyline: Vec<Point> = Vec::new();
+    for p in points {
+        let is_new = match polyline.last() {
+            Some(last) => (p - *last).square_length() > 1e-8,
+            None => true,
+        };
+        if is_new {
+            polyline.push(p);
+        }
+    }
+
+    let half_width = options.line_width * 0.5;
+    if polyline.len() < 2 || half_width <= 0.0 {
+        return Ok(output.end_geometry());
+    }
+
+    let normal = |d: Vector| vector(-d.y, d.x);
+
+    let mut d0 = (polyline[1] - polyline[0]).normalize();
+    let mut length0 = (polyline[1] - polyline[0]).length();
+    let mut prev_left = output.add_vertex(polyline[0] + normal(d0) * half_width)?;
+    let mut prev_right = output.add_vertex(polyline[0] - normal(d0) * half_width)?;
+
+    for i in 1..polyline.len() - 1 {
+        let p = polyline[i];
+        let d1 = (polyline[i + 1] - p).normalize();
+        let length1 = (polyline[i + 1] - p).length();
+        let (n0, n1) = (normal(d0), normal(d1));
+
+        let turn = d0.cross(d1);
+        let cos = d0.dot(d1);
+        // The cosine of half of the angle between the normals. The miter is
+        // `half_width / half_cos` long.
+        let half_cos = ((1.0 + cos) * 0.5).max(0.0).sqrt();
+        let miter = || (n0 + n1).normalize() * (half_width / half_cos);
+
+        // The inner corner of the join can be shared by both segments if it doesn't go
+        // past the middle of either of them. This is never the case for U-turns.
+        let inner_fits = half_width * turn.abs() < 0.5 * length0.min(length1) * (1.0 + cos);
+        let is_straight = turn.abs() < 1e-5 && cos > 0.0;
+        let use_miter =
+            options.line_join == LineJoin::Miter && half_cos * options.miter_limit >= 1.0;
+
+        let inner_is_left = turn > 0.0;
+        let outer_sign = if inner_is_left { -1.0 } else { 1.0 };
+        let outer_start = p + n0 * (half_width * outer_sign);
+        let outer_end = p + n1 * (half_width * outer_sign);
+
+        // The points of the outer side of the join, between the two segments.
+        let mut join_points = Vec::new();
+        if options.line_join == LineJoin::Round {
+            let sweep = turn.abs().atan2(cos) * -outer_sign;
+            let start_angle = (n0 * outer_sign).angle_from_x_axis().radians;
+            let step = circle_flattening_step(half_width, options.tolerance) / half_width;
+            let num_segments = (sweep.abs() / step).ceil().max(1.0) as u32;
+            for k in 1..num_segments {
+                let angle = start_angle + sweep * k as f32 / num_segments as f32;
+                join_points.push(p + vector(angle.cos(), angle.sin()) * half_width);
+            }
+        } else if use_miter && !inner_fits {
+            join_points.push(p + miter() * outer_sign);
+        }
+
+        if inner_fits && (is_straight || use_miter) {
+            let left = output.add_vertex(p + miter())?;
+            let right = output.add_vertex(p - miter())?;
+            add_ribbon_quad(output, prev_left, prev_right, left, right);
+            prev_left = left;
+            prev_right = right;
+        } else {
+            // Either both segments share the inner corner, or they end at the full width
+            // of the line and the join is a fan around the point itself.
+            let (center, center_position, first_outer, next_inner) = if inner_fits {
+                let inner_position = p - miter() * outer_sign;
+                let inner = output.add_vertex(inner_position)?;
+                let first_outer = output.add_vertex(outer_start)?;
+                let (left, right) = if inner_is_left {
+                    (inner, first_outer)
+                } else {
+                    (first_outer, inner)
+                };
+                add_ribbon_quad(output, prev_left, prev_right, left, right);
+                (inner, inner_position, first_outer, inner)
+            } else {
+                let first_inner = output.add_vertex(p - n0 * (half_width * outer_sign))?;
+                let first_outer = output.add_vertex(outer_start)?;
+                let center = output.add_vertex(p)?;
+                let next_inner = output.add_vertex(p - n1 * (half_width * outer_sign))?;
+                let (left, right) = if inner_is_left {
+                    (first_inner, first_outer)
+                } else {
+                    (first_outer, first_inner)
+                };
+                add_ribbon_quad(output, prev_left, prev_right, left, right);
+                (center, p, first_outer, next_inner)
+            };
+
+            join_points.push(outer_end);
+            let mut prev_outer = first_outer;
+            let mut prev_position = outer_start;
+            for &position in &join_points {
+                let outer = output.add_vertex(position)?;
+                // The bevel of a U-turn is flat.
+                let area = (prev_position - center_position).cross(position - center_position);
+                if area != 0.0 {
+                    if inner_is_left {
+                        output.add_triangle(center, prev_outer, outer);
+                    } else {
+                        output.add_triangle(center, outer, prev_outer);
+                    }
+                }
+                prev_outer = outer;
+                prev_position = position;
+            }
+
+            if inner_is_left {
+                prev_left = next_inner;
+                prev_right = prev_outer;
+            } else {
+                prev_left = prev_outer;
+                prev_right = next_inner;
+            }
+        }
+
+        d0 = d1;
+        length0 = length1;
+    }
+
+    let last = polyline[polyline.len() - 1];
+    let left = output.add_vertex(last + normal(d0) * half_width)?;
+    let right = output.add_vertex(last - normal(d0) * half_width)?;
+    add_ribbon_quad(output, prev_left, prev_right, left, right);
+
+    Ok(output.end_geometry())
+}
+
+// Adds the two triangles of a section of a ribbon in triangle strip order.
+fn add_ribbon_quad(
+    output: &mut dyn BasicGeometryBuilder,
+    prev_left: VertexId,
+    prev_right: VertexId,
+    left: VertexId,
+    right: VertexId,
+) {
+    output.add_triangle(prev_left, prev_right, left);
+    output.add_triangle(left, prev_right, right);
+}
+
 /// Tessellate an arbitrary shape that is described by an iterator of points.
 pub fn fill_polyline<Iter>(
     polyline: Iter,
@@ -742,6 +969,77 @@ where
     tessellator.tessellate(FromPolyline::closed(polyline.into_iter()), options, output)
 }
 
+/// Tessellate an arbitrary polygon.
+///
+/// The polygon is implicitly closed and goes through the fill tessellator, so it can be
+/// concave or self-intersecting. Self-intersecting polygons are filled according to the
+/// fill rule of the options (even-odd by default).
+pub fn fill_polygon(
+    points: &[Point],
+    tessellator: &mut FillTessellator,
+    options: &FillOptions,
+    output: &mut dyn FillGeometryBuilder,
+) -> TessellationResult {
+    fill_polyline(points.iter().cloned(), tessellator, options, output)
+}
+
+/// Tessellate a convex polygon.
+///
+/// See `fill_convex_polyline`.
+pub fn fill_convex_polygon(
+    points: &[Point],
+    options: &FillOptions,
+    output: &mut dyn FillGeometryBuilder,
+) -> TessellationResult {
+    fill_convex_polyline(points.iter().cloned(), options, output)
+}
+
+/// Tessellate parallel hatch lines covering the fill of a path.
+///
+/// The lines are `spacing` apart and run in the direction given by `angle`, measured
+/// from the x axis. They are produced by `lyon_algorithms`' `Hatcher`, which clips them
+/// to the fill of the path with the even-odd fill rule after flattening it with the
+/// tolerance of the stroke options. Each clipped segment is stroked as an open sub-path.
+///
+/// Cross-hatching is obtained by calling this function a second time with another angle.
+pub fn hatch_fill(
+    path: &PathSlice,
+    spacing: f32,
+    angle: Angle,
+    options: &StrokeOptions,
+    output: &mut dyn StrokeGeometryBuilder,
+) -> TessellationResult {
+    let mut hatches = Vec::new();
+    if spacing > 0.0 {
+        let hatching_options = HatchingOptions::tolerance(options.tolerance)
+            .with_angle(angle)
+            .with_tangents(false);
+        Hatcher::new().hatch_path(
+            path.iter(),
+            &hatching_options,
+            &mut RegularHatchingPattern {
+                interval: spacing,
+                callback: &mut |segment: &HatchSegment| {
+                    let first = segment.a.position;
+                    let last = segment.b.position;
+                    hatches.push(PathEvent::Begin { at: first });
+                    hatches.push(PathEvent::Line {
+                        from: first,
+                        to: last,
+                    });
+                    hatches.push(PathEvent::End {
+                        last,
+                        first,
+                        close: false,
+                    });
+                },
+            },
+        );
+    }
+
+    StrokeTessellator::new().tessellate(hatches, options, output)
+}
+
 // Returns the maximum length of individual line segments when approximating a
 // circle.
 //
@@ -794,3 +1092,561 @@ fn issue_366() {
     )
     .unwrap();
 }
+
+#[cfg(test)]
+fn ribbon(points: &[Point], options: &StrokeOptions) -> VertexBuffers<Point, u16> {
+    let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+    fill_polyline_as_ribbon(
+        points.iter().cloned(),
+        options,
+        &mut simple_builder(&mut buffers),
+    )
+    .unwrap();
+
+    for v in &buffers.vertices {
+        assert!(v.x.is_finite() && v.y.is_finite(), "{:?}", buffers.vertices);
+    }
+
+    buffers
+}
+
+#[cfg(test)]
+fn ribbon_area(buffers: &VertexBuffers<Point, u16>) -> f32 {
+    let mut area = 0.0;
+    for triangle in buffers.indices.chunks(3) {
+        let a = buffers.vertices[triangle[0] as usize];
+        let b = buffers.vertices[triangle[1] as usize];
+        let c = buffers.vertices[triangle[2] as usize];
+        let triangle_area = (b - a).cross(c - a) * 0.5;
+        // All triangles have the same winding.
+        assert!(triangle_area > 0.0);
+        area += triangle_area;
+    }
+    area
+}
+
+#[test]
+fn polyline_ribbon() {
+    let polyline = [point(0.0, 0.0), point(10.0, 0.0), point(10.0, 10.0)];
+    let options = StrokeOptions::default().with_line_width(2.0);
+
+    let miter = ribbon(&polyline, &options.with_line_join(LineJoin::Miter));
+    // A single pair of vertices per point.
+    assert_eq!(miter.vertices.len(), 6);
+    assert_eq!(miter.indices.len(), 12);
+    let has_vertex = |p: Point| miter.vertices.iter().any(|v| (*v - p).length() < 0.0001);
+    assert!(has_vertex(point(11.0, -1.0)));
+    assert!(has_vertex(point(9.0, 1.0)));
+    assert!((ribbon_area(&miter) - 40.0).abs() < 0.001);
+
+    let bevel = ribbon(&polyline, &options.with_line_join(LineJoin::Bevel));
+    // The join adds a vertex and a triangle.
+    assert_eq!(bevel.vertices.len(), 7);
+    assert_eq!(bevel.indices.len(), 15);
+    assert!((ribbon_area(&bevel) - 39.5).abs() < 0.001);
+
+    let round = ribbon(&polyline, &options.with_line_join(LineJoin::Round));
+    let expected = 39.0 + PI / 4.0;
+    assert!(ribbon_area(&round) > 39.5);
+    assert!(ribbon_area(&round) < expected + 0.001);
+
+    // A miter join that exceeds the limit is beveled. The miter of a right angle is
+    // sqrt(2) times longer than the line width.
+    let limited = ribbon(
+        &polyline,
+        &options
+            .with_line_join(LineJoin::Miter)
+            .with_miter_limit(1.4),
+    );
+    assert_eq!(limited.vertices, bevel.vertices);
+    let at_limit = ribbon(
+        &polyline,
+        &options
+            .with_line_join(LineJoin::Miter)
+            .with_miter_limit(1.415),
+    );
+    assert_eq!(at_limit.vertices, miter.vertices);
+
+    // The round join follows the tolerance.
+    let coarse = ribbon(
+        &polyline,
+        &options.with_line_join(LineJoin::Round).with_tolerance(0.1),
+    );
+    let fine = ribbon(
+        &polyline,
+        &options
+            .with_line_join(LineJoin::Round)
+            .with_tolerance(0.001),
+    );
+    assert!(coarse.vertices.len() < fine.vertices.len());
+    assert!(expected - ribbon_area(&fine) < 0.01);
+}
+
+#[test]
+fn polyline_ribbon_strip() {
+    let polyline = [
+        point(0.0, 0.0),
+        point(10.0, 0.0),
+        point(10.0, 10.0),
+        point(20.0, 15.0),
+    ];
+
+    let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+    fill_polyline_as_ribbon(
+        polyline.iter().cloned(),
+        &StrokeOptions::default()
+            .with_line_width(2.0)
+            .with_line_join(LineJoin::Miter),
+        &mut StripBuffersBuilder::new(&mut buffers, Positions),
+    )
+    .unwrap();
+
+    // A single strip without any degenerate triangle.
+    assert_eq!(buffers.indices, vec![0, 1, 2, 3, 4, 5, 6, 7]);
+}
+
+#[test]
+fn polyline_ribbon_degenerate_cases() {
+    let options = StrokeOptions::default().with_line_width(2.0);
+    let joins = [
+        LineJoin::Miter,
+        LineJoin::MiterClip,
+        LineJoin::Round,
+        LineJoin::Bevel,
+    ];
+
+    // Distance from a point to the polyline.
+    let distance = |polyline: &[Point], p: Point| {
+        let mut d = std::f32::MAX;
+        for segment in polyline.windows(2) {
+            let v = segment[1] - segment[0];
+            let t = ((p - segment[0]).dot(v) / v.square_length())
+                .max(0.0)
+                .min(1.0);
+            d = d.min((segment[0] + v * t - p).length());
+        }
+        d
+    };
+
+    for &join in &joins {
+        let options = options.with_line_join(join);
+
+        // Repeated points are skipped.
+        let repeated = ribbon(
+            &[
+                point(0.0, 0.0),
+                point(0.0, 0.0),
+                point(10.0, 0.0),
+                point(10.0, 0.0),
+                point(10.0, 10.0),
+                point(10.0, 10.0),
+            ],
+            &options,
+        );
+        let expected = ribbon(
+            &[point(0.0, 0.0), point(10.0, 0.0), point(10.0, 10.0)],
+            &options,
+        );
+        assert_eq!(repeated.vertices, expected.vertices);
+        assert_eq!(repeated.indices, expected.indices);
+
+        // U-turns can't be mitered.
+        let u_turn = [point(0.0, 0.0), point(10.0, 0.0), point(5.0, 0.0)];
+        let buffers = ribbon(&u_turn, &options);
+        ribbon_area(&buffers);
+        for v in &buffers.vertices {
+            assert!(distance(&u_turn, *v) < 1.001);
+        }
+
+        // Acute joins between short segments don't produce spikes.
+        let acute = [
+            point(0.0, 0.0),
+            point(10.0, 0.0),
+            point(9.5, 0.1),
+            point(20.0, 1.0),
+        ];
+        let buffers = ribbon(&acute, &options);
+        ribbon_area(&buffers);
+        let max_distance = if join == LineJoin::Miter {
+            options.miter_limit
+        } else {
+            1.0
+        };
+        for v in &buffers.vertices {
+            assert!(distance(&acute, *v) < max_distance + 0.001);
+        }
+    }
+}
+
+#[test]
+fn hatch_fill_square() {
+    use crate::path::Path;
+
+    let mut builder = Path::builder();
+    builder.move_to(point(0.5, 0.5));
+    builder.line_to(point(10.5, 0.5));
+    builder.line_to(point(10.5, 10.5));
+    builder.line_to(point(0.5, 10.5));
+    builder.close();
+    let square = builder.build();
+
+    let options = StrokeOptions::default()
+        .with_line_width(0.2)
+        .dont_apply_line_width();
+
+    let hatch = |angle: f32, buffers: &mut VertexBuffers<Point, u16>| {
+        hatch_fill(
+            &square.as_slice(),
+            1.0,
+            Angle::degrees(angle),
+            &options,
+            &mut simple_builder(buffers),
+        )
+        .unwrap();
+    };
+
+    // Nine horizontal lines from y = 1.5 to y = 9.5, two triangles each.
+    let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+    hatch(0.0, &mut buffers);
+    assert_eq!(buffers.indices.len(), 9 * 6);
+
+    // Cross-hatching.
+    hatch(45.0, &mut buffers);
+    hatch(-45.0, &mut buffers);
+    assert!(buffers.indices.len() > 9 * 6);
+
+    for p in &buffers.vertices {
+        assert!(p.x >= 0.5 - 0.001 && p.x <= 10.5 + 0.001, "{:?}", p);
+        assert!(p.y >= 0.5 - 0.001 && p.y <= 10.5 + 0.001, "{:?}", p);
+    }
+}
+
+#[test]
+fn fill_l_shaped_polygon() {
+    let polygon = [
+        point(0.0, 0.0),
+        point(2.0, 0.0),
+        point(2.0, 1.0),
+        point(1.0, 1.0),
+        point(1.0, 2.0),
+        point(0.0, 2.0),
+    ];
+
+    let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+    let count = fill_polygon(
+        &polygon,
+        &mut FillTessellator::new(),
+        &FillOptions::default(),
+        &mut simple_builder(&mut buffers),
+    )
+    .unwrap();
+
+    assert_eq!(count.indices, 12);
+    let mut area = 0.0;
+    for triangle in buffers.indices.chunks(3) {
+        let a = buffers.vertices[triangle[0] as usize];
+        let b = buffers.vertices[triangle[1] as usize];
+        let c = buffers.vertices[triangle[2] as usize];
+        area += (b - a).cross(c - a).abs() * 0.5;
+
+        // No triangle covers the missing quadrant.
+        let center = (a.to_vector() + b.to_vector() + c.to_vector()) / 3.0;
+        assert!(center.x < 1.0 || center.y < 1.0, "{:?}", center);
+    }
+    assert!((area - 3.0).abs() < 0.0001);
+}
+
+#[test]
+fn fill_convex_hexagon() {
+    let mut hexagon = Vec::new();
+    for i in 0..6 {
+        let angle = i as f32 * PI / 3.0;
+        hexagon.push(point(angle.cos(), angle.sin()));
+    }
+    let expected_area = 3.0 * 3.0f32.sqrt() / 2.0;
+
+    let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+    let count = fill_convex_polygon(
+        &hexagon,
+        &FillOptions::default(),
+        &mut simple_builder(&mut buffers),
+    )
+    .unwrap();
+    assert_eq!(count.vertices, 6);
+    assert_eq!(count.indices, 12);
+
+    // Same result whichever the orientation of the polygon.
+    hexagon.reverse();
+    fill_convex_polygon(
+        &hexagon,
+        &FillOptions::default(),
+        &mut simple_builder(&mut buffers),
+    )
+    .unwrap();
+    assert_eq!(buffers.indices.len(), 24);
+
+    let mut area = 0.0;
+    for triangle in buffers.indices.chunks(3) {
+        let a = buffers.vertices[triangle[0] as usize];
+        let b = buffers.vertices[triangle[1] as usize];
+        let c = buffers.vertices[triangle[2] as usize];
+        area += (b - a).cross(c - a).abs() * 0.5;
+    }
+    assert!((area - 2.0 * expected_area).abs() < 0.0001);
+}
+
+#[cfg(test)]
+fn rounded_rectangle_distance(rect: &Rect, radii: &BorderRadii, p: Point) -> f32 {
+    // Signed distance to the outline, positive outside.
+    let corners = [
+        (radii.top_left, point(rect.min_x(), rect.min_y()), 1.0, 1.0),
+        (
+            radii.top_right,
+            point(rect.max_x(), rect.min_y()),
+            -1.0,
+            1.0,
+        ),
+        (
+            radii.bottom_left,
+            point(rect.min_x(), rect.max_y()),
+            1.0,
+            -1.0,
+        ),
+        (
+            radii.bottom_right,
+            point(rect.max_x(), rect.max_y()),
+            -1.0,
+            -1.0,
+        ),
+    ];
+    for &(radius, corner, sx, sy) in &corners {
+        let center = corner + vector(sx * radius, sy * radius);
+        if (center.x - p.x) * sx > 0.0 && (center.y - p.y) * sy > 0.0 {
+            return (p - center).length() - radius;
+        }
+    }
+
+    let center = rect.center();
+    let q: Vector = vector(
+        (p.x - center.x).abs() - rect.size.width * 0.5,
+        (p.y - center.y).abs() - rect.size.height * 0.5,
+    );
+    let outside: Vector = vector(q.x.max(0.0), q.y.max(0.0));
+    outside.length() + q.x.max(q.y).min(0.0)
+}
+
+#[test]
+fn fill_rounded_rectangles() {
+    let rect = Rect::new(point(10.0, 20.0), size(100.0, 50.0));
+    let tolerance = 0.1;
+
+    let check = |radii: BorderRadii, clamped: BorderRadii| {
+        let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+        fill_rounded_rectangle(
+            &rect,
+            &radii,
+            &FillOptions::tolerance(tolerance),
+            &mut simple_builder(&mut buffers),
+        )
+        .unwrap();
+
+        let mut area = 0.0;
+        for triangle in buffers.indices.chunks(3) {
+            let a = buffers.vertices[triangle[0] as usize];
+            let b = buffers.vertices[triangle[1] as usize];
+            let c = buffers.vertices[triangle[2] as usize];
+            area += (b - a).cross(c - a).abs() * 0.5;
+        }
+
+        // The corners are flattened within the tolerance.
+        let corners = [
+            clamped.top_left,
+            clamped.top_right,
+            clamped.bottom_left,
+            clamped.bottom_right,
+        ];
+        let expected_area = rect.size.width * rect.size.height
+            - corners.iter().map(|r| r * r).sum::<f32>() * (1.0 - PI / 4.0);
+        let arcs_length = corners.iter().sum::<f32>() * PI * 0.5;
+        assert!(area <= expected_area + 0.01, "{} {}", area, expected_area);
+        assert!(
+            area >= expected_area - arcs_length * tolerance,
+            "{} {}",
+            area,
+            expected_area
+        );
+
+        for p in &buffers.vertices {
+            let d = rounded_rectangle_distance(&rect, &clamped, *p);
+            assert!(d <= 0.001, "{:?} {}", p, d);
+        }
+    };
+
+    // Equal radii.
+    check(
+        BorderRadii::new_all_same(10.0),
+        BorderRadii::new_all_same(10.0),
+    );
+
+    // Asymmetric radii.
+    let radii = BorderRadii::new(0.0, 5.0, 20.0, 40.0);
+    check(radii, radii);
+
+    // Radii larger than the rectangle are clamped so that the corners don't overlap.
+    check(
+        BorderRadii::new_all_same(1000.0),
+        BorderRadii::new_all_same(25.0),
+    );
+}
+
+#[test]
+fn stroke_rounded_rectangles() {
+    let rect = Rect::new(point(10.0, 20.0), size(100.0, 50.0));
+    let tolerance = 0.1;
+
+    let check = |radii: BorderRadii, clamped: BorderRadii| {
+        let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+        stroke_rounded_rectangle(
+            &rect,
+            &radii,
+            &StrokeOptions::tolerance(tolerance)
+                .with_line_width(2.0)
+                .with_line_join(LineJoin::Round),
+            &mut simple_builder(&mut buffers),
+        )
+        .unwrap();
+
+        assert!(!buffers.indices.is_empty());
+        for p in &buffers.vertices {
+            let d = rounded_rectangle_distance(&rect, &clamped, *p);
+            assert!((d.abs() - 1.0).abs() <= tolerance + 0.01, "{:?} {}", p, d);
+        }
+    };
+
+    check(
+        BorderRadii::new_all_same(10.0),
+        BorderRadii::new_all_same(10.0),
+    );
+    let radii = BorderRadii::new(0.0, 5.0, 20.0, 40.0);
+    check(radii, radii);
+    check(
+        BorderRadii::new_all_same(1000.0),
+        BorderRadii::new_all_same(25.0),
+    );
+}
+
+#[cfg(test)]
+fn triangles_area(buffers: &VertexBuffers<Point, u16>) -> f32 {
+    let mut area = 0.0;
+    for triangle in buffers.indices.chunks(3) {
+        let a = buffers.vertices[triangle[0] as usize];
+        let b = buffers.vertices[triangle[1] as usize];
+        let c = buffers.vertices[triangle[2] as usize];
+        area += (b - a).cross(c - a).abs() * 0.5;
+    }
+    area
+}
+
+#[test]
+fn fill_circle_tolerance() {
+    let tolerance = 0.1;
+    let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+    fill_circle(
+        point(5.0, 5.0),
+        10.0,
+        &FillOptions::tolerance(tolerance),
+        &mut simple_builder(&mut buffers),
+    )
+    .unwrap();
+
+    let expected_area = PI * 100.0;
+    let area = triangles_area(&buffers);
+    assert!(area <= expected_area, "{} {}", area, expected_area);
+    assert!(
+        area >= expected_area - 2.0 * PI * 10.0 * tolerance,
+        "{} {}",
+        area,
+        expected_area
+    );
+}
+
+#[test]
+fn stroke_circle_ring() {
+    let center = point(5.0, 5.0);
+    let tolerance = 0.1;
+    for &(radius, line_width) in &[(10.0, 1.0), (10.0, 8.0), (2.0, 10.0)] {
+        let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+        stroke_circle(
+            center,
+            radius,
+            &StrokeOptions::tolerance(tolerance).with_line_width(line_width),
+            &mut simple_builder(&mut buffers),
+        )
+        .unwrap();
+
+        // The vertices are on the inner and outer circles of the ring. The ring is a disk
+        // if the line is wider than the circle.
+        let inner = f32::max(radius - line_width * 0.5, 0.0);
+        let outer = radius + line_width * 0.5;
+        for p in &buffers.vertices {
+            let d = (*p - center).length();
+            assert!(
+                (d - inner).abs() <= tolerance || (d - outer).abs() <= tolerance,
+                "{:?} {} {} {}",
+                p,
+                d,
+                radius,
+                line_width
+            );
+        }
+
+        // A closed ring without gaps or overlaps.
+        let expected_area = PI * (outer * outer - inner * inner);
+        let area = triangles_area(&buffers);
+        assert!(
+            (area - expected_area).abs() <= 2.0 * PI * (outer + inner) * tolerance,
+            "{} {}",
+            area,
+            expected_area
+        );
+    }
+}
+
+#[test]
+fn fill_ellipse_tolerance() {
+    let center = point(5.0, 5.0);
+    let radii = vector(100.0, 10.0);
+    let rotation = Angle::degrees(30.0);
+    let tolerance = 0.1;
+
+    let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+    fill_ellipse(
+        center,
+        radii,
+        rotation,
+        &FillOptions::tolerance(tolerance),
+        &mut simple_builder(&mut buffers),
+    )
+    .unwrap();
+
+    // The vertices are on the ellipse.
+    let (sin, cos) = rotation.radians.sin_cos();
+    for p in &buffers.vertices {
+        let v = *p - center;
+        let x = (v.x * cos + v.y * sin) / radii.x;
+        let y = (v.y * cos - v.x * sin) / radii.y;
+        let d = (x * x + y * y).sqrt();
+        assert!((d - 1.0).abs() < 0.001, "{:?} {}", p, d);
+    }
+
+    let expected_area = PI * radii.x * radii.y;
+    let perimeter = 2.0 * PI * radii.x;
+    let area = triangles_area(&buffers);
+    assert!(area <= expected_area + 0.01, "{} {}", area, expected_area);
+    assert!(
+        area >= expected_area - perimeter * tolerance,
+        "{} {}",
+        area,
+        expected_area
+    );
+}
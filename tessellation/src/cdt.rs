@@ -0,0 +1,268 @@
+//! Constrained Delaunay triangulation of the output of the fill tessellator.
+//!
+//! The fill tessellator produces a valid triangulation in which every edge of the
+//! boundary of the shape is a triangle edge. This is turned into a constrained
+//! Delaunay triangulation by flipping interior edges until every one of them
+//! satisfies the Delaunay criterion (Lawson's algorithm), which converges from
+//! any initial triangulation.
+//!
+//! The edges of the input path are the constraints: the tessellator reports the
+//! path edges that start at each vertex and triangle edges that lie on one of them
+//! are never flipped, whether they are on the boundary of the fill or inside of it.
+
+use crate::geometry_builder::{Count, FillGeometryBuilder, GeometryBuilder, GeometryBuilderError};
+use crate::math::Point;
+use crate::{FillAttributes, VertexId};
+
+use std::collections::HashMap;
+
+/// A geometry builder that forwards vertices to another builder and holds the
+/// triangles back until the end of the geometry, where it applies edge flips.
+pub(crate) struct CdtBuilder<'l> {
+    output: &'l mut dyn FillGeometryBuilder,
+    // Output id and position of each vertex, indexed by the ids we hand out.
+    vertices: Vec<(VertexId, Point)>,
+    // The other end of the path edges that start at vertex `i` are in
+    // `edge_ends[edge_offsets[i]..edge_offsets[i + 1]]`.
+    edge_ends: Vec<Point>,
+    edge_offsets: Vec<usize>,
+    triangles: Vec<[u32; 3]>,
+}
+
+impl<'l> CdtBuilder<'l> {
+    pub fn new(output: &'l mut dyn FillGeometryBuilder) -> Self {
+        CdtBuilder {
+            output,
+            vertices: Vec::new(),
+            edge_ends: Vec::new(),
+            edge_offsets: vec![0],
+            triangles: Vec::new(),
+        }
+    }
+
+    fn clear(&mut self) {
+        self.vertices.clear();
+        self.edge_ends.clear();
+        self.edge_offsets.clear();
+        self.edge_offsets.push(0);
+        self.triangles.clear();
+    }
+
+    // Whether the segment between two vertices lies on an edge of the path.
+    fn is_constraint(&self, a: u32, b: u32) -> bool {
+        let on_edge = |from: u32, to: u32| {
+            let start = self.vertices[from as usize].1;
+            let p = self.vertices[to as usize].1;
+            let range = self.edge_offsets[from as usize]..self.edge_offsets[from as usize + 1];
+            self.edge_ends[range]
+                .iter()
+                .any(|&end| is_on_segment(start, end, p))
+        };
+
+        on_edge(a, b) || on_edge(b, a)
+    }
+}
+
+// Whether `p` is on the segment, with a generous threshold because intersections
+// are not exactly on the edges they split. Treating a non-constraint edge as a
+// constraint only prevents a flip.
+fn is_on_segment(from: Point, to: Point, p: Point) -> bool {
+    let edge = to - from;
+    let v = p - from;
+    let dot = edge.dot(v);
+    let square_length = edge.square_length();
+
+    dot > 0.0
+        && dot <= square_length * 1.001
+        && edge.cross(v).abs() <= 0.001 * square_length.sqrt() * v.length()
+}
+
+impl<'l> GeometryBuilder for CdtBuilder<'l> {
+    fn begin_geometry(&mut self) {
+        self.clear();
+        self.output.begin_geometry();
+    }
+
+    fn end_geometry(&mut self) -> Count {
+        let mut triangles = std::mem::replace(&mut self.triangles, Vec::new());
+        delaunay_flips(
+            &self.vertices,
+            &|a, b| self.is_constraint(a, b),
+            &mut triangles,
+        );
+        self.triangles = triangles;
+
+        for &[a, b, c] in &self.triangles {
+            self.output.add_triangle(
+                self.vertices[a as usize].0,
+                self.vertices[b as usize].0,
+                self.vertices[c as usize].0,
+            );
+        }
+
+        self.clear();
+
+        self.output.end_geometry()
+    }
+
+    fn add_triangle(&mut self, a: VertexId, b: VertexId, c: VertexId) {
+        self.triangles.push([a.0, b.0, c.0]);
+    }
+
+    fn abort_geometry(&mut self) {
+        self.clear();
+        self.output.abort_geometry();
+    }
+}
+
+impl<'l> FillGeometryBuilder for CdtBuilder<'l> {
+    fn add_fill_vertex(
+        &mut self,
+        position: Point,
+        attributes: FillAttributes,
+    ) -> Result<VertexId, GeometryBuilderError> {
+        let edge_ends = &mut self.edge_ends;
+        attributes.for_each_edge_end(&mut |end| edge_ends.push(end));
+
+        let id = self.output.add_fill_vertex(position, attributes)?;
+        self.vertices.push((id, position));
+        self.edge_offsets.push(self.edge_ends.len());
+
+        Ok(VertexId::from_usize(self.vertices.len() - 1))
+    }
+}
+
+fn edge_key(a: u32, b: u32) -> (u32, u32) {
+    if a < b {
+        (a, b)
+    } else {
+        (b, a)
+    }
+}
+
+fn orientation(a: Point, b: Point, c: Point) -> f64 {
+    let (ax, ay) = (a.x as f64, a.y as f64);
+    (b.x as f64 - ax) * (c.y as f64 - ay) - (b.y as f64 - ay) * (c.x as f64 - ax)
+}
+
+// Positive if `d` is inside the circumcircle of the counter-clockwise triangle `a, b, c`.
+pub(crate) fn in_circle(a: Point, b: Point, c: Point, d: Point) -> f64 {
+    let (dx, dy) = (d.x as f64, d.y as f64);
+    let (adx, ady) = (a.x as f64 - dx, a.y as f64 - dy);
+    let (bdx, bdy) = (b.x as f64 - dx, b.y as f64 - dy);
+    let (cdx, cdy) = (c.x as f64 - dx, c.y as f64 - dy);
+    let ad = adx * adx + ady * ady;
+    let bd = bdx * bdx + bdy * bdy;
+    let cd = cdx * cdx + cdy * cdy;
+
+    adx * (bdy * cd - bd * cdy) - ady * (bdx * cd - bd * cdx) + ad * (bdx * cdy - bdy * cdx)
+}
+
+/// Flips the edges shared by two triangles until the triangulation is Delaunay.
+///
+/// Edges that belong to a single triangle are on the boundary of the shape and
+/// constrained edges are never flipped.
+fn delaunay_flips(
+    vertices: &[(VertexId, Point)],
+    is_constraint: &dyn Fn(u32, u32) -> bool,
+    triangles: &mut Vec<[u32; 3]>,
+) {
+    let position = |id: u32| vertices[id as usize].1;
+
+    // Work with counter-clockwise triangles and skip degenerate ones.
+    let mut triangles_ccw = Vec::with_capacity(triangles.len());
+    let mut degenerate = Vec::new();
+    for &[a, b, c] in triangles.iter() {
+        let o = orientation(position(a), position(b), position(c));
+        if o > 0.0 {
+            triangles_ccw.push([a, b, c]);
+        } else if o < 0.0 {
+            triangles_ccw.push([a, c, b]);
+        } else {
+            degenerate.push([a, b, c]);
+        }
+    }
+
+    // The map is only used for lookups. The flips are seeded in the order of the
+    // triangles so that the result doesn't depend on hashing when several
+    // diagonals are valid (for example with co-circular points).
+    let mut edges: HashMap<(u32, u32), [usize; 2]> = HashMap::new();
+    let mut stack: Vec<(u32, u32)> = Vec::new();
+    let none = std::usize::MAX;
+    for (idx, tri) in triangles_ccw.iter().enumerate() {
+        for i in 0..3 {
+            let key = edge_key(tri[i], tri[(i + 1) % 3]);
+            let entry = edges.entry(key).or_insert([none, none]);
+            if entry[0] == none {
+                entry[0] = idx;
+                stack.push(key);
+            } else {
+                entry[1] = idx;
+            }
+        }
+    }
+    // Guard against cycles caused by numerical precision issues.
+    let mut remaining_flips = triangles_ccw.len() * triangles_ccw.len() + 16;
+
+    while let Some(key) = stack.pop() {
+        let [t1, t2] = match edges.get(&key) {
+            Some(&tris) if tris[1] != none => tris,
+            _ => {
+                continue;
+            }
+        };
+
+        // Rotate t1 so that it is [a, b, c] with the edge being a -> b.
+        let mut tri1 = triangles_ccw[t1];
+        while edge_key(tri1[0], tri1[1]) != key {
+            tri1.rotate_left(1);
+        }
+        let [a, b, c] = tri1;
+        let tri2 = triangles_ccw[t2];
+        let d = *tri2.iter().find(|&&v| v != a && v != b).unwrap();
+
+        let (pa, pb, pc, pd) = (position(a), position(b), position(c), position(d));
+        let scale = (pa - pb).square_length() as f64;
+        if in_circle(pa, pb, pc, pd) <= scale * scale * 1e-10 {
+            continue;
+        }
+
+        if is_constraint(a, b) {
+            continue;
+        }
+
+        // Only flip if the quadrilateral is convex.
+        if orientation(pc, pa, pd) <= 0.0 || orientation(pd, pb, pc) <= 0.0 {
+            continue;
+        }
+
+        if remaining_flips == 0 {
+            break;
+        }
+        remaining_flips -= 1;
+
+        triangles_ccw[t1] = [c, a, d];
+        triangles_ccw[t2] = [d, b, c];
+
+        edges.remove(&key);
+        edges.insert(edge_key(c, d), [t1, t2]);
+        for &(edge, from, to) in &[((a, d), t2, t1), ((b, c), t1, t2)] {
+            if let Some(tris) = edges.get_mut(&edge_key(edge.0, edge.1)) {
+                for t in tris.iter_mut() {
+                    if *t == from {
+                        *t = to;
+                    }
+                }
+            }
+        }
+
+        stack.push(edge_key(c, a));
+        stack.push(edge_key(a, d));
+        stack.push(edge_key(d, b));
+        stack.push(edge_key(b, c));
+    }
+
+    triangles.clear();
+    triangles.extend_from_slice(&triangles_ccw);
+    triangles.extend_from_slice(&degenerate);
+}
@@ -0,0 +1,317 @@
+//! Output geometry for analytic anti-aliasing.
+//!
+//! Rather than relying on MSAA, a fragment shader can compute the exact coverage of
+//! a pixel by the shape if it knows the equations of the edges that lie on the
+//! shape's boundary. The [`CoverageBuilder`](struct.CoverageBuilder.html) geometry
+//! builder collects the output of a tessellator and produces non-indexed triangles
+//! where each vertex carries the equations of the three edges of its triangle.
+//!
+//! Each equation `[a, b, c]` is normalized so that `a * x + b * y + c` is the signed
+//! distance to the edge, positive towards the inside of the triangle. Edges that
+//! are shared by two triangles are inside the shape and do not contribute to
+//! anti-aliasing: they are represented with `FULL_COVERAGE_EDGE`, which always
+//! evaluates to a very large positive distance.
+//!
+//! A typical fragment shader computes the coverage with something like
+//! `min(clamp(d0 + 0.5, 0, 1), clamp(d1 + 0.5, 0, 1), clamp(d2 + 0.5, 0, 1))`
+//! where the `dN` are the edge equations evaluated at the pixel center, in pixels.
+//!
+//! Boundary edges are detected by looking at which edges are referenced by a single
+//! triangle, so this works best with the output of the fill tessellator which shares
+//! vertices between adjacent triangles.
+//!
+//! No geometry is generated outside of the shape and the triangles of the fill don't
+//! overlap, so each pixel is shaded by a single triangle and its coverage never exceeds
+//! one, including at concave corners where several boundary triangles meet. The
+//! coverage can be used as premultiplied alpha without darkening or seams between
+//! adjacent triangles. The flip side is that pixels whose center is outside of the shape
+//! are not shaded, so edges are anti-aliased on their inner side only.
+
+use crate::geometry_builder::{
+    BasicGeometryBuilder, Count, FillGeometryBuilder, GeometryBuilder, GeometryBuilderError,
+};
+use crate::math::{vector, Point};
+use crate::{FillAttributes, VertexId};
+
+/// The edge equation used for edges that are not on the boundary of the shape.
+pub const FULL_COVERAGE_EDGE: [f32; 3] = [0.0, 0.0, std::f32::MAX];
+
+/// A vertex produced by the [`CoverageBuilder`](struct.CoverageBuilder.html).
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct CoverageVertex {
+    pub position: Point,
+    /// Equations of the three edges of the triangle this vertex belongs to.
+    pub edges: [[f32; 3]; 3],
+}
+
+/// A geometry builder that outputs non-indexed triangles with the edge equations
+/// required for analytic anti-aliasing.
+///
+/// See the [module documentation](index.html).
+pub struct CoverageBuilder<'l> {
+    output: &'l mut Vec<CoverageVertex>,
+    output_offset: usize,
+    positions: Vec<Point>,
+    triangles: Vec<[VertexId; 3]>,
+}
+
+impl<'l> CoverageBuilder<'l> {
+    pub fn new(output: &'l mut Vec<CoverageVertex>) -> Self {
+        let output_offset = output.len();
+        CoverageBuilder {
+            output,
+            output_offset,
+            positions: Vec::new(),
+            triangles: Vec::new(),
+        }
+    }
+}
+
+impl<'l> GeometryBuilder for CoverageBuilder<'l> {
+    fn begin_geometry(&mut self) {
+        self.output_offset = self.output.len();
+        self.positions.clear();
+        self.triangles.clear();
+    }
+
+    fn end_geometry(&mut self) -> Count {
+        let edge_key = |a: VertexId, b: VertexId| {
+            if a.0 < b.0 {
+                (a.0, b.0)
+            } else {
+                (b.0, a.0)
+            }
+        };
+
+        let mut edges = Vec::with_capacity(self.triangles.len() * 3);
+        for &[a, b, c] in &self.triangles {
+            edges.push(edge_key(a, b));
+            edges.push(edge_key(b, c));
+            edges.push(edge_key(c, a));
+        }
+        edges.sort();
+
+        // Number of triangles referencing each edge.
+        let mut edge_counts: Vec<((u32, u32), u32)> = Vec::with_capacity(edges.len());
+        for edge in edges {
+            match edge_counts.last_mut() {
+                Some(last) if last.0 == edge => {
+                    last.1 += 1;
+                }
+                _ => {
+                    edge_counts.push((edge, 1));
+                }
+            }
+        }
+
+        let is_boundary = |a: VertexId, b: VertexId| {
+            let key = edge_key(a, b);
+            match edge_counts.binary_search_by_key(&key, |e| e.0) {
+                Ok(idx) => edge_counts[idx].1 == 1,
+                Err(_) => false,
+            }
+        };
+
+        for &[a, b, c] in &self.triangles {
+            let pa = self.positions[a.to_usize()];
+            let pb = self.positions[b.to_usize()];
+            let pc = self.positions[c.to_usize()];
+
+            let edges = [
+                if is_boundary(a, b) {
+                    edge_equation(pa, pb, pc)
+                } else {
+                    FULL_COVERAGE_EDGE
+                },
+                if is_boundary(b, c) {
+                    edge_equation(pb, pc, pa)
+                } else {
+                    FULL_COVERAGE_EDGE
+                },
+                if is_boundary(c, a) {
+                    edge_equation(pc, pa, pb)
+                } else {
+                    FULL_COVERAGE_EDGE
+                },
+            ];
+
+            for &position in &[pa, pb, pc] {
+                self.output.push(CoverageVertex { position, edges });
+            }
+        }
+
+        self.positions.clear();
+        self.triangles.clear();
+
+        Count {
+            vertices: (self.output.len() - self.output_offset) as u32,
+            indices: 0,
+        }
+    }
+
+    fn add_triangle(&mut self, a: VertexId, b: VertexId, c: VertexId) {
+        self.triangles.push([a, b, c]);
+    }
+
+    fn abort_geometry(&mut self) {
+        self.output.truncate(self.output_offset);
+        self.positions.clear();
+        self.triangles.clear();
+    }
+}
+
+impl<'l> FillGeometryBuilder for CoverageBuilder<'l> {
+    fn add_fill_vertex(
+        &mut self,
+        position: Point,
+        _attributes: FillAttributes,
+    ) -> Result<VertexId, GeometryBuilderError> {
+        self.add_vertex(position)
+    }
+}
+
+impl<'l> BasicGeometryBuilder for CoverageBuilder<'l> {
+    fn add_vertex(&mut self, position: Point) -> Result<VertexId, GeometryBuilderError> {
+        self.positions.push(position);
+        Ok(VertexId::from_usize(self.positions.len() - 1))
+    }
+}
+
+// Equation of the line going through `from` and `to`, oriented so that `opposite`
+// is on the positive side.
+fn edge_equation(from: Point, to: Point, opposite: Point) -> [f32; 3] {
+    let d = to - from;
+    let length = d.length();
+    if length == 0.0 {
+        return FULL_COVERAGE_EDGE;
+    }
+
+    let mut n = vector(-d.y, d.x) / length;
+    if n.dot(opposite - from) < 0.0 {
+        n = -n;
+    }
+
+    [n.x, n.y, -n.dot(from.to_vector())]
+}
+
+#[test]
+fn boundary_and_interior_triangles() {
+    use crate::math::point;
+
+    // A triangle subdivided into four: the middle one only has interior edges.
+    let mut output = Vec::new();
+    let mut builder = CoverageBuilder::new(&mut output);
+    builder.begin_geometry();
+    let a = builder.add_vertex(point(0.0, 0.0)).unwrap();
+    let b = builder.add_vertex(point(4.0, 0.0)).unwrap();
+    let c = builder.add_vertex(point(0.0, 4.0)).unwrap();
+    let ab = builder.add_vertex(point(2.0, 0.0)).unwrap();
+    let bc = builder.add_vertex(point(2.0, 2.0)).unwrap();
+    let ca = builder.add_vertex(point(0.0, 2.0)).unwrap();
+    builder.add_triangle(a, ab, ca);
+    builder.add_triangle(ab, b, bc);
+    builder.add_triangle(ca, bc, c);
+    builder.add_triangle(ab, bc, ca);
+    let count = builder.end_geometry();
+
+    assert_eq!(count.vertices, 12);
+    assert_eq!(output.len(), 12);
+
+    let eval = |eq: [f32; 3], p: Point| eq[0] * p.x + eq[1] * p.y + eq[2];
+
+    for triangle in output[..9].chunks(3) {
+        let edges = triangle[0].edges;
+        assert!(triangle.iter().all(|v| v.edges == edges));
+        let num_boundary_edges = edges.iter().filter(|&&e| e != FULL_COVERAGE_EDGE).count();
+        assert_eq!(num_boundary_edges, 2);
+    }
+
+    // Edge equations evaluate to the distance to the boundary.
+    let first = output[0].edges;
+    assert!((eval(first[0], point(1.0, 0.5)) - 0.5).abs() < 0.0001);
+
+    for v in &output[9..] {
+        assert_eq!(v.edges, [FULL_COVERAGE_EDGE; 3]);
+    }
+}
+
+#[test]
+fn no_coverage_overshoot_at_concave_corners() {
+    use crate::math::point;
+    use crate::path::Path;
+    use crate::{FillOptions, FillTessellator};
+
+    // A five-pointed star, with concave corners at the inner vertices.
+    let mut inner_vertices = Vec::new();
+    let mut builder = Path::builder();
+    for i in 0..10 {
+        let angle = i as f32 * std::f32::consts::PI / 5.0;
+        let radius = if i % 2 == 0 { 100.0 } else { 40.0 };
+        let p = point(angle.cos() * radius, angle.sin() * radius);
+        if i % 2 == 1 {
+            inner_vertices.push(p);
+        }
+        if i == 0 {
+            builder.move_to(p);
+        } else {
+            builder.line_to(p);
+        }
+    }
+    builder.close();
+    let path = builder.build();
+
+    let mut output = Vec::new();
+    FillTessellator::new()
+        .tessellate_path(
+            &path,
+            &FillOptions::default(),
+            &mut CoverageBuilder::new(&mut output),
+        )
+        .unwrap();
+
+    let eval = |eq: [f32; 3], p: Point| eq[0] * p.x + eq[1] * p.y + eq[2];
+    let clamp = |d: f32| d.max(0.0).min(1.0);
+    let contains = |t: &[CoverageVertex], p: Point| {
+        let side = |a: Point, b: Point| (b - a).cross(p - a);
+        let d = [
+            side(t[0].position, t[1].position),
+            side(t[1].position, t[2].position),
+            side(t[2].position, t[0].position),
+        ];
+        d.iter().all(|&d| d > 0.0) || d.iter().all(|&d| d < 0.0)
+    };
+
+    // Sample pixel centers around the concave corners, where the fringes of the two
+    // boundary edges meet.
+    let mut num_covered = 0;
+    for corner in &inner_vertices {
+        for i in 0..16 {
+            for j in 0..16 {
+                let p = point(
+                    corner.x + i as f32 * 0.25 - 2.0 + 0.0137,
+                    corner.y + j as f32 * 0.25 - 2.0 + 0.0071,
+                );
+
+                let mut total = 0.0;
+                for triangle in output.chunks(3) {
+                    if !contains(triangle, p) {
+                        continue;
+                    }
+                    let edges = triangle[0].edges;
+                    let coverage = clamp(eval(edges[0], p) + 0.5)
+                        .min(clamp(eval(edges[1], p) + 0.5))
+                        .min(clamp(eval(edges[2], p) + 0.5));
+                    total += coverage;
+                }
+
+                assert!(total <= 1.0, "{:?} {}", p, total);
+                if total > 0.0 {
+                    num_covered += 1;
+                }
+            }
+        }
+    }
+    assert!(num_covered > 0);
+}
@@ -1,8 +1,8 @@
 use crate::fill::{compare_positions, is_after};
 use crate::geom::{CubicBezierSegment, QuadraticBezierSegment};
-use crate::math::{point, Point};
+use crate::math::{point, Point, Vector};
 use crate::path::{EndpointId, IdEvent, PathEvent, PositionStore};
-use crate::Orientation;
+use crate::{capped_flattening_tolerance, FillOptions, Orientation};
 
 use std::cmp::Ordering;
 use std::mem::swap;
@@ -32,6 +32,8 @@ pub(crate) struct EdgeData {
     pub is_edge: bool,
     pub from_id: EndpointId,
     pub to_id: EndpointId,
+    // Index of the path event that produced this edge.
+    pub src_event: u32,
 }
 
 #[doc(hidden)]
@@ -114,7 +116,9 @@ impl EventQueue {
             nth: 0,
             prev_evt_is_edge: false,
             tolerance: 0.1,
+            max_subdivision_depth: FillOptions::DEFAULT_MAX_SUBDIVISION_DEPTH,
             prev_endpoint_id: EndpointId(std::u32::MAX),
+            current_event: 0,
         }
     }
 
@@ -258,6 +262,39 @@ impl EventQueue {
         (id as usize) < self.events.len()
     }
 
+    /// Returns the minimum and maximum coordinates of the events, if any.
+    pub(crate) fn bounds(&self) -> Option<(Point, Point)> {
+        let first = self.events.first()?.position;
+        let mut min = first;
+        let mut max = first;
+        for evt in &self.events {
+            min = min.min(evt.position);
+            max = max.max(evt.position);
+        }
+
+        Some((min, max))
+    }
+
+    /// Maps all positions `p` to `(p - offset) * scale`.
+    pub(crate) fn normalize(&mut self, offset: Vector, scale: f32) {
+        for evt in &mut self.events {
+            evt.position = ((evt.position - offset).to_vector() * scale).to_point();
+        }
+        for edge in &mut self.edge_data {
+            edge.to = ((edge.to - offset).to_vector() * scale).to_point();
+        }
+    }
+
+    /// Reverts the effect of `normalize`.
+    pub(crate) fn denormalize(&mut self, offset: Vector, scale: f32) {
+        for evt in &mut self.events {
+            evt.position = (evt.position.to_vector() / scale).to_point() + offset;
+        }
+        for edge in &mut self.edge_data {
+            edge.to = (edge.to.to_vector() / scale).to_point() + offset;
+        }
+    }
+
     /// Returns the position of a given event in the queue.
     pub(crate) fn position(&self, id: TessEventId) -> Point {
         self.events[id as usize].position
@@ -421,7 +458,9 @@ pub(crate) struct EventQueueBuilder {
     queue: EventQueue,
     prev_evt_is_edge: bool,
     tolerance: f32,
+    max_subdivision_depth: u32,
     prev_endpoint_id: EndpointId,
+    current_event: u32,
 }
 
 impl EventQueueBuilder {
@@ -445,6 +484,7 @@ impl EventQueueBuilder {
         self.queue.reset();
         self.nth = 0;
         self.prev_evt_is_edge = false;
+        self.current_event = 0;
     }
 
     pub fn set_path(
@@ -479,6 +519,7 @@ impl EventQueueBuilder {
                             self.end(first, endpoint_id);
                         }
                     }
+                    self.next_event();
                 }
             }
 
@@ -512,6 +553,7 @@ impl EventQueueBuilder {
                             self.end(reorient(first), endpoint_id);
                         }
                     }
+                    self.next_event();
                 }
             }
         }
@@ -520,6 +562,55 @@ impl EventQueueBuilder {
         debug_assert!(!self.prev_evt_is_edge);
     }
 
+    /// Resets the builder so that a path can be added one event at a time
+    /// using `path_event`.
+    pub fn begin_path(&mut self, tolerance: f32) {
+        self.reset();
+        self.tolerance = tolerance;
+    }
+
+    /// Sets the maximum number of times the curves are subdivided when flattening them.
+    pub fn set_max_subdivision_depth(&mut self, depth: u32) {
+        self.max_subdivision_depth = depth;
+    }
+
+    /// Sets the tolerance used to flatten the curves added after this call.
+    pub fn set_tolerance(&mut self, tolerance: f32) {
+        self.tolerance = tolerance;
+    }
+
+    /// Adds a single path event.
+    ///
+    /// Must be preceded by a call to `begin_path`.
+    pub fn path_event(&mut self, evt: PathEvent, sweep_orientation: Orientation) {
+        let endpoint_id = EndpointId(std::u32::MAX);
+        let orient = |p: Point| match sweep_orientation {
+            Orientation::Vertical => p,
+            Orientation::Horizontal => reorient(p),
+        };
+
+        match evt {
+            PathEvent::Begin { at } => {
+                self.begin(orient(at), endpoint_id);
+            }
+            PathEvent::Line { to, .. } => {
+                self.line_segment(orient(to), endpoint_id, 0.0, 1.0);
+            }
+            PathEvent::Quadratic { ctrl, to, .. } => {
+                self.quadratic_bezier_segment(orient(ctrl), orient(to), endpoint_id);
+            }
+            PathEvent::Cubic {
+                ctrl1, ctrl2, to, ..
+            } => {
+                self.cubic_bezier_segment(orient(ctrl1), orient(ctrl2), orient(to), endpoint_id);
+            }
+            PathEvent::End { first, .. } => {
+                self.end(orient(first), endpoint_id);
+            }
+        }
+        self.next_event();
+    }
+
     pub fn set_path_with_ids(
         &mut self,
         tolerance: f32,
@@ -561,6 +652,7 @@ impl EventQueueBuilder {
                             self.end(points.get_endpoint(first), first);
                         }
                     }
+                    self.next_event();
                 }
             }
 
@@ -594,6 +686,7 @@ impl EventQueueBuilder {
                             self.end(reorient(points.get_endpoint(first)), first);
                         }
                     }
+                    self.next_event();
                 }
             }
         }
@@ -602,6 +695,10 @@ impl EventQueueBuilder {
         debug_assert!(!self.prev_evt_is_edge);
     }
 
+    fn next_event(&mut self) {
+        self.current_event += 1;
+    }
+
     fn vertex_event(&mut self, at: Point, endpoint_id: EndpointId) {
         self.queue.push_unsorted(at);
         self.queue.edge_data.push(EdgeData {
@@ -611,6 +708,7 @@ impl EventQueueBuilder {
             is_edge: false,
             from_id: endpoint_id,
             to_id: endpoint_id,
+            src_event: self.current_event,
         });
     }
 
@@ -623,6 +721,7 @@ impl EventQueueBuilder {
             is_edge: false,
             from_id,
             to_id,
+            src_event: self.current_event,
         });
     }
 
@@ -690,6 +789,7 @@ impl EventQueueBuilder {
             is_edge: true,
             from_id,
             to_id,
+            src_event: self.current_event,
         });
 
         self.nth += 1;
@@ -747,7 +847,12 @@ impl EventQueueBuilder {
         let mut from = segment.from;
         let mut first = None;
         let is_first_edge = self.nth == 0;
-        segment.for_each_flattened_with_t(self.tolerance, &mut |to, t1| {
+        let tolerance = capped_flattening_tolerance(
+            self.tolerance,
+            self.max_subdivision_depth,
+            |tolerance, _| segment.num_flattening_segments(tolerance),
+        );
+        segment.for_each_flattened_with_t(tolerance, &mut |to, t1| {
             if from == to {
                 return;
             }
@@ -820,7 +925,12 @@ impl EventQueueBuilder {
         let mut from = segment.from;
         let mut first = None;
         let is_first_edge = self.nth == 0;
-        segment.for_each_flattened_with_t(self.tolerance, &mut |to, t1| {
+        let tolerance = capped_flattening_tolerance(
+            self.tolerance,
+            self.max_subdivision_depth,
+            |tolerance, max| segment.num_flattening_segments_up_to(tolerance, max),
+        );
+        segment.for_each_flattened_with_t(tolerance, &mut |to, t1| {
             if from == to {
                 return;
             }
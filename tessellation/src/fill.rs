@@ -1,15 +1,23 @@
+use crate::cdt::CdtBuilder;
 use crate::event_queue::*;
 use crate::geom::math::*;
 use crate::geom::LineSegment;
+use crate::geometry_builder::{
+    simple_builder, BuffersBuilder, Count, FillVertexConstructor, GeometryBuilder,
+    GeometryBuilderError, NoOutput, VertexBuffers,
+};
 use crate::monotone::*;
+use crate::path::iterator::PathIterator;
 use crate::path::{
-    AttributeStore, EndpointId, FillRule, IdEvent, PathEvent, PathSlice, PositionStore,
+    AttributeStore, EndpointId, Event, FillRule, IdEvent, Path, PathEvent, PathSlice, PositionStore,
 };
 use crate::{FillGeometryBuilder, Orientation, VertexId};
 use crate::{
-    FillOptions, InternalError, Side, TessellationError, TessellationResult, VertexSource,
+    FillOptions, InternalError, OpenSubpathPolicy, Side, TessellationError, TessellationResult,
+    VertexSource,
 };
 use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap};
 use std::f32;
 use std::ops::Range;
 
@@ -69,6 +77,7 @@ impl WindingState {
 struct ActiveEdgeScan {
     vertex_events: Vec<(SpanIdx, Side)>,
     edges_to_split: Vec<ActiveEdgeIdx>,
+    boundary_edges: Vec<ActiveEdgeIdx>,
     spans_to_end: Vec<SpanIdx>,
     merge_event: bool,
     split_event: bool,
@@ -82,6 +91,7 @@ impl ActiveEdgeScan {
         ActiveEdgeScan {
             vertex_events: Vec::new(),
             edges_to_split: Vec::new(),
+            boundary_edges: Vec::new(),
             spans_to_end: Vec::new(),
             merge_event: false,
             split_event: false,
@@ -94,6 +104,7 @@ impl ActiveEdgeScan {
     fn reset(&mut self) {
         self.vertex_events.clear();
         self.edges_to_split.clear();
+        self.boundary_edges.clear();
         self.spans_to_end.clear();
         self.merge_event = false;
         self.split_event = false;
@@ -146,20 +157,39 @@ struct ActiveEdges {
 struct Span {
     tess: MonotoneTessellator,
     remove: bool,
+    winding: i16,
+}
+
+impl Span {
+    fn flush(
+        &mut self,
+        triangle_windings: &mut Option<Vec<i16>>,
+        output: &mut dyn FillGeometryBuilder,
+    ) {
+        if let Some(windings) = triangle_windings {
+            let n = self.tess.num_triangles();
+            windings.extend(std::iter::repeat(self.winding).take(n));
+        }
+        self.tess.flush(output);
+    }
 }
 
 struct Spans {
     spans: Vec<Span>,
+    // When set, the winding number of each emitted triangle is recorded
+    // in the order the triangles are sent to the output.
+    triangle_windings: Option<Vec<i16>>,
 }
 
 impl Spans {
-    fn begin_span(&mut self, span_idx: SpanIdx, position: &Point, vertex: VertexId) {
+    fn begin_span(&mut self, span_idx: SpanIdx, position: &Point, vertex: VertexId, winding: i16) {
         let idx = span_idx as usize;
         self.spans.insert(
             idx,
             Span {
                 tess: MonotoneTessellator::new().begin(*position, vertex),
                 remove: false,
+                winding,
             },
         );
     }
@@ -178,7 +208,7 @@ impl Spans {
         let span = &mut self.spans[idx];
         span.remove = true;
         span.tess.end(*position, id);
-        span.tess.flush(output);
+        span.flush(&mut self.triangle_windings, output);
     }
 
     fn merge_spans(
@@ -266,6 +296,10 @@ struct PendingEdge {
 ///
 /// The tessellator does not handle `NaN` values in any of its inputs.
 ///
+/// The output is deterministic: tessellating the same input with the same options
+/// always produces the same vertices and indices in the same order. The tessellator
+/// avoids any data structure with unspecified iteration order to guarantee this.
+///
 /// ## Associating custom attributes with vertices.
 ///
 /// It is sometimes useful to be able to link vertices generated by the tessellator back
@@ -448,8 +482,43 @@ pub struct FillTessellator {
     log: bool,
     assume_no_intersection: bool,
     attrib_buffer: Vec<f32>,
+    boundary_edges: Option<Vec<BoundaryEdge>>,
+    // Points inserted in the triangulation, see `tessellate_with_steiner_points`.
+    steiner_points: Vec<Point>,
+    // Offset and scale applied to the event queue when normalizing coordinates.
+    normalization: Option<(Vector, f32)>,
+
+    events: EventQueue,
+}
 
+/// Reusable storage for the internal data structures of the fill tessellator.
+///
+/// See `FillTessellator::tessellate_path_with_scratch`.
+pub struct FillScratch {
     events: EventQueue,
+    active_edges: Vec<ActiveEdge>,
+    edges_below: Vec<PendingEdge>,
+    spans: Vec<Span>,
+    attrib_buffer: Vec<f32>,
+}
+
+impl FillScratch {
+    /// Constructor.
+    pub fn new() -> Self {
+        FillScratch {
+            events: EventQueue::new(),
+            active_edges: Vec::new(),
+            edges_below: Vec::new(),
+            spans: Vec::new(),
+            attrib_buffer: Vec::new(),
+        }
+    }
+}
+
+impl Default for FillScratch {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl FillTessellator {
@@ -468,10 +537,16 @@ impl FillTessellator {
             edges_below: Vec::new(),
             fill_rule: FillRule::EvenOdd,
             orientation: Orientation::Vertical,
-            fill: Spans { spans: Vec::new() },
+            fill: Spans {
+                spans: Vec::new(),
+                triangle_windings: None,
+            },
             log,
             assume_no_intersection: false,
             attrib_buffer: Vec::new(),
+            boundary_edges: None,
+            steiner_points: Vec::new(),
+            normalization: None,
 
             events: EventQueue::new(),
         }
@@ -491,12 +566,21 @@ impl FillTessellator {
         output: &mut dyn FillGeometryBuilder,
     ) -> TessellationResult {
         let mut queue_builder = self.create_event_queue().into_builder();
+        queue_builder.set_max_subdivision_depth(options.max_subdivision_depth);
 
-        queue_builder.set_path(
-            options.tolerance,
-            options.sweep_orientation,
-            path.into_iter(),
-        );
+        if let Some(mut preprocessor) = PathPreprocessor::new(options) {
+            for evt in path {
+                preprocessor.event(evt)?;
+            }
+            queue_builder.begin_path(options.tolerance);
+            preprocessor.build(&mut queue_builder, options.sweep_orientation);
+        } else {
+            queue_builder.set_path(
+                options.tolerance,
+                options.sweep_orientation,
+                path.into_iter(),
+            );
+        }
 
         let mut event_queue = queue_builder.build();
 
@@ -517,13 +601,24 @@ impl FillTessellator {
         output: &mut dyn FillGeometryBuilder,
     ) -> TessellationResult {
         let mut queue_builder = self.create_event_queue().into_builder();
-
-        queue_builder.set_path_with_ids(
-            options.tolerance,
-            options.sweep_orientation,
-            path.into_iter(),
-            positions,
-        );
+        queue_builder.set_max_subdivision_depth(options.max_subdivision_depth);
+
+        if options.open_subpath_policy == OpenSubpathPolicy::Close {
+            queue_builder.set_path_with_ids(
+                options.tolerance,
+                options.sweep_orientation,
+                path.into_iter(),
+                positions,
+            );
+        } else {
+            let events = apply_open_subpath_policy(path, options.open_subpath_policy)?;
+            queue_builder.set_path_with_ids(
+                options.tolerance,
+                options.sweep_orientation,
+                events.into_iter(),
+                positions,
+            );
+        }
 
         let mut event_queue = queue_builder.build();
 
@@ -569,6 +664,503 @@ impl FillTessellator {
         }
     }
 
+    /// Compute the tessellation from a path slice, using the allocations of a scratch
+    /// object for the internal data structures.
+    ///
+    /// This makes it possible to reuse allocations explicitly, for example when using
+    /// short-lived tessellators in a hot loop or when several tessellators are used one
+    /// after the other: they can share a single `FillScratch` object.
+    pub fn tessellate_path_with_scratch<'l>(
+        &mut self,
+        path: impl Into<PathSlice<'l>>,
+        options: &FillOptions,
+        scratch: &mut FillScratch,
+        builder: &mut dyn FillGeometryBuilder,
+    ) -> TessellationResult {
+        let path = path.into();
+
+        self.swap_scratch(scratch);
+        let result = if path.num_attributes() > 0 {
+            self.tessellate_with_ids(path.id_iter(), &path, Some(&path), options, builder)
+        } else {
+            self.tessellate(path.iter(), options, builder)
+        };
+        self.swap_scratch(scratch);
+
+        result
+    }
+
+    fn swap_scratch(&mut self, scratch: &mut FillScratch) {
+        std::mem::swap(&mut self.events, &mut scratch.events);
+        std::mem::swap(&mut self.active.edges, &mut scratch.active_edges);
+        std::mem::swap(&mut self.edges_below, &mut scratch.edges_below);
+        std::mem::swap(&mut self.fill.spans, &mut scratch.spans);
+        std::mem::swap(&mut self.attrib_buffer, &mut scratch.attrib_buffer);
+    }
+
+    /// Compute the tessellation from a path iterator, producing separate vertex
+    /// buffers for each winding number.
+    ///
+    /// The triangles of each region of the fill are put in the buffers associated to
+    /// the region's winding number. The returned layers are sorted by winding number.
+    /// Only regions that are inside the shape according to the fill rule of the options
+    /// are produced.
+    ///
+    /// This is useful to render self-overlapping or nested shapes with a different
+    /// appearance per overlap depth.
+    pub fn tessellate_layered(
+        &mut self,
+        path: impl IntoIterator<Item = PathEvent>,
+        options: &FillOptions,
+    ) -> Result<Vec<(i16, VertexBuffers<Point, u16>)>, TessellationError> {
+        let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+
+        self.fill.triangle_windings = Some(Vec::new());
+        let result = self.tessellate(path, options, &mut simple_builder(&mut buffers));
+        let windings = self.fill.triangle_windings.take().unwrap_or_default();
+        result?;
+
+        let invalid = std::u16::MAX;
+        // For each layer, the index of each vertex in the layer's buffers.
+        let mut layers: Vec<(i16, VertexBuffers<Point, u16>, Vec<u16>)> = Vec::new();
+        for (triangle, &winding) in buffers.indices.chunks(3).zip(windings.iter()) {
+            let layer_idx = match layers.binary_search_by_key(&winding, |layer| layer.0) {
+                Ok(idx) => idx,
+                Err(idx) => {
+                    let remap = vec![invalid; buffers.vertices.len()];
+                    layers.insert(idx, (winding, VertexBuffers::new(), remap));
+                    idx
+                }
+            };
+
+            let (_, ref mut layer, ref mut remap) = layers[layer_idx];
+            for &vertex in triangle {
+                let vertex = vertex as usize;
+                if remap[vertex] == invalid {
+                    remap[vertex] = layer.vertices.len() as u16;
+                    layer.vertices.push(buffers.vertices[vertex]);
+                }
+                layer.indices.push(remap[vertex]);
+            }
+        }
+
+        Ok(layers
+            .into_iter()
+            .map(|(winding, layer, _)| (winding, layer))
+            .collect())
+    }
+
+    /// Compute the even-odd and the non-zero tessellations of a path with a single sweep.
+    ///
+    /// Returns the even-odd fill followed by the non-zero fill. The fill rule of the
+    /// options is ignored.
+    ///
+    /// The regions filled with the even-odd rule are a subset of the ones filled with the
+    /// non-zero rule, so the path is tessellated once with the non-zero rule while keeping
+    /// track of the winding number of each triangle, and the triangles with an odd winding
+    /// number make up the even-odd fill. This is cheaper than tessellating the path twice.
+    pub fn tessellate_both_rules(
+        &mut self,
+        path: impl IntoIterator<Item = PathEvent>,
+        options: &FillOptions,
+    ) -> Result<(VertexBuffers<Point, u16>, VertexBuffers<Point, u16>), TessellationError> {
+        let mut non_zero: VertexBuffers<Point, u16> = VertexBuffers::new();
+
+        self.fill.triangle_windings = Some(Vec::new());
+        let result = self.tessellate(
+            path,
+            &options.with_fill_rule(FillRule::NonZero),
+            &mut simple_builder(&mut non_zero),
+        );
+        let windings = self.fill.triangle_windings.take().unwrap_or_default();
+        result?;
+
+        let invalid = std::u16::MAX;
+        let mut remap = vec![invalid; non_zero.vertices.len()];
+        let mut even_odd: VertexBuffers<Point, u16> = VertexBuffers::new();
+        for (triangle, &winding) in non_zero.indices.chunks(3).zip(windings.iter()) {
+            if winding % 2 == 0 {
+                continue;
+            }
+
+            for &vertex in triangle {
+                let vertex = vertex as usize;
+                if remap[vertex] == invalid {
+                    remap[vertex] = even_odd.vertices.len() as u16;
+                    even_odd.vertices.push(non_zero.vertices[vertex]);
+                }
+                even_odd.indices.push(remap[vertex]);
+            }
+        }
+
+        Ok((even_odd, non_zero))
+    }
+
+    /// Compute the tessellation from a path slice, with the triangles grouped by the
+    /// sub-path they originate from.
+    ///
+    /// Returns the geometry along with a list of `(sub_path_index, index_range)` pairs,
+    /// sorted by sub-path index, where each range refers to the triangles of the index
+    /// buffer that belong to the sub-path. This makes it possible to render each contour
+    /// of a shape with a separate draw call. Sub-paths that do not produce any triangle
+    /// are not listed.
+    ///
+    /// The fill tessellator merges all of the sub-paths into a single filled region,
+    /// so the grouping is only meaningful if the sub-paths don't overlap each other.
+    pub fn tessellate_grouped_by_sub_path<'l>(
+        &mut self,
+        path: impl Into<PathSlice<'l>>,
+        options: &FillOptions,
+    ) -> Result<(VertexBuffers<Point, u16>, Vec<(usize, Range<usize>)>), TessellationError> {
+        let path = path.into();
+
+        let mut sub_path_starts = Vec::new();
+        for evt in path.id_iter() {
+            if let IdEvent::Begin { at } = evt {
+                sub_path_starts.push(at.0);
+            }
+        }
+
+        let mut tagged: VertexBuffers<(Point, usize), u16> = VertexBuffers::new();
+        self.tessellate_with_ids(
+            path.id_iter(),
+            &path,
+            None,
+            options,
+            &mut BuffersBuilder::new(
+                &mut tagged,
+                SubPathIndex {
+                    sub_path_starts: &sub_path_starts,
+                },
+            ),
+        )?;
+
+        let mut triangles: Vec<(usize, [u16; 3])> = tagged
+            .indices
+            .chunks(3)
+            .map(|tri| (tagged.vertices[tri[0] as usize].1, [tri[0], tri[1], tri[2]]))
+            .collect();
+        // A stable sort preserves the order of the triangles within each sub-path.
+        triangles.sort_by_key(|tri| tri.0);
+
+        let mut output: VertexBuffers<Point, u16> =
+            VertexBuffers::with_capacity(tagged.vertices.len(), tagged.indices.len());
+        output
+            .vertices
+            .extend(tagged.vertices.iter().map(|vertex| vertex.0));
+
+        let mut groups: Vec<(usize, Range<usize>)> = Vec::new();
+        for (sub_path, triangle) in triangles {
+            let start = output.indices.len();
+            output.indices.extend_from_slice(&triangle);
+            match groups.last_mut() {
+                Some(group) if group.0 == sub_path => {
+                    group.1.end = output.indices.len();
+                }
+                _ => {
+                    groups.push((sub_path, start..output.indices.len()));
+                }
+            }
+        }
+
+        Ok((output, groups))
+    }
+
+    /// Compute the tessellation from a path iterator and record the edges that are on
+    /// the boundary of the tessellated shape.
+    ///
+    /// The boundary edges are appended to `boundary`. Each of them refers to the vertices
+    /// sent to the output and to the path event it originates from, which makes it possible
+    /// to map the rendered geometry back to the segments of the source path.
+    pub fn tessellate_with_boundary(
+        &mut self,
+        path: impl IntoIterator<Item = PathEvent>,
+        options: &FillOptions,
+        output: &mut dyn FillGeometryBuilder,
+        boundary: &mut Vec<BoundaryEdge>,
+    ) -> TessellationResult {
+        self.boundary_edges = Some(std::mem::replace(boundary, Vec::new()));
+        let result = self.tessellate(path, options, output);
+        *boundary = self.boundary_edges.take().unwrap_or_default();
+
+        result
+    }
+
+    /// Compute the tessellation from a path slice, with additional vertices at the
+    /// provided positions.
+    ///
+    /// Each point inside of the filled area becomes a vertex of the triangulation: the
+    /// triangles containing it are split so that no edge crosses it. This is useful to
+    /// anchor deformations or simulations at specific locations. Points outside of the
+    /// filled area and points that are already at the position of a vertex are ignored.
+    ///
+    /// The added vertices have no source, so `FillAttributes::sources` is empty for them
+    /// and their interpolated custom attributes are zero. The triangles are split without
+    /// regard to their shape, so thin triangles can be produced around the points.
+    pub fn tessellate_with_steiner_points<'l>(
+        &mut self,
+        path: impl Into<PathSlice<'l>>,
+        points: &[Point],
+        options: &FillOptions,
+        output: &mut dyn FillGeometryBuilder,
+    ) -> TessellationResult {
+        let path = path.into();
+
+        self.steiner_points.clear();
+        self.steiner_points.extend_from_slice(points);
+        let result = if path.num_attributes() > 0 {
+            self.tessellate_with_ids(path.id_iter(), &path, Some(&path), options, output)
+        } else {
+            self.tessellate(path.iter(), options, output)
+        };
+        self.steiner_points.clear();
+
+        result
+    }
+
+    /// Compute the tessellation of a path once, in a form that can be turned into vertex
+    /// buffers several times.
+    ///
+    /// See [`TessellationPlan`](struct.TessellationPlan.html).
+    pub fn plan(
+        &mut self,
+        path: &Path,
+        options: &FillOptions,
+    ) -> Result<TessellationPlan, TessellationError> {
+        let mut recorder = TriangleRecorder::default();
+        self.tessellate(path.iter(), options, &mut recorder)?;
+
+        // The vertex sources refer to the event queue.
+        let events = std::mem::replace(&mut self.events, EventQueue::new());
+        let indices = recorder
+            .triangles
+            .iter()
+            .flat_map(|triangle| triangle.iter())
+            .map(|id| id.0)
+            .collect();
+
+        Ok(TessellationPlan {
+            events,
+            vertices: recorder.vertices,
+            indices,
+        })
+    }
+
+    /// Compute the tessellation from a path iterator, along with the direction of the
+    /// boundary of the shape at each vertex.
+    ///
+    /// Vertices on the boundary of the filled area have `is_boundary` set and carry the
+    /// normalized average of the directions of their adjacent boundary edges. The boundary
+    /// is oriented so that the filled area is on the side of `vector(-tangent.y, tangent.x)`.
+    /// Interior vertices carry a zero tangent.
+    ///
+    /// This is useful to extrude an outline or a glow along the boundary of a fill in a
+    /// vertex shader.
+    pub fn tessellate_with_boundary_tangents(
+        &mut self,
+        path: impl IntoIterator<Item = PathEvent>,
+        options: &FillOptions,
+    ) -> Result<VertexBuffers<BoundaryTangentVertex, u16>, TessellationError> {
+        let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+        let mut boundary = Vec::new();
+        self.tessellate_with_boundary(
+            path,
+            options,
+            &mut simple_builder(&mut buffers),
+            &mut boundary,
+        )?;
+
+        // The vertex opposite to each edge of the triangles.
+        let mut opposite_vertex = HashMap::new();
+        for tri in buffers.indices.chunks(3) {
+            for i in 0..3 {
+                let (a, b) = (tri[i], tri[(i + 1) % 3]);
+                opposite_vertex.insert((a.min(b), a.max(b)), tri[(i + 2) % 3]);
+            }
+        }
+
+        let mut output: VertexBuffers<BoundaryTangentVertex, u16> = VertexBuffers {
+            vertices: buffers
+                .vertices
+                .iter()
+                .map(|&position| BoundaryTangentVertex {
+                    position,
+                    tangent: vector(0.0, 0.0),
+                    is_boundary: false,
+                })
+                .collect(),
+            indices: buffers.indices,
+        };
+
+        for edge in &boundary {
+            let (from, to) = (edge.from.to_usize(), edge.to.to_usize());
+            let (a, b) = (from as u16, to as u16);
+            let p_from = output.vertices[from].position;
+            let mut direction = output.vertices[to].position - p_from;
+            if let Some(&c) = opposite_vertex.get(&(a.min(b), a.max(b))) {
+                let p_c = output.vertices[c as usize].position;
+                if direction.cross(p_c - p_from) < 0.0 {
+                    direction = -direction;
+                }
+            }
+
+            let direction = direction.normalize();
+            for &idx in &[from, to] {
+                let vertex = &mut output.vertices[idx];
+                vertex.tangent += direction;
+                vertex.is_boundary = true;
+            }
+        }
+
+        for vertex in &mut output.vertices {
+            if vertex.tangent.square_length() > 0.0 {
+                vertex.tangent = vertex.tangent.normalize();
+            }
+        }
+
+        Ok(output)
+    }
+
+    /// Compute a constrained Delaunay triangulation of the fill.
+    ///
+    /// The edges on the boundary of the filled area are constrained: they always appear
+    /// as triangle edges. The interior edges satisfy the Delaunay criterion, which means
+    /// no vertex lies strictly inside the circumcircle of a triangle across an interior
+    /// edge. This tends to avoid thin slivers and is useful for simulation and finite
+    /// element meshes.
+    ///
+    /// Every edge of the flattened path that is a triangle edge of the fill is kept,
+    /// including the ones that lie inside of the filled area with the non-zero fill rule.
+    ///
+    /// This is more expensive than `tessellate`.
+    pub fn tessellate_cdt(
+        &mut self,
+        path: impl IntoIterator<Item = PathEvent>,
+        options: &FillOptions,
+        output: &mut dyn FillGeometryBuilder,
+    ) -> TessellationResult {
+        self.tessellate(path, options, &mut CdtBuilder::new(output))
+    }
+
+    /// Compute the tessellation of the part of the fill that is above a horizontal line.
+    ///
+    /// Only the area of the shape where `y <= sweep_y` is produced, which corresponds to
+    /// the part of the shape that the tessellator has swept through when its sweep line
+    /// reaches `sweep_y` (with the default vertical sweep orientation). This can be used to
+    /// progressively reveal a fill, for example when scrubbing through an animation.
+    ///
+    /// Curves are flattened before being clipped, and the fill rule is preserved.
+    pub fn tessellate_up_to(
+        &mut self,
+        path: impl IntoIterator<Item = PathEvent>,
+        sweep_y: f32,
+        options: &FillOptions,
+        output: &mut dyn FillGeometryBuilder,
+    ) -> TessellationResult {
+        let mut builder = Path::builder();
+        let mut polygon = Vec::new();
+        let mut clipped = Vec::new();
+        for evt in path.into_iter().flattened(options.tolerance) {
+            match evt {
+                PathEvent::Begin { at } => {
+                    polygon.clear();
+                    polygon.push(at);
+                }
+                PathEvent::Line { to, .. } => {
+                    polygon.push(to);
+                }
+                PathEvent::End { .. } => {
+                    clip_polygon_below(&polygon, sweep_y, &mut clipped);
+                    if clipped.len() > 2 {
+                        builder.move_to(clipped[0]);
+                        for p in &clipped[1..] {
+                            builder.line_to(*p);
+                        }
+                        builder.close();
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        self.tessellate(&builder.build(), options, output)
+    }
+
+    /// Tessellate a small internal shape in order to pre-allocate the internal buffers
+    /// and warm up the code paths of the tessellator.
+    ///
+    /// The first tessellation performed by a tessellator is typically slower than the
+    /// following ones because of memory allocations. Latency-sensitive applications can
+    /// call this method ahead of time (for example during loading) so that the first
+    /// real tessellation doesn't cause a spike.
+    pub fn warmup(&mut self) {
+        // A self-intersecting polygon and a curve.
+        let events = [
+            PathEvent::Begin {
+                at: point(0.0, 0.0),
+            },
+            PathEvent::Line {
+                from: point(0.0, 0.0),
+                to: point(10.0, 10.0),
+            },
+            PathEvent::Line {
+                from: point(10.0, 10.0),
+                to: point(10.0, 0.0),
+            },
+            PathEvent::Line {
+                from: point(10.0, 0.0),
+                to: point(0.0, 10.0),
+            },
+            PathEvent::End {
+                last: point(0.0, 10.0),
+                first: point(0.0, 0.0),
+                close: true,
+            },
+            PathEvent::Begin {
+                at: point(2.0, 12.0),
+            },
+            PathEvent::Quadratic {
+                from: point(2.0, 12.0),
+                ctrl: point(5.0, 20.0),
+                to: point(8.0, 12.0),
+            },
+            PathEvent::End {
+                last: point(8.0, 12.0),
+                first: point(2.0, 12.0),
+                close: true,
+            },
+        ];
+
+        let _ = self.tessellate(
+            events.iter().cloned(),
+            &FillOptions::tolerance(0.1).with_fill_rule(FillRule::NonZero),
+            &mut NoOutput::new(),
+        );
+    }
+
+    /// Start a tessellation that is fed one path event at a time.
+    ///
+    /// The events are buffered by the returned `FillBuilder` and the
+    /// tessellation happens when `FillBuilder::finish` is called.
+    pub fn begin<'l>(
+        &'l mut self,
+        options: &FillOptions,
+        output: &'l mut dyn FillGeometryBuilder,
+    ) -> FillBuilder<'l> {
+        let mut events = self.create_event_queue().into_builder();
+        events.set_max_subdivision_depth(options.max_subdivision_depth);
+        events.begin_path(options.tolerance);
+
+        FillBuilder {
+            events,
+            preprocessor: PathPreprocessor::new(options),
+            options: *options,
+            tessellator: self,
+            output,
+            error: None,
+        }
+    }
+
     fn tessellate_impl(
         &mut self,
         options: &FillOptions,
@@ -579,6 +1171,12 @@ impl FillTessellator {
             return Err(TessellationError::UnsupportedParamater);
         }
 
+        if let Some(pixel_size) = options.conservative_pixel_size {
+            if !(pixel_size > 0.0) {
+                return Err(TessellationError::UnsupportedParamater);
+            }
+        }
+
         self.reset();
 
         if let Some(store) = attrib_store {
@@ -591,10 +1189,48 @@ impl FillTessellator {
         self.orientation = options.sweep_orientation;
         self.assume_no_intersection = !options.handle_intersections;
 
+        self.normalization = if options.normalize_coordinates {
+            self.normalize_events()
+        } else {
+            None
+        };
+
+        if options.no_shared_vertices
+            || !self.steiner_points.is_empty()
+            || options.conservative_pixel_size.is_some()
+        {
+            let mut recorder = TriangleRecorder::default();
+            self.sweep(attrib_store, &mut recorder)?;
+            for &p in &self.steiner_points {
+                recorder.insert_point(p);
+            }
+            if let Some(pixel_size) = options.conservative_pixel_size {
+                recorder.dilate(pixel_size * 0.5);
+            }
+            return self.emit_recorded_triangles(
+                &recorder,
+                !options.no_shared_vertices,
+                attrib_store,
+                builder,
+            );
+        }
+
+        self.sweep(attrib_store, builder)
+    }
+
+    fn sweep(
+        &mut self,
+        attrib_store: Option<&dyn AttributeStore>,
+        builder: &mut dyn FillGeometryBuilder,
+    ) -> TessellationResult {
         builder.begin_geometry();
 
         let result = self.tessellator_loop(attrib_store, builder);
 
+        if let Some((offset, scale)) = self.normalization.take() {
+            self.events.denormalize(offset, scale);
+        }
+
         if let Err(e) = result {
             tess_log!(self, "Tessellation failed with error: {:?}.", e);
             builder.abort_geometry();
@@ -612,7 +1248,7 @@ impl FillTessellator {
         // miss the triangles they contain.
         for span in &mut self.fill.spans {
             if !span.remove {
-                span.tess.flush(builder);
+                span.flush(&mut self.fill.triangle_windings, builder);
             }
         }
 
@@ -621,6 +1257,80 @@ impl FillTessellator {
         Ok(builder.end_geometry())
     }
 
+    // Emits the recorded triangles, with three new vertices for each triangle if the
+    // vertices are not shared.
+    fn emit_recorded_triangles(
+        &mut self,
+        recorder: &TriangleRecorder,
+        shared_vertices: bool,
+        attrib_store: Option<&dyn AttributeStore>,
+        builder: &mut dyn FillGeometryBuilder,
+    ) -> TessellationResult {
+        builder.begin_geometry();
+
+        let mut emitted = vec![VertexId::INVALID; recorder.vertices.len()];
+        for triangle in &recorder.triangles {
+            let mut ids = [VertexId::INVALID; 3];
+            for (id, vertex) in ids.iter_mut().zip(triangle.iter()) {
+                if shared_vertices && emitted[vertex.to_usize()] != VertexId::INVALID {
+                    *id = emitted[vertex.to_usize()];
+                    continue;
+                }
+
+                let (position, current_event) = recorder.vertices[vertex.to_usize()];
+                let result = builder.add_fill_vertex(
+                    position,
+                    FillAttributes {
+                        events: &self.events,
+                        current_event,
+                        attrib_store,
+                        attrib_buffer: &mut self.attrib_buffer,
+                    },
+                );
+
+                match result {
+                    Ok(new_id) => {
+                        *id = new_id;
+                        emitted[vertex.to_usize()] = new_id;
+                    }
+                    Err(e) => {
+                        builder.abort_geometry();
+                        return Err(e.into());
+                    }
+                }
+            }
+
+            builder.add_triangle(ids[0], ids[1], ids[2]);
+        }
+
+        Ok(builder.end_geometry())
+    }
+
+    // Centers the events around the origin and scales them by a power of two so that
+    // they fit in a well-conditioned range of coordinates.
+    fn normalize_events(&mut self) -> Option<(Vector, f32)> {
+        // Half of the extent of the normalized geometry.
+        const NORMALIZED_HALF_EXTENT: f32 = 1024.0;
+
+        let (min, max) = self.events.bounds()?;
+        let offset = min.lerp(max, 0.5).to_vector();
+        let half_extent = (max.x - min.x).max(max.y - min.y) * 0.5;
+        if !half_extent.is_finite() {
+            return None;
+        }
+
+        // Scaling by a power of two doesn't lose any precision.
+        let scale = if half_extent > 0.0 {
+            2.0f32.powi((NORMALIZED_HALF_EXTENT / half_extent).log2().round() as i32)
+        } else {
+            1.0
+        };
+
+        self.events.normalize(offset, scale);
+
+        Some((offset, scale))
+    }
+
     /// Enable/disable some verbose logging during the tessellation, for
     /// debugging purposes.
     pub fn set_logging(&mut self, is_enabled: bool) {
@@ -679,9 +1389,14 @@ impl FillTessellator {
             return Err(TessellationError::UnsupportedParamater);
         }
 
+        let mut position = self.current_position;
+        if let Some((offset, scale)) = self.normalization {
+            position = (position.to_vector() / scale).to_point() + offset;
+        }
+
         let position = match self.orientation {
-            Orientation::Vertical => self.current_position,
-            Orientation::Horizontal => reorient(self.current_position),
+            Orientation::Vertical => position,
+            Orientation::Horizontal => reorient(position),
         };
 
         self.current_vertex = output.add_fill_vertex(
@@ -1008,8 +1723,13 @@ impl FillTessellator {
                     scan.spans_to_end.push(winding.span_index);
                 }
 
+                let was_in = winding.is_in;
                 winding.update(self.fill_rule, active_edge.winding);
 
+                if was_in != winding.is_in {
+                    scan.boundary_edges.push(active_edge_idx);
+                }
+
                 tess_log!(
                     self,
                     " x span: {} in: {}",
@@ -1169,6 +1889,17 @@ impl FillTessellator {
 
         self.fill.cleanup_spans();
 
+        if let Some(boundary_edges) = &mut self.boundary_edges {
+            for &edge_idx in &scan.boundary_edges {
+                let active_edge = &self.active.edges[edge_idx];
+                boundary_edges.push(BoundaryEdge {
+                    from: active_edge.from_id,
+                    to: self.current_vertex,
+                    event: self.events.edge_data[active_edge.src_edge as usize].src_event as usize,
+                });
+            }
+        }
+
         for &edge_idx in &scan.edges_to_split {
             let active_edge = &mut self.active.edges[edge_idx];
             let to = active_edge.to;
@@ -1268,6 +1999,7 @@ impl FillTessellator {
                     winding.span_index,
                     &self.current_position,
                     self.current_vertex,
+                    winding.number,
                 );
             }
             winding.update(self.fill_rule, pending_edge.winding);
@@ -1361,11 +2093,13 @@ impl FillTessellator {
             )
         };
 
+        let winding = self.fill.spans[left_span_idx as usize].winding;
         self.fill.spans.insert(
             new_span_idx as usize,
             Span {
                 tess: MonotoneTessellator::new().begin(upper_position, upper_id),
                 remove: false,
+                winding,
             },
         );
 
@@ -1739,7 +2473,7 @@ impl FillTessellator {
 
             if winding.span_index >= self.fill.spans.len() as i32 {
                 self.fill
-                    .begin_span(winding.span_index, &edge.from, edge.from_id);
+                    .begin_span(winding.span_index, &edge.from, edge.from_id, winding.number);
             }
         }
 
@@ -1764,6 +2498,112 @@ impl FillTessellator {
     }
 }
 
+/// An edge on the boundary of a fill tessellation.
+///
+/// See `FillTessellator::tessellate_with_boundary`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct BoundaryEdge {
+    /// The upper vertex of the edge in the sweep order.
+    pub from: VertexId,
+    /// The lower vertex of the edge in the sweep order.
+    pub to: VertexId,
+    /// The index of the path event that produced this edge, in the order of the path
+    /// iterator. The edge that closes a sub-path refers to its `End` event.
+    pub event: usize,
+}
+
+/// A vertex produced by `FillTessellator::tessellate_with_boundary_tangents`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct BoundaryTangentVertex {
+    pub position: Point,
+    /// The direction of the boundary at this vertex, or zero for interior vertices.
+    pub tangent: Vector,
+    /// Whether the vertex is on the boundary of the filled area.
+    pub is_boundary: bool,
+}
+
+/// A fill tessellation that is driven one path event at a time.
+///
+/// Created with `FillTessellator::begin`. Each sub-path must be terminated
+/// with an `End` event before calling `finish`.
+///
+/// ## Per sub-path tolerance
+///
+/// The flattening tolerance can be overridden between sub-paths with
+/// `set_tolerance`, for example to approximate a detailed outer contour
+/// more precisely than a coarse hole. The override only affects how the
+/// curves of the following sub-paths are flattened: the fill rule is
+/// shared by the whole tessellation. Where sub-paths with different
+/// tolerances overlap, each contour keeps its own approximation so their
+/// edges may not line up exactly.
+pub struct FillBuilder<'l> {
+    events: EventQueueBuilder,
+    // Buffers the events when the options require preprocessing the path.
+    preprocessor: Option<PathPreprocessor>,
+    options: FillOptions,
+    tessellator: &'l mut FillTessellator,
+    output: &'l mut dyn FillGeometryBuilder,
+    error: Option<TessellationError>,
+}
+
+impl<'l> FillBuilder<'l> {
+    /// Add a path event to the tessellation.
+    pub fn event(&mut self, evt: PathEvent) {
+        match self.preprocessor {
+            Some(ref mut preprocessor) => {
+                if let Err(error) = preprocessor.event(evt) {
+                    self.error.get_or_insert(error);
+                }
+            }
+            None => {
+                self.events.path_event(evt, self.options.sweep_orientation);
+            }
+        }
+    }
+
+    /// Override the flattening tolerance for the events added after this call.
+    ///
+    /// Typically called before the `Begin` event of a sub-path.
+    pub fn set_tolerance(&mut self, tolerance: f32) {
+        if tolerance.is_nan() || tolerance <= 0.0 {
+            self.error = Some(TessellationError::UnsupportedParamater);
+            return;
+        }
+
+        match self.preprocessor {
+            Some(ref mut preprocessor) => preprocessor.set_tolerance(tolerance),
+            None => self.events.set_tolerance(tolerance),
+        }
+    }
+
+    /// Restore the tolerance specified in the fill options.
+    pub fn reset_tolerance(&mut self) {
+        let tolerance = self.options.tolerance;
+        match self.preprocessor {
+            Some(ref mut preprocessor) => preprocessor.set_tolerance(tolerance),
+            None => self.events.set_tolerance(tolerance),
+        }
+    }
+
+    /// Run the tessellation on the events received so far.
+    pub fn finish(self) -> TessellationResult {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+
+        let mut events = self.events;
+        if let Some(preprocessor) = self.preprocessor {
+            preprocessor.build(&mut events, self.options.sweep_orientation);
+        }
+
+        let mut event_queue = events.build();
+        std::mem::swap(&mut self.tessellator.events, &mut event_queue);
+
+        self.tessellator
+            .tessellate_impl(&self.options, None, self.output)
+    }
+}
+
 pub(crate) fn points_are_equal(a: Point, b: Point) -> bool {
     // TODO: Use the tolerance threshold?
     a == b
@@ -1800,6 +2640,601 @@ fn reorient(p: Point) -> Point {
     point(p.y, -p.x)
 }
 
+/// Clips a polygon to the half-plane where `y <= max_y` (Sutherland-Hodgman).
+///
+/// The edges introduced along the clipping line overlap each other in opposite
+/// directions when the polygon has several parts below the line, so the winding
+/// numbers of the remaining area are preserved.
+fn clip_polygon_below(polygon: &[Point], max_y: f32, output: &mut Vec<Point>) {
+    output.clear();
+    if polygon.is_empty() {
+        return;
+    }
+
+    let mut prev = polygon[polygon.len() - 1];
+    for &p in polygon {
+        let prev_inside = prev.y <= max_y;
+        let inside = p.y <= max_y;
+        if inside != prev_inside {
+            let t = (max_y - prev.y) / (p.y - prev.y);
+            output.push(point(prev.x + (p.x - prev.x) * t, max_y));
+        }
+        if inside {
+            output.push(p);
+        }
+        prev = p;
+    }
+}
+
+/// Merges the endpoints that are within `epsilon` of each other.
+///
+/// Each endpoint is replaced with the first endpoint of the path that is close enough, so
+/// the result doesn't depend on how the points are aligned with a grid.
+fn weld_endpoints(events: &mut [PathEvent], epsilon: f32) {
+    // Points are bucketed in a grid of `epsilon`-sized cells so that the candidates
+    // for welding are in the same cell or in one of the neighbor cells.
+    let mut cells: HashMap<(i32, i32), Vec<Point>> = HashMap::new();
+    let mut welded: HashMap<(u32, u32), Point> = HashMap::new();
+    let mut weld = |p: Point| -> Point {
+        let key = (p.x.to_bits(), p.y.to_bits());
+        if let Some(w) = welded.get(&key) {
+            return *w;
+        }
+
+        let cell = (
+            (p.x / epsilon).floor() as i32,
+            (p.y / epsilon).floor() as i32,
+        );
+        let mut result = None;
+        'search: for dx in -1..=1 {
+            for dy in -1..=1 {
+                if let Some(points) = cells.get(&(cell.0 + dx, cell.1 + dy)) {
+                    for q in points {
+                        if (*q - p).square_length() <= epsilon * epsilon {
+                            result = Some(*q);
+                            break 'search;
+                        }
+                    }
+                }
+            }
+        }
+
+        let result = result.unwrap_or_else(|| {
+            cells.entry(cell).or_insert_with(Vec::new).push(p);
+            p
+        });
+        welded.insert(key, result);
+
+        result
+    };
+
+    for evt in events.iter_mut() {
+        *evt = match *evt {
+            PathEvent::Begin { at } => PathEvent::Begin { at: weld(at) },
+            PathEvent::Line { from, to } => PathEvent::Line {
+                from: weld(from),
+                to: weld(to),
+            },
+            PathEvent::Quadratic { from, ctrl, to } => PathEvent::Quadratic {
+                from: weld(from),
+                ctrl,
+                to: weld(to),
+            },
+            PathEvent::Cubic {
+                from,
+                ctrl1,
+                ctrl2,
+                to,
+            } => PathEvent::Cubic {
+                from: weld(from),
+                ctrl1,
+                ctrl2,
+                to: weld(to),
+            },
+            PathEvent::End { last, first, close } => PathEvent::End {
+                last: weld(last),
+                first: weld(first),
+                close,
+            },
+        };
+    }
+}
+
+/// Moves the endpoints of horizontal and vertical line segments to the pixel grid.
+fn pixel_snap(events: &mut [PathEvent], transform: &Transform) {
+    let inverse = match transform.inverse() {
+        Some(inverse) => inverse,
+        None => {
+            return;
+        }
+    };
+
+    // Maximum deviation in pixels for a segment to be considered axis-aligned.
+    let epsilon = 0.001;
+
+    let mut start = 0;
+    while start < events.len() {
+        let mut end = start;
+        while end < events.len() {
+            if let PathEvent::End { .. } = events[end] {
+                break;
+            }
+            end += 1;
+        }
+
+        // Endpoints of the sub-path in pixel space, and whether to snap them
+        // horizontally and vertically.
+        let mut points: Vec<(Point, bool, bool)> = Vec::with_capacity(end - start + 1);
+        for evt in &events[start..end] {
+            let at = match *evt {
+                PathEvent::Begin { at } => at,
+                PathEvent::Line { to, .. } => to,
+                PathEvent::Quadratic { to, .. } => to,
+                PathEvent::Cubic { to, .. } => to,
+                PathEvent::End { .. } => unreachable!(),
+            };
+            points.push((transform.transform_point(at), false, false));
+        }
+
+        let closed = match events.get(end) {
+            Some(&PathEvent::End { close, .. }) => close,
+            _ => false,
+        };
+
+        let num_points = points.len();
+        let mut mark = |a: usize, b: usize| {
+            let d = points[b].0 - points[a].0;
+            if d.x.abs() <= epsilon && d.y.abs() > epsilon {
+                points[a].1 = true;
+                points[b].1 = true;
+            }
+            if d.y.abs() <= epsilon && d.x.abs() > epsilon {
+                points[a].2 = true;
+                points[b].2 = true;
+            }
+        };
+        for (i, evt) in events[start + 1..end].iter().enumerate() {
+            if let PathEvent::Line { .. } = evt {
+                mark(i, i + 1);
+            }
+        }
+        if closed && num_points > 1 {
+            mark(num_points - 1, 0);
+        }
+
+        let positions: Vec<Point> = points
+            .iter()
+            .map(|&(p, snap_x, snap_y)| {
+                let snapped = point(
+                    if snap_x { p.x.round() } else { p.x },
+                    if snap_y { p.y.round() } else { p.y },
+                );
+                inverse.transform_point(snapped)
+            })
+            .collect();
+
+        for (i, evt) in events[start..end].iter_mut().enumerate() {
+            match evt {
+                PathEvent::Begin { at } => {
+                    *at = positions[i];
+                }
+                PathEvent::Line { from, to } => {
+                    *from = positions[i - 1];
+                    *to = positions[i];
+                }
+                PathEvent::Quadratic { from, to, .. } => {
+                    *from = positions[i - 1];
+                    *to = positions[i];
+                }
+                PathEvent::Cubic { from, to, .. } => {
+                    *from = positions[i - 1];
+                    *to = positions[i];
+                }
+                PathEvent::End { .. } => {}
+            }
+        }
+
+        if let Some(PathEvent::End { last, first, .. }) = events.get_mut(end) {
+            *last = positions[num_points - 1];
+            *first = positions[0];
+        }
+
+        start = end + 1;
+    }
+}
+
+// Computes the index of the sub-path a vertex originates from.
+struct SubPathIndex<'l> {
+    sub_path_starts: &'l [u32],
+}
+
+impl<'l> FillVertexConstructor<(Point, usize)> for SubPathIndex<'l> {
+    fn new_vertex(&mut self, position: Point, attributes: FillAttributes) -> (Point, usize) {
+        let id = match attributes.sources().next() {
+            Some(VertexSource::Endpoint { id }) => id,
+            Some(VertexSource::Edge { from, .. }) => from,
+            None => EndpointId(0),
+        };
+
+        let sub_path = match self.sub_path_starts.binary_search(&id.0) {
+            Ok(idx) => idx,
+            Err(idx) => idx.max(1) - 1,
+        };
+
+        (position, sub_path)
+    }
+}
+
+// Removes the open sub-paths or fails if there are any, depending on the policy.
+fn apply_open_subpath_policy<Endpoint: Copy, CtrlPoint: Copy>(
+    events: impl IntoIterator<Item = Event<Endpoint, CtrlPoint>>,
+    policy: OpenSubpathPolicy,
+) -> Result<Vec<Event<Endpoint, CtrlPoint>>, TessellationError> {
+    let mut output = Vec::new();
+    let mut subpath_start = 0;
+    for evt in events {
+        push_with_open_subpath_policy(evt, policy, &mut subpath_start, &mut output)?;
+    }
+
+    Ok(output)
+}
+
+// Adds an event to the output unless it ends an open sub-path that the policy removes.
+//
+// `subpath_start` is the index of the beginning of the current sub-path in the output.
+fn push_with_open_subpath_policy<Endpoint: Copy, CtrlPoint: Copy>(
+    evt: Event<Endpoint, CtrlPoint>,
+    policy: OpenSubpathPolicy,
+    subpath_start: &mut usize,
+    output: &mut Vec<Event<Endpoint, CtrlPoint>>,
+) -> Result<(), TessellationError> {
+    match evt {
+        Event::Begin { .. } => {
+            *subpath_start = output.len();
+        }
+        Event::End { close: false, .. } => {
+            let has_segments = output.len() > *subpath_start + 1;
+            match policy {
+                OpenSubpathPolicy::Close => {}
+                OpenSubpathPolicy::Ignore => {
+                    output.truncate(*subpath_start);
+                    return Ok(());
+                }
+                OpenSubpathPolicy::Error if has_segments => {
+                    return Err(TessellationError::OpenSubpath);
+                }
+                OpenSubpathPolicy::Error => {}
+            }
+        }
+        _ => {}
+    }
+    output.push(evt);
+
+    Ok(())
+}
+
+// Applies the open sub-path policy, the pixel snapping and the endpoint welding of the
+// fill options to a path, for both `FillTessellator::tessellate` and `FillBuilder`.
+//
+// The events are buffered because welding needs the whole path. Each event keeps the
+// flattening tolerance that was set when it was added.
+struct PathPreprocessor {
+    events: Vec<PathEvent>,
+    tolerances: Vec<f32>,
+    tolerance: f32,
+    subpath_start: usize,
+    open_subpath_policy: OpenSubpathPolicy,
+    pixel_transform: Option<Transform>,
+    epsilon: f32,
+}
+
+impl PathPreprocessor {
+    // Returns `None` if the options don't require any preprocessing.
+    fn new(options: &FillOptions) -> Option<Self> {
+        if !options.pixel_snap
+            && options.epsilon <= 0.0
+            && options.open_subpath_policy == OpenSubpathPolicy::Close
+        {
+            return None;
+        }
+
+        let pixel_transform = if options.pixel_snap {
+            Some(options.pixel_transform.unwrap_or_else(Transform::identity))
+        } else {
+            None
+        };
+
+        Some(PathPreprocessor {
+            events: Vec::new(),
+            tolerances: Vec::new(),
+            tolerance: options.tolerance,
+            subpath_start: 0,
+            open_subpath_policy: options.open_subpath_policy,
+            pixel_transform,
+            epsilon: options.epsilon,
+        })
+    }
+
+    fn set_tolerance(&mut self, tolerance: f32) {
+        self.tolerance = tolerance;
+    }
+
+    fn event(&mut self, evt: PathEvent) -> Result<(), TessellationError> {
+        push_with_open_subpath_policy(
+            evt,
+            self.open_subpath_policy,
+            &mut self.subpath_start,
+            &mut self.events,
+        )?;
+        self.tolerances.resize(self.events.len(), self.tolerance);
+
+        Ok(())
+    }
+
+    // Adds the preprocessed events to the event queue builder, which must have begun
+    // a path.
+    fn build(mut self, queue: &mut EventQueueBuilder, sweep_orientation: Orientation) {
+        if let Some(transform) = self.pixel_transform {
+            pixel_snap(&mut self.events, &transform);
+        }
+        if self.epsilon > 0.0 {
+            weld_endpoints(&mut self.events, self.epsilon);
+        }
+
+        for (evt, &tolerance) in self.events.into_iter().zip(self.tolerances.iter()) {
+            queue.set_tolerance(tolerance);
+            queue.path_event(evt, sweep_orientation);
+        }
+    }
+}
+
+// Records the output of the sweep so that it can be emitted again with distinct
+// vertices for each triangle.
+#[derive(Default)]
+struct TriangleRecorder {
+    vertices: Vec<(Point, TessEventId)>,
+    triangles: Vec<[VertexId; 3]>,
+}
+
+impl TriangleRecorder {
+    // Splits the triangles that contain the point so that it becomes one of their
+    // vertices.
+    fn insert_point(&mut self, p: Point) {
+        const EPSILON: f32 = 1e-6;
+
+        if self.vertices.iter().any(|&(position, _)| position == p) {
+            return;
+        }
+
+        let id = VertexId::from_usize(self.vertices.len());
+        let mut inserted = false;
+        for i in 0..self.triangles.len() {
+            let [a, b, c] = self.triangles[i];
+            let pa = self.vertices[a.to_usize()].0;
+            let pb = self.vertices[b.to_usize()].0;
+            let pc = self.vertices[c.to_usize()].0;
+            let area = (pb - pa).cross(pc - pa);
+            if area == 0.0 {
+                continue;
+            }
+
+            // Barycentric coordinates of the point.
+            let wa = (pc - pb).cross(p - pb) / area;
+            let wb = (pa - pc).cross(p - pc) / area;
+            let wc = (pb - pa).cross(p - pa) / area;
+            if wa < -EPSILON || wb < -EPSILON || wc < -EPSILON {
+                continue;
+            }
+
+            let on_bc = wa <= EPSILON;
+            let on_ca = wb <= EPSILON;
+            let on_ab = wc <= EPSILON;
+            match (on_bc, on_ca, on_ab) {
+                (false, false, false) => {
+                    self.triangles[i] = [a, b, id];
+                    self.triangles.push([b, c, id]);
+                    self.triangles.push([c, a, id]);
+                }
+                (true, false, false) => {
+                    self.triangles[i] = [a, b, id];
+                    self.triangles.push([a, id, c]);
+                }
+                (false, true, false) => {
+                    self.triangles[i] = [a, b, id];
+                    self.triangles.push([id, b, c]);
+                }
+                (false, false, true) => {
+                    self.triangles[i] = [a, id, c];
+                    self.triangles.push([id, b, c]);
+                }
+                // Too close to a vertex.
+                _ => {
+                    continue;
+                }
+            }
+            inserted = true;
+        }
+
+        if inserted {
+            self.vertices.push((p, INVALID_EVENT_ID));
+        }
+    }
+
+    // Adds triangles covering the points within `half_size` of the boundary along each
+    // axis, which is the Minkowski sum of the boundary and a square.
+    fn dilate(&mut self, half_size: f32) {
+        // Edges that belong to a single triangle are on the boundary of the fill. They are
+        // counted in a sorted map and emitted in the order of the triangles, so that the
+        // output doesn't depend on hashing.
+        let edge_key = |pa: Point, pb: Point| {
+            let ka = (pa.x.to_bits(), pa.y.to_bits());
+            let kb = (pb.x.to_bits(), pb.y.to_bits());
+            if ka < kb {
+                (ka, kb)
+            } else {
+                (kb, ka)
+            }
+        };
+        let mut edges = Vec::new();
+        let mut counts = BTreeMap::new();
+        for triangle in &self.triangles {
+            for &(a, b) in &[(0, 1), (1, 2), (2, 0)] {
+                let pa = self.vertices[triangle[a].to_usize()].0;
+                let pb = self.vertices[triangle[b].to_usize()].0;
+                *counts.entry(edge_key(pa, pb)).or_insert(0) += 1;
+                edges.push((pa, pb));
+            }
+        }
+
+        let mut hull = Vec::with_capacity(8);
+        for (from, to) in edges {
+            if counts[&edge_key(from, to)] != 1 {
+                continue;
+            }
+
+            hull.clear();
+            for &corner in &[from, to] {
+                hull.push(point(corner.x - half_size, corner.y - half_size));
+                hull.push(point(corner.x + half_size, corner.y - half_size));
+                hull.push(point(corner.x + half_size, corner.y + half_size));
+                hull.push(point(corner.x - half_size, corner.y + half_size));
+            }
+            convex_hull(&mut hull);
+
+            let first = VertexId::from_usize(self.vertices.len());
+            for &p in &hull {
+                self.vertices.push((p, INVALID_EVENT_ID));
+            }
+            for i in 1..(hull.len() as u32 - 1) {
+                self.triangles.push([first, first + i, first + i + 1]);
+            }
+        }
+    }
+}
+
+// Replaces the points with their convex hull in counter-clockwise order (with the y axis
+// pointing up), using Andrew's monotone chain algorithm.
+fn convex_hull(points: &mut Vec<Point>) {
+    points.sort_by(|a, b| {
+        a.x.partial_cmp(&b.x)
+            .unwrap_or(Ordering::Equal)
+            .then(a.y.partial_cmp(&b.y).unwrap_or(Ordering::Equal))
+    });
+    points.dedup();
+
+    let mut hull: Vec<Point> = Vec::with_capacity(points.len() + 1);
+    for pass in 0..2 {
+        let start = hull.len();
+        for i in 0..points.len() {
+            let p = if pass == 0 {
+                points[i]
+            } else {
+                points[points.len() - 1 - i]
+            };
+            while hull.len() >= start + 2
+                && (hull[hull.len() - 1] - hull[hull.len() - 2]).cross(p - hull[hull.len() - 2])
+                    <= 0.0
+            {
+                hull.pop();
+            }
+            hull.push(p);
+        }
+        // The last point is the first one of the other chain.
+        hull.pop();
+    }
+
+    *points = hull;
+}
+
+impl GeometryBuilder for TriangleRecorder {
+    fn begin_geometry(&mut self) {
+        self.vertices.clear();
+        self.triangles.clear();
+    }
+
+    fn end_geometry(&mut self) -> Count {
+        Count {
+            vertices: self.vertices.len() as u32,
+            indices: self.triangles.len() as u32 * 3,
+        }
+    }
+
+    fn add_triangle(&mut self, a: VertexId, b: VertexId, c: VertexId) {
+        self.triangles.push([a, b, c]);
+    }
+
+    fn abort_geometry(&mut self) {
+        self.vertices.clear();
+        self.triangles.clear();
+    }
+}
+
+impl FillGeometryBuilder for TriangleRecorder {
+    fn add_fill_vertex(
+        &mut self,
+        position: Point,
+        attributes: FillAttributes,
+    ) -> Result<VertexId, GeometryBuilderError> {
+        self.vertices.push((position, attributes.current_event));
+
+        Ok(VertexId::from_usize(self.vertices.len() - 1))
+    }
+}
+
+/// The topology of a fill tessellation, with the vertex positions and sources, which can
+/// produce vertex buffers with any vertex constructor.
+///
+/// The tessellation, which is the expensive part, is done once by `FillTessellator::plan`.
+/// `build` then only runs the vertex constructor on each vertex, which is cheap and can
+/// be done as many times as needed, for example each frame with different colors. The
+/// plan is immutable so it can be shared between threads.
+///
+/// The custom attributes of the path are not kept: `FillAttributes::interpolated_attributes`
+/// returns an empty slice when building the vertices.
+pub struct TessellationPlan {
+    events: EventQueue,
+    vertices: Vec<(Point, TessEventId)>,
+    indices: Vec<u32>,
+}
+
+impl TessellationPlan {
+    pub fn num_vertices(&self) -> usize {
+        self.vertices.len()
+    }
+
+    pub fn indices(&self) -> &[u32] {
+        &self.indices
+    }
+
+    /// Produces vertex buffers, calling the vertex constructor on each vertex.
+    pub fn build<OutputVertex>(
+        &self,
+        mut ctor: impl FillVertexConstructor<OutputVertex>,
+    ) -> VertexBuffers<OutputVertex, u32> {
+        let mut attrib_buffer = Vec::new();
+        let vertices = self
+            .vertices
+            .iter()
+            .map(|&(position, current_event)| {
+                ctor.new_vertex(
+                    position,
+                    FillAttributes {
+                        events: &self.events,
+                        current_event,
+                        attrib_buffer: &mut attrib_buffer,
+                        attrib_store: None,
+                    },
+                )
+            })
+            .collect();
+
+        VertexBuffers {
+            vertices,
+            indices: self.indices.clone(),
+        }
+    }
+}
+
 /// Extra vertex information from the `FillTessellator`, accessible when building vertices.
 pub struct FillAttributes<'l> {
     events: &'l EventQueue,
@@ -1845,6 +3280,22 @@ impl<'l> FillAttributes<'l> {
         None
     }
 
+    /// Calls the callback with the other end of each edge of the flattened path that
+    /// starts at this vertex.
+    ///
+    /// Edges that were split at intersections further down may report their original end.
+    pub(crate) fn for_each_edge_end(&self, callback: &mut dyn FnMut(Point)) {
+        let mut current = self.current_event;
+        while self.events.valid_id(current) {
+            let edge = &self.events.edge_data[current as usize];
+            if edge.is_edge {
+                callback(edge.to);
+            }
+
+            current = self.events.next_sibling_id(current);
+        }
+    }
+
     /// Fetch or interpolate the custom attribute values at this vertex.
     pub fn interpolated_attributes(&mut self) -> &[f32] {
         if self.attrib_store.is_none() {
@@ -1861,7 +3312,16 @@ impl<'l> FillAttributes<'l> {
 
         let num_attributes = store.num_attributes();
 
-        let first = sources.next().unwrap();
+        let first = match sources.next() {
+            Some(first) => first,
+            None => {
+                // Vertices without sources such as Steiner points.
+                for attribute in self.attrib_buffer.iter_mut() {
+                    *attribute = 0.0;
+                }
+                return self.attrib_buffer;
+            }
+        };
         let mut next = sources.next();
 
         // Fast path for the single-source-single-endpoint common case.
@@ -2036,9 +3496,6 @@ fn log_svg_preamble(_tess: &FillTessellator) {
     );
 }
 
-#[cfg(test)]
-use crate::geometry_builder::*;
-
 #[cfg(test)]
 fn eq(a: Point, b: Point) -> bool {
     (a.x - b.x).abs() < 0.00001 && (a.y - b.y).abs() < 0.00001
@@ -2406,3 +3863,15 @@ fn fill_vertex_source_03() {
         }
     }
 }
+
+#[test]
+fn warmup() {
+    let mut tess = FillTessellator::new();
+    tess.warmup();
+
+    assert!(tess.active.edges.capacity() > 0);
+    assert!(tess.edges_below.capacity() > 0);
+    assert!(tess.fill.spans.capacity() > 0);
+    assert!(tess.events.events.capacity() > 0);
+    assert!(tess.events.edge_data.capacity() > 0);
+}
@@ -2,7 +2,7 @@ use crate::extra::rust_logo::build_logo_path;
 use crate::geom::math::*;
 use crate::geometry_builder::*;
 use crate::path::builder::{Build, FlatPathBuilder, PathBuilder};
-use crate::path::{Path, PathSlice};
+use crate::path::{FillRule, Path, PathSlice};
 use crate::{FillAttributes, FillOptions, FillTessellator, TessellationError, VertexId};
 
 use std::env;
@@ -2182,3 +2182,1082 @@ fn issue_529() {
     // SVG path syntax:
     // "M 203.01 174.67 L 203.04 174.72 L 203 174.68 ZM 203 174.66 L 203.01 174.68 L 202.99 174.68 Z"
 }
+
+#[test]
+fn event_by_event() {
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to(point(5.0, 10.0));
+    builder.close();
+    let path = builder.build();
+
+    let mut tess = FillTessellator::new();
+    let options = FillOptions::default();
+
+    let mut expected: VertexBuffers<Point, u16> = VertexBuffers::new();
+    tess.tessellate(&path, &options, &mut simple_builder(&mut expected))
+        .unwrap();
+
+    let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+    {
+        let mut output = simple_builder(&mut buffers);
+        let mut fill = tess.begin(&options, &mut output);
+        for evt in path.iter() {
+            fill.event(evt);
+        }
+        fill.finish().unwrap();
+    }
+
+    assert_eq!(buffers.indices.len(), 3);
+    assert_eq!(buffers.vertices, expected.vertices);
+    assert_eq!(buffers.indices, expected.indices);
+}
+
+#[test]
+fn per_sub_path_tolerance() {
+    fn circle(center: Point) -> Path {
+        let mut builder = Path::builder();
+        builder.move_to(center + vector(10.0, 0.0));
+        builder.arc(
+            center,
+            vector(10.0, 10.0),
+            Angle::degrees(360.0),
+            Angle::zero(),
+        );
+        builder.close();
+        builder.build()
+    }
+
+    // Two identical circles far apart, the first one flattened with a much
+    // finer tolerance than the second one.
+    let fine = circle(point(0.0, 0.0));
+    let coarse = circle(point(100.0, 0.0));
+
+    let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+    {
+        let mut tess = FillTessellator::new();
+        let options = FillOptions::tolerance(0.1);
+        let mut output = simple_builder(&mut buffers);
+        let mut fill = tess.begin(&options, &mut output);
+
+        fill.set_tolerance(0.01);
+        for evt in fine.iter() {
+            fill.event(evt);
+        }
+
+        fill.set_tolerance(1.0);
+        for evt in coarse.iter() {
+            fill.event(evt);
+        }
+
+        fill.finish().unwrap();
+    }
+
+    let num_fine = buffers.vertices.iter().filter(|p| p.x < 50.0).count();
+    let num_coarse = buffers.vertices.iter().filter(|p| p.x >= 50.0).count();
+
+    assert!(num_coarse > 0);
+    assert!(num_fine > num_coarse);
+}
+
+#[test]
+fn deterministic_output() {
+    // The tessellator must produce exactly the same output when given
+    // the same input twice.
+    let mut path = Path::builder().with_svg();
+    build_logo_path(&mut path);
+    let path = path.build();
+
+    let tessellate = |tess: &mut FillTessellator| {
+        let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+        tess.tessellate(
+            &path,
+            &FillOptions::tolerance(0.05),
+            &mut simple_builder(&mut buffers),
+        )
+        .unwrap();
+        buffers
+    };
+
+    let mut tess = FillTessellator::new();
+    let first = tessellate(&mut tess);
+    // Reuse the same tessellator as well as a fresh one.
+    let second = tessellate(&mut tess);
+    let third = tessellate(&mut FillTessellator::new());
+
+    assert_eq!(first.indices, second.indices);
+    assert_eq!(first.indices, third.indices);
+    assert_eq!(first.vertices, second.vertices);
+    assert_eq!(first.vertices, third.vertices);
+}
+
+#[test]
+fn layered_output() {
+    // A square with a smaller square inside it, both with the same orientation,
+    // so that the inner square has a winding number of two.
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(0.0, 10.0));
+    builder.line_to(point(10.0, 10.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.close();
+    builder.move_to(point(2.0, 2.0));
+    builder.line_to(point(2.0, 8.0));
+    builder.line_to(point(8.0, 8.0));
+    builder.line_to(point(8.0, 2.0));
+    builder.close();
+    let path = builder.build();
+
+    let mut tess = FillTessellator::new();
+    let layers = tess
+        .tessellate_layered(
+            &path,
+            &FillOptions::tolerance(0.05).with_fill_rule(FillRule::NonZero),
+        )
+        .unwrap();
+
+    assert_eq!(layers.len(), 2);
+
+    let area = |buffers: &VertexBuffers<Point, u16>| {
+        let mut area = 0.0;
+        for triangle in buffers.indices.chunks(3) {
+            let a = buffers.vertices[triangle[0] as usize];
+            let b = buffers.vertices[triangle[1] as usize];
+            let c = buffers.vertices[triangle[2] as usize];
+            area += ((b - a).cross(c - a) * 0.5).abs();
+        }
+        area
+    };
+
+    let (outer_winding, ref outer) = layers[0];
+    let (inner_winding, ref inner) = layers[1];
+    assert_eq!(outer_winding, 1);
+    assert_eq!(inner_winding, 2);
+    assert!((area(outer) - 64.0).abs() < 0.001);
+    assert!((area(inner) - 36.0).abs() < 0.001);
+}
+
+#[test]
+fn boundary_edge_events() {
+    use crate::BoundaryEdge;
+
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 1.0));
+    builder.line_to(point(3.0, 8.0));
+    builder.close();
+    let path = builder.build();
+
+    let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+    let mut boundary: Vec<BoundaryEdge> = Vec::new();
+    FillTessellator::new()
+        .tessellate_with_boundary(
+            &path,
+            &FillOptions::default(),
+            &mut simple_builder(&mut buffers),
+            &mut boundary,
+        )
+        .unwrap();
+
+    assert_eq!(boundary.len(), 3);
+
+    // Events are: begin (0), line (1), line (2), end (3).
+    for edge in &boundary {
+        let from = buffers.vertices[edge.from.to_usize()];
+        let to = buffers.vertices[edge.to.to_usize()];
+        let segment = match edge.event {
+            1 => (point(0.0, 0.0), point(10.0, 1.0)),
+            2 => (point(10.0, 1.0), point(3.0, 8.0)),
+            3 => (point(3.0, 8.0), point(0.0, 0.0)),
+            _ => panic!("unexpected event {:?}", edge.event),
+        };
+        assert!((from, to) == segment || (to, from) == segment);
+    }
+
+    let mut events: Vec<usize> = boundary.iter().map(|edge| edge.event).collect();
+    events.sort();
+    assert_eq!(events, vec![1, 2, 3]);
+}
+
+#[test]
+fn normalized_coordinates() {
+    // A self-intersecting star.
+    let star = [
+        point(0.0, -50.0),
+        point(30.0, 40.0),
+        point(-48.0, -15.0),
+        point(48.0, -15.0),
+        point(-30.0, 40.0),
+    ];
+
+    let tessellate = |offset: Vector| {
+        let mut builder = Path::builder();
+        builder.polygon(&star.iter().map(|p| *p + offset).collect::<Vec<_>>());
+        let path = builder.build();
+
+        let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+        FillTessellator::new()
+            .tessellate(
+                &path,
+                &FillOptions::tolerance(0.05).with_normalized_coordinates(true),
+                &mut simple_builder(&mut buffers),
+            )
+            .unwrap();
+
+        for v in &mut buffers.vertices {
+            *v = *v - offset;
+        }
+
+        buffers
+    };
+
+    let near_origin = tessellate(vector(0.0, 0.0));
+    let far_away = tessellate(vector(1.0e7, 1.0e7));
+
+    assert_eq!(near_origin.indices.len(), far_away.indices.len());
+    assert_eq!(near_origin.vertices.len(), far_away.vertices.len());
+    for (a, b) in near_origin.vertices.iter().zip(far_away.vertices.iter()) {
+        // The input coordinates are exact but the intersections far away from
+        // the origin can't be represented as precisely.
+        assert!((*a - *b).length() < 1.0);
+    }
+}
+
+#[test]
+fn constrained_delaunay() {
+    use crate::cdt::in_circle;
+    use std::collections::HashMap;
+
+    // An elongated polygon with a notch, which produces thin triangles with the
+    // regular tessellation.
+    let mut polygon = Vec::new();
+    for i in 0..24 {
+        let angle = i as f32 * std::f32::consts::PI * 2.0 / 24.0;
+        let radius = if i == 6 { 2.0 } else { 5.0 };
+        polygon.push(point(angle.cos() * 20.0, angle.sin() * radius));
+    }
+
+    let mut builder = Path::builder();
+    builder.move_to(polygon[0]);
+    for p in &polygon[1..] {
+        builder.line_to(*p);
+    }
+    builder.close();
+    let path = builder.build();
+
+    let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+    FillTessellator::new()
+        .tessellate_cdt(
+            &path,
+            &FillOptions::tolerance(0.05),
+            &mut simple_builder(&mut buffers),
+        )
+        .unwrap();
+
+    let vertex = |p: Point| buffers.vertices.iter().position(|v| *v == p).unwrap() as u16;
+    let key = |a: u16, b: u16| if a < b { (a, b) } else { (b, a) };
+
+    // Opposite vertices of each edge.
+    let mut edges: HashMap<(u16, u16), Vec<u16>> = HashMap::new();
+    for tri in buffers.indices.chunks(3) {
+        for i in 0..3 {
+            let (a, b, c) = (tri[i], tri[(i + 1) % 3], tri[(i + 2) % 3]);
+            edges.entry(key(a, b)).or_insert_with(Vec::new).push(c);
+        }
+    }
+
+    // All edges of the path are triangle edges.
+    for i in 0..polygon.len() {
+        let a = vertex(polygon[i]);
+        let b = vertex(polygon[(i + 1) % polygon.len()]);
+        assert_eq!(edges[&key(a, b)].len(), 1);
+    }
+
+    // Interior edges are locally Delaunay.
+    let position = |id: u16| buffers.vertices[id as usize];
+    let mut num_interior_edges = 0;
+    for (&(a, b), opposite) in &edges {
+        if opposite.len() != 2 {
+            continue;
+        }
+        num_interior_edges += 1;
+        let (pa, pb, pc, pd) = (
+            position(a),
+            position(b),
+            position(opposite[0]),
+            position(opposite[1]),
+        );
+        let (pa, pb) = if (pb - pa).cross(pc - pa) > 0.0 {
+            (pa, pb)
+        } else {
+            (pb, pa)
+        };
+        assert!(in_circle(pa, pb, pc, pd) <= 0.001);
+    }
+
+    assert_eq!(num_interior_edges, polygon.len() - 3);
+}
+
+// Tessellates the path with `FillTessellator::begin`, one event at a time.
+#[cfg(test)]
+fn fill_with_builder(
+    path: &Path,
+    options: &FillOptions,
+) -> Result<VertexBuffers<Point, u16>, TessellationError> {
+    let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+    {
+        let mut tess = FillTessellator::new();
+        let mut output = simple_builder(&mut buffers);
+        let mut fill = tess.begin(options, &mut output);
+        for evt in path.iter() {
+            fill.event(evt);
+        }
+        fill.finish()?;
+    }
+
+    Ok(buffers)
+}
+
+#[test]
+fn pixel_snapping() {
+    let mut builder = Path::builder();
+    // A rectangle that is almost aligned to the pixel grid.
+    builder.move_to(point(0.98, 2.01));
+    builder.line_to(point(10.03, 2.01));
+    builder.line_to(point(10.03, 5.7));
+    builder.line_to(point(0.98, 5.7));
+    builder.close();
+    // A triangle with diagonal edges.
+    builder.move_to(point(20.5, 0.3));
+    builder.line_to(point(30.2, 10.6));
+    builder.line_to(point(15.4, 8.7));
+    builder.close();
+    let path = builder.build();
+
+    let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+    FillTessellator::new()
+        .tessellate(
+            &path,
+            &FillOptions::tolerance(0.05).with_pixel_snap(true),
+            &mut simple_builder(&mut buffers),
+        )
+        .unwrap();
+
+    let is_integer = |v: f32| v == v.round();
+    let mut num_rect_vertices = 0;
+    for v in &buffers.vertices {
+        if v.x < 15.0 {
+            num_rect_vertices += 1;
+            assert!(is_integer(v.x) && is_integer(v.y), "{:?}", v);
+        }
+    }
+    assert_eq!(num_rect_vertices, 4);
+    assert!(buffers.vertices.contains(&point(20.5, 0.3)));
+    assert!(buffers.vertices.contains(&point(30.2, 10.6)));
+    assert!(buffers.vertices.contains(&point(15.4, 8.7)));
+
+    // The fill builder snaps as well.
+    let streamed =
+        fill_with_builder(&path, &FillOptions::tolerance(0.05).with_pixel_snap(true)).unwrap();
+    assert_eq!(streamed.vertices, buffers.vertices);
+    assert_eq!(streamed.indices, buffers.indices);
+
+    // With a transform, snapping happens in the pixel space.
+    let options = FillOptions::tolerance(0.05)
+        .with_pixel_snap(true)
+        .with_pixel_transform(Transform::create_scale(2.0, 2.0));
+    let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+    FillTessellator::new()
+        .tessellate(&path, &options, &mut simple_builder(&mut buffers))
+        .unwrap();
+    let streamed = fill_with_builder(&path, &options).unwrap();
+    assert_eq!(streamed.vertices, buffers.vertices);
+    assert_eq!(streamed.indices, buffers.indices);
+
+    for v in &buffers.vertices {
+        if v.x < 15.0 {
+            assert!(is_integer(v.x * 2.0) && is_integer(v.y * 2.0), "{:?}", v);
+        }
+    }
+    assert!(
+        buffers.vertices.contains(&point(5.5, 2.0)) || buffers.vertices.contains(&point(1.0, 2.0))
+    );
+}
+
+#[test]
+fn tessellate_up_to_sweep_line() {
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to(point(10.0, 10.0));
+    builder.line_to(point(0.0, 10.0));
+    builder.close();
+    let path = builder.build();
+
+    let area = |sweep_y: f32| {
+        let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+        FillTessellator::new()
+            .tessellate_up_to(
+                &path,
+                sweep_y,
+                &FillOptions::tolerance(0.05),
+                &mut simple_builder(&mut buffers),
+            )
+            .unwrap();
+
+        let mut area = 0.0;
+        for triangle in buffers.indices.chunks(3) {
+            let a = buffers.vertices[triangle[0] as usize];
+            let b = buffers.vertices[triangle[1] as usize];
+            let c = buffers.vertices[triangle[2] as usize];
+            assert!(a.y <= sweep_y && b.y <= sweep_y && c.y <= sweep_y);
+            area += ((b - a).cross(c - a) * 0.5).abs();
+        }
+        area
+    };
+
+    assert!((area(5.0) - 50.0).abs() < 0.001);
+    assert!((area(2.5) - 25.0).abs() < 0.001);
+    assert!((area(20.0) - 100.0).abs() < 0.001);
+    assert_eq!(area(-1.0), 0.0);
+}
+
+#[test]
+fn both_fill_rules() {
+    // Two overlapping squares with the same orientation and a nested square
+    // with the opposite orientation.
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to(point(10.0, 10.0));
+    builder.line_to(point(0.0, 10.0));
+    builder.close();
+    builder.move_to(point(5.0, 5.0));
+    builder.line_to(point(15.0, 5.0));
+    builder.line_to(point(15.0, 15.0));
+    builder.line_to(point(5.0, 15.0));
+    builder.close();
+    builder.move_to(point(1.0, 1.0));
+    builder.line_to(point(1.0, 3.0));
+    builder.line_to(point(3.0, 3.0));
+    builder.line_to(point(3.0, 1.0));
+    builder.close();
+    let path = builder.build();
+
+    let mut tess = FillTessellator::new();
+    let (even_odd, non_zero) = tess
+        .tessellate_both_rules(&path, &FillOptions::tolerance(0.05))
+        .unwrap();
+
+    let tessellate = |rule: FillRule| {
+        let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+        FillTessellator::new()
+            .tessellate(
+                &path,
+                &FillOptions::tolerance(0.05).with_fill_rule(rule),
+                &mut simple_builder(&mut buffers),
+            )
+            .unwrap();
+        buffers
+    };
+
+    let covers = |buffers: &VertexBuffers<Point, u16>, p: Point| {
+        buffers.indices.chunks(3).any(|triangle| {
+            let a = buffers.vertices[triangle[0] as usize];
+            let b = buffers.vertices[triangle[1] as usize];
+            let c = buffers.vertices[triangle[2] as usize];
+            let d1 = (b - a).cross(p - a);
+            let d2 = (c - b).cross(p - b);
+            let d3 = (a - c).cross(p - c);
+            (d1 >= 0.0 && d2 >= 0.0 && d3 >= 0.0) || (d1 <= 0.0 && d2 <= 0.0 && d3 <= 0.0)
+        })
+    };
+
+    let expected_even_odd = tessellate(FillRule::EvenOdd);
+    let expected_non_zero = tessellate(FillRule::NonZero);
+
+    for i in 0..32 {
+        for j in 0..32 {
+            let p = point(i as f32 * 0.5 + 0.13, j as f32 * 0.5 + 0.17);
+            assert_eq!(covers(&even_odd, p), covers(&expected_even_odd, p));
+            assert_eq!(covers(&non_zero, p), covers(&expected_non_zero, p));
+        }
+    }
+
+    assert_eq!(non_zero.indices.len(), expected_non_zero.indices.len());
+    assert_eq!(even_odd.indices.len(), expected_even_odd.indices.len());
+}
+
+#[test]
+fn tessellate_with_scratch() {
+    use crate::FillScratch;
+
+    let mut builder = Path::builder().with_svg();
+    build_logo_path(&mut builder);
+    let path = builder.build();
+
+    let options = FillOptions::tolerance(0.05);
+
+    let mut expected: VertexBuffers<Point, u16> = VertexBuffers::new();
+    FillTessellator::new()
+        .tessellate_path(&path, &options, &mut simple_builder(&mut expected))
+        .unwrap();
+
+    // Several tessellators sharing the same scratch object.
+    let mut scratch = FillScratch::new();
+    for _ in 0..3 {
+        let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+        FillTessellator::new()
+            .tessellate_path_with_scratch(
+                &path,
+                &options,
+                &mut scratch,
+                &mut simple_builder(&mut buffers),
+            )
+            .unwrap();
+
+        assert_eq!(buffers.vertices, expected.vertices);
+        assert_eq!(buffers.indices, expected.indices);
+    }
+}
+
+#[test]
+fn grouped_by_sub_path() {
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to(point(5.0, 10.0));
+    builder.close();
+    builder.move_to(point(20.0, 5.0));
+    builder.line_to(point(30.0, 5.0));
+    builder.line_to(point(25.0, 15.0));
+    builder.close();
+    let path = builder.build();
+
+    let (buffers, groups) = FillTessellator::new()
+        .tessellate_grouped_by_sub_path(&path, &FillOptions::default())
+        .unwrap();
+
+    assert_eq!(groups.len(), 2);
+    assert_eq!(groups[0], (0, 0..3));
+    assert_eq!(groups[1], (1, 3..6));
+    assert_eq!(buffers.indices.len(), 6);
+
+    for &(sub_path, ref range) in &groups {
+        for &idx in &buffers.indices[range.clone()] {
+            let position = buffers.vertices[idx as usize];
+            assert_eq!(position.x < 15.0, sub_path == 0);
+        }
+    }
+}
+
+#[test]
+fn boundary_tangents() {
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to(point(10.0, 10.0));
+    builder.line_to(point(0.0, 10.0));
+    builder.close();
+    let path = builder.build();
+
+    let buffers = FillTessellator::new()
+        .tessellate_with_boundary_tangents(path.iter(), &FillOptions::default())
+        .unwrap();
+
+    assert_eq!(buffers.vertices.len(), 4);
+    let center = point(5.0, 5.0);
+    for vertex in &buffers.vertices {
+        assert!(vertex.is_boundary);
+
+        // At the corners, the tangent bisects the two adjacent edges, which are
+        // axis-aligned.
+        let t = vertex.tangent;
+        assert!((t.length() - 1.0).abs() < 0.0001);
+        assert!((t.x.abs() - t.y.abs()).abs() < 0.0001);
+
+        // The filled area is on the left of the tangent.
+        let normal = vector(-t.y, t.x);
+        assert!(normal.dot(center - vertex.position) > 0.0);
+    }
+}
+
+#[test]
+fn no_shared_vertices() {
+    let mut path = Path::builder().with_svg();
+    build_logo_path(&mut path);
+    let path = path.build();
+
+    let mut shared: VertexBuffers<Point, u16> = VertexBuffers::new();
+    FillTessellator::new()
+        .tessellate_path(
+            &path,
+            &FillOptions::default(),
+            &mut simple_builder(&mut shared),
+        )
+        .unwrap();
+
+    let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+    FillTessellator::new()
+        .tessellate_path(
+            &path,
+            &FillOptions::default().with_no_shared_vertices(true),
+            &mut simple_builder(&mut buffers),
+        )
+        .unwrap();
+
+    assert_eq!(buffers.indices.len(), shared.indices.len());
+    assert_eq!(buffers.vertices.len(), buffers.indices.len());
+    assert!(buffers.vertices.len() > shared.vertices.len());
+
+    let mut used = vec![false; buffers.vertices.len()];
+    for &idx in &buffers.indices {
+        assert!(!used[idx as usize]);
+        used[idx as usize] = true;
+    }
+
+    // The triangles are the same, only the vertices are duplicated.
+    for (a, b) in buffers.indices.iter().zip(shared.indices.iter()) {
+        assert_eq!(buffers.vertices[*a as usize], shared.vertices[*b as usize]);
+    }
+}
+
+#[test]
+fn star_fill_rules() {
+    let area = |path: &Path, rule: FillRule| {
+        let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+        FillTessellator::new()
+            .tessellate(
+                path,
+                &FillOptions::tolerance(0.05).with_fill_rule(rule),
+                &mut simple_builder(&mut buffers),
+            )
+            .unwrap();
+
+        buffers
+            .indices
+            .chunks(3)
+            .map(|triangle| {
+                let a = buffers.vertices[triangle[0] as usize];
+                let b = buffers.vertices[triangle[1] as usize];
+                let c = buffers.vertices[triangle[2] as usize];
+                (b - a).cross(c - a).abs() * 0.5
+            })
+            .sum::<f32>()
+    };
+
+    // A pentagram: the inner pentagon has a winding number of two.
+    let radius = 10.0;
+    let angle = |i: u32| (i as f32 * 144.0 + 90.0).to_radians();
+    let mut builder = Path::builder();
+    builder.move_to(point(angle(0).cos() * radius, angle(0).sin() * radius));
+    for i in 1..5 {
+        builder.line_to(point(angle(i).cos() * radius, angle(i).sin() * radius));
+    }
+    builder.close();
+    let star = builder.build();
+
+    let inner_radius = radius * 72.0f32.to_radians().cos() / 36.0f32.to_radians().cos();
+    let pentagon_area = 2.5 * inner_radius * inner_radius * 72.0f32.to_radians().sin();
+
+    let even_odd = area(&star, FillRule::EvenOdd);
+    let non_zero = area(&star, FillRule::NonZero);
+    assert!(even_odd > 0.0);
+    assert!(
+        (non_zero - even_odd - pentagon_area).abs() < 0.01,
+        "{} {} {}",
+        non_zero,
+        even_odd,
+        pentagon_area
+    );
+
+    // The lobes of a figure-eight have opposite winding numbers and don't overlap, so
+    // both are filled with either rule.
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 10.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to(point(0.0, 10.0));
+    builder.close();
+    let figure_eight = builder.build();
+
+    assert!((area(&figure_eight, FillRule::EvenOdd) - 50.0).abs() < 0.01);
+    assert!((area(&figure_eight, FillRule::NonZero) - 50.0).abs() < 0.01);
+}
+
+#[test]
+fn epsilon_welds_near_coincident_points() {
+    // Two of the vertices of the bottom edge are almost at the same position.
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(5.0, 0.0));
+    builder.line_to(point(5.0005, 0.0002));
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to(point(10.0, 10.0));
+    builder.line_to(point(0.0, 10.0));
+    builder.close();
+    let path = builder.build();
+
+    let num_vertices = |options: &FillOptions| {
+        let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+        FillTessellator::new()
+            .tessellate_path(&path, options, &mut simple_builder(&mut buffers))
+            .unwrap();
+
+        // The fill builder welds the points as well.
+        let streamed = fill_with_builder(&path, options).unwrap();
+        assert_eq!(streamed.vertices, buffers.vertices);
+        assert_eq!(streamed.indices, buffers.indices);
+
+        buffers.vertices.len()
+    };
+
+    assert_eq!(num_vertices(&FillOptions::default()), 6);
+    assert_eq!(num_vertices(&FillOptions::epsilon(0.0001)), 6);
+    assert_eq!(num_vertices(&FillOptions::epsilon(0.01)), 5);
+    assert_eq!(num_vertices(&FillOptions::non_zero().with_epsilon(0.01)), 5);
+}
+
+#[test]
+fn steiner_points() {
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to(point(10.0, 10.0));
+    builder.line_to(point(0.0, 10.0));
+    builder.close();
+    let path = builder.build();
+
+    let tessellate = |points: &[Point], options: &FillOptions| {
+        let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+        FillTessellator::new()
+            .tessellate_with_steiner_points(
+                &path,
+                points,
+                options,
+                &mut simple_builder(&mut buffers),
+            )
+            .unwrap();
+        buffers
+    };
+
+    let area = |buffers: &VertexBuffers<Point, u16>| -> f32 {
+        buffers
+            .indices
+            .chunks(3)
+            .map(|tri| {
+                let a = buffers.vertices[tri[0] as usize];
+                let b = buffers.vertices[tri[1] as usize];
+                let c = buffers.vertices[tri[2] as usize];
+                (b - a).cross(c - a).abs() * 0.5
+            })
+            .sum()
+    };
+
+    // The center of the square, on the diagonal of the two triangles.
+    let center = point(5.0, 5.0);
+    let buffers = tessellate(&[center], &FillOptions::default());
+    assert_eq!(buffers.vertices.len(), 5);
+    assert!(buffers.vertices.contains(&center));
+    assert_eq!(buffers.indices.len(), 4 * 3);
+    assert_eq!(area(&buffers), 100.0);
+
+    // Points outside of the shape or at existing vertices are ignored.
+    let buffers = tessellate(
+        &[point(2.0, 7.0), point(20.0, 5.0), point(10.0, 10.0)],
+        &FillOptions::default(),
+    );
+    assert_eq!(buffers.vertices.len(), 5);
+    assert!(buffers.vertices.contains(&point(2.0, 7.0)));
+    assert_eq!(area(&buffers), 100.0);
+
+    let buffers = tessellate(
+        &[center],
+        &FillOptions::default().with_no_shared_vertices(true),
+    );
+    assert_eq!(buffers.vertices.len(), 4 * 3);
+    assert!(buffers.vertices.contains(&center));
+}
+
+#[test]
+fn tessellation_plan() {
+    use crate::geometry_builder::Positions;
+
+    let mut builder = Path::builder().with_svg();
+    build_logo_path(&mut builder);
+    let path = builder.build();
+
+    let options = FillOptions::tolerance(0.05);
+    let plan = FillTessellator::new().plan(&path, &options).unwrap();
+
+    let red = plan.build(|position: Point, _: FillAttributes| (position, [1.0, 0.0, 0.0]));
+    let blue = plan.build(|position: Point, _: FillAttributes| (position, [0.0, 0.0, 1.0]));
+
+    assert_eq!(red.indices, blue.indices);
+    assert_eq!(red.vertices.len(), plan.num_vertices());
+    for (r, b) in red.vertices.iter().zip(blue.vertices.iter()) {
+        assert_eq!(r.0, b.0);
+        assert!(r.1 != b.1);
+    }
+
+    // Same geometry as a direct tessellation.
+    let mut expected: VertexBuffers<Point, u32> = VertexBuffers::new();
+    FillTessellator::new()
+        .tessellate_path(
+            &path,
+            &options,
+            &mut BuffersBuilder::new(&mut expected, Positions),
+        )
+        .unwrap();
+    let positions = plan.build(Positions);
+    assert_eq!(positions.vertices, expected.vertices);
+    assert_eq!(positions.indices, expected.indices);
+
+    // The vertex sources are available.
+    let sources = plan.build(|_: Point, attributes: FillAttributes| attributes.sources().count());
+    assert!(sources.vertices.iter().all(|&count| count > 0));
+}
+
+#[test]
+fn open_subpath_policy() {
+    use crate::OpenSubpathPolicy;
+
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to(point(10.0, 10.0));
+    builder.line_to(point(0.0, 10.0));
+    builder.close();
+    // An open triangle.
+    builder.move_to(point(20.0, 0.0));
+    builder.line_to(point(30.0, 0.0));
+    builder.line_to(point(20.0, 10.0));
+    let path = builder.build();
+
+    let area = |policy| -> Result<f32, TessellationError> {
+        let options = FillOptions::open_subpath_policy(policy);
+        let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+        let result = FillTessellator::new().tessellate_path(
+            &path,
+            &options,
+            &mut simple_builder(&mut buffers),
+        );
+
+        // The fill builder applies the policy as well.
+        let streamed = fill_with_builder(&path, &options);
+        if let Err(e) = result {
+            assert_eq!(streamed.err(), Some(e.clone()));
+            return Err(e);
+        }
+        let streamed = streamed.unwrap();
+        assert_eq!(streamed.vertices, buffers.vertices);
+        assert_eq!(streamed.indices, buffers.indices);
+
+        Ok(buffers
+            .indices
+            .chunks(3)
+            .map(|tri| {
+                let a = buffers.vertices[tri[0] as usize];
+                let b = buffers.vertices[tri[1] as usize];
+                let c = buffers.vertices[tri[2] as usize];
+                (b - a).cross(c - a).abs() * 0.5
+            })
+            .sum())
+    };
+
+    assert_eq!(area(OpenSubpathPolicy::Close), Ok(150.0));
+    assert_eq!(area(OpenSubpathPolicy::Ignore), Ok(100.0));
+    assert_eq!(
+        area(OpenSubpathPolicy::Error),
+        Err(TessellationError::OpenSubpath)
+    );
+}
+
+#[test]
+fn conservative_fill() {
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to(point(10.0, 10.0));
+    builder.close();
+    let path = builder.build();
+
+    let tessellate = |options: &FillOptions| {
+        let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+        FillTessellator::new()
+            .tessellate_path(&path, options, &mut simple_builder(&mut buffers))
+            .unwrap();
+        buffers
+    };
+
+    let covers = |buffers: &VertexBuffers<Point, u16>, p: Point| {
+        buffers.indices.chunks(3).any(|tri| {
+            let a = buffers.vertices[tri[0] as usize];
+            let b = buffers.vertices[tri[1] as usize];
+            let c = buffers.vertices[tri[2] as usize];
+            let d1 = (b - a).cross(p - a);
+            let d2 = (c - b).cross(p - b);
+            let d3 = (a - c).cross(p - c);
+            (d1 >= 0.0 && d2 >= 0.0 && d3 >= 0.0) || (d1 <= 0.0 && d2 <= 0.0 && d3 <= 0.0)
+        })
+    };
+
+    let normal = tessellate(&FillOptions::default());
+    let conservative = tessellate(&FillOptions::conservative(1.0));
+
+    // The boundary is pushed out by half a pixel along each axis.
+    let mut min = conservative.vertices[0];
+    let mut max = conservative.vertices[0];
+    for v in &conservative.vertices {
+        min = min.min(*v);
+        max = max.max(*v);
+    }
+    assert_eq!(min, point(-0.5, -0.5));
+    assert_eq!(max, point(10.5, 10.5));
+
+    let inside = [
+        point(5.0, 2.0),
+        point(5.0, -0.4),
+        point(10.4, 5.0),
+        point(10.4, 10.4),
+        point(-0.4, -0.4),
+        // Half a pixel away along both axes from the diagonal edge.
+        point(4.6, 5.4),
+    ];
+    let outside = [
+        point(5.0, -0.6),
+        point(10.6, 5.0),
+        point(10.6, 10.4),
+        point(-0.6, 0.0),
+        point(4.4, 5.6),
+    ];
+    for &p in &inside {
+        assert!(covers(&conservative, p), "{:?}", p);
+    }
+    for &p in &outside {
+        assert!(!covers(&conservative, p), "{:?}", p);
+    }
+    assert!(covers(&normal, point(5.0, 2.0)));
+    assert!(!covers(&normal, point(5.0, -0.4)));
+    assert!(!covers(&normal, point(4.6, 5.4)));
+
+    // The output is the same every time.
+    for _ in 0..10 {
+        let again = tessellate(&FillOptions::conservative(1.0));
+        assert_eq!(again.vertices, conservative.vertices);
+        assert_eq!(again.indices, conservative.indices);
+    }
+
+    let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+    assert_eq!(
+        FillTessellator::new().tessellate_path(
+            &path,
+            &FillOptions::conservative(0.0),
+            &mut simple_builder(&mut buffers)
+        ),
+        Err(TessellationError::UnsupportedParamater)
+    );
+}
+
+#[test]
+fn constrained_delaunay_is_deterministic() {
+    // Co-circular points, for which several triangulations are valid.
+    let mut builder = Path::builder();
+    for i in 0..16 {
+        let angle = i as f32 * std::f32::consts::PI * 2.0 / 16.0;
+        let p = point(angle.cos() * 10.0, angle.sin() * 10.0);
+        if i == 0 {
+            builder.move_to(p);
+        } else {
+            builder.line_to(p);
+        }
+    }
+    builder.close();
+    let path = builder.build();
+
+    let tessellate = || {
+        let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+        FillTessellator::new()
+            .tessellate_cdt(
+                &path,
+                &FillOptions::default(),
+                &mut simple_builder(&mut buffers),
+            )
+            .unwrap();
+        buffers
+    };
+
+    let first = tessellate();
+    for _ in 0..10 {
+        let again = tessellate();
+        assert_eq!(again.vertices, first.vertices);
+        assert_eq!(again.indices, first.indices);
+    }
+}
+
+#[test]
+fn constrained_delaunay_interior_edges() {
+    // With the non-zero fill rule the edges of the inner triangle are inside of the
+    // filled area. The edge between (0, 0) and (10, 0) is not Delaunay but it is a
+    // constraint.
+    let inner = [point(0.0, 0.0), point(10.0, 0.0), point(5.0, 1.0)];
+
+    let mut builder = Path::builder();
+    builder.move_to(point(-1.0, 0.0));
+    builder.line_to(point(5.0, -1.0));
+    builder.line_to(point(11.0, 0.0));
+    builder.line_to(point(5.0, 3.0));
+    builder.close();
+    builder.move_to(inner[0]);
+    builder.line_to(inner[1]);
+    builder.line_to(inner[2]);
+    builder.close();
+    let path = builder.build();
+
+    let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+    FillTessellator::new()
+        .tessellate_cdt(
+            &path,
+            &FillOptions::tolerance(0.05).with_fill_rule(FillRule::NonZero),
+            &mut simple_builder(&mut buffers),
+        )
+        .unwrap();
+
+    let vertex = |p: Point| buffers.vertices.iter().position(|v| *v == p).unwrap() as u16;
+    for i in 0..inner.len() {
+        let a = vertex(inner[i]);
+        let b = vertex(inner[(i + 1) % inner.len()]);
+        let num_triangles = buffers
+            .indices
+            .chunks(3)
+            .filter(|tri| tri.contains(&a) && tri.contains(&b))
+            .count();
+        assert_eq!(num_triangles, 2);
+    }
+}
+
+#[test]
+fn max_subdivision_depth() {
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.cubic_bezier_to(point(0.0, 1.0e4), point(1.0e4, 1.0e4), point(1.0e4, 0.0));
+    builder.close();
+    let path = builder.build();
+
+    let num_vertices = |options: &FillOptions| {
+        let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+        FillTessellator::new()
+            .tessellate_path(&path, options, &mut simple_builder(&mut buffers))
+            .unwrap();
+        buffers.vertices.len()
+    };
+
+    // Without the guard, the curve is flattened into about a hundred segments.
+    assert!(num_vertices(&FillOptions::tolerance(1.0).with_max_subdivision_depth(20)) > 64);
+
+    // With it, into at most 16 segments.
+    let n = num_vertices(&FillOptions::tolerance(1.0).with_max_subdivision_depth(4));
+    assert!(n > 2);
+    assert!(n <= 17, "{}", n);
+}
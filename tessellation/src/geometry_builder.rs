@@ -28,6 +28,8 @@
 //!   [`VertexBuffers`](struct.VertexBuffers.html) and implements the various gemoetry
 //!   builder traits. It takes care of filling the buffers while producing vertices is
 //!   delegated to a vertex constructor.
+//! * The struct [`StripBuffersBuilder`](struct.StripBuffersBuilder.html) which is similar
+//!   to `BuffersBuilder` but writes indices for triangle strips instead of triangle lists.
 //! * The traits [`FillVertexConstructor`](trait.FillVertexConstructor.html),
 //!   [`StrokeVertexConstructor`](trait.StrokeVertexConstructor.html) and
 //!   [`BasicVertexConstructor`](trait.BasicVertexConstructor.html), used by
@@ -228,7 +230,7 @@
 //! }
 //! ```
 
-use crate::math::Point;
+use crate::math::{point, Point, Rect, Transform};
 use crate::{FillAttributes, Index, StrokeAttributes, VertexId};
 
 use std;
@@ -350,6 +352,255 @@ impl<OutputVertex, OutputIndex> VertexBuffers<OutputVertex, OutputIndex> {
     }
 }
 
+impl<OutputVertex, OutputIndex> VertexBuffers<OutputVertex, OutputIndex>
+where
+    OutputVertex: Clone,
+    OutputIndex: Copy + From<VertexId> + Add<Output = OutputIndex> + MaxIndex,
+{
+    /// Appends the vertices and indices of another buffer, offsetting its indices so that
+    /// they keep referring to the same vertices.
+    ///
+    /// This is useful to combine geometry tessellated separately, for example on several
+    /// threads, into a single draw call.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the combined number of vertices can't be addressed by the index type.
+    pub fn extend_from(&mut self, other: &VertexBuffers<OutputVertex, OutputIndex>) {
+        let offset = self.vertices.len();
+        assert!(
+            offset + other.vertices.len() <= OutputIndex::MAX,
+            "VertexBuffers::extend_from: {} vertices can't be addressed by the index type",
+            offset + other.vertices.len(),
+        );
+
+        let offset = OutputIndex::from(VertexId::from_usize(offset));
+        self.vertices.extend_from_slice(&other.vertices);
+        self.indices
+            .extend(other.indices.iter().map(|&idx| idx + offset));
+    }
+}
+
+impl<OutputIndex> VertexBuffers<Point, OutputIndex>
+where
+    OutputIndex: Copy + Into<u32>,
+{
+    /// Merges adjacent triangles into larger convex polygons.
+    ///
+    /// Interior edges of the triangulation are removed whenever the two polygons on each
+    /// side of them can be merged into a convex polygon. Degenerate triangles are kept as
+    /// they are. This reduces the number of primitives for consumers that can render
+    /// convex polygons directly.
+    pub fn merge_coplanar_triangles(&self) -> PolygonBuffers<Point, OutputIndex> {
+        let position = |idx: OutputIndex| self.vertices[idx.into() as usize];
+        let cross = |a: Point, b: Point, c: Point| (b - a).cross(c - b);
+        let key = |a: u32, b: u32| if a < b { (a, b) } else { (b, a) };
+
+        // Counter-clockwise polygons, or None if the polygon was merged into another one.
+        let mut polygons: Vec<Option<Vec<OutputIndex>>> = Vec::new();
+        let mut edges: std::collections::HashMap<(u32, u32), Vec<usize>> =
+            std::collections::HashMap::new();
+        for triangle in self.indices.chunks(3) {
+            if triangle.len() < 3 {
+                break;
+            }
+            let (a, b, c) = (triangle[0], triangle[1], triangle[2]);
+            let area = cross(position(a), position(b), position(c));
+            let polygon = if area < 0.0 {
+                vec![a, c, b]
+            } else {
+                vec![a, b, c]
+            };
+            if area != 0.0 {
+                for i in 0..3 {
+                    let edge = key(polygon[i].into(), polygon[(i + 1) % 3].into());
+                    edges
+                        .entry(edge)
+                        .or_insert_with(Vec::new)
+                        .push(polygons.len());
+                }
+            }
+            polygons.push(Some(polygon));
+        }
+
+        let mut interior_edges: Vec<(u32, u32)> = edges
+            .iter()
+            .filter(|(_, polygons)| polygons.len() == 2)
+            .map(|(edge, _)| *edge)
+            .collect();
+        interior_edges.sort();
+
+        let is_convex = |polygon: &[OutputIndex]| {
+            let n = polygon.len();
+            (0..n).all(|i| {
+                let a = position(polygon[i]);
+                let b = position(polygon[(i + 1) % n]);
+                let c = position(polygon[(i + 2) % n]);
+                let turn = cross(a, b, c);
+                let scale = (b - a).length() * (c - b).length();
+                turn >= -scale * 0.00001 && (turn > scale * 0.00001 || (b - a).dot(c - b) > 0.0)
+            })
+        };
+
+        for edge in interior_edges {
+            let (p, q) = {
+                let sides = &edges[&edge];
+                (sides[0], sides[1])
+            };
+            if p == q {
+                continue;
+            }
+
+            let (poly_p, poly_q) = match (&polygons[p], &polygons[q]) {
+                (Some(poly_p), Some(poly_q)) => (poly_p, poly_q),
+                _ => {
+                    continue;
+                }
+            };
+
+            // Find the shared edge as a -> b in p, which is b -> a in q.
+            let np = poly_p.len();
+            let nq = poly_q.len();
+            let start_p = match (0..np)
+                .find(|&i| key(poly_p[i].into(), poly_p[(i + 1) % np].into()) == edge)
+            {
+                Some(i) => i,
+                None => {
+                    continue;
+                }
+            };
+            let a: u32 = poly_p[start_p].into();
+            let start_q = match (0..nq).find(|&i| poly_q[i].into() == a) {
+                Some(i) => i,
+                None => {
+                    continue;
+                }
+            };
+
+            // Walk p from b to a, then q from after a to before b.
+            let mut merged = Vec::with_capacity(np + nq - 2);
+            for i in 0..np {
+                merged.push(poly_p[(start_p + 1 + i) % np]);
+            }
+            for i in 1..(nq - 1) {
+                merged.push(poly_q[(start_q + i) % nq]);
+            }
+
+            if !is_convex(&merged) {
+                continue;
+            }
+
+            for i in 0..nq {
+                let e = key(poly_q[i].into(), poly_q[(i + 1) % nq].into());
+                if let Some(sides) = edges.get_mut(&e) {
+                    for side in sides.iter_mut() {
+                        if *side == q {
+                            *side = p;
+                        }
+                    }
+                }
+            }
+            polygons[p] = Some(merged);
+            polygons[q] = None;
+        }
+
+        let mut output = PolygonBuffers {
+            vertices: self.vertices.clone(),
+            indices: Vec::with_capacity(self.indices.len()),
+            polygon_sizes: Vec::new(),
+        };
+        for polygon in polygons.into_iter().flatten() {
+            output.polygon_sizes.push(polygon.len() as u32);
+            output.indices.extend_from_slice(&polygon);
+        }
+
+        output
+    }
+}
+
+/// Structure that holds a mesh of polygons.
+///
+/// The indices of each polygon are stored contiguously in `indices`, and `polygon_sizes`
+/// contains the number of indices of each polygon, in order.
+///
+/// See `VertexBuffers::merge_coplanar_triangles`.
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct PolygonBuffers<OutputVertex, OutputIndex> {
+    pub vertices: Vec<OutputVertex>,
+    pub indices: Vec<OutputIndex>,
+    pub polygon_sizes: Vec<u32>,
+}
+
+/// A mesh that is drawn several times with different transforms.
+///
+/// With GPU instancing, a shape is tessellated once in its own coordinate space and the
+/// transform of each instance is applied in the vertex shader, instead of tessellating the
+/// shape again or duplicating its vertices for each instance. `mesh` is uploaded once and
+/// `transforms` is uploaded as a per-instance buffer.
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct InstanceBuffer<OutputVertex, OutputIndex> {
+    pub mesh: VertexBuffers<OutputVertex, OutputIndex>,
+    pub transforms: Vec<Transform>,
+}
+
+impl<OutputVertex, OutputIndex> InstanceBuffer<OutputVertex, OutputIndex> {
+    /// Constructor, without any instance.
+    pub fn new(mesh: VertexBuffers<OutputVertex, OutputIndex>) -> Self {
+        InstanceBuffer {
+            mesh,
+            transforms: Vec::new(),
+        }
+    }
+
+    /// Adds an instance of the mesh and returns its index.
+    pub fn add_instance(&mut self, transform: Transform) -> usize {
+        self.transforms.push(transform);
+        self.transforms.len() - 1
+    }
+
+    pub fn num_instances(&self) -> usize {
+        self.transforms.len()
+    }
+}
+
+impl<OutputVertex, OutputIndex> InstanceBuffer<OutputVertex, OutputIndex>
+where
+    OutputVertex: Clone,
+    OutputIndex: Copy + From<VertexId> + Add<Output = OutputIndex> + MaxIndex,
+{
+    /// Returns the geometry of all instances in a single buffer.
+    ///
+    /// `transform_vertex` applies the transform of an instance to a vertex of the mesh.
+    /// This is a fall-back for renderers that don't support instancing.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the vertices of all instances can't be addressed by the index type.
+    pub fn to_vertex_buffers<F>(
+        &self,
+        transform_vertex: F,
+    ) -> VertexBuffers<OutputVertex, OutputIndex>
+    where
+        F: Fn(&OutputVertex, &Transform) -> OutputVertex,
+    {
+        let mut output = VertexBuffers::with_capacity(
+            self.mesh.vertices.len() * self.transforms.len(),
+            self.mesh.indices.len() * self.transforms.len(),
+        );
+        let mut instance = self.mesh.clone();
+        for transform in &self.transforms {
+            for (vertex, src) in instance.vertices.iter_mut().zip(self.mesh.vertices.iter()) {
+                *vertex = transform_vertex(src, transform);
+            }
+            output.extend_from(&instance);
+        }
+
+        output
+    }
+}
+
 /// A temporary view on a `VertexBuffers` object which facilitate the population of vertex and index
 /// data.
 ///
@@ -433,6 +684,79 @@ impl BasicVertexConstructor<Point> for Positions {
     }
 }
 
+/// A vertex constructor that tags the vertices produced by another constructor
+/// with a constant atlas index.
+///
+/// This is useful when batching shapes that sample different regions of a texture
+/// atlas: create one `WithAtlasIndex` per tessellation with the index of the
+/// sub-texture to use.
+pub struct WithAtlasIndex<Ctor> {
+    pub constructor: Ctor,
+    pub atlas_index: u32,
+}
+
+impl<Ctor> WithAtlasIndex<Ctor> {
+    pub fn new(constructor: Ctor, atlas_index: u32) -> Self {
+        WithAtlasIndex {
+            constructor,
+            atlas_index,
+        }
+    }
+}
+
+/// A vertex produced by the [`WithAtlasIndex`](struct.WithAtlasIndex.html) constructor.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct AtlasVertex<Vertex> {
+    pub vertex: Vertex,
+    pub atlas_index: u32,
+}
+
+impl<Ctor, OutputVertex> FillVertexConstructor<AtlasVertex<OutputVertex>> for WithAtlasIndex<Ctor>
+where
+    Ctor: FillVertexConstructor<OutputVertex>,
+{
+    fn new_vertex(
+        &mut self,
+        position: Point,
+        attributes: FillAttributes,
+    ) -> AtlasVertex<OutputVertex> {
+        AtlasVertex {
+            vertex: self.constructor.new_vertex(position, attributes),
+            atlas_index: self.atlas_index,
+        }
+    }
+}
+
+impl<Ctor, OutputVertex> StrokeVertexConstructor<AtlasVertex<OutputVertex>> for WithAtlasIndex<Ctor>
+where
+    Ctor: StrokeVertexConstructor<OutputVertex>,
+{
+    fn new_vertex(
+        &mut self,
+        position: Point,
+        attributes: StrokeAttributes,
+    ) -> AtlasVertex<OutputVertex> {
+        AtlasVertex {
+            vertex: self.constructor.new_vertex(position, attributes),
+            atlas_index: self.atlas_index,
+        }
+    }
+}
+
+impl<Ctor, OutputVertex> BasicVertexConstructor<AtlasVertex<OutputVertex>> for WithAtlasIndex<Ctor>
+where
+    Ctor: BasicVertexConstructor<OutputVertex>,
+{
+    fn new_vertex(&mut self, position: Point) -> AtlasVertex<OutputVertex> {
+        AtlasVertex {
+            vertex: self.constructor.new_vertex(position),
+            atlas_index: self.atlas_index,
+        }
+    }
+}
+
 impl<F, OutputVertex> FillVertexConstructor<OutputVertex> for F
 where
     F: Fn(Point, FillAttributes) -> OutputVertex,
@@ -611,97 +935,983 @@ where
     }
 }
 
-/// A geometry builder that does not output any geometry.
+/// A geometry builder that writes triangle strips into a `VertexBuffers`.
 ///
-/// Mostly useful for testing.
-pub struct NoOutput {
-    count: Count,
+/// Each triangle that shares an edge with the last two vertices of the current strip
+/// extends the strip with a single index, as long as the strip keeps the winding order of
+/// the triangle (the winding of every other triangle of a strip is flipped when it is
+/// rendered). Otherwise a new strip is started. The stroke tessellator and the ribbon
+/// helpers produce their triangles in strip order which makes them a good fit for this
+/// builder.
+///
+/// By default, disconnected strips are joined with degenerate triangles. On hardware that
+/// supports it, a primitive restart index can be inserted between strips instead using
+/// `with_primitive_restart`. Geometry added to buffers that already contain indices is
+/// separated from them in the same way, the existing indices are assumed to be written by
+/// a `StripBuffersBuilder` with the same settings.
+pub struct StripBuffersBuilder<'l, OutputVertex: 'l, OutputIndex: 'l, Ctor> {
+    buffers: &'l mut VertexBuffers<OutputVertex, OutputIndex>,
+    vertex_offset: Index,
+    index_offset: Index,
+    vertex_constructor: Ctor,
+    restart_index: Option<(OutputIndex, usize)>,
+    // The last two vertices of the current strip.
+    strip_end: Option<(VertexId, VertexId)>,
+    // The number of indices since the start of the strip, used to know the winding order
+    // of the next triangle.
+    strip_len: usize,
 }
 
-impl NoOutput {
-    pub fn new() -> Self {
-        NoOutput {
-            count: Count {
-                vertices: 0,
-                indices: 0,
-            },
+impl<'l, OutputVertex: 'l, OutputIndex: 'l, Ctor>
+    StripBuffersBuilder<'l, OutputVertex, OutputIndex, Ctor>
+{
+    pub fn new(buffers: &'l mut VertexBuffers<OutputVertex, OutputIndex>, ctor: Ctor) -> Self {
+        let vertex_offset = buffers.vertices.len() as Index;
+        let index_offset = buffers.indices.len() as Index;
+        let strip_len = buffers.indices.len();
+        StripBuffersBuilder {
+            buffers,
+            vertex_offset,
+            index_offset,
+            vertex_constructor: ctor,
+            restart_index: None,
+            strip_end: None,
+            strip_len,
+        }
+    }
+
+    /// Separate disconnected strips with a primitive restart index instead of
+    /// degenerate triangles.
+    ///
+    /// The index is typically the maximum value of the index type, for example
+    /// `0xFFFF` for `u16` indices. Adding a vertex that would be addressed by the restart
+    /// index fails with `GeometryBuilderError::TooManyVertices`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the buffers already contain a vertex addressed by the restart index.
+    pub fn with_primitive_restart(mut self, index: OutputIndex) -> Self
+    where
+        OutputIndex: Copy + Into<u32>,
+    {
+        let value = index.into() as usize;
+        assert!(
+            value >= self.buffers.vertices.len(),
+            "StripBuffersBuilder: the restart index {} is used by a vertex",
+            value,
+        );
+        self.restart_index = Some((index, value));
+        self
+    }
+
+    pub fn buffers<'a, 'b: 'a>(&'b self) -> &'a VertexBuffers<OutputVertex, OutputIndex> {
+        self.buffers
+    }
+
+    fn push_vertex(&mut self, vertex: OutputVertex) -> Result<VertexId, GeometryBuilderError>
+    where
+        OutputIndex: MaxIndex,
+    {
+        self.buffers.vertices.push(vertex);
+        let len = self.buffers.vertices.len();
+        let max = match self.restart_index {
+            Some((_, value)) => value.min(OutputIndex::MAX),
+            None => OutputIndex::MAX,
+        };
+        if len > max {
+            return Err(GeometryBuilderError::TooManyVertices);
         }
+        Ok(VertexId((len - 1) as Index - self.vertex_offset))
+    }
+
+    fn push_index(&mut self, id: VertexId)
+    where
+        OutputIndex: From<VertexId>,
+    {
+        self.buffers.indices.push((id + self.vertex_offset).into());
+        self.strip_len += 1;
+    }
+
+    // Separates the next strip from the indices already in the buffer.
+    fn end_strip(&mut self, next: VertexId)
+    where
+        OutputIndex: Copy + From<VertexId>,
+    {
+        let last = match self.buffers.indices.last() {
+            Some(&last) => last,
+            None => {
+                return;
+            }
+        };
+
+        match self.restart_index {
+            Some((restart, _)) => {
+                self.buffers.indices.push(restart);
+                self.strip_len = 0;
+            }
+            None => {
+                self.buffers.indices.push(last);
+                self.strip_len += 1;
+                self.push_index(next);
+            }
+        }
+    }
+
+    fn reset(&mut self) {
+        self.strip_end = None;
+        // Degenerate triangles don't reset the winding order. Restart indices do, but a
+        // new strip is always started after one.
+        self.strip_len = self.buffers.indices.len();
     }
 }
 
-impl GeometryBuilder for NoOutput {
+// Whether the two triangles have the same vertices in the same cyclic order.
+fn same_winding(a: [VertexId; 3], b: [VertexId; 3]) -> bool {
+    (0..3).any(|i| a[0] == b[i] && a[1] == b[(i + 1) % 3] && a[2] == b[(i + 2) % 3])
+}
+
+impl<'l, OutputVertex, OutputIndex, Ctor> GeometryBuilder
+    for StripBuffersBuilder<'l, OutputVertex, OutputIndex, Ctor>
+where
+    OutputVertex: 'l,
+    OutputIndex: Copy + From<VertexId> + MaxIndex,
+{
     fn begin_geometry(&mut self) {
-        self.count.vertices = 0;
-        self.count.indices = 0;
+        self.vertex_offset = self.buffers.vertices.len() as Index;
+        self.index_offset = self.buffers.indices.len() as Index;
+        self.reset();
+    }
+
+    fn end_geometry(&mut self) -> Count {
+        Count {
+            vertices: self.buffers.vertices.len() as u32 - self.vertex_offset,
+            indices: self.buffers.indices.len() as u32 - self.index_offset,
+        }
+    }
+
+    fn abort_geometry(&mut self) {
+        self.buffers.vertices.truncate(self.vertex_offset as usize);
+        self.buffers.indices.truncate(self.index_offset as usize);
+        self.reset();
     }
 
     fn add_triangle(&mut self, a: VertexId, b: VertexId, c: VertexId) {
         debug_assert!(a != b);
         debug_assert!(a != c);
         debug_assert!(b != c);
-        self.count.indices += 3;
-    }
 
-    fn end_geometry(&mut self) -> Count {
-        self.count
-    }
+        let triangle = [a, b, c];
+        if let Some((p, q)) = self.strip_end {
+            if triangle.contains(&p) && triangle.contains(&q) {
+                let next = *triangle.iter().find(|&&v| v != p && v != q).unwrap();
+                // Odd triangles of the strip are rendered with the opposite winding.
+                let rendered = if self.strip_len % 2 == 0 {
+                    [p, q, next]
+                } else {
+                    [q, p, next]
+                };
+                if same_winding(rendered, triangle) {
+                    self.push_index(next);
+                    self.strip_end = Some((q, next));
+                    return;
+                }
+            }
+        }
 
-    fn abort_geometry(&mut self) {}
+        // Start a new strip. A restart index resets the winding order while degenerate
+        // triangles preserve it, in which case the first two vertices are swapped if the
+        // new triangle is an odd one.
+        let even = self.restart_index.is_some() || self.strip_len % 2 == 0;
+        let (first, second) = if even { (a, b) } else { (b, a) };
+        self.end_strip(first);
+        self.push_index(first);
+        self.push_index(second);
+        self.push_index(c);
+        self.strip_end = Some((second, c));
+    }
 }
 
-impl FillGeometryBuilder for NoOutput {
+impl<'l, OutputVertex, OutputIndex, Ctor> FillGeometryBuilder
+    for StripBuffersBuilder<'l, OutputVertex, OutputIndex, Ctor>
+where
+    OutputVertex: 'l,
+    OutputIndex: Copy + From<VertexId> + MaxIndex,
+    Ctor: FillVertexConstructor<OutputVertex>,
+{
     fn add_fill_vertex(
         &mut self,
-        _pos: Point,
-        _attributes: FillAttributes,
+        position: Point,
+        attributes: FillAttributes,
     ) -> Result<VertexId, GeometryBuilderError> {
-        if self.count.vertices >= std::u32::MAX {
-            return Err(GeometryBuilderError::TooManyVertices);
-        }
-        self.count.vertices += 1;
-        Ok(VertexId(self.count.vertices as Index - 1))
+        let vertex = self.vertex_constructor.new_vertex(position, attributes);
+        self.push_vertex(vertex)
     }
 }
 
-impl StrokeGeometryBuilder for NoOutput {
+impl<'l, OutputVertex, OutputIndex, Ctor> StrokeGeometryBuilder
+    for StripBuffersBuilder<'l, OutputVertex, OutputIndex, Ctor>
+where
+    OutputVertex: 'l,
+    OutputIndex: Copy + From<VertexId> + MaxIndex,
+    Ctor: StrokeVertexConstructor<OutputVertex>,
+{
     fn add_stroke_vertex(
         &mut self,
-        _position: Point,
-        _: StrokeAttributes,
+        position: Point,
+        attributes: StrokeAttributes,
     ) -> Result<VertexId, GeometryBuilderError> {
-        if self.count.vertices >= std::u32::MAX {
-            return Err(GeometryBuilderError::TooManyVertices);
-        }
-        self.count.vertices += 1;
-        Ok(VertexId(self.count.vertices as Index - 1))
+        let vertex = self.vertex_constructor.new_vertex(position, attributes);
+        self.push_vertex(vertex)
     }
 }
 
-impl BasicGeometryBuilder for NoOutput {
-    fn add_vertex(&mut self, _pos: Point) -> Result<VertexId, GeometryBuilderError> {
-        if self.count.vertices >= std::u32::MAX {
-            return Err(GeometryBuilderError::TooManyVertices);
-        }
-        self.count.vertices += 1;
-        Ok(VertexId(self.count.vertices as Index - 1))
+impl<'l, OutputVertex, OutputIndex, Ctor> BasicGeometryBuilder
+    for StripBuffersBuilder<'l, OutputVertex, OutputIndex, Ctor>
+where
+    OutputVertex: 'l,
+    OutputIndex: Copy + From<VertexId> + MaxIndex,
+    Ctor: BasicVertexConstructor<OutputVertex>,
+{
+    fn add_vertex(&mut self, position: Point) -> Result<VertexId, GeometryBuilderError> {
+        let vertex = self.vertex_constructor.new_vertex(position);
+        self.push_vertex(vertex)
     }
 }
 
-impl GeometryReceiver for NoOutput {
-    fn set_geometry(&mut self, _vertices: &[Point], _indices: &[u32]) {}
+// The bounding rectangle of a set of points, updated as points are added.
+struct Bounds {
+    min: Point,
+    max: Point,
 }
 
-/// Provides the maximum value of an index.
+impl Bounds {
+    fn new() -> Self {
+        Bounds {
+            min: point(std::f32::MAX, std::f32::MAX),
+            max: point(std::f32::MIN, std::f32::MIN),
+        }
+    }
+
+    fn add(&mut self, position: Point) {
+        self.min = self.min.min(position);
+        self.max = self.max.max(position);
+    }
+
+    // Returns an empty rectangle if no point was added.
+    fn to_rect(&self) -> Rect {
+        if self.min.x > self.max.x {
+            return Rect::zero();
+        }
+
+        Rect::new(self.min, (self.max - self.min).to_size())
+    }
+}
+
+/// Vertex and index buffers of positions that keep track of the bounding rectangle
+/// of their vertices.
 ///
-/// This should be the maximum value representable by the index type up
-/// to u32::MAX because the tessellators can't internally represent more
-/// than u32::MAX indices.
-pub trait MaxIndex {
-    const MAX: usize;
+/// The bounds are updated as vertices are added so that they can be queried at any
+/// time without scanning the vertex buffer. Geometry can be accumulated over several
+/// tessellations.
+pub struct BoundedVertexBuffers<OutputIndex> {
+    buffers: VertexBuffers<Point, OutputIndex>,
+    bounds: Bounds,
+    vertex_offset: Index,
+    index_offset: Index,
 }
 
-impl MaxIndex for u8 {
-    const MAX: usize = std::u8::MAX as usize;
+impl<OutputIndex> BoundedVertexBuffers<OutputIndex> {
+    /// Constructor
+    pub fn new() -> Self {
+        BoundedVertexBuffers {
+            buffers: VertexBuffers::new(),
+            bounds: Bounds::new(),
+            vertex_offset: 0,
+            index_offset: 0,
+        }
+    }
+
+    /// Returns the smallest rectangle containing all vertices, or an empty rectangle
+    /// if there are no vertices.
+    pub fn bounds(&self) -> Rect {
+        self.bounds.to_rect()
+    }
+
+    pub fn buffers(&self) -> &VertexBuffers<Point, OutputIndex> {
+        &self.buffers
+    }
+
+    pub fn into_buffers(self) -> VertexBuffers<Point, OutputIndex> {
+        self.buffers
+    }
+
+    /// Removes all vertices and indices and resets the bounds.
+    pub fn clear(&mut self) {
+        self.buffers.vertices.clear();
+        self.buffers.indices.clear();
+        self.reset_bounds();
+    }
+
+    fn reset_bounds(&mut self) {
+        self.bounds = Bounds::new();
+        for v in &self.buffers.vertices {
+            self.bounds.add(*v);
+        }
+    }
+}
+
+impl<OutputIndex> BoundedVertexBuffers<OutputIndex>
+where
+    OutputIndex: MaxIndex,
+{
+    fn push_vertex(&mut self, position: Point) -> Result<VertexId, GeometryBuilderError> {
+        self.buffers.vertices.push(position);
+        let len = self.buffers.vertices.len();
+        if len > OutputIndex::MAX {
+            return Err(GeometryBuilderError::TooManyVertices);
+        }
+        self.bounds.add(position);
+
+        Ok(VertexId((len - 1) as Index - self.vertex_offset))
+    }
+}
+
+impl<OutputIndex> GeometryBuilder for BoundedVertexBuffers<OutputIndex>
+where
+    OutputIndex: Add + From<VertexId> + MaxIndex,
+{
+    fn begin_geometry(&mut self) {
+        self.vertex_offset = self.buffers.vertices.len() as Index;
+        self.index_offset = self.buffers.indices.len() as Index;
+    }
+
+    fn end_geometry(&mut self) -> Count {
+        Count {
+            vertices: self.buffers.vertices.len() as u32 - self.vertex_offset,
+            indices: self.buffers.indices.len() as u32 - self.index_offset,
+        }
+    }
+
+    fn abort_geometry(&mut self) {
+        self.buffers.vertices.truncate(self.vertex_offset as usize);
+        self.buffers.indices.truncate(self.index_offset as usize);
+        // The bounds can't be shrunk incrementally, recompute them.
+        self.reset_bounds();
+    }
+
+    fn add_triangle(&mut self, a: VertexId, b: VertexId, c: VertexId) {
+        debug_assert!(a != b);
+        debug_assert!(a != c);
+        debug_assert!(b != c);
+        self.buffers.indices.push((a + self.vertex_offset).into());
+        self.buffers.indices.push((b + self.vertex_offset).into());
+        self.buffers.indices.push((c + self.vertex_offset).into());
+    }
+}
+
+impl<OutputIndex> FillGeometryBuilder for BoundedVertexBuffers<OutputIndex>
+where
+    OutputIndex: Add + From<VertexId> + MaxIndex,
+{
+    fn add_fill_vertex(
+        &mut self,
+        position: Point,
+        _attributes: FillAttributes,
+    ) -> Result<VertexId, GeometryBuilderError> {
+        self.push_vertex(position)
+    }
+}
+
+impl<OutputIndex> StrokeGeometryBuilder for BoundedVertexBuffers<OutputIndex>
+where
+    OutputIndex: Add + From<VertexId> + MaxIndex,
+{
+    fn add_stroke_vertex(
+        &mut self,
+        position: Point,
+        _attributes: StrokeAttributes,
+    ) -> Result<VertexId, GeometryBuilderError> {
+        self.push_vertex(position)
+    }
+}
+
+impl<OutputIndex> BasicGeometryBuilder for BoundedVertexBuffers<OutputIndex>
+where
+    OutputIndex: Add + From<VertexId> + MaxIndex,
+{
+    fn add_vertex(&mut self, position: Point) -> Result<VertexId, GeometryBuilderError> {
+        self.push_vertex(position)
+    }
+}
+
+/// A geometry builder that writes interleaved vertex data into a byte buffer.
+///
+/// The vertex constructor is a closure that appends the bytes of each vertex to the
+/// buffer, for example `Fn(Point, FillAttributes, &mut Vec<u8>)` for the fill tessellator
+/// and `Fn(Point, StrokeAttributes, &mut Vec<u8>)` for the stroke tessellator. This lets
+/// the output be uploaded to the GPU without converting it from a `VertexBuffers`.
+///
+/// The number of bytes written per vertex is up to the closure. Vertex ids are assigned
+/// from the number of vertices added since the builder was created.
+pub struct InterleavedBuffersBuilder<'l, OutputIndex: 'l, Ctor> {
+    vertices: &'l mut Vec<u8>,
+    indices: &'l mut Vec<OutputIndex>,
+    vertex_constructor: Ctor,
+    num_vertices: Index,
+    vertex_offset: Index,
+    byte_offset: usize,
+    index_offset: Index,
+}
+
+impl<'l, OutputIndex: 'l, Ctor> InterleavedBuffersBuilder<'l, OutputIndex, Ctor> {
+    pub fn new(vertices: &'l mut Vec<u8>, indices: &'l mut Vec<OutputIndex>, ctor: Ctor) -> Self {
+        let byte_offset = vertices.len();
+        let index_offset = indices.len() as Index;
+        InterleavedBuffersBuilder {
+            vertices,
+            indices,
+            vertex_constructor: ctor,
+            num_vertices: 0,
+            vertex_offset: 0,
+            byte_offset,
+            index_offset,
+        }
+    }
+
+    /// Returns the number of vertices written into the byte buffer by this builder.
+    pub fn num_vertices(&self) -> u32 {
+        self.num_vertices
+    }
+
+    fn push_vertex(&mut self) -> Result<VertexId, GeometryBuilderError>
+    where
+        OutputIndex: MaxIndex,
+    {
+        self.num_vertices += 1;
+        if self.num_vertices as usize > OutputIndex::MAX {
+            return Err(GeometryBuilderError::TooManyVertices);
+        }
+        Ok(VertexId(self.num_vertices - 1 - self.vertex_offset))
+    }
+}
+
+impl<'l, OutputIndex, Ctor> GeometryBuilder for InterleavedBuffersBuilder<'l, OutputIndex, Ctor>
+where
+    OutputIndex: Add + From<VertexId> + MaxIndex,
+{
+    fn begin_geometry(&mut self) {
+        self.vertex_offset = self.num_vertices;
+        self.byte_offset = self.vertices.len();
+        self.index_offset = self.indices.len() as Index;
+    }
+
+    fn end_geometry(&mut self) -> Count {
+        Count {
+            vertices: self.num_vertices - self.vertex_offset,
+            indices: self.indices.len() as u32 - self.index_offset,
+        }
+    }
+
+    fn abort_geometry(&mut self) {
+        self.vertices.truncate(self.byte_offset);
+        self.indices.truncate(self.index_offset as usize);
+        self.num_vertices = self.vertex_offset;
+    }
+
+    fn add_triangle(&mut self, a: VertexId, b: VertexId, c: VertexId) {
+        debug_assert!(a != b);
+        debug_assert!(a != c);
+        debug_assert!(b != c);
+        self.indices.push((a + self.vertex_offset).into());
+        self.indices.push((b + self.vertex_offset).into());
+        self.indices.push((c + self.vertex_offset).into());
+    }
+}
+
+impl<'l, OutputIndex, Ctor> FillGeometryBuilder for InterleavedBuffersBuilder<'l, OutputIndex, Ctor>
+where
+    OutputIndex: Add + From<VertexId> + MaxIndex,
+    Ctor: Fn(Point, FillAttributes, &mut Vec<u8>),
+{
+    fn add_fill_vertex(
+        &mut self,
+        position: Point,
+        attributes: FillAttributes,
+    ) -> Result<VertexId, GeometryBuilderError> {
+        (self.vertex_constructor)(position, attributes, self.vertices);
+        self.push_vertex()
+    }
+}
+
+impl<'l, OutputIndex, Ctor> StrokeGeometryBuilder
+    for InterleavedBuffersBuilder<'l, OutputIndex, Ctor>
+where
+    OutputIndex: Add + From<VertexId> + MaxIndex,
+    Ctor: Fn(Point, StrokeAttributes, &mut Vec<u8>),
+{
+    fn add_stroke_vertex(
+        &mut self,
+        position: Point,
+        attributes: StrokeAttributes,
+    ) -> Result<VertexId, GeometryBuilderError> {
+        (self.vertex_constructor)(position, attributes, self.vertices);
+        self.push_vertex()
+    }
+}
+
+/// A geometry builder that does not output any geometry.
+///
+/// Mostly useful for testing.
+pub struct NoOutput {
+    count: Count,
+}
+
+impl NoOutput {
+    pub fn new() -> Self {
+        NoOutput {
+            count: Count {
+                vertices: 0,
+                indices: 0,
+            },
+        }
+    }
+}
+
+impl GeometryBuilder for NoOutput {
+    fn begin_geometry(&mut self) {
+        self.count.vertices = 0;
+        self.count.indices = 0;
+    }
+
+    fn add_triangle(&mut self, a: VertexId, b: VertexId, c: VertexId) {
+        debug_assert!(a != b);
+        debug_assert!(a != c);
+        debug_assert!(b != c);
+        self.count.indices += 3;
+    }
+
+    fn end_geometry(&mut self) -> Count {
+        self.count
+    }
+
+    fn abort_geometry(&mut self) {}
+}
+
+impl FillGeometryBuilder for NoOutput {
+    fn add_fill_vertex(
+        &mut self,
+        _pos: Point,
+        _attributes: FillAttributes,
+    ) -> Result<VertexId, GeometryBuilderError> {
+        if self.count.vertices >= std::u32::MAX {
+            return Err(GeometryBuilderError::TooManyVertices);
+        }
+        self.count.vertices += 1;
+        Ok(VertexId(self.count.vertices as Index - 1))
+    }
+}
+
+impl StrokeGeometryBuilder for NoOutput {
+    fn add_stroke_vertex(
+        &mut self,
+        _position: Point,
+        _: StrokeAttributes,
+    ) -> Result<VertexId, GeometryBuilderError> {
+        if self.count.vertices >= std::u32::MAX {
+            return Err(GeometryBuilderError::TooManyVertices);
+        }
+        self.count.vertices += 1;
+        Ok(VertexId(self.count.vertices as Index - 1))
+    }
+}
+
+impl BasicGeometryBuilder for NoOutput {
+    fn add_vertex(&mut self, _pos: Point) -> Result<VertexId, GeometryBuilderError> {
+        if self.count.vertices >= std::u32::MAX {
+            return Err(GeometryBuilderError::TooManyVertices);
+        }
+        self.count.vertices += 1;
+        Ok(VertexId(self.count.vertices as Index - 1))
+    }
+}
+
+impl GeometryReceiver for NoOutput {
+    fn set_geometry(&mut self, _vertices: &[Point], _indices: &[u32]) {}
+}
+
+/// Statistics about the triangles received by a [`ValidatingBuilder`](struct.ValidatingBuilder.html).
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ValidationReport {
+    /// Number of triangles with a zero area.
+    pub degenerate: usize,
+    /// Number of triangles with a negative signed area.
+    pub cw: usize,
+    /// Number of triangles with a positive signed area.
+    pub ccw: usize,
+}
+
+/// A geometry builder that checks the triangles it receives before forwarding them to
+/// another builder.
+///
+/// Triangles with a zero area (including triangles referring to the same vertex several
+/// times) are counted as degenerate and are not forwarded. The orientation of the other
+/// triangles is recorded using the sign of their area: with the y axis pointing up,
+/// counter-clockwise triangles have a positive area. The counts can be read with `report`
+/// and are reset by `begin_geometry`.
+///
+/// Mostly useful to debug tessellators and geometry builders.
+pub struct ValidatingBuilder<Builder> {
+    builder: Builder,
+    positions: Vec<Point>,
+    report: ValidationReport,
+    panic_on_degenerate: bool,
+}
+
+impl<Builder> ValidatingBuilder<Builder> {
+    pub fn new(builder: Builder) -> Self {
+        ValidatingBuilder {
+            builder,
+            positions: Vec::new(),
+            report: ValidationReport::default(),
+            panic_on_degenerate: false,
+        }
+    }
+
+    /// Panic instead of counting degenerate triangles.
+    pub fn with_panic_on_degenerate(mut self, panic_on_degenerate: bool) -> Self {
+        self.panic_on_degenerate = panic_on_degenerate;
+        self
+    }
+
+    /// Returns the statistics about the triangles added since the last call to
+    /// `begin_geometry`.
+    pub fn report(&self) -> ValidationReport {
+        self.report
+    }
+
+    pub fn into_inner(self) -> Builder {
+        self.builder
+    }
+
+    fn record_vertex(
+        &mut self,
+        position: Point,
+        id: Result<VertexId, GeometryBuilderError>,
+    ) -> Result<VertexId, GeometryBuilderError> {
+        if let Ok(id) = id {
+            let idx = id.to_usize();
+            if idx >= self.positions.len() {
+                self.positions.resize(idx + 1, point(0.0, 0.0));
+            }
+            self.positions[idx] = position;
+        }
+
+        id
+    }
+}
+
+impl<Builder: GeometryBuilder> GeometryBuilder for ValidatingBuilder<Builder> {
+    fn begin_geometry(&mut self) {
+        self.positions.clear();
+        self.report = ValidationReport::default();
+        self.builder.begin_geometry();
+    }
+
+    fn end_geometry(&mut self) -> Count {
+        self.builder.end_geometry()
+    }
+
+    fn add_triangle(&mut self, a: VertexId, b: VertexId, c: VertexId) {
+        let pa = self.positions[a.to_usize()];
+        let pb = self.positions[b.to_usize()];
+        let pc = self.positions[c.to_usize()];
+        let area = (pb - pa).cross(pc - pa);
+
+        if area == 0.0 {
+            if self.panic_on_degenerate {
+                panic!("Degenerate triangle {:?} {:?} {:?}", pa, pb, pc);
+            }
+            self.report.degenerate += 1;
+            return;
+        }
+
+        if area > 0.0 {
+            self.report.ccw += 1;
+        } else {
+            self.report.cw += 1;
+        }
+
+        self.builder.add_triangle(a, b, c);
+    }
+
+    fn abort_geometry(&mut self) {
+        self.builder.abort_geometry();
+    }
+}
+
+impl<Builder: FillGeometryBuilder> FillGeometryBuilder for ValidatingBuilder<Builder> {
+    fn add_fill_vertex(
+        &mut self,
+        position: Point,
+        attributes: FillAttributes,
+    ) -> Result<VertexId, GeometryBuilderError> {
+        let id = self.builder.add_fill_vertex(position, attributes);
+        self.record_vertex(position, id)
+    }
+}
+
+impl<Builder: StrokeGeometryBuilder> StrokeGeometryBuilder for ValidatingBuilder<Builder> {
+    fn add_stroke_vertex(
+        &mut self,
+        position: Point,
+        attributes: StrokeAttributes,
+    ) -> Result<VertexId, GeometryBuilderError> {
+        let id = self.builder.add_stroke_vertex(position, attributes);
+        self.record_vertex(position, id)
+    }
+}
+
+impl<Builder: BasicGeometryBuilder> BasicGeometryBuilder for ValidatingBuilder<Builder> {
+    fn add_vertex(&mut self, position: Point) -> Result<VertexId, GeometryBuilderError> {
+        let id = self.builder.add_vertex(position);
+        self.record_vertex(position, id)
+    }
+}
+
+/// A geometry builder that forwards each distinct vertex to another builder only once.
+///
+/// Vertices are identified by a key computed by a user-supplied function from the
+/// position and the attributes of the vertex, since the output vertices are usually not
+/// hashable. When a vertex has the same key as a vertex received since the last call to
+/// `begin_geometry`, the id of the existing vertex is returned instead of adding a new one,
+/// and the triangles are forwarded with this id. Triangles that end up referring to the
+/// same vertex several times are dropped.
+///
+/// The key function must distinguish vertices that the output needs to keep separate, for
+/// example stroke vertices with different normals when the line width is applied in a
+/// vertex shader.
+///
+/// # Example
+///
+/// ```
+/// # extern crate lyon_tessellation as tess;
+/// # use tess::geometry_builder::{simple_builder, DedupBuilder, VertexBuffers};
+/// # use tess::math::{point, Point};
+/// # use tess::path::Path;
+/// # use tess::{FillAttributes, FillOptions, FillTessellator};
+/// # fn main() {
+/// # let mut builder = Path::builder();
+/// # builder.move_to(point(0.0, 0.0));
+/// # builder.line_to(point(1.0, 0.0));
+/// # builder.line_to(point(1.0, 1.0));
+/// # builder.close();
+/// # let path = builder.build();
+/// let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+/// let mut builder = DedupBuilder::new(
+///     simple_builder(&mut buffers),
+///     |position: Point, _: &mut FillAttributes| (position.x.to_bits(), position.y.to_bits()),
+/// );
+///
+/// let options = FillOptions::default().with_no_shared_vertices(true);
+/// FillTessellator::new().tessellate_path(&path, &options, &mut builder).unwrap();
+/// # }
+/// ```
+pub struct DedupBuilder<Builder, Key, KeyFn> {
+    builder: Builder,
+    key_fn: KeyFn,
+    ids: std::collections::HashMap<Key, VertexId>,
+}
+
+impl<Builder, Key: std::hash::Hash + Eq, KeyFn> DedupBuilder<Builder, Key, KeyFn> {
+    pub fn new(builder: Builder, key_fn: KeyFn) -> Self {
+        DedupBuilder {
+            builder,
+            key_fn,
+            ids: std::collections::HashMap::new(),
+        }
+    }
+
+    pub fn into_inner(self) -> Builder {
+        self.builder
+    }
+
+    fn dedup_vertex(
+        &mut self,
+        key: Key,
+        add_vertex: impl FnOnce(&mut Builder) -> Result<VertexId, GeometryBuilderError>,
+    ) -> Result<VertexId, GeometryBuilderError> {
+        if let Some(&id) = self.ids.get(&key) {
+            return Ok(id);
+        }
+
+        let id = add_vertex(&mut self.builder)?;
+        self.ids.insert(key, id);
+
+        Ok(id)
+    }
+}
+
+impl<Builder: GeometryBuilder, Key, KeyFn> GeometryBuilder for DedupBuilder<Builder, Key, KeyFn> {
+    fn begin_geometry(&mut self) {
+        self.ids.clear();
+        self.builder.begin_geometry();
+    }
+
+    fn end_geometry(&mut self) -> Count {
+        self.ids.clear();
+        self.builder.end_geometry()
+    }
+
+    fn add_triangle(&mut self, a: VertexId, b: VertexId, c: VertexId) {
+        if a == b || b == c || c == a {
+            return;
+        }
+
+        self.builder.add_triangle(a, b, c);
+    }
+
+    fn abort_geometry(&mut self) {
+        self.ids.clear();
+        self.builder.abort_geometry();
+    }
+}
+
+impl<Builder, Key, KeyFn> FillGeometryBuilder for DedupBuilder<Builder, Key, KeyFn>
+where
+    Builder: FillGeometryBuilder,
+    Key: std::hash::Hash + Eq,
+    KeyFn: FnMut(Point, &mut FillAttributes) -> Key,
+{
+    fn add_fill_vertex(
+        &mut self,
+        position: Point,
+        mut attributes: FillAttributes,
+    ) -> Result<VertexId, GeometryBuilderError> {
+        let key = (self.key_fn)(position, &mut attributes);
+        self.dedup_vertex(key, |builder| builder.add_fill_vertex(position, attributes))
+    }
+}
+
+impl<Builder, Key, KeyFn> StrokeGeometryBuilder for DedupBuilder<Builder, Key, KeyFn>
+where
+    Builder: StrokeGeometryBuilder,
+    Key: std::hash::Hash + Eq,
+    KeyFn: FnMut(Point, &mut StrokeAttributes) -> Key,
+{
+    fn add_stroke_vertex(
+        &mut self,
+        position: Point,
+        mut attributes: StrokeAttributes,
+    ) -> Result<VertexId, GeometryBuilderError> {
+        let key = (self.key_fn)(position, &mut attributes);
+        self.dedup_vertex(key, |builder| {
+            builder.add_stroke_vertex(position, attributes)
+        })
+    }
+}
+
+/// A geometry builder that computes the bounding rectangle of the vertices it forwards
+/// to another builder.
+///
+/// The bounds are reset by `begin_geometry` and can be read with `bounds` after
+/// `end_geometry`, which avoids iterating over the output to find its extents. Aborting
+/// the geometry also resets the bounds since the vertices are discarded.
+pub struct BoundsBuilder<Builder> {
+    builder: Builder,
+    bounds: Bounds,
+}
+
+impl<Builder> BoundsBuilder<Builder> {
+    pub fn new(builder: Builder) -> Self {
+        BoundsBuilder {
+            builder,
+            bounds: Bounds::new(),
+        }
+    }
+
+    /// Returns the smallest rectangle containing the vertices added since the last call
+    /// to `begin_geometry`, or an empty rectangle if there are no vertices.
+    pub fn bounds(&self) -> Rect {
+        self.bounds.to_rect()
+    }
+
+    pub fn into_inner(self) -> Builder {
+        self.builder
+    }
+
+    fn record_vertex(
+        &mut self,
+        position: Point,
+        id: Result<VertexId, GeometryBuilderError>,
+    ) -> Result<VertexId, GeometryBuilderError> {
+        if id.is_ok() {
+            self.bounds.add(position);
+        }
+
+        id
+    }
+}
+
+impl<Builder: GeometryBuilder> GeometryBuilder for BoundsBuilder<Builder> {
+    fn begin_geometry(&mut self) {
+        self.bounds = Bounds::new();
+        self.builder.begin_geometry();
+    }
+
+    fn end_geometry(&mut self) -> Count {
+        self.builder.end_geometry()
+    }
+
+    fn add_triangle(&mut self, a: VertexId, b: VertexId, c: VertexId) {
+        self.builder.add_triangle(a, b, c);
+    }
+
+    fn abort_geometry(&mut self) {
+        self.bounds = Bounds::new();
+        self.builder.abort_geometry();
+    }
+}
+
+impl<Builder: FillGeometryBuilder> FillGeometryBuilder for BoundsBuilder<Builder> {
+    fn add_fill_vertex(
+        &mut self,
+        position: Point,
+        attributes: FillAttributes,
+    ) -> Result<VertexId, GeometryBuilderError> {
+        let id = self.builder.add_fill_vertex(position, attributes);
+        self.record_vertex(position, id)
+    }
+}
+
+impl<Builder: StrokeGeometryBuilder> StrokeGeometryBuilder for BoundsBuilder<Builder> {
+    fn add_stroke_vertex(
+        &mut self,
+        position: Point,
+        attributes: StrokeAttributes,
+    ) -> Result<VertexId, GeometryBuilderError> {
+        let id = self.builder.add_stroke_vertex(position, attributes);
+        self.record_vertex(position, id)
+    }
+}
+
+impl<Builder: BasicGeometryBuilder> BasicGeometryBuilder for BoundsBuilder<Builder> {
+    fn add_vertex(&mut self, position: Point) -> Result<VertexId, GeometryBuilderError> {
+        let id = self.builder.add_vertex(position);
+        self.record_vertex(position, id)
+    }
+}
+
+/// Provides the maximum value of an index.
+///
+/// This should be the maximum value representable by the index type up
+/// to u32::MAX because the tessellators can't internally represent more
+/// than u32::MAX indices.
+pub trait MaxIndex {
+    const MAX: usize;
+}
+
+impl MaxIndex for u8 {
+    const MAX: usize = std::u8::MAX as usize;
 }
 impl MaxIndex for i8 {
     const MAX: usize = std::i8::MAX as usize;
@@ -731,3 +1941,574 @@ impl MaxIndex for usize {
 impl MaxIndex for isize {
     const MAX: usize = std::u32::MAX as usize;
 }
+
+#[test]
+fn atlas_index() {
+    use crate::basic_shapes::fill_rectangle;
+    use crate::math::rect;
+    use crate::FillOptions;
+
+    let mut buffers: VertexBuffers<AtlasVertex<Point>, u16> = VertexBuffers::new();
+
+    fill_rectangle(
+        &rect(0.0, 0.0, 1.0, 1.0),
+        &FillOptions::default(),
+        &mut BuffersBuilder::new(&mut buffers, WithAtlasIndex::new(Positions, 3)),
+    )
+    .unwrap();
+
+    let n = buffers.vertices.len();
+    assert!(n > 0);
+
+    fill_rectangle(
+        &rect(2.0, 0.0, 1.0, 1.0),
+        &FillOptions::default(),
+        &mut BuffersBuilder::new(&mut buffers, WithAtlasIndex::new(Positions, 7)),
+    )
+    .unwrap();
+
+    assert!(buffers.vertices.len() > n);
+    for v in &buffers.vertices[..n] {
+        assert_eq!(v.atlas_index, 3);
+    }
+    for v in &buffers.vertices[n..] {
+        assert_eq!(v.atlas_index, 7);
+    }
+}
+
+#[test]
+fn bounded_vertex_buffers() {
+    use crate::basic_shapes::{fill_circle, fill_rectangle};
+    use crate::math::rect;
+    use crate::FillOptions;
+
+    let mut buffers: BoundedVertexBuffers<u16> = BoundedVertexBuffers::new();
+    assert_eq!(buffers.bounds(), Rect::zero());
+
+    fill_circle(
+        point(1.0, 2.0),
+        3.0,
+        &FillOptions::tolerance(0.01),
+        &mut buffers,
+    )
+    .unwrap();
+
+    let bounds = buffers.bounds();
+    assert!((bounds.min_x() + 2.0).abs() < 0.01);
+    assert!((bounds.min_y() + 1.0).abs() < 0.01);
+    assert!((bounds.max_x() - 4.0).abs() < 0.01);
+    assert!((bounds.max_y() - 5.0).abs() < 0.01);
+
+    fill_rectangle(
+        &rect(0.0, 0.0, 10.0, 1.0),
+        &FillOptions::default(),
+        &mut buffers,
+    )
+    .unwrap();
+
+    let bounds = buffers.bounds();
+    assert!((bounds.min_x() + 2.0).abs() < 0.01);
+    assert!((bounds.max_x() - 10.0).abs() < 0.01);
+}
+
+#[test]
+fn strip_primitive_restart() {
+    let build = |restart: Option<u16>| {
+        let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+        {
+            let mut builder = StripBuffersBuilder::new(&mut buffers, Positions);
+            if let Some(index) = restart {
+                builder = builder.with_primitive_restart(index);
+            }
+
+            builder.begin_geometry();
+            let mut ids = Vec::new();
+            for i in 0..7 {
+                ids.push(builder.add_vertex(point(i as f32, (i % 2) as f32)).unwrap());
+            }
+            // Two connected triangles, then a disconnected one.
+            builder.add_triangle(ids[0], ids[1], ids[2]);
+            builder.add_triangle(ids[2], ids[1], ids[3]);
+            builder.add_triangle(ids[4], ids[5], ids[6]);
+            builder.end_geometry();
+        }
+        buffers.indices
+    };
+
+    assert_eq!(build(Some(0xFFFF)), vec![0, 1, 2, 3, 0xFFFF, 4, 5, 6]);
+    assert_eq!(build(None), vec![0, 1, 2, 3, 3, 4, 4, 5, 6]);
+}
+
+#[cfg(test)]
+fn decode_strip(indices: &[u16], restart: Option<u16>) -> Vec<[u16; 3]> {
+    // Triangles rotated so that the smallest index comes first, keeping the winding.
+    fn normalize(t: [u16; 3]) -> [u16; 3] {
+        let i = (0..3).min_by_key(|&i| t[i]).unwrap();
+        [t[i], t[(i + 1) % 3], t[(i + 2) % 3]]
+    }
+
+    let mut triangles = Vec::new();
+    for strip in indices.split(|&i| Some(i) == restart) {
+        for k in 2..strip.len() {
+            let (a, b, c) = (strip[k - 2], strip[k - 1], strip[k]);
+            if a == b || b == c || a == c {
+                continue;
+            }
+            let t = if k % 2 == 0 { [a, b, c] } else { [b, a, c] };
+            triangles.push(normalize(t));
+        }
+    }
+    triangles.sort();
+
+    triangles
+}
+
+#[test]
+fn strip_two_geometries() {
+    use crate::basic_shapes::{fill_circle, stroke_polyline};
+    use crate::{FillOptions, StrokeOptions};
+
+    let polyline = [
+        point(0.0, 0.0),
+        point(10.0, 0.0),
+        point(10.0, 10.0),
+        point(20.0, 5.0),
+    ];
+
+    let build = |output: &mut dyn StrokeGeometryBuilder| {
+        stroke_polyline(
+            polyline.iter().cloned(),
+            false,
+            &StrokeOptions::default(),
+            output,
+        )
+        .unwrap();
+    };
+
+    let mut expected: VertexBuffers<Point, u16> = VertexBuffers::new();
+    build(&mut simple_builder(&mut expected));
+    fill_circle(
+        point(5.0, 5.0),
+        3.0,
+        &FillOptions::default(),
+        &mut simple_builder(&mut expected),
+    )
+    .unwrap();
+    // Each triangle of the list is a strip of its own.
+    let mut expected_triangles: Vec<[u16; 3]> = expected
+        .indices
+        .chunks(3)
+        .flat_map(|t| decode_strip(t, None))
+        .collect();
+    expected_triangles.sort();
+
+    for &restart in &[None, Some(0xFFFF)] {
+        let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+        fn strip(
+            buffers: &mut VertexBuffers<Point, u16>,
+            restart: Option<u16>,
+        ) -> StripBuffersBuilder<'_, Point, u16, Positions> {
+            let builder = StripBuffersBuilder::new(buffers, Positions);
+            match restart {
+                Some(index) => builder.with_primitive_restart(index),
+                None => builder,
+            }
+        }
+
+        // Each geometry uses a separate builder on the same buffers.
+        build(&mut strip(&mut buffers, restart));
+        fill_circle(
+            point(5.0, 5.0),
+            3.0,
+            &FillOptions::default(),
+            &mut strip(&mut buffers, restart),
+        )
+        .unwrap();
+
+        assert_eq!(buffers.vertices, expected.vertices);
+        assert_eq!(decode_strip(&buffers.indices, restart), expected_triangles);
+    }
+}
+
+#[test]
+fn strip_winding_parity() {
+    let build = |restart: Option<u16>| {
+        let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+        {
+            let mut builder = StripBuffersBuilder::new(&mut buffers, Positions);
+            if let Some(index) = restart {
+                builder = builder.with_primitive_restart(index);
+            }
+
+            builder.begin_geometry();
+            let mut ids = Vec::new();
+            for i in 0..5 {
+                ids.push(builder.add_vertex(point(i as f32, (i % 2) as f32)).unwrap());
+            }
+            builder.add_triangle(ids[0], ids[1], ids[2]);
+            // Shares the edge (1, 2) but extending the strip would flip it.
+            builder.add_triangle(ids[1], ids[2], ids[3]);
+            builder.add_triangle(ids[2], ids[3], ids[4]);
+            builder.end_geometry();
+        }
+        buffers.indices
+    };
+
+    let expected = vec![[0, 1, 2], [1, 2, 3], [2, 3, 4]];
+    assert_eq!(decode_strip(&build(None), None), expected);
+    assert_eq!(decode_strip(&build(Some(0xFFFF)), Some(0xFFFF)), expected);
+}
+
+#[test]
+#[should_panic]
+fn strip_restart_index_used_by_a_vertex() {
+    let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+    buffers.vertices = vec![point(0.0, 0.0); 4];
+    StripBuffersBuilder::new(&mut buffers, Positions).with_primitive_restart(2);
+}
+
+#[test]
+fn strip_restart_index_too_many_vertices() {
+    let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+    let mut builder = StripBuffersBuilder::new(&mut buffers, Positions).with_primitive_restart(2);
+    builder.begin_geometry();
+    assert!(builder.add_vertex(point(0.0, 0.0)).is_ok());
+    assert!(builder.add_vertex(point(1.0, 0.0)).is_ok());
+    assert_eq!(
+        builder.add_vertex(point(2.0, 0.0)),
+        Err(GeometryBuilderError::TooManyVertices)
+    );
+}
+
+#[test]
+fn merge_coplanar_triangles() {
+    use crate::basic_shapes::fill_rectangle;
+    use crate::math::rect;
+    use crate::FillOptions;
+
+    let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+    fill_rectangle(
+        &rect(0.0, 0.0, 1.0, 1.0),
+        &FillOptions::default(),
+        &mut simple_builder(&mut buffers),
+    )
+    .unwrap();
+    assert_eq!(buffers.indices.len(), 6);
+
+    let polygons = buffers.merge_coplanar_triangles();
+    assert_eq!(polygons.polygon_sizes, vec![4]);
+    let mut indices = polygons.indices.clone();
+    indices.sort();
+    assert_eq!(indices, vec![0, 1, 2, 3]);
+
+    // An L shape can't be a single convex polygon.
+    let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+    buffers.vertices = vec![
+        point(0.0, 0.0),
+        point(2.0, 0.0),
+        point(2.0, 1.0),
+        point(1.0, 1.0),
+        point(1.0, 2.0),
+        point(0.0, 2.0),
+    ];
+    buffers.indices = vec![0, 1, 2, 0, 2, 3, 0, 3, 4, 0, 4, 5];
+    let polygons = buffers.merge_coplanar_triangles();
+    assert_eq!(polygons.polygon_sizes.len(), 2);
+    assert_eq!(polygons.indices.len(), 8);
+}
+
+#[test]
+fn validating_builder() {
+    let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+    let mut builder = ValidatingBuilder::new(simple_builder(&mut buffers));
+    builder.begin_geometry();
+    let a = builder.add_vertex(point(0.0, 0.0)).unwrap();
+    let b = builder.add_vertex(point(1.0, 0.0)).unwrap();
+    let c = builder.add_vertex(point(1.0, 1.0)).unwrap();
+    let d = builder.add_vertex(point(2.0, 0.0)).unwrap();
+    builder.add_triangle(a, b, c);
+    builder.add_triangle(a, c, b);
+    builder.add_triangle(c, b, a);
+    // Collinear points.
+    builder.add_triangle(a, b, d);
+    // Repeated vertex.
+    builder.add_triangle(a, a, c);
+    let count = builder.end_geometry();
+
+    assert_eq!(
+        builder.report(),
+        ValidationReport {
+            degenerate: 2,
+            cw: 2,
+            ccw: 1,
+        }
+    );
+    // Only the valid triangles are forwarded.
+    assert_eq!(count.indices, 9);
+
+    builder.begin_geometry();
+    builder.end_geometry();
+    assert_eq!(builder.report(), ValidationReport::default());
+}
+
+#[test]
+#[should_panic]
+fn validating_builder_panic() {
+    let mut builder = ValidatingBuilder::new(NoOutput::new()).with_panic_on_degenerate(true);
+    builder.begin_geometry();
+    let a = builder.add_vertex(point(0.0, 0.0)).unwrap();
+    let b = builder.add_vertex(point(1.0, 1.0)).unwrap();
+    let c = builder.add_vertex(point(2.0, 2.0)).unwrap();
+    builder.add_triangle(a, b, c);
+}
+
+#[test]
+fn dedup_builder() {
+    use crate::path::Path;
+    use crate::{FillOptions, FillTessellator};
+
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(1.0, 0.0));
+    builder.line_to(point(1.0, 1.0));
+    builder.line_to(point(0.0, 1.0));
+    builder.close();
+    let path = builder.build();
+
+    // Each triangle has its own vertices.
+    let options = FillOptions::default().with_no_shared_vertices(true);
+
+    let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+    {
+        let mut dedup = DedupBuilder::new(
+            simple_builder(&mut buffers),
+            |position: Point, _: &mut FillAttributes| (position.x.to_bits(), position.y.to_bits()),
+        );
+        let mut tessellator = FillTessellator::new();
+        tessellator
+            .tessellate_path(&path, &options, &mut dedup)
+            .unwrap();
+        // The ids of the first geometry are not reused.
+        tessellator
+            .tessellate_path(&path, &options, &mut dedup)
+            .unwrap();
+    }
+
+    assert_eq!(buffers.vertices.len(), 8);
+    assert_eq!(buffers.indices.len(), 12);
+    assert!(buffers.indices[..6].iter().all(|&idx| idx < 4));
+    assert!(buffers.indices[6..].iter().all(|&idx| idx >= 4));
+}
+
+#[test]
+fn interleaved_buffers_builder() {
+    use crate::path::Path;
+    use crate::{FillOptions, FillTessellator, StrokeOptions, StrokeTessellator};
+
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(1.0, 0.0));
+    builder.line_to(point(1.0, 1.0));
+    builder.line_to(point(0.0, 1.0));
+    builder.close();
+    let path = builder.build();
+
+    fn write_f32(value: f32, output: &mut Vec<u8>) {
+        output.extend_from_slice(&value.to_bits().to_le_bytes());
+    }
+
+    // Two floats per vertex.
+    let fill_vertex = |position: Point, _: FillAttributes, output: &mut Vec<u8>| {
+        write_f32(position.x, output);
+        write_f32(position.y, output);
+    };
+
+    let mut vertices: Vec<u8> = Vec::new();
+    let mut indices: Vec<u16> = Vec::new();
+    let mut builder = InterleavedBuffersBuilder::new(&mut vertices, &mut indices, fill_vertex);
+    let mut tessellator = FillTessellator::new();
+    let count = tessellator
+        .tessellate_path(&path, &FillOptions::default(), &mut builder)
+        .unwrap();
+    assert_eq!(count.vertices, 4);
+    assert_eq!(count.indices, 6);
+
+    // Too many vertices for u16 indices, the geometry is aborted.
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    for i in 1..70_000 {
+        let angle = i as f32 * std::f32::consts::PI * 2.0 / 70_000.0;
+        builder.line_to(point(angle.cos() * 1000.0, angle.sin() * 1000.0));
+    }
+    builder.close();
+    let circle = builder.build();
+    let mut builder = InterleavedBuffersBuilder::new(&mut vertices, &mut indices, fill_vertex);
+    assert!(tessellator
+        .tessellate_path(&circle, &FillOptions::default(), &mut builder)
+        .is_err());
+    assert_eq!(builder.num_vertices(), 0);
+
+    assert_eq!(vertices.len(), 4 * 8);
+    assert_eq!(indices.len(), 6);
+    let first_vertex = |idx: usize| {
+        let bytes = &vertices[idx * 8..idx * 8 + 4];
+        f32::from_bits(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    };
+    assert!((0..4).all(|idx| first_vertex(idx) == 0.0 || first_vertex(idx) == 1.0));
+
+    // Four floats per vertex, appended after the fill.
+    let mut stroke_indices: Vec<u16> = Vec::new();
+    let mut builder = InterleavedBuffersBuilder::new(
+        &mut vertices,
+        &mut stroke_indices,
+        |position: Point, attributes: StrokeAttributes, output: &mut Vec<u8>| {
+            let normal = attributes.normal();
+            write_f32(position.x, output);
+            write_f32(position.y, output);
+            write_f32(normal.x, output);
+            write_f32(normal.y, output);
+        },
+    );
+    let count = StrokeTessellator::new()
+        .tessellate_path(&path, &StrokeOptions::default(), &mut builder)
+        .unwrap();
+    assert_eq!(builder.num_vertices(), count.vertices);
+    assert_eq!(vertices.len(), 4 * 8 + count.vertices as usize * 16);
+    assert!(stroke_indices
+        .iter()
+        .all(|&idx| (idx as u32) < count.vertices));
+}
+
+#[test]
+fn bounds_builder() {
+    use crate::path::Path;
+    use crate::{LineJoin, StrokeOptions, StrokeTessellator};
+
+    let mut builder = Path::builder();
+    builder.move_to(point(1.0, 2.0));
+    builder.line_to(point(5.0, 2.0));
+    builder.line_to(point(5.0, 4.0));
+    builder.close();
+    let path = builder.build();
+
+    let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+    let bounds = {
+        let mut builder = BoundsBuilder::new(simple_builder(&mut buffers));
+        assert_eq!(builder.bounds(), Rect::zero());
+
+        let options = StrokeOptions::default()
+            .with_line_width(2.0)
+            .with_line_join(LineJoin::Bevel);
+        StrokeTessellator::new()
+            .tessellate_path(&path, &options, &mut builder)
+            .unwrap();
+
+        builder.bounds()
+    };
+
+    let mut min = buffers.vertices[0];
+    let mut max = buffers.vertices[0];
+    for v in &buffers.vertices {
+        min = min.min(*v);
+        max = max.max(*v);
+    }
+    assert_eq!(bounds.min(), min);
+    assert_eq!(bounds.max(), max);
+    assert!(min.x < 1.0 && min.y < 2.0);
+    assert!(max.x > 5.0 && max.y > 4.0);
+
+    // The bounds are reset for each geometry.
+    let mut builder = BoundsBuilder::new(NoOutput::new());
+    builder.begin_geometry();
+    builder.add_vertex(point(-10.0, -10.0)).unwrap();
+    builder.end_geometry();
+    builder.begin_geometry();
+    builder.add_vertex(point(1.0, 1.0)).unwrap();
+    builder.add_vertex(point(3.0, 2.0)).unwrap();
+    builder.end_geometry();
+    assert_eq!(
+        builder.bounds(),
+        Rect::new(point(1.0, 1.0), crate::math::size(2.0, 1.0))
+    );
+
+    builder.abort_geometry();
+    assert_eq!(builder.bounds(), Rect::zero());
+}
+
+#[test]
+fn extend_from() {
+    let quad = |x: f32| {
+        let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+        buffers.vertices.extend_from_slice(&[
+            point(x, 0.0),
+            point(x + 1.0, 0.0),
+            point(x + 1.0, 1.0),
+            point(x, 1.0),
+        ]);
+        buffers.indices.extend_from_slice(&[0, 1, 2, 0, 2, 3]);
+        buffers
+    };
+
+    let mut buffers = quad(0.0);
+    buffers.extend_from(&quad(5.0));
+
+    assert_eq!(buffers.vertices.len(), 8);
+    assert_eq!(buffers.indices, vec![0, 1, 2, 0, 2, 3, 4, 5, 6, 4, 6, 7]);
+    for triangle in buffers.indices[6..].chunks(3) {
+        for &idx in triangle {
+            assert!(buffers.vertices[idx as usize].x >= 5.0);
+        }
+    }
+}
+
+#[test]
+#[should_panic]
+fn extend_from_overflow() {
+    let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+    buffers.vertices.resize(40_000, point(0.0, 0.0));
+    let other = buffers.clone();
+    buffers.extend_from(&other);
+}
+
+#[test]
+fn instance_buffer() {
+    use crate::basic_shapes::fill_rectangle;
+    use crate::math::{rect, vector};
+    use crate::FillOptions;
+
+    let mut mesh: VertexBuffers<Point, u16> = VertexBuffers::new();
+    fill_rectangle(
+        &rect(0.0, 0.0, 1.0, 1.0),
+        &FillOptions::default(),
+        &mut simple_builder(&mut mesh),
+    )
+    .unwrap();
+
+    let mut instances = InstanceBuffer::new(mesh.clone());
+    let offsets = [vector(0.0, 0.0), vector(10.0, 0.0), vector(0.0, 10.0)];
+    for (i, offset) in offsets.iter().enumerate() {
+        assert_eq!(
+            instances.add_instance(Transform::create_translation(offset.x, offset.y)),
+            i
+        );
+    }
+    assert_eq!(instances.num_instances(), 3);
+
+    // The mesh is shared by the instances.
+    assert_eq!(instances.mesh.vertices, mesh.vertices);
+    assert_eq!(instances.mesh.indices, mesh.indices);
+
+    let expanded = instances.to_vertex_buffers(|p, transform| transform.transform_point(*p));
+    assert_eq!(expanded.vertices.len(), mesh.vertices.len() * 3);
+    assert_eq!(expanded.indices.len(), mesh.indices.len() * 3);
+    let num_indices = mesh.indices.len();
+    for (i, offset) in offsets.iter().enumerate() {
+        for (j, &idx) in expanded.indices[i * num_indices..(i + 1) * num_indices]
+            .iter()
+            .enumerate()
+        {
+            let expected = mesh.vertices[mesh.indices[j] as usize] + *offset;
+            assert_eq!(expanded.vertices[idx as usize], expected);
+        }
+    }
+}
@@ -0,0 +1,174 @@
+//! Package tessellated geometry in the binary layout of glTF buffers.
+//!
+//! glTF meshes reference their vertex and index data through accessors, which describe
+//! the type and the bounds of the data, and buffer views, which are ranges of a binary
+//! buffer. [`to_gltf_buffers`](fn.to_gltf_buffers.html) produces both from a
+//! `VertexBuffers<Point, u32>`, leaving the JSON document to the caller.
+//!
+//! glTF positions are three-dimensional: the tessellated geometry is placed in the
+//! `z = 0` plane. All values are stored in little-endian byte order, as required by the
+//! specification.
+
+use crate::geometry_builder::VertexBuffers;
+use crate::math::Point;
+
+/// `componentType` of 32 bits floating point values.
+pub const COMPONENT_TYPE_FLOAT: u32 = 5126;
+/// `componentType` of 32 bits unsigned integers.
+pub const COMPONENT_TYPE_UNSIGNED_INT: u32 = 5125;
+/// `target` of buffer views containing vertex attributes.
+pub const TARGET_ARRAY_BUFFER: u32 = 34962;
+/// `target` of buffer views containing indices.
+pub const TARGET_ELEMENT_ARRAY_BUFFER: u32 = 34963;
+
+/// The `type` of a glTF accessor.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub enum AccessorType {
+    Scalar,
+    Vec3,
+}
+
+impl AccessorType {
+    /// The name of the type in glTF documents.
+    pub fn name(self) -> &'static str {
+        match self {
+            AccessorType::Scalar => "SCALAR",
+            AccessorType::Vec3 => "VEC3",
+        }
+    }
+}
+
+/// The content of a glTF buffer view along with its `target`.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct GltfBufferView {
+    pub data: Vec<u8>,
+    pub target: u32,
+}
+
+/// The metadata of a glTF accessor covering a whole buffer view.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct GltfAccessor {
+    pub component_type: u32,
+    pub accessor_type: AccessorType,
+    /// Number of elements (not components or bytes).
+    pub count: usize,
+    /// Per-component minimum, required by glTF for positions.
+    pub min: Option<[f32; 3]>,
+    /// Per-component maximum, required by glTF for positions.
+    pub max: Option<[f32; 3]>,
+}
+
+/// The buffer views and accessors of a triangle mesh.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct GltfBuffers {
+    pub positions: GltfBufferView,
+    pub position_accessor: GltfAccessor,
+    pub indices: GltfBufferView,
+    pub index_accessor: GltfAccessor,
+}
+
+/// Packages tessellated geometry into glTF buffer views and accessors.
+///
+/// Positions are stored as `VEC3` floats and indices as unsigned 32 bits integers. The
+/// byte length of each buffer view is a multiple of four so that they can be laid out
+/// one after the other in the same buffer without padding.
+pub fn to_gltf_buffers(buffers: &VertexBuffers<Point, u32>) -> GltfBuffers {
+    let mut positions = Vec::with_capacity(buffers.vertices.len() * 12);
+    let mut min = [0.0; 3];
+    let mut max = [0.0; 3];
+    if let Some(first) = buffers.vertices.first() {
+        min = [first.x, first.y, 0.0];
+        max = min;
+    }
+
+    for p in &buffers.vertices {
+        push_u32(&mut positions, p.x.to_bits());
+        push_u32(&mut positions, p.y.to_bits());
+        push_u32(&mut positions, 0.0f32.to_bits());
+
+        min[0] = min[0].min(p.x);
+        min[1] = min[1].min(p.y);
+        max[0] = max[0].max(p.x);
+        max[1] = max[1].max(p.y);
+    }
+
+    let mut indices = Vec::with_capacity(buffers.indices.len() * 4);
+    for &idx in &buffers.indices {
+        push_u32(&mut indices, idx);
+    }
+
+    GltfBuffers {
+        positions: GltfBufferView {
+            data: positions,
+            target: TARGET_ARRAY_BUFFER,
+        },
+        position_accessor: GltfAccessor {
+            component_type: COMPONENT_TYPE_FLOAT,
+            accessor_type: AccessorType::Vec3,
+            count: buffers.vertices.len(),
+            min: Some(min),
+            max: Some(max),
+        },
+        indices: GltfBufferView {
+            data: indices,
+            target: TARGET_ELEMENT_ARRAY_BUFFER,
+        },
+        index_accessor: GltfAccessor {
+            component_type: COMPONENT_TYPE_UNSIGNED_INT,
+            accessor_type: AccessorType::Scalar,
+            count: buffers.indices.len(),
+            min: None,
+            max: None,
+        },
+    }
+}
+
+fn push_u32(output: &mut Vec<u8>, val: u32) {
+    output.push(val as u8);
+    output.push((val >> 8) as u8);
+    output.push((val >> 16) as u8);
+    output.push((val >> 24) as u8);
+}
+
+#[test]
+fn gltf_position_bounds() {
+    use crate::geometry_builder::{BuffersBuilder, Positions};
+    use crate::math::point;
+    use crate::path::Path;
+    use crate::{FillOptions, FillTessellator};
+
+    let mut builder = Path::builder();
+    builder.move_to(point(-2.0, 1.0));
+    builder.line_to(point(5.0, 3.0));
+    builder.line_to(point(4.0, 8.0));
+    builder.line_to(point(-1.0, 6.0));
+    builder.close();
+    let path = builder.build();
+
+    let mut buffers: VertexBuffers<Point, u32> = VertexBuffers::new();
+    FillTessellator::new()
+        .tessellate_path(
+            &path,
+            &FillOptions::default(),
+            &mut BuffersBuilder::new(&mut buffers, Positions),
+        )
+        .unwrap();
+
+    let gltf = to_gltf_buffers(&buffers);
+
+    assert_eq!(gltf.position_accessor.min, Some([-2.0, 1.0, 0.0]));
+    assert_eq!(gltf.position_accessor.max, Some([5.0, 8.0, 0.0]));
+    assert_eq!(gltf.position_accessor.count, buffers.vertices.len());
+    assert_eq!(gltf.positions.data.len(), buffers.vertices.len() * 12);
+    assert_eq!(gltf.index_accessor.count, buffers.indices.len());
+    assert_eq!(gltf.indices.data.len(), buffers.indices.len() * 4);
+
+    // Little-endian encoding.
+    let x = &gltf.positions.data[0..4];
+    let x = (x[0] as u32) | ((x[1] as u32) << 8) | ((x[2] as u32) << 16) | ((x[3] as u32) << 24);
+    assert_eq!(f32::from_bits(x), buffers.vertices[0].x);
+}
@@ -0,0 +1,377 @@
+//! Incremental re-tessellation of edited paths.
+//!
+//! When a small part of a large path is edited, the fill only changes in the region
+//! covered by the modified segments, before and after the edit.
+//! [`retessellate_fill`](fn.retessellate_fill.html) re-tessellates that region and
+//! splices the result into the existing geometry instead of tessellating the whole
+//! path again.
+//!
+//! The spliced geometry covers exactly the same area as a full re-tessellation but its
+//! triangles are different: triangles that straddle the boundary of the region are cut,
+//! which introduces T-junctions along it.
+
+use crate::geometry_builder::{BuffersBuilder, Positions, VertexBuffers};
+use crate::math::{point, Point};
+use crate::path::iterator::PathIterator;
+use crate::path::{Path, PathEvent};
+use crate::{FillOptions, FillTessellator, TessellationError};
+
+/// Updates the fill tessellation of a path after it was edited.
+///
+/// `buffers` must contain the fill tessellation of `old_path` produced with the same
+/// options, for example with a `BuffersBuilder` and the `Positions` constructor. It is
+/// updated in place to contain a tessellation of `new_path`.
+///
+/// The events of both paths are compared to find the region affected by the edit. Only
+/// this region is re-tessellated, and the existing triangles that overlap it are clipped.
+/// If the structure of the path changed (for example if segments were added or removed),
+/// the whole path is tessellated again.
+pub fn retessellate_fill(
+    old_path: &Path,
+    new_path: &Path,
+    options: &FillOptions,
+    buffers: &mut VertexBuffers<Point, u32>,
+) -> Result<(), TessellationError> {
+    let region = match changed_region(old_path, new_path) {
+        Some(Change::None) => {
+            return Ok(());
+        }
+        Some(Change::Region(min, max)) => (
+            point(min.x - options.tolerance, min.y - options.tolerance),
+            point(max.x + options.tolerance, max.y + options.tolerance),
+        ),
+        None => {
+            buffers.vertices.clear();
+            buffers.indices.clear();
+            FillTessellator::new().tessellate_path(
+                new_path,
+                options,
+                &mut BuffersBuilder::new(buffers, Positions),
+            )?;
+            return Ok(());
+        }
+    };
+
+    // Tessellate the new path within the region.
+    let clipped_path = clip_path(new_path, options.tolerance, region);
+    let mut inside: VertexBuffers<Point, u32> = VertexBuffers::new();
+    FillTessellator::new().tessellate_path(
+        &clipped_path,
+        options,
+        &mut BuffersBuilder::new(&mut inside, Positions),
+    )?;
+
+    // Keep the existing geometry outside of the region.
+    let mut output: VertexBuffers<Point, u32> = VertexBuffers::new();
+    let mut remap = vec![std::u32::MAX; buffers.vertices.len()];
+    let mut polygon = Vec::new();
+    for triangle in buffers.indices.chunks(3) {
+        let vertices = [
+            buffers.vertices[triangle[0] as usize],
+            buffers.vertices[triangle[1] as usize],
+            buffers.vertices[triangle[2] as usize],
+        ];
+
+        if !overlaps(&vertices, region) {
+            for &idx in triangle {
+                if remap[idx as usize] == std::u32::MAX {
+                    remap[idx as usize] = output.vertices.len() as u32;
+                    output.vertices.push(buffers.vertices[idx as usize]);
+                }
+                output.indices.push(remap[idx as usize]);
+            }
+            continue;
+        }
+
+        // The outside of the region is split into four convex parts: the left and
+        // right sides and the parts above and below the region in between.
+        let (min, max) = region;
+        let parts = [
+            [Some((0, min.x, true)), None, None],
+            [Some((0, max.x, false)), None, None],
+            [
+                Some((0, min.x, false)),
+                Some((0, max.x, true)),
+                Some((1, min.y, true)),
+            ],
+            [
+                Some((0, min.x, false)),
+                Some((0, max.x, true)),
+                Some((1, max.y, false)),
+            ],
+        ];
+        for part in &parts {
+            polygon.clear();
+            polygon.extend_from_slice(&vertices);
+            for &(axis, value, below) in part.iter().flatten() {
+                clip_polygon(&mut polygon, axis, value, below);
+            }
+            add_fan(&mut output, &polygon);
+        }
+    }
+
+    // Add the new geometry inside of the region.
+    let offset = output.vertices.len() as u32;
+    output.vertices.extend_from_slice(&inside.vertices);
+    output
+        .indices
+        .extend(inside.indices.iter().map(|idx| idx + offset));
+
+    *buffers = output;
+
+    Ok(())
+}
+
+enum Change {
+    None,
+    Region(Point, Point),
+}
+
+// Returns the bounding box of the events that differ between the two paths, or None if
+// the paths don't have the same structure.
+fn changed_region(old_path: &Path, new_path: &Path) -> Option<Change> {
+    let mut min = point(std::f32::MAX, std::f32::MAX);
+    let mut max = point(std::f32::MIN, std::f32::MIN);
+    let mut old_events = old_path.iter();
+    let mut new_events = new_path.iter();
+    loop {
+        let (old_evt, new_evt) = match (old_events.next(), new_events.next()) {
+            (None, None) => break,
+            (Some(old_evt), Some(new_evt)) => (old_evt, new_evt),
+            _ => {
+                return None;
+            }
+        };
+
+        if old_evt == new_evt {
+            continue;
+        }
+
+        if std::mem::discriminant(&old_evt) != std::mem::discriminant(&new_evt) {
+            return None;
+        }
+
+        for p in event_points(old_evt)
+            .iter()
+            .chain(event_points(new_evt).iter())
+        {
+            min = min.min(*p);
+            max = max.max(*p);
+        }
+    }
+
+    if min.x > max.x {
+        return Some(Change::None);
+    }
+
+    Some(Change::Region(min, max))
+}
+
+// The endpoints and control points of an event. Sub-paths are implicitly closed when
+// filled so end events are treated as closing edges even if they are open.
+fn event_points(evt: PathEvent) -> Vec<Point> {
+    match evt {
+        PathEvent::Begin { at } => vec![at],
+        PathEvent::Line { from, to } => vec![from, to],
+        PathEvent::Quadratic { from, ctrl, to } => vec![from, ctrl, to],
+        PathEvent::Cubic {
+            from,
+            ctrl1,
+            ctrl2,
+            to,
+        } => vec![from, ctrl1, ctrl2, to],
+        PathEvent::End { last, first, .. } => vec![last, first],
+    }
+}
+
+fn overlaps(triangle: &[Point; 3], (min, max): (Point, Point)) -> bool {
+    let tri_min = triangle[0].min(triangle[1]).min(triangle[2]);
+    let tri_max = triangle[0].max(triangle[1]).max(triangle[2]);
+
+    tri_min.x < max.x && tri_max.x > min.x && tri_min.y < max.y && tri_max.y > min.y
+}
+
+// Clips each sub-path against the region. Clipping a polygon against a convex region
+// preserves its winding number everywhere within the region, so filling the clipped
+// path with any fill rule produces the intersection of the fill with the region.
+fn clip_path(path: &Path, tolerance: f32, (min, max): (Point, Point)) -> Path {
+    let mut builder = Path::builder();
+    let mut polygon = Vec::new();
+    for evt in path.iter().flattened(tolerance) {
+        match evt {
+            PathEvent::Begin { at } => {
+                polygon.clear();
+                polygon.push(at);
+            }
+            PathEvent::Line { to, .. } => {
+                polygon.push(to);
+            }
+            PathEvent::End { .. } => {
+                clip_polygon(&mut polygon, 0, min.x, false);
+                clip_polygon(&mut polygon, 0, max.x, true);
+                clip_polygon(&mut polygon, 1, min.y, false);
+                clip_polygon(&mut polygon, 1, max.y, true);
+                if polygon.len() > 2 {
+                    builder.polygon(&polygon);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    builder.build()
+}
+
+// Sutherland-Hodgman clipping of a polygon against the half-plane where the coordinate
+// on the provided axis is below (or above) the value.
+fn clip_polygon(polygon: &mut Vec<Point>, axis: usize, value: f32, below: bool) {
+    let coord = |p: &Point| if axis == 0 { p.x } else { p.y };
+    let inside = |p: &Point| {
+        if below {
+            coord(p) <= value
+        } else {
+            coord(p) >= value
+        }
+    };
+
+    let input = std::mem::replace(polygon, Vec::new());
+    for (i, p) in input.iter().enumerate() {
+        let prev = &input[(i + input.len() - 1) % input.len()];
+        if inside(p) != inside(prev) {
+            let t = (value - coord(prev)) / (coord(p) - coord(prev));
+            let mut intersection = prev.lerp(*p, t);
+            // Snap to the clipping line to avoid slivers.
+            if axis == 0 {
+                intersection.x = value;
+            } else {
+                intersection.y = value;
+            }
+            polygon.push(intersection);
+        }
+        if inside(p) {
+            polygon.push(*p);
+        }
+    }
+}
+
+fn add_fan(output: &mut VertexBuffers<Point, u32>, polygon: &[Point]) {
+    if polygon.len() < 3 {
+        return;
+    }
+
+    let first = output.vertices.len() as u32;
+    output.vertices.extend_from_slice(polygon);
+    for i in 1..(polygon.len() as u32 - 1) {
+        output.indices.push(first);
+        output.indices.push(first + i);
+        output.indices.push(first + i + 1);
+    }
+}
+
+#[cfg(test)]
+fn fill_area(buffers: &VertexBuffers<Point, u32>) -> f32 {
+    buffers
+        .indices
+        .chunks(3)
+        .map(|tri| {
+            let a = buffers.vertices[tri[0] as usize];
+            let b = buffers.vertices[tri[1] as usize];
+            let c = buffers.vertices[tri[2] as usize];
+            (b - a).cross(c - a).abs() * 0.5
+        })
+        .sum()
+}
+
+#[cfg(test)]
+fn fill_contains(buffers: &VertexBuffers<Point, u32>, p: Point) -> bool {
+    buffers.indices.chunks(3).any(|tri| {
+        let a = buffers.vertices[tri[0] as usize];
+        let b = buffers.vertices[tri[1] as usize];
+        let c = buffers.vertices[tri[2] as usize];
+        let d1 = (b - a).cross(p - a);
+        let d2 = (c - b).cross(p - b);
+        let d3 = (a - c).cross(p - c);
+        (d1 >= 0.0 && d2 >= 0.0 && d3 >= 0.0) || (d1 <= 0.0 && d2 <= 0.0 && d3 <= 0.0)
+    })
+}
+
+#[test]
+fn retessellate_edited_corner() {
+    // A large star-shaped polygon.
+    let polygon = |corner: Point| {
+        let mut builder = Path::builder();
+        builder.move_to(corner);
+        for i in 1..200 {
+            let angle = i as f32 * std::f32::consts::PI * 2.0 / 200.0;
+            let radius = if i % 2 == 0 { 100.0 } else { 80.0 };
+            builder.line_to(point(angle.cos() * radius, angle.sin() * radius));
+        }
+        builder.close();
+        builder.build()
+    };
+
+    let old_path = polygon(point(100.0, 0.0));
+    let new_path = polygon(point(120.0, 15.0));
+    let options = FillOptions::tolerance(0.01);
+
+    let mut buffers: VertexBuffers<Point, u32> = VertexBuffers::new();
+    FillTessellator::new()
+        .tessellate_path(
+            &old_path,
+            &options,
+            &mut BuffersBuilder::new(&mut buffers, Positions),
+        )
+        .unwrap();
+    let num_old_triangles = buffers.indices.len() / 3;
+
+    retessellate_fill(&old_path, &new_path, &options, &mut buffers).unwrap();
+
+    let mut expected: VertexBuffers<Point, u32> = VertexBuffers::new();
+    FillTessellator::new()
+        .tessellate_path(
+            &new_path,
+            &options,
+            &mut BuffersBuilder::new(&mut expected, Positions),
+        )
+        .unwrap();
+
+    // Most of the triangles were not touched.
+    let num_kept = buffers
+        .indices
+        .chunks(3)
+        .filter(|tri| {
+            let p = buffers.vertices[tri[0] as usize];
+            p.x < 70.0
+        })
+        .count();
+    assert!(num_kept > num_old_triangles / 2);
+
+    let area = fill_area(&buffers);
+    let expected_area = fill_area(&expected);
+    assert!(
+        (area - expected_area).abs() < 0.01,
+        "{} {}",
+        area,
+        expected_area
+    );
+
+    for i in 0..100 {
+        for j in 0..100 {
+            let p = point(
+                i as f32 * 2.5 - 125.0 + 0.037,
+                j as f32 * 2.5 - 125.0 + 0.013,
+            );
+            assert_eq!(
+                fill_contains(&buffers, p),
+                fill_contains(&expected, p),
+                "{:?}",
+                p
+            );
+        }
+    }
+
+    // Nothing changed.
+    let before = buffers.clone();
+    retessellate_fill(&new_path, &new_path, &options, &mut buffers).unwrap();
+    assert_eq!(buffers.indices, before.indices);
+}
@@ -179,6 +179,7 @@
 #![allow(dead_code)]
 //#![allow(needless_return, new_without_default_derive)] // clippy
 
+use lyon_algorithms as algorithms;
 pub use lyon_path as path;
 
 #[cfg(test)]
@@ -189,12 +190,17 @@ use lyon_extra as extra;
 pub extern crate serde;
 
 pub mod basic_shapes;
+mod cdt;
+pub mod coverage;
 mod event_queue;
 mod fill;
 pub mod geometry_builder;
+pub mod gltf;
+pub mod incremental;
 mod math_utils;
 mod monotone;
 mod stroke;
+pub mod wireframe;
 
 #[cfg(test)]
 mod earcut_tests;
@@ -219,8 +225,9 @@ pub use crate::stroke::*;
 #[doc(inline)]
 pub use crate::geometry_builder::{
     BasicGeometryBuilder, BasicVertexConstructor, BuffersBuilder, Count, FillGeometryBuilder,
-    FillVertexConstructor, GeometryBuilder, GeometryBuilderError, GeometryReceiver,
-    StrokeGeometryBuilder, StrokeVertexConstructor, VertexBuffers,
+    FillVertexConstructor, GeometryBuilder, GeometryBuilderError, GeometryReceiver, InstanceBuffer,
+    PolygonBuffers, StripBuffersBuilder, StrokeGeometryBuilder, StrokeVertexConstructor,
+    VertexBuffers,
 };
 
 pub use crate::path::FillRule;
@@ -253,6 +260,8 @@ pub enum TessellationError {
     UnsupportedParamater,
     InvalidVertex,
     TooManyVertices,
+    /// The path has an open sub-path and `OpenSubpathPolicy::Error` was requested.
+    OpenSubpath,
     Internal(InternalError),
 }
 
@@ -271,6 +280,9 @@ impl From<InternalError> for TessellationError {
 }
 
 /// Left or right.
+///
+/// The left side of a stroke is on the left of the direction of travel if the y axis
+/// points up (and on the right if it points down, as in most 2D APIs).
 #[derive(Copy, Clone, Debug, PartialEq)]
 #[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
 pub enum Side {
@@ -404,6 +416,70 @@ pub enum LineJoin {
     Bevel,
 }
 
+/// Alternating lengths of visible and invisible segments along a stroke.
+///
+/// See `StrokeOptions::with_dash_pattern`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct DashPattern {
+    lengths: [f32; DashPattern::MAX_LENGTHS],
+    num_lengths: usize,
+    /// Distance along each sub-path at which the pattern starts.
+    pub offset: f32,
+}
+
+impl DashPattern {
+    /// Maximum number of lengths in a pattern, after odd patterns are repeated.
+    pub const MAX_LENGTHS: usize = 16;
+
+    /// Creates a dash pattern from alternating dash and gap lengths.
+    ///
+    /// As in SVG, a pattern with an odd number of lengths is repeated to yield an even
+    /// number of lengths, so `[5, 3, 2]` is equivalent to `[5, 3, 2, 5, 3, 2]`.
+    ///
+    /// Panics if a length is negative or if there are more than `MAX_LENGTHS` lengths.
+    pub fn new(lengths: &[f32], offset: f32) -> Self {
+        let num_lengths = if lengths.len() % 2 == 1 {
+            lengths.len() * 2
+        } else {
+            lengths.len()
+        };
+        assert!(num_lengths <= Self::MAX_LENGTHS);
+        assert!(lengths.iter().all(|&length| length >= 0.0));
+
+        let mut pattern = DashPattern {
+            lengths: [0.0; Self::MAX_LENGTHS],
+            num_lengths,
+            offset,
+        };
+        for i in 0..num_lengths {
+            pattern.lengths[i] = lengths[i % lengths.len()];
+        }
+
+        pattern
+    }
+
+    /// The alternating dash and gap lengths, starting with a dash.
+    pub fn lengths(&self) -> &[f32] {
+        &self.lengths[..self.num_lengths]
+    }
+}
+
+/// How the fill tessellator handles sub-paths that are not closed.
+///
+/// See `FillOptions::open_subpath_policy`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub enum OpenSubpathPolicy {
+    /// Each open sub-path is filled as if it was closed with a line segment between its
+    /// last and first points.
+    Close,
+    /// Open sub-paths are not filled.
+    Ignore,
+    /// The tessellation fails with `TessellationError::OpenSubpath`.
+    Error,
+}
+
 /// Vertical or Horizontal.
 #[derive(Copy, Clone, Debug, PartialEq)]
 #[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
@@ -413,7 +489,7 @@ pub enum Orientation {
 }
 
 /// Parameters for the tessellator.
-#[derive(Copy, Clone, Debug, PartialEq)]
+#[derive(Copy, Clone, Debug)]
 #[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
 pub struct StrokeOptions {
     /// What cap to use at the start of each sub-path.
@@ -438,6 +514,9 @@ pub struct StrokeOptions {
 
     /// See the SVG specification.
     ///
+    /// `LineJoin::Miter` joins fall back to bevel joins when the ratio between the
+    /// length of the miter and the line width is strictly greater than the limit.
+    ///
     /// Must be greater than or equal to 1.0.
     /// Default value: `StrokeOptions::DEFAULT_MITER_LIMIT`.
     pub miter_limit: f32,
@@ -448,15 +527,71 @@ pub struct StrokeOptions {
     /// Default value: `StrokeOptions::DEFAULT_TOLERANCE`.
     pub tolerance: f32,
 
+    /// Base-2 logarithm of the maximum number of line segments a curve can be
+    /// approximated with.
+    ///
+    /// This is not a recursion depth: each curve produces at most
+    /// `2^max_subdivision_depth` line segments. Curves that would need more segments to
+    /// honor the tolerance, for example with huge control points, are flattened with a
+    /// coarser tolerance instead. This bounds the amount of geometry generated from
+    /// adversarial input.
+    ///
+    /// Default value: `StrokeOptions::DEFAULT_MAX_SUBDIVISION_DEPTH`.
+    pub max_subdivision_depth: u32,
+
     /// Apply line width
     ///
     /// When set to false, the generated vertices will all be positioned in the centre
     /// of the line. The width can be applied later on (eg in a vertex shader) by adding
-    /// the vertex normal multiplied by the line with to each vertex position.
+    /// the vertex normal multiplied by half of the line width to each vertex position.
+    /// See `StrokeAttributes::normal`.
     ///
     /// Default value: `true`.
     pub apply_line_width: bool,
 
+    /// Radius of the arc generated by round line joins.
+    ///
+    /// When set, round joins bulge out with the specified radius instead of following
+    /// a circle of half the line width. Only affects `LineJoin::Round`.
+    ///
+    /// Default value: `None`.
+    pub join_radius: Option<f32>,
+
+    /// A callback that generates the geometry of line joins.
+    ///
+    /// When set, it is used instead of the join specified by `line_join`, except between
+    /// the segments of flattened curves and between nearly collinear segments where a
+    /// simple miter join is always used.
+    ///
+    /// See [`CustomJoinBuilder`](struct.CustomJoinBuilder.html).
+    ///
+    /// Default value: `None`.
+    #[cfg_attr(feature = "serialization", serde(skip))]
+    pub custom_join: Option<CustomJoin>,
+
+    /// Whether to remove the parts of the stroke that overlap with each other.
+    ///
+    /// By default the triangles of the stroke overlap where the path intersects itself
+    /// and at joins between edges that are shorter than the width of the stroke (which
+    /// is common with flattened curves), causing double blending with semi-transparent
+    /// colors.
+    /// With this option, the triangles of the stroke are filled again with the fill
+    /// tessellator and the non-zero fill rule, producing a single layer of triangles
+    /// covering the union of the stroke. This is significantly more expensive.
+    ///
+    /// The attributes of the new vertices are interpolated from the vertices of the
+    /// stroke triangle they come from. This has no effect if `apply_line_width` is false.
+    ///
+    /// Default value: `false`.
+    pub union_overlaps: bool,
+
+    /// Splits the stroke into dashes.
+    ///
+    /// See [`with_dash_pattern`](#method.with_dash_pattern).
+    ///
+    /// Default value: `None`.
+    pub dash_pattern: Option<DashPattern>,
+
     // To be able to add fields without making it a breaking change, add an empty private field
     // which makes it impossible to create a StrokeOptions without calling the constructor.
     _private: (),
@@ -475,6 +610,7 @@ impl StrokeOptions {
     pub const DEFAULT_LINE_JOIN: LineJoin = LineJoin::Miter;
     pub const DEFAULT_LINE_WIDTH: f32 = 1.0;
     pub const DEFAULT_TOLERANCE: f32 = 0.1;
+    pub const DEFAULT_MAX_SUBDIVISION_DEPTH: u32 = 16;
 
     pub const DEFAULT: Self = StrokeOptions {
         start_cap: Self::DEFAULT_LINE_CAP,
@@ -483,7 +619,12 @@ impl StrokeOptions {
         line_width: Self::DEFAULT_LINE_WIDTH,
         miter_limit: Self::DEFAULT_MITER_LIMIT,
         tolerance: Self::DEFAULT_TOLERANCE,
+        max_subdivision_depth: Self::DEFAULT_MAX_SUBDIVISION_DEPTH,
         apply_line_width: true,
+        join_radius: None,
+        custom_join: None,
+        union_overlaps: false,
+        dash_pattern: None,
         _private: (),
     };
 
@@ -498,6 +639,12 @@ impl StrokeOptions {
         self
     }
 
+    #[inline]
+    pub fn with_max_subdivision_depth(mut self, depth: u32) -> Self {
+        self.max_subdivision_depth = depth;
+        self
+    }
+
     #[inline]
     pub fn with_line_cap(mut self, cap: LineCap) -> Self {
         self.start_cap = cap;
@@ -536,11 +683,54 @@ impl StrokeOptions {
         self
     }
 
+    #[inline]
+    pub fn with_join_radius(mut self, radius: f32) -> Self {
+        self.join_radius = Some(radius);
+        self
+    }
+
+    #[inline]
+    pub fn with_custom_join(mut self, join: CustomJoin) -> Self {
+        self.custom_join = Some(join);
+        self
+    }
+
     #[inline]
     pub fn dont_apply_line_width(mut self) -> Self {
         self.apply_line_width = false;
         self
     }
+
+    #[inline]
+    pub fn with_apply_line_width(mut self, apply: bool) -> Self {
+        self.apply_line_width = apply;
+        self
+    }
+
+    #[inline]
+    pub fn with_union_overlaps(mut self, union: bool) -> Self {
+        self.union_overlaps = union;
+        self
+    }
+
+    /// Strokes the path with dashes.
+    ///
+    /// `lengths` alternates between the lengths of the dashes and of the gaps between them,
+    /// measured along the path. The pattern restarts at the beginning of each sub-path,
+    /// shifted by `offset`, and continues across the segments of the sub-path, including
+    /// the closing segment of closed sub-paths. Each dash is stroked as an open sub-path,
+    /// so caps are applied at both of its ends.
+    ///
+    /// Patterns with a total length of zero are ignored. Custom attributes are not
+    /// interpolated along dashes, so the pattern is also ignored by `tessellate_with_ids`
+    /// and when tessellating paths that have custom attributes.
+    ///
+    /// See [`DashPattern::new`](struct.DashPattern.html#method.new).
+    #[inline]
+    pub fn with_dash_pattern(mut self, lengths: &[f32], offset: f32) -> Self {
+        self.dash_pattern = Some(DashPattern::new(lengths, offset));
+        self
+    }
 }
 
 impl Default for StrokeOptions {
@@ -549,6 +739,27 @@ impl Default for StrokeOptions {
     }
 }
 
+impl PartialEq for StrokeOptions {
+    fn eq(&self, other: &Self) -> bool {
+        // Custom joins are compared by address. A function can have several addresses, so
+        // options with the same custom join may compare as different.
+        let custom_join = |options: &Self| options.custom_join.map(|join| join as usize);
+
+        self.start_cap == other.start_cap
+            && self.end_cap == other.end_cap
+            && self.line_join == other.line_join
+            && self.line_width == other.line_width
+            && self.miter_limit == other.miter_limit
+            && self.tolerance == other.tolerance
+            && self.max_subdivision_depth == other.max_subdivision_depth
+            && self.apply_line_width == other.apply_line_width
+            && self.join_radius == other.join_radius
+            && custom_join(self) == custom_join(other)
+            && self.union_overlaps == other.union_overlaps
+            && self.dash_pattern == other.dash_pattern
+    }
+}
+
 /// Parameters for the fill tessellator.
 #[derive(Copy, Clone, Debug, PartialEq)]
 #[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
@@ -560,6 +771,18 @@ pub struct FillOptions {
     /// Default value: `FillOptions::DEFAULT_TOLERANCE`.
     pub tolerance: f32,
 
+    /// Base-2 logarithm of the maximum number of line segments a curve can be
+    /// approximated with.
+    ///
+    /// This is not a recursion depth: each curve produces at most
+    /// `2^max_subdivision_depth` line segments. Curves that would need more segments to
+    /// honor the tolerance, for example with huge control points, are flattened with a
+    /// coarser tolerance instead. This bounds the amount of geometry generated from
+    /// adversarial input.
+    ///
+    /// Default value: `FillOptions::DEFAULT_MAX_SUBDIVISION_DEPTH`.
+    pub max_subdivision_depth: u32,
+
     /// Set the fill rule.
     ///
     /// See the [SVG specification](https://www.w3.org/TR/SVG/painting.html#FillRuleProperty).
@@ -583,6 +806,86 @@ pub struct FillOptions {
     /// Default value: `true`.
     pub handle_intersections: bool,
 
+    /// Whether to translate and scale the geometry into a well-conditioned range of
+    /// coordinates before the tessellation.
+    ///
+    /// Paths with very large coordinates (for example geographic coordinates in meters)
+    /// are prone to floating point precision issues. With this option, the geometry is
+    /// centered around the origin and scaled by a power of two before the sweep, and the
+    /// output vertices are mapped back to the original coordinate space.
+    ///
+    /// Default value: `false`.
+    pub normalize_coordinates: bool,
+
+    /// Whether to snap the horizontal and vertical line segments of the path to the
+    /// pixel grid.
+    ///
+    /// The endpoints of axis-aligned line segments are moved to the nearest pixel
+    /// boundary along the axis perpendicular to the segment, so that axis-aligned shapes
+    /// such as rectangles have crisp edges. Diagonal lines and curves are left untouched,
+    /// except for endpoints shared with a snapped segment.
+    ///
+    /// This is only applied by `FillTessellator::tessellate` and by `tessellate_path` for
+    /// paths without custom attributes.
+    ///
+    /// Default value: `false`.
+    pub pixel_snap: bool,
+
+    /// The transform from the coordinate space of the path to pixels, used when snapping
+    /// to the pixel grid.
+    ///
+    /// `None` means that the path is already expressed in pixels.
+    ///
+    /// Default value: `None`.
+    pub pixel_transform: Option<math::Transform>,
+
+    /// Whether to emit three distinct vertices for each triangle instead of sharing
+    /// vertices between adjacent triangles.
+    ///
+    /// This is useful to give each triangle its own flat attributes, for example a
+    /// color picked per triangle, at the cost of a larger vertex buffer. Vertex ids are
+    /// never reused between triangles.
+    ///
+    /// Default value: `false`.
+    pub no_shared_vertices: bool,
+
+    /// Distance under which the endpoints of the path are considered to be at the same
+    /// position.
+    ///
+    /// The sweep compares positions exactly. When this is greater than zero, endpoints
+    /// that are within this distance of each other are welded into a single vertex
+    /// before the tessellation, which removes the slivers and spurious intersections that
+    /// nearly coincident points produce. The distance is in the coordinate space of the
+    /// path: welding happens after snapping to the pixel grid (see `pixel_snap`) and
+    /// before `normalize_coordinates` is applied. Control points are left untouched.
+    ///
+    /// Like `pixel_snap`, this is only applied by `FillTessellator::tessellate` and by
+    /// `tessellate_path` for paths without custom attributes.
+    ///
+    /// Default value: `0.0`.
+    pub epsilon: f32,
+
+    /// What to do with sub-paths that are not closed.
+    ///
+    /// Sub-paths are considered open if they don't end with a `close` command, even if
+    /// their last point is at the position of the first one. Sub-paths without any
+    /// segment are ignored in all cases.
+    ///
+    /// Default value: `OpenSubpathPolicy::Close`.
+    pub open_subpath_policy: OpenSubpathPolicy,
+
+    /// When set, the fill is dilated so that it includes every pixel of this size that
+    /// is even partially covered by the path.
+    ///
+    /// This emulates conservative rasterization: every point within half a pixel of the
+    /// fill, horizontally and vertically, is covered. The dilation is made of extra
+    /// triangles around the boundary which overlap the fill, so the output is meant to
+    /// be used as a coverage mask or rendered opaque. The pixel size is in the coordinate
+    /// space of the path.
+    ///
+    /// Default value: `None`.
+    pub conservative_pixel_size: Option<f32>,
+
     // To be able to add fields without making it a breaking change, add an empty private field
     // which makes it impossible to create a FillOptions without the calling constructor.
     _private: (),
@@ -595,12 +898,22 @@ impl FillOptions {
     pub const DEFAULT_FILL_RULE: FillRule = FillRule::EvenOdd;
     /// Default orientation.
     pub const DEFAULT_SWEEP_ORIENTATION: Orientation = Orientation::Vertical;
+    /// Default maximum curve subdivision depth.
+    pub const DEFAULT_MAX_SUBDIVISION_DEPTH: u32 = 16;
 
     pub const DEFAULT: Self = FillOptions {
         tolerance: Self::DEFAULT_TOLERANCE,
+        max_subdivision_depth: Self::DEFAULT_MAX_SUBDIVISION_DEPTH,
         fill_rule: Self::DEFAULT_FILL_RULE,
         sweep_orientation: Self::DEFAULT_SWEEP_ORIENTATION,
         handle_intersections: true,
+        normalize_coordinates: false,
+        pixel_snap: false,
+        pixel_transform: None,
+        no_shared_vertices: false,
+        epsilon: 0.0,
+        open_subpath_policy: OpenSubpathPolicy::Close,
+        conservative_pixel_size: None,
         _private: (),
     };
 
@@ -614,6 +927,21 @@ impl FillOptions {
         Self::DEFAULT.with_tolerance(tolerance)
     }
 
+    #[inline]
+    pub fn epsilon(epsilon: f32) -> Self {
+        Self::DEFAULT.with_epsilon(epsilon)
+    }
+
+    #[inline]
+    pub fn open_subpath_policy(policy: OpenSubpathPolicy) -> Self {
+        Self::DEFAULT.with_open_subpath_policy(policy)
+    }
+
+    #[inline]
+    pub fn conservative(pixel_size: f32) -> Self {
+        Self::DEFAULT.with_conservative(pixel_size)
+    }
+
     #[inline]
     pub fn non_zero() -> Self {
         let mut options = Self::DEFAULT;
@@ -627,6 +955,12 @@ impl FillOptions {
         self
     }
 
+    #[inline]
+    pub fn with_max_subdivision_depth(mut self, depth: u32) -> Self {
+        self.max_subdivision_depth = depth;
+        self
+    }
+
     #[inline]
     pub fn with_fill_rule(mut self, rule: FillRule) -> Self {
         self.fill_rule = rule;
@@ -644,6 +978,48 @@ impl FillOptions {
         self.handle_intersections = intersections;
         self
     }
+
+    #[inline]
+    pub fn with_normalized_coordinates(mut self, normalize: bool) -> Self {
+        self.normalize_coordinates = normalize;
+        self
+    }
+
+    #[inline]
+    pub fn with_pixel_snap(mut self, snap: bool) -> Self {
+        self.pixel_snap = snap;
+        self
+    }
+
+    #[inline]
+    pub fn with_pixel_transform(mut self, transform: math::Transform) -> Self {
+        self.pixel_transform = Some(transform);
+        self
+    }
+
+    #[inline]
+    pub fn with_no_shared_vertices(mut self, no_shared_vertices: bool) -> Self {
+        self.no_shared_vertices = no_shared_vertices;
+        self
+    }
+
+    #[inline]
+    pub fn with_epsilon(mut self, epsilon: f32) -> Self {
+        self.epsilon = epsilon;
+        self
+    }
+
+    #[inline]
+    pub fn with_open_subpath_policy(mut self, policy: OpenSubpathPolicy) -> Self {
+        self.open_subpath_policy = policy;
+        self
+    }
+
+    #[inline]
+    pub fn with_conservative(mut self, pixel_size: f32) -> Self {
+        self.conservative_pixel_size = Some(pixel_size);
+        self
+    }
 }
 
 impl Default for FillOptions {
@@ -652,6 +1028,36 @@ impl Default for FillOptions {
     }
 }
 
+// Returns the tolerance to use to flatten a curve into at most `2^max_depth` line segments.
+//
+// This is the requested tolerance unless the curve needs too many segments, in which case
+// the tolerance is raised until the segment count fits.
+//
+// `num_segments` is called with a tolerance and the largest count it needs to be exact
+// for. Above that count it may return any greater value. This bounds the work
+// done to count the segments of curves with huge coordinates.
+pub(crate) fn capped_flattening_tolerance(
+    tolerance: f32,
+    max_depth: u32,
+    num_segments: impl Fn(f32, u32) -> u32,
+) -> f32 {
+    let max_segments = 1u32 << max_depth.min(31);
+    let max_count = max_segments.saturating_mul(4);
+    let mut tolerance = tolerance;
+    // The segment count decreases at least as fast as the square root of the
+    // tolerance, so this usually converges in one or two iterations.
+    for _ in 0..32 {
+        let n = num_segments(tolerance, max_count).min(max_count);
+        if n <= max_segments || !tolerance.is_finite() {
+            break;
+        }
+        let ratio = n as f32 / max_segments as f32;
+        tolerance *= (ratio * ratio).max(2.0);
+    }
+
+    tolerance
+}
+
 type Index = u32;
 
 /// A virtual vertex offset in a geometry.
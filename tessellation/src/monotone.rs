@@ -123,6 +123,10 @@ impl MonotoneTessellator {
         self.triangles.push((a.id, b.id, c.id));
     }
 
+    pub fn num_triangles(&self) -> usize {
+        self.triangles.len()
+    }
+
     pub fn flush(&mut self, output: &mut dyn FillGeometryBuilder) {
         for &(a, b, c) in &self.triangles {
             output.add_triangle(a, b, c);
@@ -5,12 +5,17 @@ use crate::geom::utils::{directed_angle, normalized_tangent};
 use crate::geom::{Arc, CubicBezierSegment, LineSegment, QuadraticBezierSegment};
 use crate::math_utils::compute_normal;
 use crate::path::builder::{Build, FlatPathBuilder, PathBuilder};
-use crate::path::{AttributeStore, EndpointId, IdEvent, PathEvent, PathSlice, PositionStore};
-use crate::{GeometryBuilderError, StrokeGeometryBuilder, VertexId};
+use crate::path::iterator::PathIterator;
+use crate::path::{AttributeStore, EndpointId, IdEvent, Path, PathEvent, PathSlice, PositionStore};
 use crate::{
+    capped_flattening_tolerance, DashPattern, FillAttributes, FillOptions, FillTessellator,
     LineCap, LineJoin, Order, Side, StrokeOptions, TessellationError, TessellationResult,
     VertexSource,
 };
+use crate::{
+    Count, FillGeometryBuilder, GeometryBuilder, GeometryBuilderError, StrokeGeometryBuilder,
+    VertexId,
+};
 
 use std::f32::consts::PI;
 const EPSILON: f32 = 1e-4;
@@ -100,6 +105,23 @@ impl StrokeTessellator {
         options: &StrokeOptions,
         builder: &mut dyn StrokeGeometryBuilder,
     ) -> TessellationResult {
+        if let Some(pattern) = options.dash_pattern {
+            let mut dashed_options = *options;
+            dashed_options.dash_pattern = None;
+            if pattern.lengths().iter().sum::<f32>() <= 0.0 {
+                return self.tessellate(input, &dashed_options, builder);
+            }
+
+            let dashes = dash_path(input, &pattern, options.tolerance);
+            return self.tessellate(dashes, &dashed_options, builder);
+        }
+
+        if options.union_overlaps && options.apply_line_width {
+            let mut recorder = StrokeRecorder::new(0);
+            self.tessellate(input, &options.with_union_overlaps(false), &mut recorder)?;
+            return recorder.tessellate_union(options.tolerance, builder);
+        }
+
         builder.begin_geometry();
         {
             let mut attrib_buffer: Vec<f32> = Vec::new();
@@ -127,6 +149,19 @@ impl StrokeTessellator {
         options: &StrokeOptions,
         builder: &mut dyn StrokeGeometryBuilder,
     ) -> TessellationResult {
+        if options.union_overlaps && options.apply_line_width {
+            let num_attributes = custom_attributes.map_or(0, |store| store.num_attributes());
+            let mut recorder = StrokeRecorder::new(num_attributes);
+            self.tessellate_with_ids(
+                path,
+                positions,
+                custom_attributes,
+                &options.with_union_overlaps(false),
+                &mut recorder,
+            )?;
+            return recorder.tessellate_union(options.tolerance, builder);
+        }
+
         builder.begin_geometry();
         {
             let custom_attributes = custom_attributes.unwrap_or(&());
@@ -162,6 +197,127 @@ impl StrokeTessellator {
     }
 }
 
+// Splits the flattened sub-paths into a sequence of open sub-paths, one per dash.
+fn dash_path(
+    input: impl IntoIterator<Item = PathEvent>,
+    pattern: &DashPattern,
+    tolerance: f32,
+) -> Vec<PathEvent> {
+    let mut dasher = Dasher {
+        lengths: pattern.lengths(),
+        index: 0,
+        remaining: 0.0,
+        dash_start: point(0.0, 0.0),
+        current: point(0.0, 0.0),
+        output: Vec::new(),
+    };
+
+    let total_length: f32 = dasher.lengths.iter().sum();
+
+    for evt in input.into_iter().flattened(tolerance) {
+        match evt {
+            PathEvent::Begin { at } => {
+                let mut distance = pattern.offset % total_length;
+                if distance < 0.0 {
+                    distance += total_length;
+                }
+                dasher.begin(at, distance);
+            }
+            PathEvent::Line { from, to } => {
+                dasher.edge(from, to);
+            }
+            PathEvent::End { last, first, close } => {
+                if close {
+                    dasher.edge(last, first);
+                }
+                dasher.end();
+            }
+            _ => {}
+        }
+    }
+
+    dasher.output
+}
+
+struct Dasher<'l> {
+    lengths: &'l [f32],
+    // Even indices are dashes and odd indices are gaps.
+    index: usize,
+    // Distance left until the end of the current dash or gap.
+    remaining: f32,
+    dash_start: Point,
+    current: Point,
+    output: Vec<PathEvent>,
+}
+
+impl<'l> Dasher<'l> {
+    fn in_dash(&self) -> bool {
+        self.index % 2 == 0
+    }
+
+    fn begin(&mut self, at: Point, mut distance: f32) {
+        self.index = 0;
+        while distance >= self.lengths[self.index] {
+            distance -= self.lengths[self.index];
+            self.index = (self.index + 1) % self.lengths.len();
+        }
+        self.remaining = self.lengths[self.index] - distance;
+
+        if self.in_dash() {
+            self.output.push(PathEvent::Begin { at });
+            self.dash_start = at;
+        }
+        self.current = at;
+    }
+
+    fn edge(&mut self, from: Point, to: Point) {
+        let length = (to - from).length();
+        let mut t = 0.0;
+        while length - t > self.remaining {
+            t += self.remaining;
+            let p = from.lerp(to, t / length);
+            if self.in_dash() {
+                if p != self.current {
+                    self.output.push(PathEvent::Line {
+                        from: self.current,
+                        to: p,
+                    });
+                }
+                self.output.push(PathEvent::End {
+                    last: p,
+                    first: self.dash_start,
+                    close: false,
+                });
+            } else {
+                self.output.push(PathEvent::Begin { at: p });
+                self.dash_start = p;
+            }
+            self.current = p;
+            self.index = (self.index + 1) % self.lengths.len();
+            self.remaining = self.lengths[self.index];
+        }
+
+        self.remaining -= length - t;
+        if self.in_dash() && to != self.current {
+            self.output.push(PathEvent::Line {
+                from: self.current,
+                to,
+            });
+        }
+        self.current = to;
+    }
+
+    fn end(&mut self) {
+        if self.in_dash() {
+            self.output.push(PathEvent::End {
+                last: self.current,
+                first: self.dash_start,
+                close: false,
+            });
+        }
+    }
+}
+
 macro_rules! add_vertex {
     ($builder: expr, position: $position: expr) => {{
         let mut position = $position;
@@ -200,7 +356,9 @@ pub struct StrokeBuilder<'l> {
     previous_right_id: VertexId,
     second_left_id: VertexId,
     second_right_id: VertexId,
-    prev_normal: Vector,
+    // Normals of the front and back vertices at the end of the last join.
+    front_end_normal: Vector,
+    back_end_normal: Vector,
     previous_front_side: Side,
     nth: u32,
     length: f32,
@@ -225,7 +383,8 @@ impl<'l> Build for StrokeBuilder<'l> {
         self.previous = Point::new(0.0, 0.0);
         self.current = Point::new(0.0, 0.0);
         self.second = Point::new(0.0, 0.0);
-        self.prev_normal = Vector::new(0.0, 0.0);
+        self.front_end_normal = Vector::new(0.0, 0.0);
+        self.back_end_normal = Vector::new(0.0, 0.0);
         self.first_endpoint = EndpointId::INVALID;
         self.second_endpoint = EndpointId::INVALID;
         self.current_endpoint = EndpointId::INVALID;
@@ -260,12 +419,14 @@ impl<'l> FlatPathBuilder for StrokeBuilder<'l> {
 impl<'l> PathBuilder for StrokeBuilder<'l> {
     fn quadratic_bezier_to(&mut self, ctrl: Point, to: Point) {
         let mut first = true;
-        QuadraticBezierSegment {
+        let segment = QuadraticBezierSegment {
             from: self.current,
             ctrl,
             to,
-        }
-        .for_each_flattened(self.options.tolerance, &mut |point| {
+        };
+        let tolerance =
+            self.flattening_tolerance(|tolerance, _| segment.num_flattening_segments(tolerance));
+        segment.for_each_flattened(tolerance, &mut |point| {
             self.edge_to(point, EndpointId::INVALID, 0.0, first);
             first = false;
         });
@@ -273,13 +434,16 @@ impl<'l> PathBuilder for StrokeBuilder<'l> {
 
     fn cubic_bezier_to(&mut self, ctrl1: Point, ctrl2: Point, to: Point) {
         let mut first = true;
-        CubicBezierSegment {
+        let segment = CubicBezierSegment {
             from: self.current,
             ctrl1,
             ctrl2,
             to,
-        }
-        .for_each_flattened(self.options.tolerance, &mut |point| {
+        };
+        let tolerance = self.flattening_tolerance(|tolerance, max| {
+            segment.num_flattening_segments_up_to(tolerance, max)
+        });
+        segment.for_each_flattened(tolerance, &mut |point| {
             self.edge_to(point, EndpointId::INVALID, 0.0, first);
             first = false;
         });
@@ -325,7 +489,8 @@ impl<'l> StrokeBuilder<'l> {
             second: zero,
             previous: zero,
             current: zero,
-            prev_normal: Vector::new(0.0, 0.0),
+            front_end_normal: Vector::new(0.0, 0.0),
+            back_end_normal: Vector::new(0.0, 0.0),
             previous_left_id: VertexId(0),
             previous_right_id: VertexId(0),
             second_left_id: VertexId(0),
@@ -362,6 +527,14 @@ impl<'l> StrokeBuilder<'l> {
         self.options = *options;
     }
 
+    fn flattening_tolerance(&self, num_segments: impl Fn(f32, u32) -> u32) -> f32 {
+        capped_flattening_tolerance(
+            self.options.tolerance,
+            self.options.max_subdivision_depth,
+            num_segments,
+        )
+    }
+
     #[cold]
     fn builder_error(&mut self, e: GeometryBuilderError) {
         if self.error.is_none() {
@@ -389,39 +562,39 @@ impl<'l> StrokeBuilder<'l> {
                     // when flattening a curve so we reset it after each
                     // iteration.
                     let previous_endpoint = self.current_endpoint;
-                    QuadraticBezierSegment {
+                    let segment = QuadraticBezierSegment {
                         from: self.current,
                         ctrl: positions.get_control_point(ctrl),
                         to: positions.get_endpoint(to),
-                    }
-                    .for_each_flattened_with_t(
-                        self.options.tolerance,
-                        &mut |point, t| {
-                            self.edge_to(point, to, t, first);
-                            self.previous_endpoint = previous_endpoint;
-                            first = false;
-                        },
-                    );
+                    };
+                    let tolerance = self.flattening_tolerance(|tolerance, _| {
+                        segment.num_flattening_segments(tolerance)
+                    });
+                    segment.for_each_flattened_with_t(tolerance, &mut |point, t| {
+                        self.edge_to(point, to, t, first);
+                        self.previous_endpoint = previous_endpoint;
+                        first = false;
+                    });
                 }
                 IdEvent::Cubic {
                     ctrl1, ctrl2, to, ..
                 } => {
                     let mut first = true;
                     let previous_endpoint = self.current_endpoint;
-                    CubicBezierSegment {
+                    let segment = CubicBezierSegment {
                         from: self.current,
                         ctrl1: positions.get_control_point(ctrl1),
                         ctrl2: positions.get_control_point(ctrl2),
                         to: positions.get_endpoint(to),
-                    }
-                    .for_each_flattened_with_t(
-                        self.options.tolerance,
-                        &mut |point, t| {
-                            self.edge_to(point, to, t, first);
-                            self.previous_endpoint = previous_endpoint;
-                            first = false;
-                        },
-                    );
+                    };
+                    let tolerance = self.flattening_tolerance(|tolerance, max| {
+                        segment.num_flattening_segments_up_to(tolerance, max)
+                    });
+                    segment.for_each_flattened_with_t(tolerance, &mut |point, t| {
+                        self.edge_to(point, to, t, first);
+                        self.previous_endpoint = previous_endpoint;
+                        first = false;
+                    });
                 }
                 IdEvent::End { close: true, .. } => {
                     self.close();
@@ -461,7 +634,14 @@ impl<'l> StrokeBuilder<'l> {
             let second = self.second;
             self.edge_to(second, self.second_endpoint, self.second_t, true);
 
-            self.attributes.normal = self.prev_normal;
+            // The first edge starts at the end of the join, with the advancement of the
+            // start of the sub-path.
+            let (left_normal, right_normal) = match self.previous_front_side {
+                Side::Left => (self.front_end_normal, self.back_end_normal),
+                Side::Right => (self.back_end_normal, self.front_end_normal),
+            };
+
+            self.attributes.normal = left_normal;
             self.attributes.side = Side::Left;
             self.attributes.src = VertexSource::Endpoint {
                 id: self.previous_endpoint,
@@ -471,7 +651,7 @@ impl<'l> StrokeBuilder<'l> {
 
             let first_left_id = add_vertex!(self, position: self.previous);
 
-            self.attributes.normal = -self.prev_normal;
+            self.attributes.normal = right_normal;
             self.attributes.side = Side::Right;
 
             let first_right_id = add_vertex!(self, position: self.previous);
@@ -559,12 +739,16 @@ impl<'l> StrokeBuilder<'l> {
             if self.options.end_cap == LineCap::Square {
                 // The easiest way to implement square caps is to lie about the current position
                 // and move it slightly to accommodate for the width/2 extra length.
-                self.current += d.normalize();
+                self.current += d.normalize() * (self.options.line_width / 2.0);
             }
             let p = self.current + d;
             self.edge_to(p, self.previous_endpoint, 0.0, true);
             // Restore the real current position.
             self.current = current;
+            if self.options.end_cap == LineCap::Square {
+                // The extension of the cap isn't part of the path.
+                self.length -= self.options.line_width / 2.0;
+            }
 
             if self.options.end_cap == LineCap::Round {
                 let left_id = self.previous_left_id;
@@ -577,15 +761,15 @@ impl<'l> StrokeBuilder<'l> {
             let mut first = self.first;
             let d = first - self.second;
 
+            self.attributes.advancement = self.sub_path_start_length;
             if self.options.start_cap == LineCap::Square {
-                first += d.normalize();
+                first += d.normalize() * (self.options.line_width / 2.0);
+                self.attributes.advancement -= self.options.line_width / 2.0;
             }
 
             let n2 = normalized_tangent(d);
             let n1 = -n2;
 
-            self.attributes.advancement = self.sub_path_start_length;
-
             self.attributes.normal = n1;
             self.attributes.side = Side::Left;
 
@@ -625,14 +809,14 @@ impl<'l> StrokeBuilder<'l> {
 
         let previous_edge = self.current - self.previous;
         let next_edge = to - self.current;
-        let join_type = if with_join {
-            self.options.line_join
+        let (join_type, custom_join) = if with_join {
+            (self.options.line_join, self.options.custom_join)
         } else {
-            LineJoin::Miter
+            (LineJoin::Miter, None)
         };
 
         let (start_left_id, start_right_id, end_left_id, end_right_id, front_side) =
-            self.tessellate_join(previous_edge, next_edge, join_type);
+            self.tessellate_join(previous_edge, next_edge, join_type, custom_join);
 
         // Tessellate the edge
         if self.nth > 1 {
@@ -801,13 +985,20 @@ impl<'l> StrokeBuilder<'l> {
             let back_end_vertex = add_vertex!(self, position: self.current);
             // return
             return match order {
-                Order::Before => (back_start_vertex, back_end_vertex, Some(order)),
-                Order::After => (back_end_vertex, back_start_vertex, Some(order)),
+                Order::Before => {
+                    self.back_end_normal = back_end_vertex_normal;
+                    (back_start_vertex, back_end_vertex, Some(order))
+                }
+                Order::After => {
+                    self.back_end_normal = back_start_vertex_normal;
+                    (back_end_vertex, back_start_vertex, Some(order))
+                }
             };
         }
 
         self.attributes.normal = -front_normal;
         self.attributes.side = front_side.opposite();
+        self.back_end_normal = -front_normal;
 
         // Standard Case
         let back_start_vertex = add_vertex!(self, position: self.current);
@@ -821,6 +1012,7 @@ impl<'l> StrokeBuilder<'l> {
         previous_edge: Vector,
         next_edge: Vector,
         mut join_type: LineJoin,
+        mut custom_join: Option<CustomJoin>,
     ) -> (VertexId, VertexId, VertexId, VertexId, Side) {
         // This function needs to differentiate the "front" of the join (aka. the pointy side)
         // from the back. The front is where subdivision or adjustments may be needed.
@@ -869,6 +1061,7 @@ impl<'l> StrokeBuilder<'l> {
             // TODO: the 0.95 threshold above is completely arbitrary and needs
             // adjustments.
             join_type = LineJoin::Miter;
+            custom_join = None;
         } else if join_type == LineJoin::Miter && self.miter_limit_is_exceeded(normal) {
             // Per SVG spec: If the stroke-miterlimit is exceeded, the line join
             // falls back to bevel.
@@ -887,6 +1080,13 @@ impl<'l> StrokeBuilder<'l> {
         };
 
         let (start_vertex, end_vertex) = match join_type {
+            _ if custom_join.is_some() => self.tessellate_custom_join(
+                custom_join.unwrap(),
+                prev_tangent,
+                next_tangent,
+                front_side,
+                back_join_vertex,
+            ),
             LineJoin::Round => {
                 self.tessellate_round_join(prev_tangent, next_tangent, front_side, back_join_vertex)
             }
@@ -905,7 +1105,7 @@ impl<'l> StrokeBuilder<'l> {
                 self.attributes.normal = front_normal;
                 self.attributes.side = front_side;
                 let end_vertex = add_vertex!(self, position: self.current);
-                self.prev_normal = normal;
+                self.front_end_normal = front_normal;
 
                 if let Some(_order) = order {
                     let t2 = match _order {
@@ -925,7 +1125,10 @@ impl<'l> StrokeBuilder<'l> {
                         .add_triangle(start_vertex, end_vertex, back_join_vertex);
 
                     match _order {
-                        Order::Before => (end_vertex, start_vertex),
+                        Order::Before => {
+                            self.front_end_normal = n1;
+                            (end_vertex, start_vertex)
+                        }
                         Order::After => (start_vertex, end_vertex),
                     }
                 } else {
@@ -989,7 +1192,7 @@ impl<'l> StrokeBuilder<'l> {
 
         let last_vertex = add_vertex!(self, position: self.current);
 
-        self.prev_normal = next_normal;
+        self.front_end_normal = next_normal * neg_if_right;
 
         let (v1, v2, v3) = if front_side.is_left() {
             (start_vertex, last_vertex, back_vertex)
@@ -1001,6 +1204,28 @@ impl<'l> StrokeBuilder<'l> {
         (start_vertex, last_vertex)
     }
 
+    fn tessellate_custom_join(
+        &mut self,
+        callback: CustomJoin,
+        prev_tangent: Vector,
+        next_tangent: Vector,
+        front_side: Side,
+        back_vertex: VertexId,
+    ) -> (VertexId, VertexId) {
+        let (start_vertex, end_vertex) = callback(&mut CustomJoinBuilder {
+            stroker: self,
+            prev_tangent,
+            next_tangent,
+            front_side,
+            back_vertex,
+        });
+
+        let neg_if_right = if front_side.is_left() { 1.0 } else { -1.0 };
+        self.front_end_normal = vector(-next_tangent.y, next_tangent.x) * neg_if_right;
+
+        (start_vertex, end_vertex)
+    }
+
     fn tessellate_round_join(
         &mut self,
         prev_tangent: Vector,
@@ -1010,8 +1235,19 @@ impl<'l> StrokeBuilder<'l> {
     ) -> (VertexId, VertexId) {
         let join_angle = get_join_angle(prev_tangent, next_tangent);
 
+        let half_width = self.options.line_width / 2.0;
+        let radius = self.options.join_radius.unwrap_or(half_width);
+        // The intermediate vertices of the join are pushed out when the join radius
+        // differs from the half line width. The first and last ones have to stay on
+        // the edges of the stroke.
+        let radius_scale = if half_width > 0.0 {
+            radius / half_width
+        } else {
+            1.0
+        };
+
         let max_radius_segment_angle =
-            compute_max_radius_segment_angle(self.options.line_width / 2.0, self.options.tolerance);
+            compute_max_radius_segment_angle(radius, self.options.tolerance);
         let num_segments = (join_angle.abs() as f32 / max_radius_segment_angle).ceil() as u32;
         debug_assert!(num_segments > 0);
         // Calculate angle of each step
@@ -1034,14 +1270,18 @@ impl<'l> StrokeBuilder<'l> {
         let rotation_matrix = [[cos, sin], [-sin, cos]];
 
         let mut n = initial_normal;
-        for _ in 0..num_segments {
+        for i in 0..num_segments {
             // incrementally rotate the normal
             n = vector(
                 n.x * rotation_matrix[0][0] + n.y * rotation_matrix[0][1],
                 n.x * rotation_matrix[1][0] + n.y * rotation_matrix[1][1],
             );
 
-            self.attributes.normal = n;
+            self.attributes.normal = if i + 1 < num_segments {
+                n * radius_scale
+            } else {
+                n
+            };
             self.attributes.side = front_side;
 
             let current_vertex = add_vertex!(self, position: self.current);
@@ -1056,7 +1296,7 @@ impl<'l> StrokeBuilder<'l> {
             last_vertex = current_vertex;
         }
 
-        self.prev_normal = n * neg_if_right;
+        self.front_end_normal = n;
 
         (start_vertex, last_vertex)
     }
@@ -1085,7 +1325,7 @@ impl<'l> StrokeBuilder<'l> {
 
         let last_vertex = add_vertex!(self, position: self.current);
 
-        self.prev_normal = normal;
+        self.front_end_normal = v2 * neg_if_right;
 
         let (v1, v2, v3) = if front_side.is_left() {
             (back_vertex, start_vertex, last_vertex)
@@ -1229,7 +1469,289 @@ fn tess_round_cap(
     )
 }
 
+/// The signature of custom line join callbacks.
+///
+/// The callback generates the geometry on the front side of the join (the outer side
+/// of the corner) and returns the front vertex that connects to the previous segment
+/// and the one that connects to the next segment. They can be the same vertex.
+///
+/// See `StrokeOptions::custom_join`.
+pub type CustomJoin = fn(&mut CustomJoinBuilder<'_, '_>) -> (VertexId, VertexId);
+
+/// The interface through which custom line join callbacks emit their geometry.
+pub struct CustomJoinBuilder<'a, 'l> {
+    stroker: &'a mut StrokeBuilder<'l>,
+    prev_tangent: Vector,
+    next_tangent: Vector,
+    front_side: Side,
+    back_vertex: VertexId,
+}
+
+impl<'a, 'l> CustomJoinBuilder<'a, 'l> {
+    /// The position of the corner on the path.
+    pub fn position(&self) -> Point {
+        self.stroker.current
+    }
+
+    /// The normalized tangent of the segment before the join.
+    pub fn prev_tangent(&self) -> Vector {
+        self.prev_tangent
+    }
+
+    /// The normalized tangent of the segment after the join.
+    pub fn next_tangent(&self) -> Vector {
+        self.next_tangent
+    }
+
+    /// Half of the line width.
+    pub fn half_width(&self) -> f32 {
+        self.stroker.options.line_width * 0.5
+    }
+
+    /// The side of the path on which the outer side of the corner is.
+    pub fn front_side(&self) -> Side {
+        self.front_side
+    }
+
+    /// The vertex at the inner side of the corner, generated by the tessellator.
+    pub fn back_vertex(&self) -> VertexId {
+        self.back_vertex
+    }
+
+    /// Adds a vertex on the front side of the join.
+    ///
+    /// The vertex is placed at `position() + normal * half_width()`, unless the line
+    /// width is not applied by the tessellator in which case the normal is only
+    /// provided in the vertex attributes.
+    pub fn add_vertex(&mut self, normal: Vector) -> VertexId {
+        self.stroker.attributes.normal = normal;
+        self.stroker.attributes.side = self.front_side;
+        add_vertex!(self.stroker, position: self.stroker.current)
+    }
+
+    /// Adds a vertex on the front side of the join at an arbitrary position.
+    ///
+    /// Unlike `add_vertex`, the position is used as is, whether or not the line width is
+    /// applied by the tessellator. The normal is only provided in the vertex attributes.
+    pub fn add_vertex_at(&mut self, position: Point, normal: Vector) -> VertexId {
+        self.stroker.attributes.normal = normal;
+        self.stroker.attributes.side = self.front_side;
+        let res = self
+            .stroker
+            .output
+            .add_stroke_vertex(position, StrokeAttributes(&mut self.stroker.attributes));
+
+        match res {
+            Ok(v) => v,
+            Err(e) => {
+                self.stroker.builder_error(e);
+                VertexId(0)
+            }
+        }
+    }
+
+    /// Adds a triangle.
+    pub fn add_triangle(&mut self, a: VertexId, b: VertexId, c: VertexId) {
+        self.stroker.output.add_triangle(a, b, c);
+    }
+}
+
 /// Extra vertex information from the `StrokeTessellator`.
+// A vertex of the stroke recorded to compute the union of its triangles.
+struct RecordedVertex {
+    position: Point,
+    normal: Vector,
+    advancement: f32,
+    side: Side,
+    src: VertexSource,
+}
+
+// Records the output of the stroke tessellator so that it can be filled again.
+struct StrokeRecorder {
+    vertices: Vec<RecordedVertex>,
+    // The custom attributes of each vertex, `num_attributes` values per vertex.
+    attributes: Vec<f32>,
+    num_attributes: usize,
+    triangles: Vec<[VertexId; 3]>,
+}
+
+impl StrokeRecorder {
+    fn new(num_attributes: usize) -> Self {
+        StrokeRecorder {
+            vertices: Vec::new(),
+            attributes: Vec::new(),
+            num_attributes,
+            triangles: Vec::new(),
+        }
+    }
+
+    // Fills the union of the recorded triangles.
+    fn tessellate_union(
+        &self,
+        tolerance: f32,
+        output: &mut dyn StrokeGeometryBuilder,
+    ) -> TessellationResult {
+        // Each triangle becomes a counter-clockwise sub-path, so that the non-zero
+        // fill rule produces the union of the triangles.
+        let mut builder = Path::builder();
+        let mut endpoint_to_vertex = Vec::with_capacity(self.triangles.len() * 3);
+        for triangle in &self.triangles {
+            let mut triangle = *triangle;
+            let [a, b, c] = triangle;
+            let (pa, pb, pc) = (
+                self.vertices[a.to_usize()].position,
+                self.vertices[b.to_usize()].position,
+                self.vertices[c.to_usize()].position,
+            );
+            let area = (pb - pa).cross(pc - pa);
+            if area == 0.0 {
+                continue;
+            }
+            if area < 0.0 {
+                triangle.swap(1, 2);
+            }
+
+            for (i, vertex) in triangle.iter().enumerate() {
+                let position = self.vertices[vertex.to_usize()].position;
+                let id = if i == 0 {
+                    builder.move_to(position)
+                } else {
+                    builder.line_to(position)
+                };
+                endpoint_to_vertex.resize(id.to_usize() + 1, *vertex);
+                endpoint_to_vertex[id.to_usize()] = *vertex;
+            }
+            builder.close();
+        }
+        let path = builder.build();
+
+        let mut fill_output = StrokeUnionBuilder {
+            recorder: self,
+            endpoint_to_vertex: &endpoint_to_vertex,
+            attrib_buffer: vec![0.0; self.num_attributes],
+            output,
+        };
+
+        FillTessellator::new().tessellate_with_ids(
+            path.id_iter(),
+            &path,
+            None,
+            &FillOptions::non_zero().with_tolerance(tolerance),
+            &mut fill_output,
+        )
+    }
+}
+
+impl GeometryBuilder for StrokeRecorder {
+    fn begin_geometry(&mut self) {
+        self.vertices.clear();
+        self.attributes.clear();
+        self.triangles.clear();
+    }
+
+    fn end_geometry(&mut self) -> Count {
+        Count {
+            vertices: self.vertices.len() as u32,
+            indices: self.triangles.len() as u32 * 3,
+        }
+    }
+
+    fn add_triangle(&mut self, a: VertexId, b: VertexId, c: VertexId) {
+        self.triangles.push([a, b, c]);
+    }
+
+    fn abort_geometry(&mut self) {
+        self.begin_geometry();
+    }
+}
+
+impl StrokeGeometryBuilder for StrokeRecorder {
+    fn add_stroke_vertex(
+        &mut self,
+        position: Point,
+        mut attributes: StrokeAttributes,
+    ) -> Result<VertexId, GeometryBuilderError> {
+        self.vertices.push(RecordedVertex {
+            position,
+            normal: attributes.normal(),
+            advancement: attributes.advancement(),
+            side: attributes.side(),
+            src: attributes.source(),
+        });
+        if self.num_attributes > 0 {
+            self.attributes
+                .extend_from_slice(attributes.interpolated_attributes());
+        }
+
+        Ok(VertexId::from_usize(self.vertices.len() - 1))
+    }
+}
+
+// Receives the fill of the union of the stroke triangles and generates stroke vertices
+// with attributes interpolated from the stroke.
+struct StrokeUnionBuilder<'l> {
+    recorder: &'l StrokeRecorder,
+    endpoint_to_vertex: &'l [VertexId],
+    attrib_buffer: Vec<f32>,
+    output: &'l mut dyn StrokeGeometryBuilder,
+}
+
+impl<'l> GeometryBuilder for StrokeUnionBuilder<'l> {
+    fn begin_geometry(&mut self) {
+        self.output.begin_geometry();
+    }
+
+    fn end_geometry(&mut self) -> Count {
+        self.output.end_geometry()
+    }
+
+    fn add_triangle(&mut self, a: VertexId, b: VertexId, c: VertexId) {
+        self.output.add_triangle(a, b, c);
+    }
+
+    fn abort_geometry(&mut self) {
+        self.output.abort_geometry();
+    }
+}
+
+impl<'l> FillGeometryBuilder for StrokeUnionBuilder<'l> {
+    fn add_fill_vertex(
+        &mut self,
+        position: Point,
+        attributes: FillAttributes,
+    ) -> Result<VertexId, GeometryBuilderError> {
+        let (from, to, t) = match attributes.sources().next() {
+            Some(VertexSource::Endpoint { id }) => (id, id, 0.0),
+            Some(VertexSource::Edge { from, to, t }) => (from, to, t),
+            None => (EndpointId(0), EndpointId(0), 0.0),
+        };
+        let a = self.endpoint_to_vertex[from.to_usize()].to_usize();
+        let b = self.endpoint_to_vertex[to.to_usize()].to_usize();
+        let (va, vb) = (&self.recorder.vertices[a], &self.recorder.vertices[b]);
+        let nearest = if t < 0.5 { va } else { vb };
+
+        let n = self.recorder.num_attributes;
+        for i in 0..n {
+            let attr_a = self.recorder.attributes[a * n + i];
+            let attr_b = self.recorder.attributes[b * n + i];
+            self.attrib_buffer[i] = attr_a + (attr_b - attr_a) * t;
+        }
+
+        let mut data = StrokeAttributesData {
+            normal: va.normal.lerp(vb.normal, t),
+            advancement: va.advancement + (vb.advancement - va.advancement) * t,
+            side: nearest.side,
+            src: nearest.src,
+            store: &(),
+            buffer: &mut self.attrib_buffer,
+            buffer_is_valid: true,
+        };
+
+        self.output
+            .add_stroke_vertex(position, StrokeAttributes(&mut data))
+    }
+}
+
 pub(crate) struct StrokeAttributesData<'l> {
     pub(crate) normal: Vector,
     pub(crate) advancement: f32,
@@ -1246,12 +1768,27 @@ pub struct StrokeAttributes<'a, 'b>(pub(crate) &'b mut StrokeAttributesData<'a>)
 impl<'a, 'b> StrokeAttributes<'a, 'b> {
     /// Normal at this vertex such that extruding the vertices along the normal would
     /// produce a stroke of width 2.0 (1.0 on each side). This vector is not normalized.
+    ///
+    /// The normal points away from the path, towards the `side()` of the vertex: along an
+    /// edge going in the direction `d`, left normals point towards `(-d.y, d.x)` and
+    /// right normals towards `(d.y, -d.x)`. Join vertices are shared by the two edges
+    /// they connect, so the normals are continuous along each side of the stroke.
+    ///
+    /// With `StrokeOptions::dont_apply_line_width` the vertex is positioned on the path
+    /// and `position + normal * line_width / 2.0` is where it would have been placed
+    /// otherwise.
     #[inline]
     pub fn normal(&self) -> Vector {
         self.0.normal
     }
 
     /// How far along the path this vertex is.
+    ///
+    /// This is the distance from the start of the path, accumulated over the sub-paths,
+    /// so it can be used to map textures or dashes along the stroke. Square caps extend
+    /// half of the line width before the start and after the end of their sub-path and so
+    /// does the advancement of their vertices. The closing point of a closed sub-path
+    /// has vertices with the advancement of both the end and the start of the sub-path.
     #[inline]
     pub fn advancement(&self) -> f32 {
         self.0.advancement
@@ -1269,7 +1806,8 @@ impl<'a, 'b> StrokeAttributes<'a, 'b> {
         self.0.src
     }
 
-    /// Returns the source of this vertex.
+    /// Returns the custom attributes of the path at this vertex, interpolated along the
+    /// edge if the vertex is not on an endpoint.
     #[inline]
     pub fn interpolated_attributes(&mut self) -> &[f32] {
         if self.0.buffer_is_valid {
@@ -1294,8 +1832,6 @@ impl<'a, 'b> StrokeAttributes<'a, 'b> {
 
 #[cfg(test)]
 use crate::geometry_builder::*;
-#[cfg(test)]
-use crate::path::Path;
 
 #[cfg(test)]
 fn test_path(path: PathSlice, options: &StrokeOptions, expected_triangle_count: Option<u32>) {
@@ -1619,3 +2155,585 @@ fn stroke_vertex_source_01() {
         }
     }
 }
+
+#[test]
+fn test_join_radius() {
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to(point(10.0, 10.0));
+    let path = builder.build();
+
+    let corner = point(10.0, 0.0);
+    let max_distance_to_corner = |options: &StrokeOptions| {
+        let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+        StrokeTessellator::new()
+            .tessellate(&path, options, &mut simple_builder(&mut buffers))
+            .unwrap();
+
+        buffers
+            .vertices
+            .iter()
+            .map(|v| (*v - corner).length())
+            .filter(|d| *d < 5.0)
+            .fold(0.0, f32::max)
+    };
+
+    let options = StrokeOptions::tolerance(0.01)
+        .with_line_width(2.0)
+        .with_line_join(LineJoin::Round);
+
+    // The inner side of the join is at a distance of sqrt(2) from the corner.
+    assert!(max_distance_to_corner(&options) < 1.5);
+    assert!(max_distance_to_corner(&options.with_join_radius(3.0)) > 2.9);
+}
+
+#[test]
+fn test_custom_join() {
+    fn bevel(join: &mut CustomJoinBuilder) -> (VertexId, VertexId) {
+        let neg_if_right = if join.front_side().is_left() {
+            1.0
+        } else {
+            -1.0
+        };
+        let prev = join.prev_tangent();
+        let next = join.next_tangent();
+        let start = join.add_vertex(vector(-prev.y, prev.x) * neg_if_right);
+        let end = join.add_vertex(vector(-next.y, next.x) * neg_if_right);
+        let back = join.back_vertex();
+        if join.front_side().is_left() {
+            join.add_triangle(start, end, back);
+        } else {
+            join.add_triangle(end, start, back);
+        }
+
+        (start, end)
+    }
+
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to(point(10.0, 10.0));
+    builder.line_to(point(20.0, 5.0));
+    builder.line_to(point(0.0, 20.0));
+    builder.close();
+    builder.move_to(point(30.0, 0.0));
+    builder.line_to(point(40.0, 5.0));
+    builder.line_to(point(30.0, 10.0));
+    let path = builder.build();
+
+    let mut expected: VertexBuffers<Point, u16> = VertexBuffers::new();
+    StrokeTessellator::new()
+        .tessellate_path(
+            &path,
+            &StrokeOptions::default()
+                .with_line_width(2.0)
+                .with_line_join(LineJoin::Bevel),
+            &mut simple_builder(&mut expected),
+        )
+        .unwrap();
+
+    let mut custom: VertexBuffers<Point, u16> = VertexBuffers::new();
+    StrokeTessellator::new()
+        .tessellate_path(
+            &path,
+            &StrokeOptions::default()
+                .with_line_width(2.0)
+                .with_custom_join(bevel),
+            &mut simple_builder(&mut custom),
+        )
+        .unwrap();
+
+    assert_eq!(custom.vertices, expected.vertices);
+    assert_eq!(custom.indices, expected.indices);
+
+    // A spike that goes further than a miter join would.
+    fn spike(join: &mut CustomJoinBuilder) -> (VertexId, VertexId) {
+        let neg_if_right = if join.front_side().is_left() {
+            1.0
+        } else {
+            -1.0
+        };
+        let prev = join.prev_tangent();
+        let next = join.next_tangent();
+        let start = join.add_vertex(vector(-prev.y, prev.x) * neg_if_right);
+        let end = join.add_vertex(vector(-next.y, next.x) * neg_if_right);
+        let direction = (prev - next).normalize();
+        let tip = join.add_vertex_at(join.position() + direction * 5.0, direction);
+        let back = join.back_vertex();
+        if join.front_side().is_left() {
+            join.add_triangle(start, end, back);
+            join.add_triangle(start, tip, end);
+        } else {
+            join.add_triangle(end, start, back);
+            join.add_triangle(end, tip, start);
+        }
+
+        (start, end)
+    }
+
+    let mut spiked: VertexBuffers<Point, u16> = VertexBuffers::new();
+    StrokeTessellator::new()
+        .tessellate_path(
+            &path,
+            &StrokeOptions::default()
+                .with_line_width(2.0)
+                .with_custom_join(spike),
+            &mut simple_builder(&mut spiked),
+        )
+        .unwrap();
+
+    // The corner between the first two edges.
+    let direction: Vector = vector(1.0, -1.0);
+    let tip = point(10.0, 0.0) + direction.normalize() * 5.0;
+    assert!(spiked.vertices.iter().any(|v| (*v - tip).length() < 0.001));
+    assert_eq!(spiked.indices.len(), expected.indices.len() + 3 * 6);
+
+    // Options with different custom joins are not equal.
+    let options = StrokeOptions::default().with_custom_join(bevel);
+    assert_eq!(options, options);
+    assert!(options != StrokeOptions::default());
+}
+
+#[test]
+fn test_max_subdivision_depth() {
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.cubic_bezier_to(point(1.0e7, -1.0e7), point(-1.0e7, 1.0e7), point(0.0, 10.0));
+    let path = builder.build();
+
+    let num_vertices = |options: &StrokeOptions| {
+        let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+        StrokeTessellator::new()
+            .tessellate_path(&path, options, &mut simple_builder(&mut buffers))
+            .unwrap();
+        buffers.vertices.len()
+    };
+
+    // Without the guard, the curve is flattened into more than a thousand segments.
+    assert!(num_vertices(&StrokeOptions::tolerance(0.01).with_max_subdivision_depth(20)) > 2000);
+
+    // With it, into at most 64 segments, each of them adding two vertices.
+    let n = num_vertices(&StrokeOptions::tolerance(0.01).with_max_subdivision_depth(6));
+    assert!(n > 2);
+    assert!(n <= 2 * 65, "{}", n);
+}
+
+#[test]
+fn test_union_overlaps() {
+    // A path that crosses itself, forming a small loop.
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to(point(10.0, 5.0));
+    builder.line_to(point(5.0, 5.0));
+    builder.line_to(point(5.0, -5.0));
+    let path = builder.build();
+
+    let area = |options: &StrokeOptions| {
+        let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+        StrokeTessellator::new()
+            .tessellate_path(&path, options, &mut simple_builder(&mut buffers))
+            .unwrap();
+
+        let mut area = 0.0;
+        for triangle in buffers.indices.chunks(3) {
+            let a = buffers.vertices[triangle[0] as usize];
+            let b = buffers.vertices[triangle[1] as usize];
+            let c = buffers.vertices[triangle[2] as usize];
+            area += ((b - a).cross(c - a) * 0.5).abs();
+        }
+
+        area
+    };
+
+    let options = StrokeOptions::default().with_line_width(2.0);
+    let naive = area(&options);
+    let union = area(&options.with_union_overlaps(true));
+
+    // Each segment overlaps its neighbors at the joins and the first segment crosses
+    // the last one, covering a 2x2 square twice.
+    assert!(union < naive - 3.9, "{} {}", union, naive);
+    assert!(union > naive * 0.75, "{} {}", union, naive);
+}
+
+#[test]
+fn test_miter_limit_bevel_fallback() {
+    let corner = |degrees: f32| {
+        let angle = degrees.to_radians();
+        let mut builder = Path::builder();
+        builder.move_to(point(-50.0, 0.0));
+        builder.line_to(point(0.0, 0.0));
+        builder.line_to(point(-50.0 * angle.cos(), 50.0 * angle.sin()));
+        let outside = -(vector(-1.0, 0.0) + vector(-angle.cos(), angle.sin())).normalize();
+        (builder.build(), outside)
+    };
+
+    // How far the join extends from the corner, towards the outside of the corner.
+    let extent = |(path, outside): &(Path, Vector), options: &StrokeOptions| {
+        let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+        StrokeTessellator::new()
+            .tessellate_path(path, options, &mut simple_builder(&mut buffers))
+            .unwrap();
+        buffers
+            .vertices
+            .iter()
+            .map(|p| p.to_vector().dot(*outside))
+            .fold(0.0, f32::max)
+    };
+
+    let miter = StrokeOptions::default()
+        .with_line_width(2.0)
+        .with_line_join(LineJoin::Miter);
+    let bevel = miter.with_line_join(LineJoin::Bevel);
+
+    // With a 10 degrees corner the miter is more than 11 times as long as the line
+    // width, the tip being at 1 / sin(5deg) half line widths from the corner.
+    let path = corner(10.0);
+    let spike = extent(&path, &miter.with_miter_limit(20.0));
+    assert!(spike > 11.0, "{}", spike);
+
+    let limited = extent(&path, &miter.with_miter_limit(2.0));
+    assert!(limited <= 1.0, "{}", limited);
+    assert_eq!(limited, extent(&path, &bevel));
+
+    // A 90 degrees corner has a miter ratio of sqrt(2), under the limit.
+    let path = corner(90.0);
+    let square = extent(&path, &miter.with_miter_limit(2.0));
+    assert!((square - 2.0f32.sqrt()).abs() < 0.001, "{}", square);
+
+    // A 60 degrees corner has a miter ratio of exactly 2: at the limit, the join
+    // is still mitered.
+    let path = corner(60.0);
+    let at_limit = extent(&path, &miter.with_miter_limit(2.0));
+    assert!((at_limit - 2.0).abs() < 0.001, "{}", at_limit);
+}
+
+#[test]
+fn test_dash_pattern() {
+    use crate::path::iterator::PathIterator;
+
+    let stroke = |path: &Path, options: &StrokeOptions| {
+        let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+        StrokeTessellator::new()
+            .tessellate(path.iter(), options, &mut simple_builder(&mut buffers))
+            .unwrap();
+        let area: f32 = buffers
+            .indices
+            .chunks(3)
+            .map(|tri| {
+                let a = buffers.vertices[tri[0] as usize];
+                let b = buffers.vertices[tri[1] as usize];
+                let c = buffers.vertices[tri[2] as usize];
+                (b - a).cross(c - a).abs() * 0.5
+            })
+            .sum();
+        (buffers.indices.len() / 3, area)
+    };
+
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    let line = builder.build();
+
+    // Dashes from 0 to 2, 4 to 6 and 8 to 10: three rectangles.
+    let options = StrokeOptions::default().with_dash_pattern(&[2.0, 2.0], 0.0);
+    let (num_triangles, area) = stroke(&line, &options);
+    assert_eq!(num_triangles, 6);
+    assert!((area - 6.0).abs() < 0.001, "{}", area);
+
+    // Dashes from 0 to 1, 3 to 5 and 7 to 9.
+    let (_, area) = stroke(&line, &options.with_dash_pattern(&[2.0, 2.0], 1.0));
+    assert!((area - 5.0).abs() < 0.001, "{}", area);
+
+    // Caps are applied to each dash.
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(2.0, 0.0));
+    let dash = builder.build();
+    let square_caps = options.with_line_cap(LineCap::Square);
+    let (_, dash_area) = stroke(
+        &dash,
+        &StrokeOptions::default().with_line_cap(LineCap::Square),
+    );
+    let (_, area) = stroke(&line, &square_caps);
+    assert!(dash_area > 2.0);
+    assert!((area - 3.0 * dash_area).abs() < 0.001, "{}", area);
+
+    // The pattern continues across segments, including flattened curves.
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(3.0, 0.0));
+    builder.quadratic_bezier_to(point(5.0, 0.0), point(7.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    let split_line = builder.build();
+    let (_, area) = stroke(&split_line, &options);
+    assert!((area - 6.0).abs() < 0.001, "{}", area);
+
+    // Dashes are open sub-paths.
+    let dashes = dash_path(line.iter(), options.dash_pattern.as_ref().unwrap(), 0.1);
+    assert_eq!(
+        dashes
+            .iter()
+            .filter(|evt| match evt {
+                PathEvent::End { close: false, .. } => true,
+                _ => false,
+            })
+            .count(),
+        3
+    );
+    assert_eq!(dashes.into_iter().flattened(0.1).count(), 9);
+}
+
+#[test]
+fn test_line_cap_areas() {
+    let stroke = |path: &Path, options: &StrokeOptions| {
+        let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+        StrokeTessellator::new()
+            .tessellate_path(path, options, &mut simple_builder(&mut buffers))
+            .unwrap();
+        let area: f32 = buffers
+            .indices
+            .chunks(3)
+            .map(|tri| {
+                let a = buffers.vertices[tri[0] as usize];
+                let b = buffers.vertices[tri[1] as usize];
+                let c = buffers.vertices[tri[2] as usize];
+                (b - a).cross(c - a).abs() * 0.5
+            })
+            .sum();
+        (buffers.vertices.len(), area)
+    };
+
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    let segment = builder.build();
+
+    let options = StrokeOptions::default().with_line_width(2.0);
+
+    // A 10 by 2 rectangle.
+    let (_, butt) = stroke(&segment, &options.with_line_cap(LineCap::Butt));
+    assert!((butt - 20.0).abs() < 0.001, "{}", butt);
+
+    // Extended by half of the line width at each end.
+    let (_, square) = stroke(&segment, &options.with_line_cap(LineCap::Square));
+    assert!((square - 24.0).abs() < 0.001, "{}", square);
+
+    let (_, start_square) = stroke(&segment, &options.with_start_cap(LineCap::Square));
+    assert!((start_square - 22.0).abs() < 0.001, "{}", start_square);
+
+    // Two half circles of radius 1, approximated within the tolerance.
+    let round_caps = options.with_line_cap(LineCap::Round);
+    for &tolerance in &[0.1, 0.01] {
+        let (_, round) = stroke(&segment, &round_caps.with_tolerance(tolerance));
+        assert!(round <= 20.0 + PI, "{}", round);
+        assert!(round > 20.0 + PI - 2.0 * PI * tolerance, "{}", round);
+    }
+
+    // Round caps are flattened with the stroke's tolerance.
+    let (coarse, _) = stroke(&segment, &round_caps.with_tolerance(0.1));
+    let (fine, _) = stroke(&segment, &round_caps.with_tolerance(0.001));
+    assert!(fine > coarse, "{} {}", fine, coarse);
+
+    // Closed sub-paths have no caps.
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to(point(10.0, 10.0));
+    builder.line_to(point(0.0, 10.0));
+    builder.close();
+    let square_path = builder.build();
+
+    let (_, closed_butt) = stroke(&square_path, &options);
+    for &cap in &[LineCap::Square, LineCap::Round] {
+        let (_, closed) = stroke(&square_path, &options.with_line_cap(cap));
+        assert_eq!(closed, closed_butt);
+    }
+}
+
+#[test]
+fn test_inner_join_overlap() {
+    // Sum of the areas covered by more than one triangle.
+    let overlap = |path: &Path, options: &StrokeOptions| {
+        let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
+        StrokeTessellator::new()
+            .tessellate_path(path, options, &mut simple_builder(&mut buffers))
+            .unwrap();
+        let triangles: Vec<[Point; 3]> = buffers
+            .indices
+            .chunks(3)
+            .map(|tri| {
+                [
+                    buffers.vertices[tri[0] as usize],
+                    buffers.vertices[tri[1] as usize],
+                    buffers.vertices[tri[2] as usize],
+                ]
+            })
+            .collect();
+
+        let mut area = 0.0;
+        for i in 0..triangles.len() {
+            for j in (i + 1)..triangles.len() {
+                area += triangle_overlap_area(triangles[i], triangles[j]);
+            }
+        }
+        area
+    };
+
+    let joins = [
+        (LineJoin::Miter, 100.0),
+        (LineJoin::MiterClip, 4.0),
+        (LineJoin::Round, 4.0),
+        (LineJoin::Bevel, 4.0),
+    ];
+    for &(join, miter_limit) in &joins {
+        let options = StrokeOptions::default()
+            .with_line_width(2.0)
+            .with_line_join(join)
+            .with_miter_limit(miter_limit);
+
+        for &degrees in &[10.0f32, 45.0, 90.0, 135.0] {
+            let angle = degrees.to_radians();
+            let tip = point(-50.0 * angle.cos(), 50.0 * angle.sin());
+
+            let mut builder = Path::builder();
+            builder.move_to(point(-50.0, 0.0));
+            builder.line_to(point(0.0, 0.0));
+            builder.line_to(tip);
+            let v = builder.build();
+
+            let area = overlap(&v, &options);
+            assert!(area < 0.001, "{:?} {} {}", join, degrees, area);
+
+            // The join at the start of a closed sub-path.
+            let mut builder = Path::builder();
+            builder.move_to(point(0.0, 0.0));
+            builder.line_to(point(-50.0, 0.0));
+            builder.line_to(tip);
+            builder.close();
+            let triangle = builder.build();
+
+            let area = overlap(&triangle, &options);
+            assert!(area < 0.001, "{:?} {} closed {}", join, degrees, area);
+        }
+    }
+}
+
+#[cfg(test)]
+fn triangle_overlap_area(a: [Point; 3], b: [Point; 3]) -> f32 {
+    let counter_clockwise = |t: [Point; 3]| {
+        if (t[1] - t[0]).cross(t[2] - t[0]) < 0.0 {
+            [t[0], t[2], t[1]]
+        } else {
+            t
+        }
+    };
+    let a = counter_clockwise(a);
+    let b = counter_clockwise(b);
+
+    // Clip the first triangle with each edge of the second one.
+    let mut polygon = a.to_vec();
+    for i in 0..3 {
+        let (from, to) = (b[i], b[(i + 1) % 3]);
+        let side = |p: Point| (to - from).cross(p - from);
+        let input = std::mem::replace(&mut polygon, Vec::new());
+        for j in 0..input.len() {
+            let p = input[j];
+            let q = input[(j + 1) % input.len()];
+            if side(p) >= 0.0 {
+                polygon.push(p);
+            }
+            if (side(p) >= 0.0) != (side(q) >= 0.0) {
+                polygon.push(p.lerp(q, side(p) / (side(p) - side(q))));
+            }
+        }
+    }
+
+    let mut area = 0.0;
+    for i in 1..polygon.len().max(2) - 1 {
+        area += (polygon[i] - polygon[0]).cross(polygon[i + 1] - polygon[0]) * 0.5;
+    }
+    area
+}
+
+#[test]
+fn test_vertex_normals() {
+    #[derive(Copy, Clone, Debug)]
+    struct Vertex {
+        position: Point,
+        normal: Vector,
+        advancement: f32,
+        side: Side,
+    }
+
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(10.0, 0.0));
+    builder.line_to(point(10.0, 10.0));
+    builder.move_to(point(20.0, 0.0));
+    builder.line_to(point(30.0, 0.0));
+    let path = builder.build();
+
+    for &cap in &[LineCap::Butt, LineCap::Square, LineCap::Round] {
+        let options = StrokeOptions::default()
+            .with_line_width(4.0)
+            .with_line_cap(cap);
+
+        let mut expected: VertexBuffers<Point, u16> = VertexBuffers::new();
+        StrokeTessellator::new()
+            .tessellate_path(&path, &options, &mut simple_builder(&mut expected))
+            .unwrap();
+
+        let mut vertices: VertexBuffers<Vertex, u16> = VertexBuffers::new();
+        StrokeTessellator::new()
+            .tessellate_path(
+                &path,
+                &options.with_apply_line_width(false),
+                &mut BuffersBuilder::new(
+                    &mut vertices,
+                    |position, attributes: StrokeAttributes| Vertex {
+                        position,
+                        normal: attributes.normal(),
+                        advancement: attributes.advancement(),
+                        side: attributes.side(),
+                    },
+                ),
+            )
+            .unwrap();
+
+        assert_eq!(vertices.indices, expected.indices);
+        for (vertex, expected) in vertices.vertices.iter().zip(expected.vertices.iter()) {
+            // Extruding the vertices along the normals gives the regular stroke.
+            let extruded = vertex.position + vertex.normal * 2.0;
+            assert!(
+                (extruded - *expected).length() < 0.001,
+                "{:?} {:?}",
+                vertex,
+                expected
+            );
+
+            // Left is on the left of the direction of travel with the y axis pointing up.
+            if vertex.position.y == 0.0 && vertex.normal.x == 0.0 {
+                let expected_y = match vertex.side {
+                    Side::Left => 1.0,
+                    Side::Right => -1.0,
+                };
+                assert_eq!(vertex.normal.y, expected_y, "{:?}", vertex);
+            }
+
+            // The advancement is the distance along the path, including the extensions of
+            // square caps.
+            let advancement = if vertex.position.x < 15.0 {
+                vertex.position.x + vertex.position.y
+            } else {
+                vertex.position.x
+            };
+            assert!(
+                (vertex.advancement - advancement).abs() < 0.001,
+                "{:?}",
+                vertex
+            );
+        }
+    }
+}
@@ -0,0 +1,92 @@
+//! Debug visualization of the triangles produced by the fill tessellator.
+
+use crate::geometry_builder::{BuffersBuilder, Positions, VertexBuffers};
+use crate::math::Point;
+use crate::path::Path;
+use crate::{FillOptions, FillTessellator, TessellationError};
+
+use std::collections::HashMap;
+
+/// Fills a path and outputs the edges of the produced triangles as a line list.
+///
+/// Each pair of indices in the output is an edge. Edges shared by adjacent triangles
+/// are only emitted once, and vertices are deduplicated by position, so that the
+/// result is the same whether or not the tessellator shares vertices between triangles
+/// (see `FillOptions::no_shared_vertices`).
+///
+/// This is meant to draw an overlay of the triangulation when debugging.
+pub fn debug_triangulation_wireframe(
+    path: &Path,
+    options: &FillOptions,
+) -> Result<VertexBuffers<Point, u32>, TessellationError> {
+    let mut triangles: VertexBuffers<Point, u32> = VertexBuffers::new();
+    FillTessellator::new().tessellate_path(
+        path,
+        options,
+        &mut BuffersBuilder::new(&mut triangles, Positions),
+    )?;
+
+    let mut output = VertexBuffers::new();
+    let mut vertex_ids = HashMap::new();
+    let mut vertex_id = |p: Point| {
+        *vertex_ids
+            .entry((p.x.to_bits(), p.y.to_bits()))
+            .or_insert_with(|| {
+                output.vertices.push(p);
+                output.vertices.len() as u32 - 1
+            })
+    };
+
+    let mut edges = Vec::new();
+    for triangle in triangles.indices.chunks(3) {
+        let a = vertex_id(triangles.vertices[triangle[0] as usize]);
+        let b = vertex_id(triangles.vertices[triangle[1] as usize]);
+        let c = vertex_id(triangles.vertices[triangle[2] as usize]);
+        for &(from, to) in &[(a, b), (b, c), (c, a)] {
+            if from != to {
+                edges.push((from.min(to), from.max(to)));
+            }
+        }
+    }
+
+    edges.sort();
+    edges.dedup();
+    for (from, to) in edges {
+        output.indices.push(from);
+        output.indices.push(to);
+    }
+
+    Ok(output)
+}
+
+#[test]
+fn wireframe_of_a_square() {
+    use crate::math::point;
+
+    let mut builder = Path::builder();
+    builder.move_to(point(0.0, 0.0));
+    builder.line_to(point(1.0, 0.0));
+    builder.line_to(point(1.0, 1.0));
+    builder.line_to(point(0.0, 1.0));
+    builder.close();
+    let path = builder.build();
+
+    for &no_shared_vertices in &[false, true] {
+        let options = FillOptions::default().with_no_shared_vertices(no_shared_vertices);
+        let wireframe = debug_triangulation_wireframe(&path, &options).unwrap();
+
+        // Four sides and one diagonal.
+        assert_eq!(wireframe.vertices.len(), 4);
+        assert_eq!(wireframe.indices.len(), 10);
+
+        let mut diagonals = 0;
+        for edge in wireframe.indices.chunks(2) {
+            let from = wireframe.vertices[edge[0] as usize];
+            let to = wireframe.vertices[edge[1] as usize];
+            if from.x != to.x && from.y != to.y {
+                diagonals += 1;
+            }
+        }
+        assert_eq!(diagonals, 1);
+    }
+}